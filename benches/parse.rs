@@ -0,0 +1,76 @@
+//! Time and memory of parsing a large synthetic spec with and without the
+//! `lowMemory` parser option (`cargo bench --bench parse`).
+//!
+//! `PARSE_BENCH_SCHEMAS` sets the number of component schemas (default 2000);
+//! there is one operation per schema.
+
+mod support;
+
+use generator::parsers::{InputParser, OpenApiParser};
+use serde_json::Value;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// System allocator that tracks live and peak heap bytes
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(live, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn run(label: &str, spec: &str, options: &HashMap<String, Value>) {
+    let baseline = LIVE.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let start = Instant::now();
+    let schema_ir = OpenApiParser.parse_str(spec, options).expect("spec parses");
+    let elapsed = start.elapsed();
+    let retained = LIVE.load(Ordering::Relaxed) - baseline;
+    let peak = PEAK.load(Ordering::Relaxed) - baseline;
+
+    println!(
+        "{:<12} {:>8.1} ms   peak {:>8.1} MiB   retained {:>8.1} MiB   ({} schemas, {} operations)",
+        label,
+        elapsed.as_secs_f64() * 1000.0,
+        mebibytes(peak),
+        mebibytes(retained),
+        schema_ir.schemas.len(),
+        schema_ir.operations.len()
+    );
+}
+
+fn mebibytes(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+fn main() {
+    let schemas = std::env::var("PARSE_BENCH_SCHEMAS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(2000);
+    let spec = support::synthetic_spec(schemas);
+    println!("spec: {:.1} MiB of JSON", mebibytes(spec.len()));
+
+    let low_memory = HashMap::from([("lowMemory".to_string(), Value::Bool(true))]);
+    run("default", &spec, &HashMap::new());
+    run("lowMemory", &spec, &low_memory);
+}
@@ -0,0 +1,142 @@
+//! Criterion benchmarks of each pipeline stage over small, medium, and huge
+//! specs (`cargo bench --bench pipeline`):
+//! - `read`: spec text to the typed OpenAPI document
+//! - `ir`: spec text to the IR, as `generator` does it
+//! - `generate/<generator>`: rendering the IR with each built-in generator's
+//!   default options; generators that require options are skipped
+//!
+//! Compare a change against a saved baseline to catch regressions:
+//! `cargo bench --bench pipeline -- --save-baseline main` before it, then
+//! `cargo bench --bench pipeline -- --baseline main` with it.
+
+mod support;
+
+use criterion::measurement::WallTime;
+use criterion::{
+    criterion_group, criterion_main, BenchmarkGroup, BenchmarkId, Criterion, Throughput,
+};
+use generator::config::GenerationConfig;
+use generator::generators::{
+    AllOfStrategy, Audience, GeneratorRegistry, Hypermedia, PatchBodies, PathPrefixMode,
+    TypeOverrides,
+};
+use generator::parsers::{
+    external_refs, resolve_name_collisions, InputParser, OpenApiParser, SchemaIR,
+};
+use std::collections::HashMap;
+use std::hint::black_box;
+
+fn fixtures() -> Vec<(&'static str, String)> {
+    vec![
+        ("small", support::PETSTORE.to_string()),
+        ("medium", support::synthetic_spec(200)),
+        ("huge", support::synthetic_spec(5000)),
+    ]
+}
+
+fn build_ir(spec: &str) -> SchemaIR {
+    let mut schema_ir = OpenApiParser
+        .parse_str(spec, &HashMap::new())
+        .expect("fixture parses");
+    resolve_name_collisions(&mut schema_ir);
+    schema_ir
+}
+
+fn default_config(name: &str, extension: &str) -> GenerationConfig {
+    GenerationConfig {
+        generator: name.to_string(),
+        output_file: format!("bench.{}", extension),
+        enabled: true,
+        template: None,
+        template_dirs: Vec::new(),
+        plugin: None,
+        options: HashMap::new(),
+    }
+}
+
+fn read(spec: &str) -> openapiv3::OpenAPI {
+    let document = external_refs::read_document("spec", spec).expect("fixture reads");
+    serde_json::from_value(document).expect("fixture is OpenAPI")
+}
+
+fn parse(c: &mut Criterion) {
+    let fixtures = fixtures();
+    let mut read_group = c.benchmark_group("read");
+    for (fixture, spec) in &fixtures {
+        configure(&mut read_group, fixture, spec);
+        read_group.bench_with_input(
+            BenchmarkId::from_parameter(fixture),
+            spec.as_str(),
+            |b, spec| b.iter(|| read(black_box(spec))),
+        );
+    }
+    read_group.finish();
+
+    let mut ir_group = c.benchmark_group("ir");
+    for (fixture, spec) in &fixtures {
+        configure(&mut ir_group, fixture, spec);
+        ir_group.bench_with_input(
+            BenchmarkId::from_parameter(fixture),
+            spec.as_str(),
+            |b, spec| b.iter(|| build_ir(black_box(spec))),
+        );
+    }
+    ir_group.finish();
+}
+
+/// Throughput in spec bytes; fewer samples for the huge spec
+fn configure(group: &mut BenchmarkGroup<'_, WallTime>, fixture: &str, spec: &str) {
+    group.throughput(Throughput::Bytes(spec.len() as u64));
+    if fixture == "huge" {
+        group.sample_size(10);
+    }
+}
+
+fn generate(c: &mut Criterion) {
+    let registry = GeneratorRegistry::new();
+    let mut names = registry.available_generators();
+    names.sort();
+
+    for (fixture, spec) in fixtures() {
+        let schema_ir = build_ir(&spec);
+        let mut group = c.benchmark_group(format!("generate/{}", fixture));
+        if fixture == "huge" {
+            group.sample_size(10);
+        }
+        for &name in &names {
+            let generator = registry.require(name).expect("registered generator");
+            let config = default_config(name, generator.file_extension());
+            if generator.validate_config(&config).is_err() {
+                continue;
+            }
+            group.bench_function(name, |b| {
+                b.iter(|| {
+                    let selected = Audience::from_config(&config)
+                        .expect("default audience")
+                        .select(&schema_ir);
+                    let prepared = AllOfStrategy::from_config(&config)
+                        .and_then(|strategy| strategy.prepare(&selected, generator, &config))
+                        .expect("IR prepares");
+                    let prepared = PathPrefixMode::from_config(&config)
+                        .expect("default mode")
+                        .apply(prepared);
+                    let prepared = Hypermedia::from_config(&config)
+                        .expect("default mode")
+                        .apply(prepared);
+                    let prepared = PatchBodies::from_config(&config).apply(prepared);
+                    let prepared = TypeOverrides::from_config(&config)
+                        .expect("overrides parse")
+                        .apply(prepared);
+                    generator
+                        .generate_from_ir(&prepared, &config)
+                        .expect("generator renders")
+                        .into_files()
+                })
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, parse, generate);
+criterion_main!(benches);
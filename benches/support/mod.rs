@@ -0,0 +1,63 @@
+//! Fixture specs shared by the benchmarks
+
+// Each benchmark uses some of them
+#![allow(dead_code)]
+
+use serde_json::json;
+
+/// `examples/petstore.yaml`: a handful of schemas and operations
+pub const PETSTORE: &str = include_str!("../../examples/petstore.yaml");
+
+const FIELDS_PER_SCHEMA: usize = 20;
+
+/// OpenAPI JSON with `schemas` object schemas of mixed scalar, enum array, and
+/// reference fields, and one `GET` operation returning each
+pub fn synthetic_spec(schemas: usize) -> String {
+    let mut components = serde_json::Map::new();
+    let mut paths = serde_json::Map::new();
+    for i in 0..schemas {
+        let mut properties = serde_json::Map::new();
+        for f in 0..FIELDS_PER_SCHEMA {
+            let property = match f % 4 {
+                0 => {
+                    json!({ "type": "string", "description": format!("Field {} of schema {}", f, i) })
+                }
+                1 => json!({ "type": "integer", "format": "int64", "minimum": 0 }),
+                2 => {
+                    json!({ "type": "array", "items": { "type": "string", "enum": ["a", "b", "c"] } })
+                }
+                _ => json!({ "$ref": format!("#/components/schemas/Schema{}", (i + 1) % schemas) }),
+            };
+            properties.insert(format!("field{}", f), property);
+        }
+        components.insert(
+            format!("Schema{}", i),
+            json!({ "type": "object", "required": ["field0"], "properties": properties }),
+        );
+        paths.insert(
+            format!("/schemas{}/{{id}}", i),
+            json!({
+                "get": {
+                    "operationId": format!("getSchema{}", i),
+                    "tags": [format!("tag{}", i % 10)],
+                    "parameters": [{ "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }],
+                    "responses": {
+                        "200": {
+                            "description": "OK",
+                            "content": { "application/json": { "schema": { "$ref": format!("#/components/schemas/Schema{}", i) } } }
+                        }
+                    }
+                }
+            }),
+        );
+    }
+
+    let document = json!({
+        "openapi": "3.0.3",
+        "info": { "title": "Parse benchmark", "version": "1.0.0" },
+        "servers": [{ "url": "https://api.example.com" }],
+        "paths": paths,
+        "components": { "schemas": components },
+    });
+    serde_json::to_string(&document).expect("spec serializes")
+}
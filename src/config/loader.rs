@@ -1,4 +1,5 @@
 use super::schema::{Config, InputConfig};
+use crate::diagnostics;
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -16,8 +17,10 @@ pub fn load_config(custom_path: Option<&Path>) -> Result<Config> {
         let content = fs::read_to_string(&config_path)
             .with_context(|| format!("Failed to read config file: {:?}", config_path))?;
 
-        let config: Config = serde_yaml::from_str(&content)
-            .with_context(|| format!("Failed to parse config file: {:?}", config_path))?;
+        let config: Config =
+            diagnostics::from_yaml_str(&config_path.display().to_string(), &content)
+                .with_context(|| format!("Failed to parse config file: {:?}", config_path))?;
+        config.check_version()?;
 
         Ok(config)
     } else if custom_path.is_some() {
@@ -1,5 +1,8 @@
-pub mod schema;
+#[cfg(feature = "cli")]
 pub mod loader;
+pub mod schema;
+pub mod version;
 
-pub use schema::GenerationConfig;
+#[cfg(feature = "cli")]
 pub use loader::{load_config, merge_with_cli_args};
+pub use schema::GenerationConfig;
@@ -6,9 +6,18 @@ use std::path::PathBuf;
 pub struct Config {
     pub version: String,
 
+    /// Generator versions this config is meant for (`">=0.4, <0.6"`), see
+    /// [`VersionReq`](super::version::VersionReq)
+    #[serde(rename = "requiredVersion", default)]
+    pub required_version: Option<String>,
+
     #[serde(default)]
     pub input: Option<InputConfig>,
 
+    /// Several specs generated as one client, in place of `input`
+    #[serde(default)]
+    pub gateway: Option<GatewayConfig>,
+
     #[serde(default)]
     pub output: Option<PathBuf>,
 
@@ -20,6 +29,36 @@ pub struct Config {
 
     #[serde(default)]
     pub type_mapping: Option<HashMap<String, HashMap<String, String>>>,
+
+    /// Write `generation-manifest.json` into the output directory
+    #[serde(default = "default_true")]
+    pub manifest: bool,
+
+    /// Fail instead of warning when schema names or operation ids collide
+    #[serde(default)]
+    pub strict: bool,
+
+    /// Leave files whose content did not change untouched, keeping their mtimes
+    #[serde(default)]
+    pub incremental: bool,
+}
+
+impl Config {
+    /// Fails unless this generator's version satisfies `requiredVersion`
+    pub fn check_version(&self) -> anyhow::Result<()> {
+        let Some(required) = &self.required_version else {
+            return Ok(());
+        };
+        let version = env!("CARGO_PKG_VERSION");
+        if !super::version::VersionReq::parse(required)?.matches(version) {
+            anyhow::bail!(
+                "This config requires generator {}, but the installed generator is {}; install a matching version",
+                required,
+                version
+            );
+        }
+        Ok(())
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -33,6 +72,45 @@ pub struct InputConfig {
     pub options: HashMap<String, serde_yaml::Value>,
 }
 
+impl InputConfig {
+    /// Parser options as JSON values
+    pub fn parser_options(&self) -> HashMap<String, serde_json::Value> {
+        self.options
+            .iter()
+            .filter_map(|(k, v)| {
+                serde_json::to_value(v)
+                    .ok()
+                    .map(|json_v| (k.clone(), json_v))
+            })
+            .collect()
+    }
+}
+
+/// Specs of several services merged into one IR, each service's operations
+/// grouped under its name (`client.users.getUser()`)
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GatewayConfig {
+    pub services: Vec<ServiceConfig>,
+
+    /// Which schema names get their service's prefix: `collisions` (default),
+    /// those declared by more than one service, or `always`
+    #[serde(rename = "schemaPrefix", default)]
+    pub schema_prefix: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ServiceConfig {
+    /// Namespace of the service's operations; also its schema prefix
+    pub name: String,
+
+    #[serde(flatten)]
+    pub input: InputConfig,
+
+    /// Prepended to the service's operation paths, where the gateway routes it
+    #[serde(rename = "pathPrefix", default)]
+    pub path_prefix: Option<String>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct GenerationConfig {
     pub generator: String,
@@ -46,6 +124,11 @@ pub struct GenerationConfig {
     #[serde(default)]
     pub template: Option<PathBuf>,
 
+    /// Template directories layered over the built-in templates, the first
+    /// taking precedence; a file replaces the same-named one below it
+    #[serde(rename = "templateDirs", default)]
+    pub template_dirs: Vec<PathBuf>,
+
     #[serde(default)]
     pub plugin: Option<PathBuf>,
 
@@ -70,11 +153,16 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             version: "1.0".to_string(),
+            required_version: None,
             input: None,
+            gateway: None,
             output: Some(PathBuf::from("generated")),
             generations: vec![],
             hooks: HooksConfig::default(),
             type_mapping: None,
+            manifest: true,
+            strict: false,
+            incremental: false,
         }
     }
 }
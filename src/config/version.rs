@@ -0,0 +1,187 @@
+//! `requiredVersion` requirements: comma-separated comparators, all of which
+//! the generator's version must satisfy (`">=0.4, <0.6"`).
+//!
+//! Comparators follow Cargo's: `>=`, `>`, `<=`, `<`, `=`, `^` (also a bare
+//! version), `~`, and `*`. Versions may be partial, `0.4` standing for any
+//! `0.4.x`, so `<=0.5` admits `0.5.9` and `>0.4` starts at `0.5.0`.
+//! Pre-release and build suffixes of the generator's version are ignored.
+
+use anyhow::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Exact,
+    Caret,
+    Tilde,
+}
+
+#[derive(Debug, Clone)]
+struct Comparator {
+    op: Op,
+    /// One to three components; missing ones match anything
+    parts: Vec<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct VersionReq {
+    comparators: Vec<Comparator>,
+}
+
+impl VersionReq {
+    pub fn parse(requirement: &str) -> Result<Self> {
+        let mut comparators = Vec::new();
+        for comparator in requirement.split(',').map(str::trim) {
+            if comparator == "*" {
+                continue;
+            }
+            let (op, version) = [
+                (">=", Op::GreaterEq),
+                ("<=", Op::LessEq),
+                (">", Op::Greater),
+                ("<", Op::Less),
+                ("=", Op::Exact),
+                ("^", Op::Caret),
+                ("~", Op::Tilde),
+            ]
+            .iter()
+            .find_map(|(prefix, op)| comparator.strip_prefix(prefix).map(|rest| (*op, rest)))
+            .unwrap_or((Op::Caret, comparator));
+            let version = version.trim().trim_start_matches('v');
+            let parts: Vec<u64> = version
+                .split('.')
+                .map(|part| part.parse().ok())
+                .collect::<Option<_>>()
+                .filter(|parts: &Vec<u64>| (1..=3).contains(&parts.len()))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "requiredVersion: '{}' is not a version comparator like \">=0.4\" or \"<1.0.0\"",
+                        comparator
+                    )
+                })?;
+            comparators.push(Comparator { op, parts });
+        }
+        Ok(Self { comparators })
+    }
+
+    /// Whether `version` (`major.minor.patch`, suffixes ignored) satisfies every comparator
+    pub fn matches(&self, version: &str) -> bool {
+        let core = version.split(['-', '+']).next().unwrap_or(version);
+        let Some(version) = core
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()
+        else {
+            return false;
+        };
+        self.comparators
+            .iter()
+            .all(|comparator| comparator.matches(&version))
+    }
+}
+
+impl Comparator {
+    fn matches(&self, version: &[u64]) -> bool {
+        let len = self.parts.len().min(version.len());
+        let truncated = &version[..len];
+        let parts = &self.parts[..];
+        match self.op {
+            Op::Greater => truncated > parts,
+            Op::GreaterEq => truncated >= parts,
+            Op::Less => truncated < parts,
+            Op::LessEq => truncated <= parts,
+            Op::Exact => truncated == parts,
+            // Same leading components up to the first non-zero one (`^0.4` is 0.4.x, `^1.2` is 1.x)
+            Op::Caret => {
+                let fixed = parts
+                    .iter()
+                    .position(|&part| part != 0)
+                    .map_or(parts.len(), |i| i + 1);
+                truncated >= parts && version[..fixed.min(len)] == parts[..fixed.min(len)]
+            }
+            // Same major and, when given, minor (`~1.2.3` is 1.2.x from 1.2.3)
+            Op::Tilde => {
+                let fixed = parts.len().min(2);
+                truncated >= parts && version[..fixed.min(len)] == parts[..fixed.min(len)]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn admits(requirement: &str, version: &str) -> bool {
+        VersionReq::parse(requirement).unwrap().matches(version)
+    }
+
+    #[test]
+    fn caret_keeps_the_leading_non_zero_component() {
+        assert!(admits("^1.2", "1.2.0"));
+        assert!(admits("^1.2", "1.9.3"));
+        assert!(!admits("^1.2", "1.1.9"));
+        assert!(!admits("^1.2", "2.0.0"));
+        assert!(admits("^0.4", "0.4.7"));
+        assert!(!admits("^0.4", "0.5.0"));
+        assert!(admits("^0.0.3", "0.0.3"));
+        assert!(!admits("^0.0.3", "0.0.4"));
+        // A bare version is a caret requirement
+        assert!(admits("0.4.2", "0.4.9"));
+        assert!(!admits("0.4.2", "0.4.1"));
+    }
+
+    #[test]
+    fn comma_separated_comparators_must_all_hold() {
+        assert!(admits(">=0.4, <0.6", "0.4.0"));
+        assert!(admits(">=0.4, <0.6", "0.5.9"));
+        assert!(!admits(">=0.4, <0.6", "0.6.0"));
+        assert!(!admits(">=0.4, <0.6", "0.3.9"));
+        assert!(admits(">= 1.0.0,< 2", "1.99.0"));
+    }
+
+    #[test]
+    fn partial_versions_stand_for_any_patch() {
+        assert!(admits("<=0.5", "0.5.9"));
+        assert!(!admits(">0.4", "0.4.9"));
+        assert!(admits(">0.4", "0.5.0"));
+        assert!(admits("=0.4", "0.4.3"));
+        assert!(admits("~1.2.3", "1.2.9"));
+        assert!(!admits("~1.2.3", "1.3.0"));
+        assert!(admits("~1", "1.8.0"));
+    }
+
+    #[test]
+    fn wildcards_and_suffixes() {
+        assert!(admits("*", "3.1.4"));
+        assert!(admits("v0.4", "0.4.0"));
+        assert!(admits(">=0.4", "0.4.0-rc.1+build.5"));
+        assert!(!admits(">=0.4", "not-a-version"));
+    }
+
+    #[test]
+    fn invalid_requirements_are_errors() {
+        for requirement in [
+            "",
+            "^",
+            ">=",
+            "0.4,",
+            "1.2.3.4",
+            ">=x.y",
+            "=> 1.0",
+            "1.*",
+            "99999999999999999999",
+        ] {
+            let error = VersionReq::parse(requirement).unwrap_err().to_string();
+            assert!(
+                error.contains("is not a version comparator"),
+                "{}: {}",
+                requirement,
+                error
+            );
+        }
+    }
+}
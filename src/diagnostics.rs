@@ -0,0 +1,346 @@
+//! Spec and config errors pointing into the text they were read from.
+//!
+//! A [`SourceError`] names the file, line, and column and, for documents that
+//! were well-formed but did not deserialize, the path to the offending node
+//! (`paths./users.get.responses.200`). The `generator` binary renders it with
+//! the surrounding source lines. An [`ErrorGroup`] reports independent errors
+//! (several malformed nodes, several failing generations) together.
+
+use miette::{Diagnostic, LabeledSpan, NamedSource, SourceCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
+
+#[derive(Debug)]
+pub struct SourceError {
+    message: String,
+    /// Dotted path to the node the error is about, as serde saw it
+    path: Option<String>,
+    /// Byte offset into the source; `None` when the node could not be found
+    offset: Option<usize>,
+    line: usize,
+    column: usize,
+    /// Boxed to keep `Result<_, SourceError>` small
+    source: Box<NamedSource<String>>,
+}
+
+impl SourceError {
+    fn new(
+        name: &str,
+        content: &str,
+        message: String,
+        path: Option<String>,
+        offset: Option<usize>,
+    ) -> Self {
+        let (line, column) = match offset {
+            Some(offset) => line_column(content, offset),
+            None => (0, 0),
+        };
+        Self {
+            message,
+            path,
+            offset,
+            line,
+            column,
+            source: Box::new(NamedSource::new(name, content.to_string())),
+        }
+    }
+
+    pub fn from_json(name: &str, content: &str, error: &serde_json::Error) -> Self {
+        let offset = offset_of(content, error.line(), error.column().saturating_sub(1));
+        Self::new(
+            name,
+            content,
+            strip_location(&error.to_string()),
+            None,
+            offset,
+        )
+    }
+
+    pub fn from_yaml(name: &str, content: &str, error: &serde_yaml::Error) -> Self {
+        let offset = error.location().and_then(|location| {
+            offset_of(
+                content,
+                location.line(),
+                location.column().saturating_sub(1),
+            )
+        });
+        Self::new(
+            name,
+            content,
+            strip_location(&error.to_string()),
+            None,
+            offset,
+        )
+    }
+
+    /// Error about the node at `path` (map keys and sequence indices) of `content`
+    pub fn at_path(name: &str, content: &str, path: &[String], message: String) -> Self {
+        let offset = locate(content, path);
+        let path = (!path.is_empty()).then(|| path.join("."));
+        Self::new(name, content, message, path, offset)
+    }
+
+    /// Error at 1-based `line` and `column` of `content`
+    pub fn at_position(
+        name: &str,
+        content: &str,
+        line: usize,
+        column: usize,
+        message: String,
+    ) -> Self {
+        let offset = offset_of(content, line, column.saturating_sub(1));
+        Self::new(name, content, message, None, offset)
+    }
+
+    /// 1-based line, or 0 when the error has no location
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// 1-based column, or 0 when the error has no location
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source.name())?;
+        if self.offset.is_some() {
+            write!(f, ":{}:{}", self.line, self.column)?;
+        }
+        match &self.path {
+            Some(path) => write!(f, ": {}: {}", path, self.message),
+            None => write!(f, ": {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+impl Diagnostic for SourceError {
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(self.source.as_ref())
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let offset = self.offset?;
+        Some(Box::new(std::iter::once(LabeledSpan::at_offset(
+            offset,
+            self.message.clone(),
+        ))))
+    }
+}
+
+/// Independent errors reported together, each on its own line
+#[derive(Debug)]
+pub struct ErrorGroup {
+    summary: String,
+    errors: Vec<anyhow::Error>,
+}
+
+impl ErrorGroup {
+    pub fn new(summary: String, errors: Vec<anyhow::Error>) -> Self {
+        Self { summary, errors }
+    }
+
+    pub fn errors(&self) -> &[anyhow::Error] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for ErrorGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.summary)?;
+        for error in &self.errors {
+            write!(f, "\n  - {:#}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ErrorGroup {}
+
+/// `T` from YAML (or JSON) text, with errors located in `content`
+pub fn from_yaml_str<T: DeserializeOwned>(name: &str, content: &str) -> Result<T, SourceError> {
+    serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(content)).map_err(|error| {
+        let path = path_segments(error.path());
+        let inner = error.into_inner();
+        // serde_yaml locates most errors itself, and names the path in the message
+        match inner.location() {
+            Some(_) => SourceError::from_yaml(name, content, &inner),
+            None => SourceError::at_path(name, content, &path, inner.to_string()),
+        }
+    })
+}
+
+/// `T` from a document parsed out of `content`, with errors located by the
+/// path of the node that failed
+pub fn from_value<T: DeserializeOwned>(
+    name: &str,
+    content: &str,
+    value: Value,
+) -> Result<T, SourceError> {
+    serde_path_to_error::deserialize(value).map_err(|error| {
+        let path = path_segments(error.path());
+        SourceError::at_path(name, content, &path, error.into_inner().to_string())
+    })
+}
+
+/// Map keys and sequence indices of `path`
+pub fn path_segments(path: &serde_path_to_error::Path) -> Vec<String> {
+    path.iter()
+        .filter_map(|segment| match segment {
+            serde_path_to_error::Segment::Map { key } => Some(key.clone()),
+            serde_path_to_error::Segment::Seq { index } => Some(index.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// serde_yaml and serde_json append "at line X column Y", which [`SourceError`] shows itself
+fn strip_location(message: &str) -> String {
+    match message.rfind(" at line ") {
+        Some(index) => message[..index].to_string(),
+        None => message.to_string(),
+    }
+}
+
+/// Byte offset of the node at `path` in YAML (or JSON) `content`: the key for
+/// mapping entries, the item for sequence entries
+fn locate(content: &str, path: &[String]) -> Option<usize> {
+    if path.is_empty() {
+        return None;
+    }
+    let mut locator = Locator {
+        target: path,
+        path: Vec::new(),
+        frames: Vec::new(),
+        found: None,
+    };
+    // A document that deserialized this far also scans; a scan error just means no location
+    let _ = Parser::new_from_str(content).load(&mut locator, false);
+    let marker = locator.found?;
+    offset_of(content, marker.line(), marker.col())
+}
+
+enum Frame {
+    Mapping { key: Option<String>, segment: bool },
+    Sequence { index: usize, segment: bool },
+}
+
+struct Locator<'a> {
+    target: &'a [String],
+    path: Vec<String>,
+    frames: Vec<Frame>,
+    found: Option<Marker>,
+}
+
+impl Locator<'_> {
+    fn is_target(&self, segment: &str) -> bool {
+        self.path.len() + 1 == self.target.len()
+            && self.target.last().is_some_and(|last| last == segment)
+            && self.path.iter().zip(self.target).all(|(a, b)| a == b)
+    }
+}
+
+impl MarkedEventReceiver for Locator<'_> {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        if self.found.is_some() {
+            return;
+        }
+        if matches!(event, Event::MappingEnd | Event::SequenceEnd) {
+            if let Some(
+                Frame::Mapping { segment: true, .. } | Frame::Sequence { segment: true, .. },
+            ) = self.frames.pop()
+            {
+                self.path.pop();
+            }
+            return;
+        }
+        let starts_node = matches!(
+            event,
+            Event::Scalar(..)
+                | Event::Alias(_)
+                | Event::MappingStart(..)
+                | Event::SequenceStart(..)
+        );
+        if !starts_node {
+            return;
+        }
+
+        // A scalar where a mapping expects a key names the next value
+        if let (
+            Some(Frame::Mapping {
+                key: key @ None, ..
+            }),
+            Event::Scalar(value, ..),
+        ) = (self.frames.last_mut(), &event)
+        {
+            *key = Some(value.clone());
+            if self.is_target(value) {
+                self.found = Some(mark);
+            }
+            return;
+        }
+
+        let segment = match self.frames.last_mut() {
+            Some(Frame::Mapping { key, .. }) => key.take(),
+            Some(Frame::Sequence { index, .. }) => {
+                *index += 1;
+                Some((*index - 1).to_string())
+            }
+            None => None,
+        };
+        if let Some(segment) = &segment {
+            if self.is_target(segment) {
+                self.found = Some(mark);
+                return;
+            }
+        }
+
+        let has_segment = segment.is_some();
+        match event {
+            Event::MappingStart(..) => self.frames.push(Frame::Mapping {
+                key: None,
+                segment: has_segment,
+            }),
+            Event::SequenceStart(..) => self.frames.push(Frame::Sequence {
+                index: 0,
+                segment: has_segment,
+            }),
+            _ => return,
+        }
+        self.path.extend(segment);
+    }
+}
+
+/// Byte offset of 1-based `line` and 0-based character `column`
+fn offset_of(content: &str, line: usize, column: usize) -> Option<usize> {
+    let start = match line {
+        0 => return None,
+        1 => 0,
+        _ => content.match_indices('\n').nth(line - 2)?.0 + 1,
+    };
+    let rest = &content[start..];
+    let width = rest
+        .char_indices()
+        .nth(column)
+        .map_or(rest.len(), |(index, _)| index);
+    Some(start + width)
+}
+
+/// 1-based line and column of byte `offset`
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
@@ -0,0 +1,463 @@
+//! Drift between a deployed API and its spec (`generator
+//! verify-spec-against-server`): a real response of an operation checked
+//! against the statuses, media types, and schemas the spec gives it.
+//!
+//! Bodies are compared by shape: JSON types, required and non-nullable
+//! fields, enum values, and fields the spec doesn't declare (unless the
+//! schema allows `additionalProperties`). Formats and validation keywords are
+//! not checked. Inputs parsed with `lowMemory` keep no schema originals, so
+//! their enums and `additionalProperties` go unchecked.
+
+use crate::parsers::{
+    BodyKind, FieldDefinition, OperationDefinition, SchemaDefinition, SchemaIR, TypeInfo,
+};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+
+/// How many references deep a body is followed, so recursive schemas end
+const MAX_DEPTH: usize = 32;
+
+/// A response the server sent
+#[derive(Debug, Clone, Copy)]
+pub struct Response<'a> {
+    pub status: u16,
+    pub content_type: Option<&'a str>,
+    pub body: &'a [u8],
+}
+
+/// One way a response differs from the spec
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    /// JSON path into the body (`$.items[0].email`); `$` for the response itself
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// What checking a response found
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Matches,
+    Drifted(Vec<Drift>),
+    /// An error status the spec declares; the IR has no schema to check its body against
+    Unchecked(String),
+}
+
+/// Check `response`, as the server answered a call of `operation`
+pub fn check(schema_ir: &SchemaIR, operation: &OperationDefinition, response: Response) -> Outcome {
+    let declared = operation
+        .original
+        .get("responses")
+        .and_then(Value::as_object);
+    let document = &schema_ir.original.data;
+    let media_types =
+        declared.and_then(|responses| declared_response(document, responses, response.status));
+    // Without the original operation (`lowMemory`), every status passes as declared
+    let status_declared = declared.is_none() || media_types.is_some();
+
+    if !(200..300).contains(&response.status) {
+        return match status_declared {
+            true => Outcome::Unchecked(format!(
+                "answered {}, which the spec declares",
+                response.status
+            )),
+            false => Outcome::Drifted(vec![drift(
+                "$",
+                format!("status {} is not declared", response.status),
+            )]),
+        };
+    }
+
+    let mut drifts = Vec::new();
+    if !status_declared {
+        drifts.push(drift(
+            "$",
+            format!("status {} is not declared", response.status),
+        ));
+    }
+    let served = response
+        .content_type
+        .map(essence)
+        .filter(|served| !served.is_empty());
+    let expected: Vec<String> = match media_types {
+        Some(Some(content)) => content
+            .keys()
+            .map(|media_type| essence(media_type))
+            .collect(),
+        Some(None) => Vec::new(),
+        None => operation
+            .response_content_type
+            .iter()
+            .map(|media_type| essence(media_type))
+            .collect(),
+    };
+    match &served {
+        Some(served)
+            if !expected.is_empty()
+                && !expected
+                    .iter()
+                    .any(|media_type| media_matches(media_type, served)) =>
+        {
+            drifts.push(drift(
+                "$",
+                format!(
+                    "served as {}, the spec says {}",
+                    served,
+                    expected.join(" or ")
+                ),
+            ))
+        }
+        None if !expected.is_empty() && !response.body.is_empty() => drifts.push(drift(
+            "$",
+            format!(
+                "served without a Content-Type, the spec says {}",
+                expected.join(" or ")
+            ),
+        )),
+        _ => {}
+    }
+
+    let json = served
+        .as_deref()
+        .is_some_and(|served| BodyKind::from_content_type(served) == BodyKind::Json);
+    if let (true, Some(reference)) = (json, &operation.response) {
+        match serde_json::from_slice::<Value>(response.body) {
+            Err(error) => drifts.push(drift("$", format!("body is not JSON: {}", error))),
+            Ok(body) => {
+                let checker = Checker { schema_ir };
+                match (reference.schema_type.as_str(), &body) {
+                    ("array", Value::Array(items)) => {
+                        for (index, item) in items.iter().enumerate() {
+                            checker.schema(
+                                &reference.name,
+                                item,
+                                &format!("$[{}]", index),
+                                0,
+                                &mut drifts,
+                            );
+                        }
+                    }
+                    ("array", other) => drifts.push(drift(
+                        "$",
+                        format!("expected an array, got {}", kind(other)),
+                    )),
+                    _ => checker.schema(&reference.name, &body, "$", 0, &mut drifts),
+                }
+            }
+        }
+    }
+
+    match drifts.is_empty() {
+        true => Outcome::Matches,
+        false => Outcome::Drifted(drifts),
+    }
+}
+
+/// The response object declared for `status` (exactly, as `2XX`, or as
+/// `default`), and its `content`; `None` when the status isn't declared
+fn declared_response<'a>(
+    document: &'a Value,
+    responses: &'a serde_json::Map<String, Value>,
+    status: u16,
+) -> Option<Option<&'a serde_json::Map<String, Value>>> {
+    let range = format!("{}XX", status / 100);
+    let response = responses
+        .get(&status.to_string())
+        .or_else(|| {
+            responses
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(&range))
+                .map(|(_, value)| value)
+        })
+        .or_else(|| responses.get("default"))?;
+    let response = resolve(document, response);
+    Some(
+        response
+            .get("content")
+            .and_then(Value::as_object)
+            .filter(|content| !content.is_empty()),
+    )
+}
+
+struct Checker<'a> {
+    schema_ir: &'a SchemaIR,
+}
+
+impl Checker<'_> {
+    /// `value` against the component schema `name`
+    fn schema(&self, name: &str, value: &Value, path: &str, depth: usize, drifts: &mut Vec<Drift>) {
+        // Unknown names are opaque (`x-codegen-ignore`d or external)
+        let Some(schema) = self.find(name) else {
+            return;
+        };
+        if depth > MAX_DEPTH {
+            return;
+        }
+
+        let fields = self.fields(schema);
+        if fields.is_empty() {
+            if let Some(values) = schema.original.get("enum").and_then(Value::as_array) {
+                if !values.contains(value) {
+                    drifts.push(drift(path, format!("{} is not a value of {}", value, name)));
+                }
+            } else if let Some(expected) = schema.original.get("type").and_then(Value::as_str) {
+                if !is_type(expected, value) {
+                    drifts.push(drift(
+                        path,
+                        format!("expected {} ({}), got {}", expected, name, kind(value)),
+                    ));
+                }
+            }
+            return;
+        }
+
+        let Value::Object(object) = value else {
+            drifts.push(drift(
+                path,
+                format!("expected {} (object), got {}", name, kind(value)),
+            ));
+            return;
+        };
+        for field in &fields {
+            let field_path = member_path(path, &field.name);
+            match object.get(&field.name) {
+                None if field.required => {
+                    drifts.push(drift(&field_path, "required field is missing"))
+                }
+                None => {}
+                Some(Value::Null) if !field.nullable => {
+                    drifts.push(drift(&field_path, "null, but not nullable"))
+                }
+                Some(Value::Null) => {}
+                Some(value) => {
+                    self.type_info(&field.type_info, value, &field_path, depth + 1, drifts)
+                }
+            }
+        }
+        if !self.open(schema) {
+            let declared: HashSet<&str> = fields.iter().map(|field| field.name.as_str()).collect();
+            for key in object.keys().filter(|key| !declared.contains(key.as_str())) {
+                drifts.push(drift(&member_path(path, key), "not in the spec"));
+            }
+        }
+    }
+
+    /// `value` against a field or item type
+    fn type_info(
+        &self,
+        type_info: &TypeInfo,
+        value: &Value,
+        path: &str,
+        depth: usize,
+        drifts: &mut Vec<Drift>,
+    ) {
+        if !type_info.union_members.is_empty() {
+            let matches = type_info.union_members.iter().any(|member| {
+                let mut member_drifts = Vec::new();
+                self.type_info(member, value, path, depth, &mut member_drifts);
+                member_drifts.is_empty()
+            });
+            if !matches {
+                drifts.push(drift(
+                    path,
+                    format!("{} matches none of the alternatives", kind(value)),
+                ));
+            }
+            return;
+        }
+
+        if type_info.is_array || !type_info.tuple_items.is_empty() {
+            let Value::Array(items) = value else {
+                drifts.push(drift(
+                    path,
+                    format!("expected an array, got {}", kind(value)),
+                ));
+                return;
+            };
+            for (index, item) in items.iter().enumerate() {
+                let item_type = type_info
+                    .tuple_items
+                    .get(index)
+                    .or(type_info.array_item_type.as_deref());
+                if let Some(item_type) = item_type {
+                    self.type_info(
+                        item_type,
+                        item,
+                        &format!("{}[{}]", path, index),
+                        depth,
+                        drifts,
+                    );
+                }
+            }
+            return;
+        }
+
+        if let Some(reference) = &type_info.reference {
+            return self.schema(reference, value, path, depth, drifts);
+        }
+        if let Some(values) = type_info
+            .enum_values
+            .as_ref()
+            .filter(|values| !values.is_empty())
+        {
+            let text = match value {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            if !values.contains(&text) {
+                drifts.push(drift(
+                    path,
+                    format!("{} is not one of {}", value, values.join(", ")),
+                ));
+            }
+            return;
+        }
+        if !is_type(&type_info.openapi_type, value) {
+            drifts.push(drift(
+                path,
+                format!("expected {}, got {}", type_info.openapi_type, kind(value)),
+            ));
+            return;
+        }
+        if let (Some(values), Value::Object(object)) = (&type_info.map_values, value) {
+            for (key, item) in object {
+                self.type_info(values, item, &member_path(path, key), depth, drifts);
+            }
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<&SchemaDefinition> {
+        self.schema_ir
+            .schemas
+            .iter()
+            .find(|schema| schema.name == name)
+    }
+
+    /// Own fields of `schema`, then those of its `allOf` parents it doesn't redeclare
+    fn fields<'s>(&'s self, schema: &'s SchemaDefinition) -> Vec<&'s FieldDefinition> {
+        let mut fields: Vec<&FieldDefinition> = schema.fields.iter().collect();
+        let mut seen = HashSet::from([schema.name.as_str()]);
+        let mut pending: Vec<&str> = schema.parents.iter().map(String::as_str).collect();
+        while let Some(parent) = pending.pop() {
+            let Some(parent) = self
+                .find(parent)
+                .filter(|parent| seen.insert(parent.name.as_str()))
+            else {
+                continue;
+            };
+            for field in &parent.fields {
+                if !fields.iter().any(|own| own.name == field.name) {
+                    fields.push(field);
+                }
+            }
+            pending.extend(parent.parents.iter().map(String::as_str));
+        }
+        fields
+    }
+
+    /// Whether `schema` or one of its parents admits undeclared fields
+    fn open(&self, schema: &SchemaDefinition) -> bool {
+        let admits = |schema: &SchemaDefinition| {
+            schema.original.is_null()
+                || matches!(
+                    schema.original.get("additionalProperties"),
+                    Some(Value::Bool(true) | Value::Object(_))
+                )
+        };
+        admits(schema)
+            || schema
+                .parents
+                .iter()
+                .filter_map(|parent| self.find(parent))
+                .any(admits)
+    }
+}
+
+/// `node` with local refs followed; unresolvable refs are returned as they are
+fn resolve<'a>(document: &'a Value, mut node: &'a Value) -> &'a Value {
+    // Bounded so that ref cycles end
+    for _ in 0..16 {
+        let Some(target) = node
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix('#'))
+            .and_then(|pointer| document.pointer(pointer))
+        else {
+            break;
+        };
+        node = target;
+    }
+    node
+}
+
+fn drift(path: &str, message: impl Into<String>) -> Drift {
+    Drift {
+        path: path.to_string(),
+        message: message.into(),
+    }
+}
+
+/// Whether `value` is of the OpenAPI `openapi_type`; types the IR adds
+/// (`any`, external types) take anything
+fn is_type(openapi_type: &str, value: &Value) -> bool {
+    match openapi_type {
+        "string" => value.is_string(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        _ => true,
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// `$.name`, or `$["content-type"]` for keys that aren't identifiers
+fn member_path(path: &str, key: &str) -> String {
+    let identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match identifier {
+        true => format!("{}.{}", path, key),
+        false => format!("{}[{}]", path, Value::String(key.to_string())),
+    }
+}
+
+/// `application/json` of `application/json; charset=utf-8`
+fn essence(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Whether the `served` media type is the declared `media_type`, which may be
+/// a range such as `application/*`
+fn media_matches(media_type: &str, served: &str) -> bool {
+    match media_type.strip_suffix("/*") {
+        Some(kind) => kind == "*" || served.split('/').next() == Some(kind),
+        None => media_type == served,
+    }
+}
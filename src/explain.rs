@@ -0,0 +1,318 @@
+//! Where a schema or operation ends up in each generation's output, for
+//! debugging surprising mappings without reading whole files
+//! (`generator explain`).
+//!
+//! Declarations are found by the names the generators give the node
+//! (`User`, `UserSchema`, `getUser`, `GetUser`): a line declaring one at any
+//! indentation, with the lines nested under it and its closing bracket.
+
+use crate::config::GenerationConfig;
+use crate::generators::naming::{self, Language};
+use crate::generators::{self, GeneratorRegistry};
+use crate::parsers::{OperationDefinition, SchemaDefinition, SchemaIR};
+use anyhow::Result;
+use std::fmt::Write;
+
+const LANGUAGES: [Language; 3] = [Language::TypeScript, Language::Python, Language::Go];
+
+/// Keywords a declaration line may start with
+const DECLARATION_KEYWORDS: &[&str] = &[
+    "export",
+    "declare",
+    "const",
+    "let",
+    "type",
+    "interface",
+    "class",
+    "enum",
+    "def",
+    "async",
+    "func",
+    "function",
+];
+
+#[derive(Debug, Clone, Copy)]
+pub enum Node<'a> {
+    Schema(&'a SchemaDefinition),
+    Operation(&'a OperationDefinition),
+}
+
+impl<'a> Node<'a> {
+    /// The schema or, failing that, the operation with the IR name `name`
+    pub fn find(schema_ir: &'a SchemaIR, name: &str) -> Option<Self> {
+        schema_ir
+            .schemas
+            .iter()
+            .find(|schema| schema.name == name)
+            .map(Node::Schema)
+            .or_else(|| {
+                schema_ir
+                    .operations
+                    .iter()
+                    .find(|op| op.id == name)
+                    .map(Node::Operation)
+            })
+    }
+
+    /// The same node in another IR of the spec, e.g. one prepared for a generation
+    pub fn find_in<'b>(&self, schema_ir: &'b SchemaIR) -> Option<Node<'b>> {
+        match self {
+            Node::Schema(schema) => schema_ir
+                .schemas
+                .iter()
+                .find(|s| s.name == schema.name)
+                .map(Node::Schema),
+            Node::Operation(op) => schema_ir
+                .operations
+                .iter()
+                .find(|o| o.id == op.id)
+                .map(Node::Operation),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Node::Schema(_) => "schema",
+            Node::Operation(_) => "operation",
+        }
+    }
+
+    /// The IR node as JSON
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Node::Schema(schema) => serde_json::to_value(schema),
+            Node::Operation(op) => serde_json::to_value(op),
+        }
+        .unwrap_or_default()
+    }
+
+    /// Names the generators may declare the node under
+    pub fn identifiers(&self) -> Vec<String> {
+        let mut identifiers = Vec::new();
+        for language in LANGUAGES {
+            let names = match self {
+                Node::Schema(schema) => {
+                    let name = naming::type_name(&schema.name, language);
+                    vec![format!("{}Schema", name), name]
+                }
+                Node::Operation(op) => vec![naming::function_name(&op.id, language)],
+            };
+            for name in names {
+                if !identifiers.contains(&name) {
+                    identifiers.push(name);
+                }
+            }
+        }
+        identifiers
+    }
+}
+
+/// What `generator explain` prints for `name`: its IR node, then the
+/// declarations each of `generations` emits for it. A generation that fails
+/// is reported in its place
+pub fn report(
+    schema_ir: &SchemaIR,
+    name: &str,
+    generations: &[GenerationConfig],
+    registry: &GeneratorRegistry,
+) -> Result<String> {
+    let node = Node::find(schema_ir, name)
+        .ok_or_else(|| anyhow::anyhow!("No schema or operation named '{}' in the spec", name))?;
+    let mut report = format!(
+        "🔎 IR {} `{}`:\n{}\n",
+        node.kind(),
+        name,
+        serde_json::to_string_pretty(&node.to_json())?
+    );
+    if generations.is_empty() {
+        anyhow::bail!("No generations enabled in the config; pick a generator with --generator");
+    }
+
+    for gen_config in generations {
+        let _ = writeln!(
+            report,
+            "\n🔧 {} ({}):",
+            gen_config.generator, gen_config.output_file
+        );
+        let rendered = registry
+            .require(&gen_config.generator)
+            .and_then(|generator| {
+                generator.validate_config(gen_config)?;
+                let prepared_ir = generators::prepare(schema_ir, generator, gen_config)?;
+                let Some(prepared) = node.find_in(&prepared_ir) else {
+                    return Ok(None);
+                };
+                let identifiers = prepared.identifiers();
+                let files = generator
+                    .generate_from_ir(&prepared_ir, gen_config)?
+                    .into_files();
+                Ok(Some((identifiers, files)))
+            });
+        match rendered {
+            Ok(None) => report.push_str("   left out by the generation's options\n"),
+            Ok(Some((identifiers, files))) => {
+                let mut found = false;
+                for file in files {
+                    let blocks = declarations(&file.content, &identifiers);
+                    if !blocks.is_empty() {
+                        let _ = writeln!(report, "\n{}:\n{}", file.filename, blocks.join("\n\n"));
+                        found = true;
+                    }
+                }
+                if !found {
+                    let _ = writeln!(
+                        report,
+                        "   no declaration of {} in the output",
+                        identifiers.join(", ")
+                    );
+                }
+            }
+            Err(error) => {
+                let _ = writeln!(report, "   failed: {:#}", error);
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Blocks of `content` declaring one of `identifiers`, in file order
+pub fn declarations(content: &str, identifiers: &[String]) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut blocks = Vec::new();
+    let mut line = 0;
+    while line < lines.len() {
+        if !identifiers
+            .iter()
+            .any(|identifier| declares(lines[line], identifier))
+        {
+            line += 1;
+            continue;
+        }
+        let end = block_end(&lines, line);
+        let start = doc_start(&lines, line);
+        blocks.push(lines[start..end].join("\n"));
+        line = end;
+    }
+    blocks
+}
+
+/// Whether `line` declares `identifier`: it starts with a declaration keyword
+/// and names it before any assignment or member access
+fn declares(line: &str, identifier: &str) -> bool {
+    let trimmed = line.trim_start();
+    let keyword = trimmed
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or("");
+    if !DECLARATION_KEYWORDS.contains(&keyword) {
+        return false;
+    }
+    let mut search = 0;
+    while let Some(found) = trimmed[search..].find(identifier) {
+        let start = search + found;
+        let end = start + identifier.len();
+        let before = trimmed[..start].chars().next_back();
+        let after = trimmed[end..].chars().next();
+        let boundary =
+            |c: Option<char>| c.is_none_or(|c| !(c.is_alphanumeric() || c == '_' || c == '$'));
+        if boundary(before) && boundary(after) {
+            return !trimmed[..start].contains(['=', '.']);
+        }
+        search = end;
+    }
+    false
+}
+
+/// End (exclusive) of the block declared on `start`: the lines indented
+/// deeper, through the closing bracket at the declaration's indentation
+fn block_end(lines: &[&str], start: usize) -> usize {
+    let indent = indentation(lines[start]);
+    let mut end = start + 1;
+    while end < lines.len() {
+        let line = lines[end];
+        if line.trim().is_empty() || indentation(line) > indent {
+            end += 1;
+            continue;
+        }
+        let trimmed = line.trim();
+        if trimmed.starts_with(['}', ')', ']']) {
+            end += 1;
+            // `): Promise<User> {` and `) -> User:` close the parameters and open the body
+            if trimmed.ends_with(['{', '(', '[', ':']) {
+                continue;
+            }
+        }
+        break;
+    }
+    while end > start + 1 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    end
+}
+
+/// First line of the comments directly above `line`
+fn doc_start(lines: &[&str], line: usize) -> usize {
+    let mut start = line;
+    while start > 0 {
+        let above = lines[start - 1].trim_start();
+        if !(above.starts_with("//")
+            || above.starts_with("/*")
+            || above.starts_with('*')
+            || above.starts_with('#'))
+        {
+            break;
+        }
+        start -= 1;
+    }
+    start
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{InputParser, OpenApiParser};
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      responses: { "200": { description: ok, content: { application/json: { schema: { $ref: "#/components/schemas/User" } } } } }
+components:
+  schemas:
+    User: { type: object, properties: { name: { type: string } } }
+"##;
+
+    fn ir() -> SchemaIR {
+        OpenApiParser
+            .parse_str(SPEC, &std::collections::HashMap::new())
+            .unwrap()
+    }
+
+    #[test]
+    fn report_shows_the_node_and_each_generation_declaring_it() {
+        let generations: Vec<GenerationConfig> =
+            serde_yaml::from_str("[{ generator: golang, outputFile: client.go }]").unwrap();
+        let report = report(&ir(), "User", &generations, &GeneratorRegistry::new()).unwrap();
+        assert!(report.starts_with("🔎 IR schema `User`:\n{"));
+        assert!(report.contains("\n🔧 golang (client.go):\n\nclient.go:\n"));
+        assert!(report.contains("type User struct {"));
+    }
+
+    #[test]
+    fn report_needs_a_known_node_and_a_generation() {
+        let registry = GeneratorRegistry::new();
+        let error = report(&ir(), "Nope", &[], &registry).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("No schema or operation named 'Nope'"));
+        let error = report(&ir(), "listUsers", &[], &registry).unwrap_err();
+        assert!(error.to_string().contains("No generations enabled"));
+    }
+}
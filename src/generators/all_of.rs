@@ -0,0 +1,175 @@
+//! How schemas extending others through `allOf` reach a generator
+//! (`allOfStrategy` generation option):
+//! - `flatten` (default): parent fields are copied into each child, so every
+//!   generator sees plain objects
+//! - `inherit`: parents stay on the schema and are ordered before their
+//!   children, for generators that emit `extends`, subclasses, or struct
+//!   embedding ([`Generator::supports_inheritance`])
+
+use super::Generator;
+use crate::config::GenerationConfig;
+use crate::parsers::{FieldDefinition, SchemaDefinition, SchemaIR};
+use anyhow::Result;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllOfStrategy {
+    Flatten,
+    Inherit,
+}
+
+impl AllOfStrategy {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("allOfStrategy").map(|v| v.as_str()) {
+            None | Some(Some("flatten")) => Ok(Self::Flatten),
+            Some(Some("inherit")) => Ok(Self::Inherit),
+            _ => anyhow::bail!(
+                "{}: option 'allOfStrategy' must be \"flatten\" or \"inherit\"",
+                config.generator
+            ),
+        }
+    }
+
+    /// The IR as `generator` should see it
+    pub fn prepare<'a>(
+        self,
+        schema_ir: &'a SchemaIR,
+        generator: &dyn Generator,
+        config: &GenerationConfig,
+    ) -> Result<Cow<'a, SchemaIR>> {
+        if self == Self::Inherit && !generator.supports_inheritance(config) {
+            anyhow::bail!(
+                "{}: option 'allOfStrategy: inherit' is not supported by this generator or its options",
+                config.generator
+            );
+        }
+        if schema_ir
+            .schemas
+            .iter()
+            .all(|schema| schema.parents.is_empty())
+        {
+            return Ok(Cow::Borrowed(schema_ir));
+        }
+
+        let mut prepared = schema_ir.clone();
+        prepared.schemas = match self {
+            Self::Flatten => flatten(&schema_ir.schemas),
+            Self::Inherit => parents_first(&schema_ir.schemas),
+        };
+        Ok(Cow::Owned(prepared))
+    }
+}
+
+fn flatten(schemas: &[SchemaDefinition]) -> Vec<SchemaDefinition> {
+    let by_name: HashMap<&str, &SchemaDefinition> = schemas
+        .iter()
+        .map(|schema| (schema.name.as_str(), schema))
+        .collect();
+
+    schemas
+        .iter()
+        .map(|schema| SchemaDefinition {
+            fields: all_fields(schema, &by_name, &mut Vec::new()),
+            parents: Vec::new(),
+            ..schema.clone()
+        })
+        .collect()
+}
+
+/// Fields of `schema` with those it inherits from `schemas`, as `flatten`
+/// gives them
+pub fn flattened_fields(
+    schema: &SchemaDefinition,
+    schemas: &[SchemaDefinition],
+) -> Vec<FieldDefinition> {
+    let by_name: HashMap<&str, &SchemaDefinition> = schemas
+        .iter()
+        .map(|schema| (schema.name.as_str(), schema))
+        .collect();
+    all_fields(schema, &by_name, &mut Vec::new())
+}
+
+/// Inherited fields first, overridden in place by the schema's own
+fn all_fields<'a>(
+    schema: &'a SchemaDefinition,
+    by_name: &HashMap<&str, &'a SchemaDefinition>,
+    visiting: &mut Vec<&'a str>,
+) -> Vec<FieldDefinition> {
+    visiting.push(&schema.name);
+    let mut fields = Vec::new();
+    for parent in &schema.parents {
+        match by_name.get(parent.as_str()) {
+            Some(parent) if !visiting.contains(&parent.name.as_str()) => {
+                merge(&mut fields, all_fields(parent, by_name, visiting));
+            }
+            _ => {}
+        }
+    }
+    merge(&mut fields, schema.fields.clone());
+    visiting.pop();
+    fields
+}
+
+/// `allOf` intersects its members, so a field required anywhere stays required
+fn merge(fields: &mut Vec<FieldDefinition>, overrides: Vec<FieldDefinition>) {
+    for mut field in overrides {
+        match fields.iter_mut().find(|f| f.name == field.name) {
+            Some(existing) => {
+                field.required |= existing.required;
+                *existing = field;
+            }
+            None => fields.push(field),
+        }
+    }
+}
+
+/// Schemas reordered so parents precede children, dropping unknown and cyclic parents
+fn parents_first(schemas: &[SchemaDefinition]) -> Vec<SchemaDefinition> {
+    let index: HashMap<&str, usize> = schemas
+        .iter()
+        .enumerate()
+        .map(|(i, schema)| (schema.name.as_str(), i))
+        .collect();
+
+    let mut ordered = Vec::with_capacity(schemas.len());
+    let mut state = vec![Visit::Pending; schemas.len()];
+    for i in 0..schemas.len() {
+        visit(i, schemas, &index, &mut state, &mut ordered);
+    }
+    ordered
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    Pending,
+    InProgress,
+    Done,
+}
+
+fn visit(
+    i: usize,
+    schemas: &[SchemaDefinition],
+    index: &HashMap<&str, usize>,
+    state: &mut [Visit],
+    ordered: &mut Vec<SchemaDefinition>,
+) {
+    if state[i] != Visit::Pending {
+        return;
+    }
+    state[i] = Visit::InProgress;
+
+    let mut schema = schemas[i].clone();
+    schema
+        .parents
+        .retain(|parent| match index.get(parent.as_str()) {
+            Some(&p) if state[p] != Visit::InProgress => {
+                visit(p, schemas, index, state, ordered);
+                true
+            }
+            _ => false,
+        });
+
+    state[i] = Visit::Done;
+    ordered.push(schema);
+}
@@ -0,0 +1,202 @@
+//! Schema fields a generation emits as `any` (`any`, `Any`, `interface{}`),
+//! counted so type erosion does not go unnoticed:
+//! - `reportAny: true` lists them while generating
+//! - `maxAnyTypes: N` fails the generation when there are more than `N`
+//!
+//! Each generator reports its own fallbacks ([`Generator::any_fields`]). A
+//! field counts when its type, or an array item, map value, or union member
+//! of it, has no type the generator can express: schemas without a `type`,
+//! unions for generators without union types, refs to `x-codegen-ignore`
+//! schemas, and maps with typed values (`additionalProperties: {type: ...}`),
+//! which come out as untyped maps. Free-form objects (`type: object` without
+//! properties) are deliberate and do not count. Generators whose runtime
+//! schemas take some parameters as `any` report those as
+//! `operationId.parameter`.
+//!
+//! [`Generator::any_fields`]: super::Generator::any_fields
+
+use crate::config::GenerationConfig;
+use crate::parsers::{Parameter, SchemaIR, TypeInfo};
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnyTypes {
+    pub report: bool,
+    max: Option<u64>,
+}
+
+impl AnyTypes {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        let max = match config.options.get("maxAnyTypes") {
+            None => None,
+            Some(value) => Some(value.as_u64().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "{}: option 'maxAnyTypes' must be a non-negative integer",
+                    config.generator
+                )
+            })?),
+        };
+        let report = config
+            .options
+            .get("reportAny")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        Ok(Self { report, max })
+    }
+
+    /// Fail when `fields` are more than `maxAnyTypes` allows
+    pub fn enforce(self, config: &GenerationConfig, fields: &[String]) -> Result<()> {
+        let Some(max) = self.max.filter(|max| fields.len() as u64 > *max) else {
+            return Ok(());
+        };
+        anyhow::bail!(
+            "'{}' emits {} field(s) as `any`, more than maxAnyTypes: {} allows:\n{}",
+            config.generator,
+            fields.len(),
+            max,
+            fields
+                .iter()
+                .map(|field| format!("  - {}", field))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+}
+
+/// `Schema.field` of every field emitted as `any`, in spec order; `unions`
+/// says whether the generator has union types
+pub fn any_fields(schema_ir: &SchemaIR, unions: bool) -> Vec<String> {
+    schema_ir
+        .schemas
+        .iter()
+        .flat_map(|schema| {
+            schema
+                .fields
+                .iter()
+                .filter(|field| degrades(&field.type_info, unions) || typed_map(&field.original))
+                .map(move |field| format!("{}.{}", schema.name, field.name))
+        })
+        .collect()
+}
+
+/// `operationId.parameter` of every parameter `degrades` says a generator
+/// takes as `any`, in spec order
+pub fn any_parameters(schema_ir: &SchemaIR, degrades: impl Fn(&Parameter) -> bool) -> Vec<String> {
+    schema_ir
+        .operations
+        .iter()
+        .flat_map(|op| {
+            op.parameters
+                .iter()
+                .filter(|param| degrades(param))
+                .map(move |param| format!("{}.{}", op.id, param.name))
+        })
+        .collect()
+}
+
+/// Whether `schema`, or its array items, is a map whose typed values the IR
+/// does not carry
+fn typed_map(schema: &JsonValue) -> bool {
+    let values = schema.get("additionalProperties");
+    values.is_some_and(|values| values.is_object() && values.get("type").is_some())
+        || schema.get("items").is_some_and(typed_map)
+}
+
+fn degrades(type_info: &TypeInfo, unions: bool) -> bool {
+    if type_info.reference.is_some() || type_info.enum_values.is_some() {
+        return false;
+    }
+    match type_info.openapi_type.as_str() {
+        "any" => true,
+        "external" => type_info.format.is_none(),
+        "union" => {
+            !unions
+                || type_info
+                    .union_members
+                    .iter()
+                    .any(|member| degrades(member, unions))
+        }
+        _ if type_info.is_array => type_info
+            .array_item_type
+            .as_deref()
+            .is_none_or(|item| degrades(item, unions)),
+        _ => type_info
+            .map_values
+            .as_deref()
+            .is_some_and(|values| degrades(values, unions)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::{golang::GolangGenerator, Generator, TypeScriptGenerator};
+    use crate::parsers::{InputParser, OpenApiParser};
+    use std::collections::HashMap;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      parameters:
+        - { name: include, in: query, schema: { type: array, items: { type: string } } }
+        - { name: page, in: query, schema: { type: integer } }
+      responses: { "200": { description: ok } }
+components:
+  schemas:
+    User:
+      type: object
+      properties:
+        labels: { type: object, additionalProperties: { type: string } }
+        history: { type: array, items: { type: object, additionalProperties: { type: integer } } }
+        free: { type: object }
+        blob: {}
+        tags: { type: array, items: { type: string } }
+"##;
+
+    fn ir() -> SchemaIR {
+        OpenApiParser.parse_str(SPEC, &HashMap::new()).unwrap()
+    }
+
+    #[test]
+    fn typed_maps_and_untyped_fields_are_reported() {
+        assert_eq!(
+            GolangGenerator.any_fields(&ir()),
+            ["User.labels", "User.history", "User.blob"]
+        );
+    }
+
+    #[test]
+    fn typescript_reports_array_parameters_of_its_operation_schemas() {
+        let fields = TypeScriptGenerator.any_fields(&ir());
+        assert!(!fields.is_empty());
+        assert_eq!(
+            fields,
+            [
+                "User.labels",
+                "User.history",
+                "User.blob",
+                "listUsers.include"
+            ]
+        );
+    }
+
+    #[test]
+    fn max_any_types_fails_past_the_cap() {
+        let config: GenerationConfig = serde_yaml::from_str(
+            "{ generator: golang, outputFile: c.go, options: { maxAnyTypes: 2 } }",
+        )
+        .unwrap();
+        let fields = GolangGenerator.any_fields(&ir());
+        let error = AnyTypes::from_config(&config)
+            .unwrap()
+            .enforce(&config, &fields)
+            .unwrap_err();
+        assert!(error.to_string().contains("emits 3 field(s)"));
+        assert!(error.to_string().contains("  - User.labels"));
+    }
+}
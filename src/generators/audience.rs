@@ -0,0 +1,341 @@
+//! Output for one audience (`audience` generation option): operations, schemas,
+//! and fields the spec marks for others (`x-audience`) are left out, so
+//! several generations of one spec give a public, a partner, and an internal
+//! client.
+//!
+//! Audiences are tiers, ordered by `audienceTiers` (default `public`,
+//! `partner`, `internal`): each sees what is marked for it or for a tier
+//! before it, and everything unmarked. Fields of a left-out schema's type, and
+//! what only left-out operations used, go with it; bodies of such a schema
+//! are left untyped. allOf members that are left out are no longer inherited.
+
+use crate::config::GenerationConfig;
+use crate::parsers::{OperationDefinition, SchemaIR, TypeInfo};
+use anyhow::{Context, Result};
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+const DEFAULT_TIERS: [&str; 3] = ["public", "partner", "internal"];
+
+#[derive(Debug, Clone, Default)]
+pub struct Audience {
+    /// Audiences seen, the selected one last; `None` keeps everything
+    visible: Option<Vec<String>>,
+}
+
+impl Audience {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        let tiers: Vec<String> = match config.options.get("audienceTiers") {
+            None => DEFAULT_TIERS.iter().map(|tier| tier.to_string()).collect(),
+            Some(value) => value
+                .as_sequence()
+                .and_then(|tiers| {
+                    tiers
+                        .iter()
+                        .map(|tier| tier.as_str().map(str::to_string))
+                        .collect()
+                })
+                .with_context(|| {
+                    format!(
+                        "{}: option 'audienceTiers' must be a list of audience names",
+                        config.generator
+                    )
+                })?,
+        };
+        let Some(selected) = config.options.get("audience") else {
+            return Ok(Self::default());
+        };
+        let position = selected
+            .as_str()
+            .and_then(|selected| tiers.iter().position(|tier| tier == selected));
+        let Some(position) = position else {
+            anyhow::bail!(
+                "{}: option 'audience' must be one of {}",
+                config.generator,
+                tiers
+                    .iter()
+                    .map(|tier| format!("\"{}\"", tier))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        };
+        Ok(Self {
+            visible: Some(tiers[..=position].to_vec()),
+        })
+    }
+
+    fn sees(&self, audiences: &[String]) -> bool {
+        match &self.visible {
+            None => true,
+            Some(visible) => {
+                audiences.is_empty() || audiences.iter().any(|audience| visible.contains(audience))
+            }
+        }
+    }
+
+    /// The IR as this audience sees it
+    pub fn select(self, schema_ir: &SchemaIR) -> Cow<'_, SchemaIR> {
+        let hides_any = schema_ir
+            .operations
+            .iter()
+            .any(|op| !self.sees(&op.audiences))
+            || schema_ir.schemas.iter().any(|schema| {
+                !self.sees(&schema.audiences)
+                    || schema
+                        .fields
+                        .iter()
+                        .any(|field| !self.sees(&field.audiences))
+            });
+        if !hides_any {
+            return Cow::Borrowed(schema_ir);
+        }
+
+        // Schemas operations use are kept only as long as a kept operation does
+        let used_by_operations = reachable(schema_ir, body_references(&schema_ir.operations));
+
+        let mut prepared = schema_ir.clone();
+        let hidden: HashSet<String> = prepared
+            .schemas
+            .iter()
+            .filter(|schema| !self.sees(&schema.audiences))
+            .map(|schema| schema.name.clone())
+            .collect();
+        prepared.operations.retain(|op| self.sees(&op.audiences));
+        prepared
+            .schemas
+            .retain(|schema| !hidden.contains(&schema.name));
+        for schema in &mut prepared.schemas {
+            schema.fields.retain(|field| {
+                self.sees(&field.audiences) && !references_any(&field.type_info, &hidden)
+            });
+            schema.parents.retain(|parent| !hidden.contains(parent));
+        }
+        for op in &mut prepared.operations {
+            for body in [&mut op.request_body, &mut op.response] {
+                if body
+                    .as_ref()
+                    .is_some_and(|body| hidden.contains(&body.name))
+                {
+                    *body = None;
+                }
+            }
+            for response in &mut op.responses {
+                if response
+                    .schema
+                    .as_ref()
+                    .is_some_and(|body| hidden.contains(&body.name))
+                {
+                    response.schema = None;
+                }
+                if response
+                    .inline_type
+                    .as_ref()
+                    .is_some_and(|type_info| references_any(type_info, &hidden))
+                {
+                    response.inline_type = None;
+                }
+            }
+        }
+        for channel in &mut prepared.channels {
+            for message in [&mut channel.send, &mut channel.receive] {
+                if message.as_ref().is_some_and(|name| hidden.contains(name)) {
+                    *message = None;
+                }
+            }
+        }
+
+        let mut roots = body_references(&prepared.operations);
+        roots.extend(
+            prepared
+                .channels
+                .iter()
+                .flat_map(|c| [&c.send, &c.receive])
+                .flatten()
+                .cloned(),
+        );
+        roots.extend(
+            prepared
+                .schemas
+                .iter()
+                .filter(|schema| !used_by_operations.contains(&schema.name))
+                .map(|schema| schema.name.clone()),
+        );
+        let kept = reachable(&prepared, roots);
+        prepared
+            .schemas
+            .retain(|schema| kept.contains(&schema.name));
+        Cow::Owned(prepared)
+    }
+}
+
+/// `roots` and the schemas they refer to, through fields and parents
+fn reachable(schema_ir: &SchemaIR, mut pending: Vec<String>) -> HashSet<String> {
+    let mut reached = HashSet::new();
+    while let Some(name) = pending.pop() {
+        if !reached.insert(name.clone()) {
+            continue;
+        }
+        let Some(schema) = schema_ir.schemas.iter().find(|schema| schema.name == name) else {
+            continue;
+        };
+        pending.extend(schema.parents.iter().cloned());
+        for field in &schema.fields {
+            collect_references(&field.type_info, &mut pending);
+        }
+    }
+    reached
+}
+
+/// Schemas the request and response bodies of `operations` name
+fn body_references(operations: &[OperationDefinition]) -> Vec<String> {
+    let mut references = Vec::new();
+    for op in operations {
+        references.extend(
+            [&op.request_body, &op.response]
+                .into_iter()
+                .flatten()
+                .map(|body| body.name.clone()),
+        );
+        for response in &op.responses {
+            references.extend(response.schema.iter().map(|body| body.name.clone()));
+            if let Some(type_info) = &response.inline_type {
+                collect_references(type_info, &mut references);
+            }
+        }
+    }
+    references
+}
+
+fn collect_references(type_info: &TypeInfo, references: &mut Vec<String>) {
+    references.extend(type_info.reference.clone());
+    for item in type_info
+        .array_item_type
+        .iter()
+        .chain(&type_info.map_values)
+    {
+        collect_references(item, references);
+    }
+    for member in type_info.union_members.iter().chain(&type_info.tuple_items) {
+        collect_references(member, references);
+    }
+}
+
+fn references_any(type_info: &TypeInfo, names: &HashSet<String>) -> bool {
+    let mut references = Vec::new();
+    collect_references(type_info, &mut references);
+    references.iter().any(|reference| names.contains(reference))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{InputParser, OpenApiParser};
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      responses: { "200": { description: ok, content: { application/json: { schema: { $ref: "#/components/schemas/User" } } } } }
+  /reports:
+    get:
+      operationId: listReports
+      x-audience: internal
+      responses: { "200": { description: ok, content: { application/json: { schema: { $ref: "#/components/schemas/Report" } } } } }
+components:
+  schemas:
+    User:
+      type: object
+      properties:
+        name: { type: string }
+        notes: { type: string, x-audience: partner }
+        secret: { $ref: "#/components/schemas/Secret" }
+    Secret:
+      type: object
+      x-audience: internal
+      properties: { key: { type: string } }
+    Report:
+      type: object
+      properties:
+        author: { $ref: "#/components/schemas/Author" }
+    Author:
+      type: object
+      properties: { name: { type: string } }
+    Event:
+      type: object
+      properties: { kind: { type: string } }
+"##;
+
+    fn ir() -> SchemaIR {
+        OpenApiParser.parse_str(SPEC, &Default::default()).unwrap()
+    }
+
+    fn audience(options: &str) -> Result<Audience> {
+        let config: GenerationConfig = serde_yaml::from_str(&format!(
+            "{{ generator: typescript, outputFile: client.ts, options: {} }}",
+            options
+        ))
+        .unwrap();
+        Audience::from_config(&config)
+    }
+
+    /// Operation ids, schema names, and `User`'s fields the audience sees
+    fn seen(options: &str) -> (Vec<String>, Vec<String>, Vec<String>) {
+        let ir = ir();
+        let selected = audience(options).unwrap().select(&ir);
+        let user = selected.schemas.iter().find(|s| s.name == "User").unwrap();
+        (
+            selected.operations.iter().map(|op| op.id.clone()).collect(),
+            selected.schemas.iter().map(|s| s.name.clone()).collect(),
+            user.fields.iter().map(|f| f.name.clone()).collect(),
+        )
+    }
+
+    #[test]
+    fn without_an_audience_everything_is_kept() {
+        let ir = ir();
+        assert!(matches!(
+            audience("{}").unwrap().select(&ir),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn operations_and_what_only_they_use_are_left_out() {
+        let (operations, schemas, fields) = seen("{ audience: public }");
+        assert_eq!(operations, ["listUsers"]);
+        // Report and Author only served listReports; Event no operation used
+        assert_eq!(schemas, ["User", "Event"]);
+        assert_eq!(fields, ["name"]);
+    }
+
+    #[test]
+    fn each_tier_sees_the_tiers_before_it() {
+        let (operations, schemas, fields) = seen("{ audience: partner }");
+        assert_eq!(operations, ["listUsers"]);
+        assert_eq!(schemas, ["User", "Event"]);
+        assert_eq!(fields, ["name", "notes"]);
+
+        let (operations, schemas, fields) = seen("{ audience: internal }");
+        assert_eq!(operations, ["listUsers", "listReports"]);
+        assert_eq!(schemas, ["User", "Secret", "Report", "Author", "Event"]);
+        assert_eq!(fields, ["name", "notes", "secret"]);
+    }
+
+    #[test]
+    fn tiers_can_be_configured() {
+        let (_, _, fields) = seen("{ audience: partner, audienceTiers: [partner, public] }");
+        assert_eq!(fields, ["name", "notes"]);
+        let (_, _, fields) = seen("{ audience: staff, audienceTiers: [public, staff] }");
+        assert_eq!(fields, ["name"]);
+
+        let error = audience("{ audience: staff }").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "typescript: option 'audience' must be one of \"public\", \"partner\", \"internal\""
+        );
+        assert!(audience("{ audienceTiers: public }").is_err());
+    }
+}
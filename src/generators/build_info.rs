@@ -0,0 +1,110 @@
+//! The generator version and the spec and config hashes a generation was
+//! built from, embedded in its files (`buildInfo` option) so services can
+//! report which contract they were built against. The hashes are the
+//! manifest's `spec.sha256` and `config_sha256`; nothing is collected or sent.
+//! - `comment` opens every file with a `generator-build:` comment holding
+//!   the fields as JSON
+//! - `const` also exports them from the main file: `BUILD_INFO` in
+//!   TypeScript and Python, `BuildInfo` in Go
+//!
+//! Files of a language without comments (JSON) are left as generated.
+
+use super::GeneratedOutput;
+use crate::config::GenerationConfig;
+use anyhow::Result;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BuildInfo {
+    #[default]
+    Off,
+    Comment,
+    Const,
+}
+
+/// What a generation was built from
+#[derive(Debug, Clone)]
+pub struct Build<'a> {
+    pub spec_sha256: &'a str,
+    pub config_sha256: &'a str,
+}
+
+impl BuildInfo {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("buildInfo").map(|v| v.as_str()) {
+            None => Ok(Self::Off),
+            Some(Some("comment")) => Ok(Self::Comment),
+            Some(Some("const")) => Ok(Self::Const),
+            _ => anyhow::bail!(
+                "{}: option 'buildInfo' must be \"comment\" or \"const\"",
+                config.generator
+            ),
+        }
+    }
+
+    /// `output` with `build` embedded in its files
+    pub fn apply(self, mut output: GeneratedOutput, build: &Build) -> GeneratedOutput {
+        if self == Self::Off {
+            return output;
+        }
+        output.content = stamp(&output.filename, &output.content, build);
+        if let Some(constant) = (self == Self::Const)
+            .then(|| constant(&output.filename, build))
+            .flatten()
+        {
+            output.content = format!("{}\n\n{}\n", output.content.trim_end(), constant);
+        }
+        for companion in &mut output.companions {
+            companion.content = stamp(&companion.filename, &companion.content, build);
+        }
+        output
+    }
+}
+
+/// `content` opened with the build comment, when `filename`'s language has comments
+fn stamp(filename: &str, content: &str, build: &Build) -> String {
+    let json = serde_json::json!({
+        "generator_version": env!("CARGO_PKG_VERSION"),
+        "spec_sha256": build.spec_sha256,
+        "config_sha256": build.config_sha256,
+    });
+    let line = match extension(filename) {
+        "ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs" | "go" | "rs" | "java"
+        | "kt" | "swift" | "dart" | "cs" => format!("// generator-build: {}", json),
+        "py" | "pyi" | "yaml" | "yml" | "toml" | "sh" | "rb" => {
+            format!("# generator-build: {}", json)
+        }
+        "md" | "html" => format!("<!-- generator-build: {} -->", json),
+        _ => return content.to_string(),
+    };
+    format!("{}\n{}", line, content)
+}
+
+/// The exported build info declaration for `filename`'s language, if it has one
+fn constant(filename: &str, build: &Build) -> Option<String> {
+    let version = env!("CARGO_PKG_VERSION");
+    if filename.ends_with(".d.ts") {
+        return None;
+    }
+    match extension(filename) {
+        "ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs" => Some(format!(
+            "export const BUILD_INFO = {{\n  generatorVersion: \"{}\",\n  specSha256: \"{}\",\n  configSha256: \"{}\",\n}} as const;",
+            version, build.spec_sha256, build.config_sha256
+        )),
+        // Two blank lines before it, as between Python's top-level definitions
+        "py" => Some(format!(
+            "\nBUILD_INFO = {{\n    \"generator_version\": \"{}\",\n    \"spec_sha256\": \"{}\",\n    \"config_sha256\": \"{}\",\n}}",
+            version, build.spec_sha256, build.config_sha256
+        )),
+        "go" => Some(format!(
+            "// BuildInfo - the generator version and the spec and config hashes this client was built from\nvar BuildInfo = struct {{\n\tGeneratorVersion string\n\tSpecSHA256       string\n\tConfigSHA256     string\n}}{{\n\tGeneratorVersion: \"{}\",\n\tSpecSHA256:       \"{}\",\n\tConfigSHA256:     \"{}\",\n}}",
+            version, build.spec_sha256, build.config_sha256
+        )),
+        _ => None,
+    }
+}
+
+fn extension(filename: &str) -> &str {
+    filename
+        .rsplit_once('.')
+        .map_or("", |(_, extension)| extension)
+}
@@ -0,0 +1,367 @@
+use super::{
+    AllOfStrategy, Audience, GeneratedOutput, Generator, Hypermedia, PatchBodies, PathPrefixMode,
+    TypeOverrides,
+};
+use crate::config::GenerationConfig;
+use crate::parsers::ir_diff::{self, Change, ChangeKind, ChangeTarget, IrDiff, SemverBump};
+use crate::parsers::{resolve_name_collisions, ParserRegistry, SchemaIR};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// Renders the operations and schema fields added, changed, or removed since an
+/// earlier version of the spec as a Markdown changelog section for client
+/// release notes.
+///
+/// `previousSpec` names the earlier spec file; with `previousRevision` it is
+/// read from that git revision instead (`git show <rev>:./<previousSpec>`).
+/// The earlier spec is parsed with the same parser options as the current one.
+///
+/// The section opens with the semver bump the changes call for, applied to
+/// `packageVersion` (the client's released version) when given; `format: json`
+/// emits the same as data for release tooling that versions the packages.
+pub struct ChangelogGenerator;
+
+impl Generator for ChangelogGenerator {
+    fn name(&self) -> &str {
+        "changelog"
+    }
+
+    fn file_extension(&self) -> &str {
+        "md"
+    }
+
+    fn generate_from_ir(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<GeneratedOutput> {
+        let previous = load_previous(schema_ir, config)?;
+        // Both sides see the IR the same way
+        let previous = Audience::from_config(config)?.select(&previous);
+        let previous = AllOfStrategy::from_config(config)?.prepare(&previous, self, config)?;
+        let previous = PathPrefixMode::from_config(config)?.apply(previous);
+        let previous = Hypermedia::from_config(config)?.apply(previous);
+        let previous = PatchBodies::from_config(config).apply(previous);
+        let previous = TypeOverrides::from_config(config)?.apply(previous);
+
+        let changes = ir_diff::diff(&previous, schema_ir);
+        let release = Release::new(&changes, config);
+        let content = match config.options.get("format").and_then(|v| v.as_str()) {
+            None | Some("markdown") => render_markdown(&previous, schema_ir, &changes, &release),
+            Some(_) => render_json(&previous, schema_ir, &changes, &release)?,
+        };
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content,
+            metadata: HashMap::new(),
+            companions: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        match config.options.get("previousSpec").map(|v| v.as_str()) {
+            Some(Some(_)) => {}
+            _ => anyhow::bail!("changelog: option 'previousSpec' must name the earlier spec file"),
+        }
+        if let Some(revision) = config.options.get("previousRevision") {
+            if revision.as_str().is_none() {
+                anyhow::bail!("changelog: option 'previousRevision' must be a git revision");
+            }
+        }
+        if let Some(version) = config.options.get("packageVersion") {
+            if version
+                .as_str()
+                .and_then(|v| SemverBump::Patch.apply(v))
+                .is_none()
+            {
+                anyhow::bail!(
+                    "changelog: option 'packageVersion' must be a major.minor.patch version"
+                );
+            }
+        }
+        match config.options.get("format").map(|v| v.as_str()) {
+            None | Some(Some("markdown")) | Some(Some("json")) => Ok(()),
+            _ => anyhow::bail!("changelog: option 'format' must be \"markdown\" or \"json\""),
+        }
+    }
+}
+
+fn load_previous(schema_ir: &SchemaIR, config: &GenerationConfig) -> Result<SchemaIR> {
+    let spec = Path::new(
+        config
+            .options
+            .get("previousSpec")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default(),
+    );
+    let registry = ParserRegistry::new();
+    let parser = registry.get(&schema_ir.original.format).ok_or_else(|| {
+        anyhow::anyhow!(
+            "changelog: no parser for format '{}'",
+            schema_ir.original.format
+        )
+    })?;
+    let options = &schema_ir.original.parser_options;
+
+    let mut previous = match config
+        .options
+        .get("previousRevision")
+        .and_then(|v| v.as_str())
+    {
+        Some(revision) => {
+            let content = git_show(revision, spec)?;
+            parser
+                .parse_str(&content, options)
+                .with_context(|| format!("changelog: failed to parse {:?} at {}", spec, revision))?
+        }
+        None => parser
+            .parse(spec, options)
+            .with_context(|| format!("changelog: failed to parse {:?}", spec))?,
+    };
+    resolve_name_collisions(&mut previous);
+    Ok(previous)
+}
+
+/// `spec` as of `revision`, relative to the working directory
+fn git_show(revision: &str, spec: &Path) -> Result<String> {
+    let spec = spec.to_string_lossy();
+    let object = if spec.starts_with("./") || spec.starts_with("../") {
+        format!("{}:{}", revision, spec)
+    } else {
+        format!("{}:./{}", revision, spec)
+    };
+    let output = Command::new("git")
+        .args(["show", &object])
+        .output()
+        .context("changelog: failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "changelog: git show {} failed: {}",
+            object,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).with_context(|| format!("changelog: {} is not UTF-8", object))
+}
+
+/// Suggested bump and, with `packageVersion`, the version it leads to
+struct Release {
+    bump: SemverBump,
+    current: Option<String>,
+    next: Option<String>,
+}
+
+impl Release {
+    fn new(changes: &IrDiff, config: &GenerationConfig) -> Self {
+        let bump = changes.suggested_bump();
+        let current = config
+            .options
+            .get("packageVersion")
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string());
+        let next = current.as_deref().and_then(|v| bump.apply(v));
+        Self {
+            bump,
+            current,
+            next,
+        }
+    }
+}
+
+fn render_json(
+    previous: &SchemaIR,
+    current: &SchemaIR,
+    changes: &IrDiff,
+    release: &Release,
+) -> Result<String> {
+    let data = serde_json::json!({
+        "from": previous.metadata.version,
+        "to": current.metadata.version,
+        "bump": release.bump,
+        "package_version": release.current,
+        "next_version": release.next,
+        "changes": changes.changes,
+    });
+    Ok(serde_json::to_string_pretty(&data)? + "\n")
+}
+
+fn render_markdown(
+    previous: &SchemaIR,
+    current: &SchemaIR,
+    changes: &IrDiff,
+    release: &Release,
+) -> String {
+    let mut output = format!("## {}\n\n", current.metadata.version);
+    if previous.metadata.version == current.metadata.version {
+        output.push_str(&format!(
+            "Changes to {} since the previous spec.\n",
+            current.metadata.title
+        ));
+    } else {
+        output.push_str(&format!(
+            "Changes to {} since {}.\n",
+            current.metadata.title, previous.metadata.version
+        ));
+    }
+    output.push_str(&format!("\nSuggested release: **{}**", release.bump.name()));
+    if let (Some(current), Some(next)) = (&release.current, &release.next) {
+        output.push_str(&format!(" (`{}` → `{}`)", current, next));
+    }
+    output.push_str(".\n");
+
+    if changes.is_empty() {
+        output.push_str("\nNo API changes.\n");
+        return output;
+    }
+
+    for (kind, heading) in [
+        (ChangeKind::Added, "Added"),
+        (ChangeKind::Changed, "Changed"),
+        (ChangeKind::Removed, "Removed"),
+    ] {
+        let entries: Vec<&Change> = changes.changes.iter().filter(|c| c.kind == kind).collect();
+        if entries.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("\n### {}\n\n", heading));
+        for change in entries {
+            output.push_str(&format!("- {}\n", entry(change)));
+        }
+    }
+
+    output
+}
+
+/// "**Breaking:** `GET /pets/{id}` (`getPet`): response `Pet` → `PetDetail`"
+fn entry(change: &Change) -> String {
+    let mut line = String::new();
+    if change.breaking {
+        line.push_str("**Breaking:** ");
+    }
+    match change.target {
+        ChangeTarget::Operation => {
+            line.push_str(&format!("`{}` (`{}`)", change.subject, change.name))
+        }
+        ChangeTarget::Schema => line.push_str(&format!("Schema `{}`", change.subject)),
+        ChangeTarget::Field => {
+            line.push_str(&format!("Field `{}`", change.subject));
+            if let Some(field_type) = &change.field_type {
+                line.push_str(&format!(" (`{}`)", field_type));
+            }
+        }
+    }
+    if !change.details.is_empty() {
+        line.push_str(&format!(": {}", change.details.join("; ")));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{InputParser, OpenApiParser};
+
+    const PREVIOUS: &str = r##"
+openapi: 3.0.3
+info: { title: Pets, version: 1.0.0 }
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses: { "204": { description: ok } }
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        id: { type: string }
+"##;
+
+    fn changelog(current: &str, options: &str) -> String {
+        let parse = |spec: &str| OpenApiParser.parse_str(spec, &HashMap::new()).unwrap();
+        let (previous, current) = (parse(PREVIOUS), parse(current));
+        let config: GenerationConfig = serde_yaml::from_str(&format!(
+            "{{ generator: changelog, outputFile: CHANGES.md, options: {{ previousSpec: api.yaml, {} }} }}",
+            options
+        ))
+        .unwrap();
+        let changes = ir_diff::diff(&previous, &current);
+        let release = Release::new(&changes, &config);
+        match config.options.get("format").and_then(|v| v.as_str()) {
+            Some("json") => render_json(&previous, &current, &changes, &release).unwrap(),
+            _ => render_markdown(&previous, &current, &changes, &release),
+        }
+    }
+
+    fn current() -> String {
+        PREVIOUS
+            .replace("version: 1.0.0", "version: 1.1.0")
+            .replace(
+                "        id: { type: string }",
+                "        id: { type: integer }\n        tag: { type: string }",
+            )
+    }
+
+    #[test]
+    fn json_entries_name_the_field_and_give_its_type_apart() {
+        let json: serde_json::Value = serde_json::from_str(&changelog(
+            &current(),
+            "format: json, packageVersion: 2.3.1",
+        ))
+        .unwrap();
+        assert_eq!(json["bump"], "major");
+        assert_eq!(json["next_version"], "3.0.0");
+        let entries: Vec<_> = json["changes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| {
+                (
+                    c["subject"].as_str(),
+                    c["name"].as_str(),
+                    c["type"].as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (Some("Pet.id"), Some("id"), Some("integer")),
+                (Some("Pet.tag"), Some("tag"), Some("string")),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_entries_of_operations_and_schemas_have_no_type() {
+        let spec = current()
+            .replace("operationId: listPets", "operationId: getPets")
+            .replace(
+                "components:\n  schemas:\n",
+                "components:\n  schemas:\n    Owner: { type: object }\n",
+            );
+        let json: serde_json::Value =
+            serde_json::from_str(&changelog(&spec, "format: json")).unwrap();
+        let changes = json["changes"].as_array().unwrap();
+        assert_eq!(changes[0]["name"], "getPets");
+        assert_eq!(changes[0]["target"], "operation");
+        assert!(changes[0].get("type").is_none());
+        let owner = changes.iter().find(|c| c["subject"] == "Owner").unwrap();
+        assert_eq!(owner["name"], "Owner");
+        assert!(owner.get("type").is_none());
+    }
+
+    #[test]
+    fn markdown_lists_changes_under_their_kind() {
+        let markdown = changelog(&current(), "packageVersion: 2.3.1");
+        assert_eq!(
+            markdown,
+            "## 1.1.0\n\nChanges to Pets since 1.0.0.\n\nSuggested release: **major** (`2.3.1` → `3.0.0`).\n\n\
+             ### Added\n\n- Field `Pet.tag` (`string`): optional\n\n\
+             ### Changed\n\n- **Breaking:** Field `Pet.id` (`integer`): type `string` → `integer`\n"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Conditional request wrappers for the `typescript`, `python`, and `golang`
+//! clients (`conditionalRequests` option, on by default).
+//!
+//! An operation taking an `If-Match` or `If-None-Match` header, on a path where
+//! a success response declares an `ETag` header, gets a second method
+//! (`updateUserConditional`) that sends an ETag the caller holds in that header
+//! and, instead of failing on 304 and 412, returns which of the three outcomes
+//! it got: the decoded body with the response's new ETag, not modified, or
+//! precondition failed. GET and HEAD fill `If-None-Match` when they take both,
+//! other methods `If-Match`.
+
+use crate::config::GenerationConfig;
+use crate::parsers::{HttpMethod, OperationDefinition, ParameterLocation, SchemaIR};
+use std::collections::HashSet;
+
+pub struct Conditionals<'a> {
+    /// Paths with an operation whose success response declares `ETag`; empty
+    /// when the option is off
+    etag_paths: HashSet<&'a str>,
+}
+
+impl<'a> Conditionals<'a> {
+    pub fn new(schema_ir: &'a SchemaIR, config: &GenerationConfig) -> Self {
+        let enabled = config
+            .options
+            .get("conditionalRequests")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        let etag_paths = schema_ir
+            .operations
+            .iter()
+            .filter(|op| {
+                enabled
+                    && op
+                        .response_headers
+                        .iter()
+                        .any(|h| h.eq_ignore_ascii_case("etag"))
+            })
+            .map(|op| op.path.as_str())
+            .collect();
+        Self { etag_paths }
+    }
+
+    /// Header the conditional wrapper of `operation` fills with the ETag, if it
+    /// gets one
+    pub fn precondition<'o>(&self, operation: &'o OperationDefinition) -> Option<&'o str> {
+        if !self.etag_paths.contains(operation.path.as_str()) {
+            return None;
+        }
+        let header = |name: &str| {
+            operation
+                .parameters
+                .iter()
+                .find(|p| {
+                    p.location == ParameterLocation::Header && p.name.eq_ignore_ascii_case(name)
+                })
+                .map(|p| p.name.as_str())
+        };
+        match operation.method {
+            HttpMethod::Get | HttpMethod::Head => {
+                header("If-None-Match").or_else(|| header("If-Match"))
+            }
+            _ => header("If-Match").or_else(|| header("If-None-Match")),
+        }
+    }
+
+    /// Methods a client emits for `operation`, as templates loop over them:
+    /// the plain one (`None`), then the conditional wrapper and its header
+    pub fn variants<'o>(&self, operation: &'o OperationDefinition) -> Vec<Option<&'o str>> {
+        std::iter::once(None)
+            .chain(self.precondition(operation).map(Some))
+            .collect()
+    }
+
+    /// Whether any operation gets a conditional wrapper
+    pub fn any(&self, schema_ir: &SchemaIR) -> bool {
+        schema_ir
+            .operations
+            .iter()
+            .any(|op| self.precondition(op).is_some())
+    }
+}
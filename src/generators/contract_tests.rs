@@ -0,0 +1,460 @@
+use super::naming::{self, snake_case, Language};
+use super::ts_imports::ImportStyle;
+use super::{GeneratedOutput, Generator};
+use crate::config::GenerationConfig;
+use crate::parsers::{BodyKind, OperationDefinition, ParameterLocation, SchemaIR, SchemaReference};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Emits a smoke-test suite that calls every operation against a live server
+/// and validates responses against the generated schemas.
+pub struct ContractTestsGenerator;
+
+impl Generator for ContractTestsGenerator {
+    fn name(&self) -> &str {
+        "contract_tests"
+    }
+
+    fn file_extension(&self) -> &str {
+        "ts"
+    }
+
+    fn generate_from_ir(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<GeneratedOutput> {
+        let framework = config
+            .options
+            .get("framework")
+            .and_then(|v| v.as_str())
+            .unwrap_or("vitest");
+
+        let content = match framework {
+            "vitest" => generate_vitest(schema_ir, config, &ImportStyle::from_config(config)?),
+            "pytest" => generate_pytest(schema_ir, config),
+            other => anyhow::bail!("Unsupported contract test framework: {}", other),
+        };
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content,
+            metadata: HashMap::new(),
+            companions: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        if let Some(framework) = config.options.get("framework") {
+            match framework.as_str() {
+                Some("vitest") | Some("pytest") => {}
+                _ => anyhow::bail!(
+                    "contract_tests: option 'framework' must be \"vitest\" or \"pytest\""
+                ),
+            }
+        }
+        ImportStyle::from_config(config).map(|_| ())
+    }
+}
+
+struct ContractOptions<'a> {
+    base_url_env: &'a str,
+    default_base_url: &'a str,
+    safe_only: bool,
+}
+
+impl<'a> ContractOptions<'a> {
+    fn from_config(schema_ir: &'a SchemaIR, config: &'a GenerationConfig) -> Self {
+        Self {
+            base_url_env: config
+                .options
+                .get("baseUrlEnvVar")
+                .and_then(|v| v.as_str())
+                .unwrap_or("API_BASE_URL"),
+            default_base_url: schema_ir
+                .metadata
+                .base_url
+                .as_deref()
+                .unwrap_or("http://localhost:3000"),
+            safe_only: config
+                .options
+                .get("safeOnly")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        }
+    }
+
+    fn includes(&self, operation: &OperationDefinition) -> bool {
+        let method = format!("{:?}", operation.method).to_uppercase();
+        // Only JSON request bodies can be built from fixtures
+        let json_request = operation
+            .request_content_type
+            .as_deref()
+            .is_none_or(|content_type| BodyKind::from_content_type(content_type) == BodyKind::Json);
+        json_request && (!self.safe_only || matches!(method.as_str(), "GET" | "HEAD" | "OPTIONS"))
+    }
+}
+
+/// Response schema to validate against, when the success response is JSON
+fn json_response(operation: &OperationDefinition) -> Option<&SchemaReference> {
+    let is_json = operation
+        .response_content_type
+        .as_deref()
+        .is_none_or(|content_type| BodyKind::from_content_type(content_type) == BodyKind::Json);
+    operation.response.as_ref().filter(|_| is_json)
+}
+
+/// Path parameter values seeded from spec examples (empty when unknown)
+fn path_params(operation: &OperationDefinition) -> Vec<(&str, String)> {
+    operation
+        .parameters
+        .iter()
+        .filter(|p| matches!(p.location, ParameterLocation::Path))
+        .map(|p| {
+            let example = match &p.example {
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(other) => other.to_string(),
+                None => String::new(),
+            };
+            (p.name.as_str(), example)
+        })
+        .collect()
+}
+
+fn generate_vitest(
+    schema_ir: &SchemaIR,
+    config: &GenerationConfig,
+    import_style: &ImportStyle,
+) -> String {
+    let options = ContractOptions::from_config(schema_ir, config);
+    let types_import = config
+        .options
+        .get("typesImport")
+        .and_then(|v| v.as_str())
+        .unwrap_or("./types");
+    let fixtures_import = config
+        .options
+        .get("fixturesImport")
+        .and_then(|v| v.as_str());
+
+    let operations: Vec<_> = schema_ir
+        .operations
+        .iter()
+        .filter(|op| options.includes(op))
+        .collect();
+
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "// Generated Contract Tests for {}\n",
+        schema_ir.metadata.title
+    ));
+    output.push_str(&format!("// Version: {}\n\n", schema_ir.metadata.version));
+
+    output.push_str(&import_style.values(&["describe", "expect", "it"], "vitest"));
+    output.push_str(&import_style.values(&["z"], "zod"));
+
+    let mut schema_imports: Vec<String> = operations
+        .iter()
+        .filter_map(|op| json_response(op))
+        .map(|r| format!("{}Schema", naming::type_name(&r.name, Language::TypeScript)))
+        .collect();
+    schema_imports.sort();
+    schema_imports.dedup();
+    if !schema_imports.is_empty() {
+        let names: Vec<&str> = schema_imports.iter().map(String::as_str).collect();
+        output.push_str(&import_style.values(&names, types_import));
+    }
+
+    if let Some(fixtures_import) = fixtures_import {
+        let mut factories: Vec<String> = operations
+            .iter()
+            .filter_map(|op| op.request_body.as_ref())
+            .map(|b| format!("make{}", naming::type_name(&b.name, Language::TypeScript)))
+            .collect();
+        factories.sort();
+        factories.dedup();
+        if !factories.is_empty() {
+            let names: Vec<&str> = factories.iter().map(String::as_str).collect();
+            output.push_str(&import_style.values(&names, fixtures_import));
+        }
+    }
+
+    output.push_str(&format!(
+        "\nconst baseUrl = process.env.{} ?? '{}';\n",
+        options.base_url_env, options.default_base_url
+    ));
+    output.push_str(
+        "const defaultHeaders: Record<string, string> = JSON.parse(process.env.CONTRACT_TEST_HEADERS ?? '{}');\n\n",
+    );
+
+    output.push_str(
+        "// Path parameter values per operation; fill in identifiers that exist on the target server.\n",
+    );
+    output.push_str("const pathParams: Record<string, Record<string, string>> = {\n");
+    for operation in &operations {
+        let params = path_params(operation);
+        if params.is_empty() {
+            continue;
+        }
+        let values: Vec<_> = params
+            .iter()
+            .map(|(name, value)| format!("{}: {}", naming::quote(name), naming::quote(value)))
+            .collect();
+        output.push_str(&format!(
+            "  {}: {{ {} }},\n",
+            naming::ts_property_key(&operation.id),
+            values.join(", ")
+        ));
+    }
+    output.push_str("};\n\n");
+
+    output.push_str(
+        "function resolvePath(operationId: string, template: string): string | undefined {\n",
+    );
+    output.push_str("  let missing = false;\n");
+    output.push_str("  const path = template.replace(/\\{([^}]+)\\}/g, (_, name: string) => {\n");
+    output.push_str("    const value = pathParams[operationId]?.[name];\n");
+    output.push_str("    if (!value) missing = true;\n");
+    output.push_str("    return encodeURIComponent(value ?? '');\n");
+    output.push_str("  });\n");
+    output.push_str("  return missing ? undefined : path;\n");
+    output.push_str("}\n\n");
+
+    output.push_str(
+        "async function call(method: string, path: string, body?: unknown): Promise<Response> {\n",
+    );
+    output.push_str("  return fetch(`${baseUrl}${path}`, {\n");
+    output.push_str("    method,\n");
+    output.push_str("    headers: { 'Content-Type': 'application/json', ...defaultHeaders },\n");
+    output.push_str("    body: body === undefined ? undefined : JSON.stringify(body),\n");
+    output.push_str("  });\n");
+    output.push_str("}\n\n");
+
+    output.push_str(&format!(
+        "describe('{} contract', () => {{\n",
+        schema_ir.metadata.title.replace('\'', "\\'")
+    ));
+
+    for operation in &operations {
+        let method = format!("{:?}", operation.method).to_uppercase();
+
+        output.push_str(&format!(
+            "  it({}, async (ctx) => {{\n",
+            naming::quote(&format!("{}: {} {}", operation.id, method, operation.path))
+        ));
+        output.push_str(&format!(
+            "    const path = resolvePath({}, {});\n",
+            naming::quote(&operation.id),
+            naming::quote(&operation.path)
+        ));
+        output.push_str("    if (!path) return ctx.skip();\n\n");
+
+        let body = match (&operation.request_body, fixtures_import) {
+            (Some(body), Some(_)) if body.schema_type == "array" => format!(
+                ", [make{}()]",
+                naming::type_name(&body.name, Language::TypeScript)
+            ),
+            (Some(body), Some(_)) => format!(
+                ", make{}()",
+                naming::type_name(&body.name, Language::TypeScript)
+            ),
+            (Some(_), None) => ", {}".to_string(),
+            (None, _) => String::new(),
+        };
+        output.push_str(&format!(
+            "    const response = await call('{}', path{});\n",
+            method, body
+        ));
+        output.push_str("    expect(response.ok, `HTTP ${response.status}`).toBe(true);\n");
+
+        if let Some(response) = json_response(operation) {
+            output.push_str(&format!(
+                "    {}.parse(await response.json());\n",
+                zod_schema(response)
+            ));
+        }
+
+        output.push_str("  });\n\n");
+    }
+
+    output.truncate(output.trim_end().len());
+    output.push_str("\n});\n");
+    output
+}
+
+fn zod_schema(schema_ref: &SchemaReference) -> String {
+    let schema_name = naming::type_name(&schema_ref.name, Language::TypeScript);
+    if schema_ref.schema_type == "array" {
+        format!("z.array({}Schema)", schema_name)
+    } else {
+        format!("{}Schema", schema_name)
+    }
+}
+
+fn generate_pytest(schema_ir: &SchemaIR, config: &GenerationConfig) -> String {
+    let options = ContractOptions::from_config(schema_ir, config);
+    let models_module = config
+        .options
+        .get("modelsModule")
+        .and_then(|v| v.as_str())
+        .unwrap_or("client");
+    let fixtures_module = config
+        .options
+        .get("fixturesModule")
+        .and_then(|v| v.as_str());
+
+    let operations: Vec<_> = schema_ir
+        .operations
+        .iter()
+        .filter(|op| options.includes(op))
+        .collect();
+
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "# Generated Contract Tests for {}\n",
+        schema_ir.metadata.title
+    ));
+    output.push_str(&format!("# Version: {}\n\n", schema_ir.metadata.version));
+
+    output.push_str("import json\n");
+    output.push_str("import os\n");
+    output.push_str("import re\n");
+    output.push_str("from typing import List, Optional\n");
+    output.push_str("from urllib.parse import quote\n\n");
+    output.push_str("import pytest\n");
+    output.push_str("import requests\n");
+    output.push_str("from pydantic import TypeAdapter\n");
+
+    let mut models: Vec<String> = operations
+        .iter()
+        .filter_map(|op| json_response(op))
+        .map(|r| naming::type_name(&r.name, Language::Python))
+        .collect();
+    models.sort();
+    models.dedup();
+    if !models.is_empty() {
+        output.push_str(&format!(
+            "\nfrom {} import {}\n",
+            models_module,
+            models.join(", ")
+        ));
+    }
+
+    if let Some(fixtures_module) = fixtures_module {
+        let mut factories: Vec<String> = operations
+            .iter()
+            .filter_map(|op| op.request_body.as_ref())
+            .map(|b| format!("make_{}", python_factory(&b.name)))
+            .collect();
+        factories.sort();
+        factories.dedup();
+        if !factories.is_empty() {
+            output.push_str(&format!(
+                "from {} import {}\n",
+                fixtures_module,
+                factories.join(", ")
+            ));
+        }
+    }
+
+    output.push_str(&format!(
+        "\nBASE_URL = os.environ.get(\"{}\", \"{}\")\n",
+        options.base_url_env, options.default_base_url
+    ));
+    output.push_str(
+        "HEADERS = {\"Content-Type\": \"application/json\", **json.loads(os.environ.get(\"CONTRACT_TEST_HEADERS\", \"{}\"))}\n\n",
+    );
+
+    output.push_str(
+        "# Path parameter values per operation; fill in identifiers that exist on the target server.\n",
+    );
+    output.push_str("PATH_PARAMS = {\n");
+    for operation in &operations {
+        let params = path_params(operation);
+        if params.is_empty() {
+            continue;
+        }
+        let values: Vec<_> = params
+            .iter()
+            .map(|(name, value)| format!("{}: {}", naming::quote(name), naming::quote(value)))
+            .collect();
+        output.push_str(&format!(
+            "    {}: {{{}}},\n",
+            naming::quote(&operation.id),
+            values.join(", ")
+        ));
+    }
+    output.push_str("}\n\n\n");
+
+    output.push_str("def resolve_path(operation_id: str, template: str) -> Optional[str]:\n");
+    output.push_str("    values = PATH_PARAMS.get(operation_id, {})\n");
+    output.push_str("    names = re.findall(r\"\\{([^}]+)\\}\", template)\n");
+    output.push_str("    if any(not values.get(name) for name in names):\n");
+    output.push_str("        return None\n");
+    output.push_str("    for name in names:\n");
+    output.push_str("        template = template.replace(\"{\" + name + \"}\", quote(str(values[name]), safe=\"\"))\n");
+    output.push_str("    return template\n\n\n");
+
+    output.push_str("def call(method: str, path: str, body=None) -> requests.Response:\n");
+    output.push_str("    return requests.request(method, BASE_URL + path, headers=HEADERS, json=body, timeout=30)\n");
+
+    for operation in &operations {
+        let method = format!("{:?}", operation.method).to_uppercase();
+
+        output.push_str(&format!(
+            "\n\ndef test_{}() -> None:\n",
+            snake_case(&naming::function_name(&operation.id, Language::Python))
+        ));
+        output.push_str(&format!("    \"\"\"{} {}\"\"\"\n", method, operation.path));
+        output.push_str(&format!(
+            "    path = resolve_path({}, {})\n",
+            naming::quote(&operation.id),
+            naming::quote(&operation.path)
+        ));
+        output.push_str("    if path is None:\n");
+        output.push_str("        pytest.skip(\"path parameters not configured\")\n\n");
+
+        let body = match (&operation.request_body, fixtures_module) {
+            (Some(body), Some(_)) if body.schema_type == "array" => format!(
+                ", [make_{}().model_dump(mode=\"json\", by_alias=True)]",
+                python_factory(&body.name)
+            ),
+            (Some(body), Some(_)) => format!(
+                ", make_{}().model_dump(mode=\"json\", by_alias=True)",
+                python_factory(&body.name)
+            ),
+            (Some(_), None) => ", {}".to_string(),
+            (None, _) => String::new(),
+        };
+        output.push_str(&format!(
+            "    response = call(\"{}\", path{})\n",
+            method, body
+        ));
+        output.push_str(
+            "    assert response.ok, f\"HTTP {response.status_code}: {response.text}\"\n",
+        );
+
+        if let Some(response) = json_response(operation) {
+            let model_name = naming::type_name(&response.name, Language::Python);
+            let model = if response.schema_type == "array" {
+                format!("List[{}]", model_name)
+            } else {
+                model_name
+            };
+            output.push_str(&format!(
+                "    TypeAdapter({}).validate_python(response.json())\n",
+                model
+            ));
+        }
+    }
+
+    output
+}
+
+/// Fixture factory suffix matching the fixtures generator (`make_<suffix>`)
+fn python_factory(schema_name: &str) -> String {
+    snake_case(&naming::type_name(schema_name, Language::Python))
+}
@@ -0,0 +1,135 @@
+use super::{GeneratedOutput, Generator};
+use crate::config::GenerationConfig;
+use crate::parsers::{FeatureHandling, SchemaIR};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Number of example locations listed per construct in the Markdown report
+const MAX_LOCATIONS: usize = 5;
+
+/// Reports which spec constructs were encountered and how they were handled,
+/// so users know what fidelity to expect from the other generators.
+pub struct CoverageReportGenerator;
+
+impl Generator for CoverageReportGenerator {
+    fn name(&self) -> &str {
+        "coverage_report"
+    }
+
+    fn file_extension(&self) -> &str {
+        "md"
+    }
+
+    fn generate_from_ir(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<GeneratedOutput> {
+        let format = config
+            .options
+            .get("format")
+            .and_then(|v| v.as_str())
+            .unwrap_or("markdown");
+
+        let content = match format {
+            "markdown" => render_markdown(schema_ir),
+            "json" => serde_json::to_string_pretty(&schema_ir.coverage)? + "\n",
+            other => anyhow::bail!("Unsupported coverage report format: {}", other),
+        };
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content,
+            metadata: HashMap::new(),
+            companions: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        if let Some(format) = config.options.get("format") {
+            match format.as_str() {
+                Some("markdown") | Some("json") => {}
+                _ => anyhow::bail!(
+                    "coverage_report: option 'format' must be \"markdown\" or \"json\""
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn render_markdown(schema_ir: &SchemaIR) -> String {
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "# Spec Coverage Report: {} ({})\n\n",
+        schema_ir.metadata.title, schema_ir.metadata.version
+    ));
+
+    let sections = [
+        (
+            FeatureHandling::Unsupported,
+            "Unsupported (mapped to `any`)",
+            "unsupported",
+        ),
+        (FeatureHandling::Degraded, "Degraded", "degraded"),
+        (FeatureHandling::Ignored, "Ignored", "ignored"),
+        (FeatureHandling::Supported, "Supported", "supported"),
+    ];
+
+    // Summary lines such as "3 oneOf schemas degraded"
+    output.push_str("## Summary\n\n");
+    for (handling, _, verb) in &sections {
+        for usage in schema_ir
+            .coverage
+            .iter()
+            .filter(|u| u.handling == *handling)
+        {
+            output.push_str(&format!(
+                "- {} {} {}\n",
+                usage.locations.len(),
+                usage.construct,
+                verb
+            ));
+        }
+    }
+    if schema_ir.coverage.is_empty() {
+        output.push_str("- No spec constructs recorded\n");
+    }
+
+    for (handling, title, _) in &sections {
+        let usages: Vec<_> = schema_ir
+            .coverage
+            .iter()
+            .filter(|u| u.handling == *handling)
+            .collect();
+        if usages.is_empty() {
+            continue;
+        }
+
+        output.push_str(&format!("\n## {}\n\n", title));
+        output.push_str("| Construct | Count | Locations |\n");
+        output.push_str("|-----------|-------|-----------|\n");
+
+        for usage in usages {
+            let mut locations: Vec<_> = usage
+                .locations
+                .iter()
+                .take(MAX_LOCATIONS)
+                .map(|l| format!("`{}`", l))
+                .collect();
+            if usage.locations.len() > MAX_LOCATIONS {
+                locations.push(format!("… {} more", usage.locations.len() - MAX_LOCATIONS));
+            }
+            output.push_str(&format!(
+                "| {} | {} | {} |\n",
+                usage.construct,
+                usage.locations.len(),
+                locations.join(", ")
+            ));
+        }
+    }
+
+    output
+}
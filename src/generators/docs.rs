@@ -0,0 +1,641 @@
+//! Doc comment rendering shared by generators.
+//!
+//! Sections (summary, description) become paragraphs; documented parameters
+//! become `@param` tags, `Args:` entries, or trailing Go lines. Every block is
+//! returned ready to paste, with continuation lines prefixed by `indent`.
+//!
+//! Descriptions are made safe for the comment they land in: `*/` cannot end
+//! a JSDoc block early, nor `"""` a docstring, and `\r` line breaks are
+//! normalized. Generation options shape the text:
+//! - `docComments: false` leaves doc comments out
+//! - `docWidth: N` wraps description lines at `N` characters, not counting
+//!   indentation and comment markers; indented lines and fenced code blocks
+//!   are kept as written
+//! - `docStripHtml: true` drops HTML tags (`<br>` and block tags become line
+//!   breaks, `<li>` a `- ` item) and decodes the common entities
+//!
+//! Operation comments end with what the spec points readers to next: its
+//! `externalDocs` and the operations its responses link to, as `@see` tags, a
+//! `See Also:` docstring section, or a Go `See also:` list.
+
+use super::naming::{self, Language};
+use crate::config::GenerationConfig;
+use crate::parsers::{OperationDefinition, OperationLink, SchemaIR};
+use anyhow::Result;
+
+/// Tags `docStripHtml` removes; other `<...>` text (`List<String>`) is kept
+const HTML_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "b",
+    "blockquote",
+    "br",
+    "code",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "kbd",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "s",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "th",
+    "thead",
+    "tr",
+    "u",
+    "ul",
+];
+
+/// Tags that start a new line where they open or close
+const HTML_BLOCK_TAGS: &[&str] = &[
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "ol",
+    "p",
+    "pre",
+    "table",
+    "tr",
+    "ul",
+];
+
+/// A reference at the end of an operation's doc comment
+#[derive(Debug, Clone)]
+pub enum SeeAlso {
+    /// `externalDocs`
+    Url {
+        url: String,
+        description: Option<String>,
+    },
+    /// A response link; `operation` is the IR id of its target when the client
+    /// has a method for it
+    Link {
+        operation: Option<String>,
+        target: String,
+        note: String,
+    },
+}
+
+impl SeeAlso {
+    /// The `externalDocs` of `operation`, then its response links
+    pub fn of(schema_ir: &SchemaIR, operation: &OperationDefinition) -> Vec<Self> {
+        let mut references = Vec::new();
+        if let Some(docs) = &operation.external_docs {
+            references.push(Self::Url {
+                url: docs.url.clone(),
+                description: docs.description.clone(),
+            });
+        }
+        for link in &operation.links {
+            references.push(Self::Link {
+                operation: link
+                    .operation
+                    .clone()
+                    .filter(|id| schema_ir.operations.iter().any(|op| &op.id == id)),
+                target: link.target.clone(),
+                note: link_note(link),
+            });
+        }
+        references
+    }
+}
+
+/// `after a 201 response, with userId = $response.body#/id. Description`
+pub fn link_note(link: &OperationLink) -> String {
+    let mut note = format!("after a {} response", link.status);
+    if !link.parameters.is_empty() {
+        let parameters: Vec<_> = link
+            .parameters
+            .iter()
+            .map(|(name, value)| format!("{} = {}", name, value))
+            .collect();
+        note.push_str(&format!(", with {}", parameters.join(", ")));
+    }
+    if let Some(description) = link
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        note.push_str(&format!(". {}", description));
+    }
+    note
+}
+
+/// How descriptions become doc comments
+#[derive(Debug, Clone, Copy)]
+pub struct DocStyle {
+    enabled: bool,
+    width: Option<usize>,
+    strip_html: bool,
+}
+
+impl Default for DocStyle {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            width: None,
+            strip_html: false,
+        }
+    }
+}
+
+impl DocStyle {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        let width = match config.options.get("docWidth") {
+            None => None,
+            Some(value) => match value.as_u64() {
+                Some(width) if width > 0 => Some(width as usize),
+                _ => anyhow::bail!(
+                    "{}: option 'docWidth' must be a positive integer",
+                    config.generator
+                ),
+            },
+        };
+        Ok(Self {
+            enabled: config
+                .options
+                .get("docComments")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true),
+            width,
+            strip_html: config
+                .options
+                .get("docStripHtml")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        })
+    }
+
+    /// Whether doc comments are emitted (`docComments` option, default true)
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// `text` with HTML stripped when configured, for string literals such as
+    /// pydantic `Field(description=...)`
+    pub fn plain(&self, text: &str) -> String {
+        self.clean(text)
+    }
+
+    /// `/** ... */` block, or `None` when there is nothing to document
+    pub fn jsdoc(
+        &self,
+        sections: &[Option<&str>],
+        params: &[(String, &str)],
+        indent: &str,
+    ) -> Option<String> {
+        self.jsdoc_see(sections, params, &[], indent)
+    }
+
+    /// [`Self::jsdoc`] with `@see` tags after the parameters
+    pub fn jsdoc_see(
+        &self,
+        sections: &[Option<&str>],
+        params: &[(String, &str)],
+        see: &[SeeAlso],
+        indent: &str,
+    ) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        let mut lines = self.paragraphs(sections);
+        if !lines.is_empty() && (!params.is_empty() || !see.is_empty()) {
+            lines.push(String::new());
+        }
+        for (name, description) in params {
+            lines.extend(self.entry(&format!("@param {} ", name), description, "  "));
+        }
+        for reference in see {
+            let (target, text) = self.reference(reference, Language::TypeScript);
+            lines.extend(self.entry(&format!("@see {} ", target), &text, "  "));
+        }
+        if lines.is_empty() {
+            return None;
+        }
+
+        let lines: Vec<_> = lines.iter().map(|l| l.replace("*/", "*\\/")).collect();
+        if lines.len() == 1 {
+            return Some(format!("/** {} */", lines[0]));
+        }
+
+        let mut block = String::from("/**\n");
+        for line in &lines {
+            block.push_str(&format!(
+                "{} *{}{}\n",
+                indent,
+                if line.is_empty() { "" } else { " " },
+                line
+            ));
+        }
+        block.push_str(&format!("{} */", indent));
+        Some(block)
+    }
+
+    /// Triple-quoted docstring with an optional Google-style `Args:` section
+    pub fn python_docstring(
+        &self,
+        sections: &[Option<&str>],
+        args: &[(String, &str)],
+        indent: &str,
+    ) -> Option<String> {
+        self.python_docstring_see(sections, args, &[], indent)
+    }
+
+    /// [`Self::python_docstring`] with a `See Also:` section after `Args:`
+    pub fn python_docstring_see(
+        &self,
+        sections: &[Option<&str>],
+        args: &[(String, &str)],
+        see: &[SeeAlso],
+        indent: &str,
+    ) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        let mut lines = self.paragraphs(sections);
+        if !args.is_empty() {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push("Args:".to_string());
+            for (name, description) in args {
+                lines.extend(self.entry(&format!("    {}: ", name), description, "        "));
+            }
+        }
+        if !see.is_empty() {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push("See Also:".to_string());
+            for reference in see {
+                let (target, text) = self.reference(reference, Language::Python);
+                let separator = if text.is_empty() { "" } else { ":" };
+                lines.extend(self.entry(
+                    &format!("    {}{} ", target, separator),
+                    &text,
+                    "        ",
+                ));
+            }
+        }
+        if lines.is_empty() {
+            return None;
+        }
+
+        let lines: Vec<_> = lines
+            .iter()
+            .map(|l| l.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\""))
+            .collect();
+        if lines.len() == 1 {
+            return Some(format!("\"\"\"{}\"\"\"", lines[0]));
+        }
+
+        let mut block = format!("\"\"\"{}\n", lines[0]);
+        for line in &lines[1..] {
+            if line.is_empty() {
+                block.push('\n');
+            } else {
+                block.push_str(&format!("{}{}\n", indent, line));
+            }
+        }
+        block.push_str(&format!("{}\"\"\"", indent));
+        Some(block)
+    }
+
+    /// `// Name - first line` comment, as Go doc comments start with the identifier
+    pub fn go_comment(
+        &self,
+        name: &str,
+        sections: &[Option<&str>],
+        indent: &str,
+    ) -> Option<String> {
+        self.go_comment_see(name, sections, &[], indent)
+    }
+
+    /// [`Self::go_comment`] ending in a `See also:` list
+    pub fn go_comment_see(
+        &self,
+        name: &str,
+        sections: &[Option<&str>],
+        see: &[SeeAlso],
+        indent: &str,
+    ) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        let mut lines = self.paragraphs(sections);
+        if !see.is_empty() && !lines.is_empty() {
+            lines.push(String::new());
+            lines.push("See also:".to_string());
+            for reference in see {
+                let (target, text) = self.reference(reference, Language::Go);
+                lines.extend(self.entry(&format!("  - {} ", target), &text, "    "));
+            }
+        }
+        if lines.is_empty() {
+            return None;
+        }
+
+        let mut block = format!("// {} - {}", name, lines[0]);
+        for line in &lines[1..] {
+            block.push_str(&format!(
+                "\n{}//{}{}",
+                indent,
+                if line.is_empty() { "" } else { " " },
+                line
+            ));
+        }
+        Some(block)
+    }
+
+    /// Target of a reference, as the comment's language links it, and the
+    /// text following it
+    fn reference(&self, reference: &SeeAlso, language: Language) -> (String, String) {
+        match reference {
+            SeeAlso::Url { url, description } => {
+                let target = match language {
+                    Language::TypeScript => format!("{{@link {}}}", url),
+                    _ => url.clone(),
+                };
+                (target, description.clone().unwrap_or_default())
+            }
+            SeeAlso::Link {
+                operation: Some(id),
+                note,
+                ..
+            } => {
+                let method = naming::function_name(id, language);
+                let target = match language {
+                    Language::TypeScript => format!("{{@link ApiClient.{}}}", method),
+                    Language::Go => format!("[ApiClient.{}]", method),
+                    _ => method,
+                };
+                (target, note.clone())
+            }
+            SeeAlso::Link {
+                operation: None,
+                target,
+                note,
+            } => (format!("`{}`", target), note.clone()),
+        }
+    }
+
+    /// Non-empty sections split into lines, separated by blank lines
+    fn paragraphs(&self, sections: &[Option<&str>]) -> Vec<String> {
+        let mut lines = Vec::new();
+        for section in sections.iter().flatten() {
+            let cleaned = self.clean(section);
+            let text = cleaned.trim();
+            if text.is_empty() || lines.iter().any(|l: &String| l == text) {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            let mut fenced = false;
+            for line in text.lines().map(str::trim_end) {
+                if line.trim_start().starts_with("```") {
+                    fenced = !fenced;
+                    lines.push(line.to_string());
+                } else if fenced || line.starts_with(char::is_whitespace) {
+                    lines.push(line.to_string());
+                } else {
+                    let continuation = if line.starts_with("- ") || line.starts_with("* ") {
+                        "  "
+                    } else {
+                        ""
+                    };
+                    lines.extend(self.wrap(line, "", continuation));
+                }
+            }
+        }
+        lines
+    }
+
+    /// A `lead` line (`@param id `) with `text` joined onto it, wrapped with
+    /// `continuation` before the following lines
+    fn entry(&self, lead: &str, text: &str, continuation: &str) -> Vec<String> {
+        let mut lines = self.wrap(&single_line(&self.clean(text)), lead, continuation);
+        // A lead without text (`@see {@link url}`)
+        if let Some(last) = lines.last_mut() {
+            last.truncate(last.trim_end().len());
+        }
+        lines
+    }
+
+    /// `text` after `lead`, broken at spaces so no line's text passes the width
+    fn wrap(&self, text: &str, lead: &str, continuation: &str) -> Vec<String> {
+        let Some(width) = self.width else {
+            return vec![format!("{}{}", lead, text)];
+        };
+        let mut lines = Vec::new();
+        let mut line = lead.to_string();
+        let mut empty = true;
+        for word in text.split(' ').filter(|word| !word.is_empty()) {
+            if !empty && line.chars().count() + 1 + word.chars().count() > width {
+                lines.push(line);
+                line = continuation.to_string();
+                empty = true;
+            }
+            if !empty {
+                line.push(' ');
+            }
+            line.push_str(word);
+            empty = false;
+        }
+        lines.push(line);
+        lines
+    }
+
+    /// `\r` line breaks normalized, and HTML stripped when configured
+    fn clean(&self, text: &str) -> String {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        if self.strip_html {
+            strip_html(&text)
+        } else {
+            text
+        }
+    }
+}
+
+fn strip_html(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        output.push_str(&rest[..start]);
+        let tag = rest[start + 1..]
+            .find('>')
+            .map(|end| &rest[start + 1..start + 1 + end]);
+        let name = tag
+            .map(|tag| {
+                tag.trim_start_matches('/')
+                    .split(|c: char| !c.is_ascii_alphanumeric())
+                    .next()
+                    .unwrap_or("")
+                    .to_ascii_lowercase()
+            })
+            .filter(|name| HTML_TAGS.contains(&name.as_str()));
+        match (tag, name) {
+            (Some(tag), Some(name)) => {
+                if name == "li" && !tag.starts_with('/') {
+                    output.push_str("\n- ");
+                } else if HTML_BLOCK_TAGS.contains(&name.as_str()) {
+                    output.push('\n');
+                }
+                rest = &rest[start + tag.len() + 2..];
+            }
+            _ => {
+                output.push('<');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    output.push_str(rest);
+
+    let decoded = output
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    // Tags that sat between words or lines leave runs of blanks behind
+    let mut lines: Vec<&str> = Vec::new();
+    for line in decoded.lines().map(str::trim_end) {
+        if !(line.is_empty() && lines.last().is_some_and(|last| last.is_empty())) {
+            lines.push(line);
+        }
+    }
+    lines.join("\n")
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(options: &str) -> DocStyle {
+        let config: GenerationConfig = serde_yaml::from_str(&format!(
+            "{{ generator: typescript, outputFile: client.ts, options: {} }}",
+            options
+        ))
+        .unwrap();
+        DocStyle::from_config(&config).unwrap()
+    }
+
+    const FOX: &str = "The quick brown fox jumps over the lazy dog";
+
+    #[test]
+    fn descriptions_wrap_at_the_configured_width() {
+        assert_eq!(
+            style("{}").jsdoc(&[Some(FOX)], &[], ""),
+            Some(format!("/** {} */", FOX))
+        );
+
+        let narrow = style("{ docWidth: 20 }");
+        assert_eq!(
+            narrow.jsdoc(&[Some(FOX)], &[], "").unwrap(),
+            "/**\n * The quick brown fox\n * jumps over the lazy\n * dog\n */"
+        );
+        let params = [("id".to_string(), "identifier of the user to fetch")];
+        assert_eq!(
+            narrow.jsdoc(&[], &params, "  ").unwrap(),
+            "/**\n   * @param id identifier\n   *   of the user to\n   *   fetch\n   */"
+        );
+        assert_eq!(
+            narrow.go_comment("User", &[Some(FOX)], "\t").unwrap(),
+            "// User - The quick brown fox\n\t// jumps over the lazy\n\t// dog"
+        );
+
+        // Indented lines and fenced code are kept as written
+        let code = "Example:\n\n```\nlet client = ApiClient::new(base_url);\n```\n    indented line that runs long";
+        assert_eq!(
+            narrow.python_docstring(&[Some(code)], &[], "    ").unwrap(),
+            "\"\"\"Example:\n\n    ```\n    let client = ApiClient::new(base_url);\n    ```\n        indented line that runs long\n    \"\"\""
+        );
+    }
+
+    #[test]
+    fn html_is_stripped_only_when_configured() {
+        let html = "<p>Returns <b>all</b> users as a List<User>:</p><ul><li>active</li><li>invited &amp; pending</li></ul>";
+        assert_eq!(style("{}").plain(html), html);
+        assert_eq!(
+            style("{ docStripHtml: true }").plain(html),
+            "\nReturns all users as a List<User>:\n\n- active\n- invited & pending"
+        );
+        assert_eq!(
+            style("{ docStripHtml: true }").plain("line<br/>break, a < b"),
+            "line\nbreak, a < b"
+        );
+    }
+
+    #[test]
+    fn comment_terminators_are_escaped_for_each_language() {
+        let docs = style("{}");
+        let text = r#"Globs like a/*/b end here */ or say """hi""" \ back"#;
+        assert_eq!(
+            docs.jsdoc(&[Some(text)], &[], "").unwrap(),
+            r#"/** Globs like a/*\/b end here *\/ or say """hi""" \ back */"#
+        );
+        assert_eq!(
+            docs.python_docstring(&[Some(text)], &[], "").unwrap(),
+            r#""""Globs like a/*/b end here */ or say \"\"\"hi\"\"\" \\ back""""#
+        );
+        assert_eq!(
+            docs.go_comment("Glob", &[Some(text)], "").unwrap(),
+            format!("// Glob - {}", text)
+        );
+        assert_eq!(
+            docs.jsdoc(&[Some("a\r\nb")], &[], "").unwrap(),
+            "/**\n * a\n * b\n */"
+        );
+    }
+
+    #[test]
+    fn doc_comments_can_be_turned_off() {
+        let docs = style("{ docComments: false }");
+        assert_eq!(docs.jsdoc(&[Some(FOX)], &[], ""), None);
+        assert_eq!(docs.python_docstring(&[Some(FOX)], &[], ""), None);
+        assert_eq!(docs.go_comment("Fox", &[Some(FOX)], ""), None);
+        assert!(DocStyle::from_config(
+            &serde_yaml::from_str(
+                "{ generator: golang, outputFile: c.go, options: { docWidth: 0 } }"
+            )
+            .unwrap()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,374 @@
+use super::naming::{self, snake_case, Language};
+use super::ts_imports::ImportStyle;
+use super::{GeneratedOutput, Generator};
+use crate::config::GenerationConfig;
+use crate::parsers::{SchemaIR, TypeInfo};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+/// Emits factory functions producing realistic fake data per schema,
+/// for use in unit tests and storybook mocks.
+pub struct FixturesGenerator;
+
+impl Generator for FixturesGenerator {
+    fn name(&self) -> &str {
+        "fixtures"
+    }
+
+    fn file_extension(&self) -> &str {
+        "ts"
+    }
+
+    fn generate_from_ir(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<GeneratedOutput> {
+        let language = config
+            .options
+            .get("language")
+            .and_then(|v| v.as_str())
+            .unwrap_or("typescript");
+
+        let content = match language {
+            "typescript" => {
+                generate_typescript(schema_ir, config, &ImportStyle::from_config(config)?)
+            }
+            "python" => generate_python(schema_ir, config),
+            other => anyhow::bail!("Unsupported fixtures language: {}", other),
+        };
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content,
+            metadata: HashMap::new(),
+            companions: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn supports_unions(&self) -> bool {
+        true
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        if let Some(language) = config.options.get("language") {
+            match language.as_str() {
+                Some("typescript") | Some("python") => {}
+                _ => anyhow::bail!(
+                    "fixtures: option 'language' must be \"typescript\" or \"python\""
+                ),
+            }
+        }
+        ImportStyle::from_config(config).map(|_| ())
+    }
+}
+
+fn generate_typescript(
+    schema_ir: &SchemaIR,
+    config: &GenerationConfig,
+    import_style: &ImportStyle,
+) -> String {
+    let types_import = config
+        .options
+        .get("typesImport")
+        .and_then(|v| v.as_str())
+        .unwrap_or("./types");
+
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "// Generated Fixtures for {}\n",
+        schema_ir.metadata.title
+    ));
+    output.push_str(&format!("// Version: {}\n\n", schema_ir.metadata.version));
+
+    if !schema_ir.schemas.is_empty() {
+        let names: Vec<_> = schema_ir
+            .schemas
+            .iter()
+            .map(|s| naming::type_name(&s.name, Language::TypeScript))
+            .collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        output.push_str(&import_style.types(&names, types_import));
+        output.push('\n');
+    }
+
+    output.push_str("let sequence = 0;\n\n");
+    output.push_str("const nextSequence = (): number => ++sequence;\n\n");
+    output.push_str("/** Reset the fixture sequence (e.g., in beforeEach) */\n");
+    output.push_str("export function resetFixtureSequence(): void {\n");
+    output.push_str("  sequence = 0;\n");
+    output.push_str("}\n\n");
+
+    for schema in &schema_ir.schemas {
+        let type_name = naming::type_name(&schema.name, Language::TypeScript);
+        output.push_str(&format!(
+            "export function make{}(overrides: Partial<{}> = {{}}): {} {{\n",
+            type_name, type_name, type_name
+        ));
+        output.push_str("  const n = nextSequence();\n");
+        output.push_str("  return {\n");
+
+        for field in &schema.fields {
+            let cyclic = is_cyclic(schema_ir, &schema.name, &field.type_info);
+            let value = match (cyclic, field.type_info.is_array, field.required) {
+                (false, _, _) => typescript_value(&field.name, &field.type_info),
+                (true, true, _) => "[]".to_string(),
+                (true, false, false) => continue,
+                (true, false, true) => format!(
+                    "{{}} as unknown as {}",
+                    field
+                        .type_info
+                        .reference
+                        .as_deref()
+                        .map(|r| naming::type_name(r, Language::TypeScript))
+                        .unwrap_or_else(|| "never".to_string())
+                ),
+            };
+            output.push_str(&format!(
+                "    {}: {},\n",
+                naming::ts_property_key(&field.name),
+                value
+            ));
+        }
+
+        output.push_str("    ...overrides,\n");
+        output.push_str("  };\n");
+        output.push_str("}\n\n");
+    }
+
+    output
+}
+
+fn typescript_value(field_name: &str, type_info: &TypeInfo) -> String {
+    if !type_info.tuple_items.is_empty() {
+        let items: Vec<String> = type_info
+            .tuple_items
+            .iter()
+            .map(|item| typescript_value(field_name, item))
+            .collect();
+        return format!("[{}]", items.join(", "));
+    }
+
+    if let Some(first) = type_info.union_members.first() {
+        return typescript_value(field_name, first);
+    }
+
+    if type_info.is_array {
+        return match &type_info.array_item_type {
+            Some(item) => format!("[{}]", typescript_value(field_name, item)),
+            None => "[]".to_string(),
+        };
+    }
+
+    if let Some(ref_name) = &type_info.reference {
+        return format!(
+            "make{}()",
+            naming::type_name(ref_name, Language::TypeScript)
+        );
+    }
+
+    if let Some(first) = type_info.enum_values.as_ref().and_then(|v| v.first()) {
+        return naming::quote(first);
+    }
+
+    match type_info.openapi_type.as_str() {
+        "string" => match type_info.format.as_deref() {
+            Some("email") => "`user${n}@example.com`".to_string(),
+            Some("uuid") => "`00000000-0000-4000-8000-${String(n).padStart(12, '0')}`".to_string(),
+            Some("date-time") => {
+                "new Date(Date.UTC(2024, 0, 1) + n * 86_400_000).toISOString()".to_string()
+            }
+            Some("date") => {
+                "new Date(Date.UTC(2024, 0, 1) + n * 86_400_000).toISOString().slice(0, 10)"
+                    .to_string()
+            }
+            Some("uri") | Some("url") => "`https://example.com/resources/${n}`".to_string(),
+            Some("hostname") => "`host-${n}.example.com`".to_string(),
+            Some("ipv4") => "`192.0.2.${n % 256}`".to_string(),
+            Some("byte") => "btoa(`bytes-${n}`)".to_string(),
+            _ => format!("`{}-${{n}}`", field_name.replace(['`', '$', '\\'], "")),
+        },
+        "integer" => "n".to_string(),
+        "number" => "n + 0.5".to_string(),
+        "boolean" => "n % 2 === 0".to_string(),
+        "object" => "{}".to_string(),
+        _ => "null".to_string(),
+    }
+}
+
+fn generate_python(schema_ir: &SchemaIR, config: &GenerationConfig) -> String {
+    let models_module = config
+        .options
+        .get("modelsModule")
+        .and_then(|v| v.as_str())
+        .unwrap_or("client");
+
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "# Generated Fixtures for {}\n",
+        schema_ir.metadata.title
+    ));
+    output.push_str(&format!("# Version: {}\n\n", schema_ir.metadata.version));
+
+    output.push_str("from datetime import datetime, timedelta\n");
+    output.push_str("from itertools import count\n");
+    output.push_str("from typing import Any, Dict\n");
+    if !schema_ir.schemas.is_empty() {
+        let names: Vec<_> = schema_ir
+            .schemas
+            .iter()
+            .map(|s| naming::type_name(&s.name, Language::Python))
+            .collect();
+        output.push_str(&format!(
+            "\nfrom {} import {}\n",
+            models_module,
+            names.join(", ")
+        ));
+    }
+    output.push_str("\n_sequence = count(1)\n\n\n");
+    output.push_str("def reset_fixture_sequence() -> None:\n");
+    output.push_str("    \"\"\"Reset the fixture sequence (e.g., in a pytest fixture)\"\"\"\n");
+    output.push_str("    global _sequence\n");
+    output.push_str("    _sequence = count(1)\n\n\n");
+
+    for schema in &schema_ir.schemas {
+        let type_name = naming::type_name(&schema.name, Language::Python);
+        output.push_str(&format!(
+            "def make_{}(**overrides: Any) -> {}:\n",
+            snake_case(&type_name),
+            type_name
+        ));
+        output.push_str("    n = next(_sequence)\n");
+        output.push_str("    values: Dict[str, Any] = {\n");
+
+        for field in &schema.fields {
+            let cyclic = is_cyclic(schema_ir, &schema.name, &field.type_info);
+            let value = match (cyclic, field.type_info.is_array, field.required) {
+                (false, _, _) => python_value(&field.name, &field.type_info),
+                (true, true, _) => "[]".to_string(),
+                (true, false, false) => continue,
+                (true, false, true) => "None".to_string(),
+            };
+            output.push_str(&format!(
+                "        {}: {},\n",
+                naming::quote(&field.name),
+                value
+            ));
+        }
+
+        output.push_str("    }\n");
+        output.push_str("    values.update(overrides)\n");
+        output.push_str(&format!("    return {}(**values)\n\n\n", type_name));
+    }
+
+    output.truncate(output.trim_end().len());
+    output.push('\n');
+    output
+}
+
+fn python_value(field_name: &str, type_info: &TypeInfo) -> String {
+    if !type_info.tuple_items.is_empty() {
+        let items: Vec<String> = type_info
+            .tuple_items
+            .iter()
+            .map(|item| python_value(field_name, item))
+            .collect();
+        return format!("[{}]", items.join(", "));
+    }
+
+    if let Some(first) = type_info.union_members.first() {
+        return python_value(field_name, first);
+    }
+
+    if type_info.is_array {
+        return match &type_info.array_item_type {
+            Some(item) => format!("[{}]", python_value(field_name, item)),
+            None => "[]".to_string(),
+        };
+    }
+
+    if let Some(ref_name) = &type_info.reference {
+        return format!(
+            "make_{}()",
+            snake_case(&naming::type_name(ref_name, Language::Python))
+        );
+    }
+
+    if let Some(first) = type_info.enum_values.as_ref().and_then(|v| v.first()) {
+        return naming::quote(first);
+    }
+
+    match type_info.openapi_type.as_str() {
+        "string" => match type_info.format.as_deref() {
+            Some("email") => "f\"user{n}@example.com\"".to_string(),
+            Some("uuid") => "f\"00000000-0000-4000-8000-{n:012d}\"".to_string(),
+            Some("date-time") | Some("date") => {
+                "datetime(2024, 1, 1) + timedelta(days=n)".to_string()
+            }
+            Some("uri") | Some("url") => "f\"https://example.com/resources/{n}\"".to_string(),
+            Some("hostname") => "f\"host-{n}.example.com\"".to_string(),
+            Some("ipv4") => "f\"192.0.2.{n % 256}\"".to_string(),
+            _ => format!(
+                "f\"{}-{{n}}\"",
+                field_name.replace(['"', '{', '}', '\\'], "")
+            ),
+        },
+        "integer" => "n".to_string(),
+        "number" => "n + 0.5".to_string(),
+        "boolean" => "n % 2 == 0".to_string(),
+        "object" => "{}".to_string(),
+        _ => "None".to_string(),
+    }
+}
+
+/// Whether building this field would recurse back into `schema_name`
+fn is_cyclic(schema_ir: &SchemaIR, schema_name: &str, type_info: &TypeInfo) -> bool {
+    let mut visited = HashSet::new();
+    referenced_schema(type_info)
+        .map(|target| reaches(schema_ir, target, schema_name, &mut visited))
+        .unwrap_or(false)
+}
+
+fn reaches<'a>(
+    schema_ir: &'a SchemaIR,
+    from: &'a str,
+    target: &str,
+    visited: &mut HashSet<&'a str>,
+) -> bool {
+    if from == target {
+        return true;
+    }
+    if !visited.insert(from) {
+        return false;
+    }
+
+    schema_ir
+        .schemas
+        .iter()
+        .find(|s| s.name == from)
+        .map(|schema| {
+            schema.fields.iter().any(|field| {
+                referenced_schema(&field.type_info)
+                    .map(|next| reaches(schema_ir, next, target, visited))
+                    .unwrap_or(false)
+            })
+        })
+        .unwrap_or(false)
+}
+
+fn referenced_schema(type_info: &TypeInfo) -> Option<&str> {
+    if !type_info.tuple_items.is_empty() {
+        return type_info.tuple_items.iter().find_map(referenced_schema);
+    }
+    if let Some(first) = type_info.union_members.first() {
+        return referenced_schema(first);
+    }
+
+    match &type_info.array_item_type {
+        Some(item) => referenced_schema(item),
+        None => type_info.reference.as_deref(),
+    }
+}
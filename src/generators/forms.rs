@@ -0,0 +1,553 @@
+//! Form configuration built from the component schemas (`forms` generator),
+//! for admin UIs rendering CRUD forms straight from the API models:
+//! - `react-hook-form` (default): a TypeScript module with, per schema, a
+//!   resolver over the `{Schema}Schema` of the `typescript` client at
+//!   `clientImport` (same `validationLibrary`) and the form's fields
+//! - `jsonforms`: a JSON document with, per schema, the JSON Schema and UI
+//!   schema JSON Forms renders
+//!
+//! `formSchemas` limits the forms to the listed schemas. Fields of another
+//! object schema's type are flattened into dotted names (`address.street`)
+//! for `react-hook-form`, and referenced through `definitions` for
+//! `jsonforms`; unions, free-form objects, and cyclic fields are JSON inputs.
+
+use super::naming::{self, Language};
+use super::ts_imports::ImportStyle;
+use super::validators::{Dialect, ValidationLibrary};
+use super::{GeneratedOutput, Generator};
+use crate::config::GenerationConfig;
+use crate::parsers::{FieldDefinition, SchemaDefinition, SchemaIR, TypeInfo};
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+pub struct FormsGenerator;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FormLibrary {
+    ReactHookForm,
+    JsonForms,
+}
+
+impl FormLibrary {
+    fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("formLibrary").map(|v| v.as_str()) {
+            None | Some(Some("react-hook-form")) => Ok(Self::ReactHookForm),
+            Some(Some("jsonforms")) => Ok(Self::JsonForms),
+            _ => anyhow::bail!(
+                "{}: option 'formLibrary' must be \"react-hook-form\" or \"jsonforms\"",
+                config.generator
+            ),
+        }
+    }
+}
+
+impl Generator for FormsGenerator {
+    fn name(&self) -> &str {
+        "forms"
+    }
+
+    fn file_extension(&self) -> &str {
+        "ts"
+    }
+
+    fn generate_from_ir(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<GeneratedOutput> {
+        let schemas = form_schemas(schema_ir, config)?;
+        let content = match FormLibrary::from_config(config)? {
+            FormLibrary::ReactHookForm => generate_react_hook_form(
+                schema_ir,
+                &schemas,
+                config,
+                &Dialect::from_config(config)?,
+                &ImportStyle::from_config(config)?,
+            ),
+            FormLibrary::JsonForms => generate_json_forms(schema_ir, &schemas)?,
+        };
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content,
+            metadata: HashMap::new(),
+            companions: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn supports_unions(&self) -> bool {
+        true
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        if FormLibrary::from_config(config)? == FormLibrary::ReactHookForm {
+            Dialect::from_config(config)?;
+            ImportStyle::from_config(config)?;
+        }
+        Ok(())
+    }
+}
+
+/// Schemas that get a form: those with fields, or the `formSchemas` listed
+fn form_schemas<'a>(
+    schema_ir: &'a SchemaIR,
+    config: &GenerationConfig,
+) -> Result<Vec<&'a SchemaDefinition>> {
+    let Some(value) = config.options.get("formSchemas") else {
+        return Ok(schema_ir
+            .schemas
+            .iter()
+            .filter(|schema| !schema.fields.is_empty())
+            .collect());
+    };
+    let names: Vec<&str> = value
+        .as_sequence()
+        .and_then(|names| names.iter().map(|name| name.as_str()).collect())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "{}: option 'formSchemas' must be a list of schema names",
+                config.generator
+            )
+        })?;
+    names
+        .into_iter()
+        .map(|name| {
+            schema_ir
+                .schemas
+                .iter()
+                .find(|schema| schema.name == name)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "{}: option 'formSchemas': no schema named '{}'",
+                        config.generator,
+                        name
+                    )
+                })
+        })
+        .collect()
+}
+
+fn find<'a>(schema_ir: &'a SchemaIR, name: &str) -> Option<&'a SchemaDefinition> {
+    schema_ir.schemas.iter().find(|schema| schema.name == name)
+}
+
+/// `first_name` / `firstName` → `First name`; acronyms are kept (`homepageURL` → `Homepage URL`)
+fn label(name: &str) -> String {
+    let mut words: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            previous = None;
+            continue;
+        }
+        let boundary = match previous {
+            None => true,
+            Some(previous) => {
+                c.is_uppercase() && (previous.is_lowercase() || previous.is_ascii_digit())
+            }
+        };
+        match words.last_mut() {
+            Some(word) if !boundary => word.push(c),
+            _ => words.push(c.to_string()),
+        }
+        previous = Some(c);
+    }
+    let words: Vec<String> = words
+        .into_iter()
+        .map(|word| {
+            let acronym = word.chars().count() > 1 && word.chars().all(|c| !c.is_lowercase());
+            if acronym {
+                word
+            } else {
+                word.to_lowercase()
+            }
+        })
+        .collect();
+    let text = words.join(" ");
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => text,
+    }
+}
+
+/// HTML input type of a scalar
+fn input_type(type_info: &TypeInfo) -> &'static str {
+    match (type_info.openapi_type.as_str(), type_info.format.as_deref()) {
+        (_, _) if type_info.enum_values.is_some() => "select",
+        ("string", Some("email")) => "email",
+        ("string", Some("uri" | "url")) => "url",
+        ("string", Some("password")) => "password",
+        ("string", Some("date")) => "date",
+        ("string", Some("date-time")) => "datetime-local",
+        ("string", Some("time")) => "time",
+        ("string", Some("binary")) => "file",
+        ("string", _) => "text",
+        ("integer" | "number", _) => "number",
+        ("boolean", _) => "checkbox",
+        _ => "json",
+    }
+}
+
+fn generate_react_hook_form(
+    schema_ir: &SchemaIR,
+    schemas: &[&SchemaDefinition],
+    config: &GenerationConfig,
+    dialect: &Dialect,
+    import_style: &ImportStyle,
+) -> String {
+    let client_import = config
+        .options
+        .get("clientImport")
+        .and_then(|v| v.as_str())
+        .unwrap_or("./client");
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        "// Generated Forms for {}\n",
+        schema_ir.metadata.title
+    ));
+    output.push_str(&format!("// Version: {}\n\n", schema_ir.metadata.version));
+
+    let (resolver, module) = match dialect.library {
+        ValidationLibrary::Zod => ("zodResolver", "@hookform/resolvers/zod"),
+        ValidationLibrary::Valibot => ("valibotResolver", "@hookform/resolvers/valibot"),
+        ValidationLibrary::Arktype => ("arktypeResolver", "@hookform/resolvers/arktype"),
+    };
+    output.push_str(&import_style.values(&[resolver], module));
+    output.push_str(&import_style.types(&["FieldValues", "Path"], "react-hook-form"));
+    let type_names: Vec<String> = schemas
+        .iter()
+        .map(|schema| naming::type_name(&schema.name, Language::TypeScript))
+        .collect();
+    if !type_names.is_empty() {
+        let schema_names: Vec<String> = type_names
+            .iter()
+            .map(|name| format!("{}Schema", name))
+            .collect();
+        let schema_names: Vec<&str> = schema_names.iter().map(String::as_str).collect();
+        output.push_str(&import_style.values(&schema_names, client_import));
+        let type_names: Vec<&str> = type_names.iter().map(String::as_str).collect();
+        output.push_str(&import_style.types(&type_names, client_import));
+    }
+    output.push_str(FORM_FIELD);
+
+    for (schema, type_name) in schemas.iter().zip(&type_names) {
+        let binding = naming::camel_case(type_name);
+        let mut fields = Vec::new();
+        collect_fields(
+            schema_ir,
+            schema,
+            "",
+            true,
+            &mut vec![schema.name.as_str()],
+            &mut fields,
+        );
+
+        output.push_str(&format!(
+            "\n/** `useForm<{0}>({{ resolver: {1}Resolver }})`, rendering `{1}Fields` */\n",
+            type_name, binding
+        ));
+        output.push_str(&format!(
+            "export const {}Resolver = {}({}Schema);\n\n",
+            binding, resolver, type_name
+        ));
+        output.push_str(&format!(
+            "export const {}Fields: FormField<{}>[] = [\n",
+            binding, type_name
+        ));
+        for field in fields {
+            output.push_str(&format!("  {},\n", field));
+        }
+        output.push_str("];\n");
+    }
+    output
+}
+
+/// Object literals of `schema`'s fields under `prefix`; `stack` holds the
+/// schemas being flattened, so cyclic fields stay JSON inputs
+fn collect_fields<'a>(
+    schema_ir: &'a SchemaIR,
+    schema: &SchemaDefinition,
+    prefix: &str,
+    required: bool,
+    stack: &mut Vec<&'a str>,
+    fields: &mut Vec<String>,
+) {
+    for field in super::all_of::flattened_fields(schema, &schema_ir.schemas) {
+        let name = format!("{}{}", prefix, field.name);
+        let type_info = &field.type_info;
+
+        // Required only when every object on the way is
+        let required = required && field.required;
+        let nested = type_info
+            .reference
+            .as_deref()
+            .filter(|_| !type_info.is_array)
+            .and_then(|reference| find(schema_ir, reference))
+            .filter(|nested| !nested.fields.is_empty() && !stack.contains(&nested.name.as_str()));
+        if let Some(nested) = nested {
+            stack.push(&nested.name);
+            collect_fields(
+                schema_ir,
+                nested,
+                &format!("{}.", name),
+                required,
+                stack,
+                fields,
+            );
+            stack.pop();
+            continue;
+        }
+        fields.push(field_literal(&name, &field, required));
+    }
+}
+
+fn field_literal(name: &str, field: &FieldDefinition, required: bool) -> String {
+    let type_info = &field.type_info;
+    let item = type_info
+        .array_item_type
+        .as_deref()
+        .filter(|_| type_info.is_array);
+    let (kind, options) = match item {
+        Some(item) if item.enum_values.is_some() => ("multiselect", item.enum_values.as_ref()),
+        Some(_) => ("array", None),
+        None if type_info.reference.is_some() || !type_info.union_members.is_empty() => {
+            ("json", None)
+        }
+        None => (input_type(type_info), type_info.enum_values.as_ref()),
+    };
+
+    let mut entries = vec![
+        format!("name: {}", naming::quote(name)),
+        format!("label: {}", naming::quote(&label(&field.name))),
+        format!("type: {}", naming::quote(kind)),
+        format!("required: {}", required),
+    ];
+    if let Some(description) = &field.description {
+        entries.push(format!(
+            "description: {}",
+            naming::quote(description.trim())
+        ));
+    }
+    if let Some(options) = options {
+        let options: Vec<String> = options.iter().map(|option| naming::quote(option)).collect();
+        entries.push(format!("options: [{}]", options.join(", ")));
+    }
+    let constraints = &field.constraints;
+    if kind == "number" {
+        if let Some(minimum) = constraints
+            .minimum
+            .filter(|_| !constraints.exclusive_minimum)
+        {
+            entries.push(format!("min: {}", minimum));
+        }
+        if let Some(maximum) = constraints
+            .maximum
+            .filter(|_| !constraints.exclusive_maximum)
+        {
+            entries.push(format!("max: {}", maximum));
+        }
+        match constraints.multiple_of {
+            Some(step) => entries.push(format!("step: {}", step)),
+            None if type_info.openapi_type == "integer" => entries.push("step: 1".to_string()),
+            None => {}
+        }
+    }
+    if matches!(kind, "text" | "email" | "url" | "password") {
+        if let Some(min_length) = constraints.min_length {
+            entries.push(format!("minLength: {}", min_length));
+        }
+        if let Some(max_length) = constraints.max_length {
+            entries.push(format!("maxLength: {}", max_length));
+        }
+        if let Some(pattern) = &constraints.pattern {
+            entries.push(format!("pattern: {}", naming::quote(pattern)));
+        }
+    }
+    format!("{{ {} }}", entries.join(", "))
+}
+
+const FORM_FIELD: &str = r#"
+/** One input of a generated form */
+export interface FormField<T extends FieldValues> {
+  /** Path of the value in the form values; dotted for nested objects (`address.street`) */
+  name: Path<T>;
+  label: string;
+  /** An HTML input type, `select` / `multiselect` (from `options`), `array`, or `json` for raw JSON */
+  type:
+    | 'text' | 'email' | 'url' | 'password' | 'date' | 'datetime-local' | 'time' | 'file'
+    | 'number' | 'checkbox' | 'select' | 'multiselect' | 'array' | 'json';
+  required: boolean;
+  description?: string;
+  options?: readonly string[];
+  min?: number;
+  max?: number;
+  step?: number;
+  minLength?: number;
+  maxLength?: number;
+  pattern?: string;
+}
+"#;
+
+fn generate_json_forms(schema_ir: &SchemaIR, schemas: &[&SchemaDefinition]) -> Result<String> {
+    let mut forms = Map::new();
+    for schema in schemas {
+        let mut definitions = Map::new();
+        let mut json_schema = object_schema(schema_ir, schema, &mut definitions);
+        if !definitions.is_empty() {
+            json_schema["definitions"] = Value::Object(definitions);
+        }
+        let elements: Vec<Value> = super::all_of::flattened_fields(schema, &schema_ir.schemas)
+            .iter()
+            .map(|field| {
+                json!({
+                    "type": "Control",
+                    "scope": format!("#/properties/{}", field.name.replace('~', "~0").replace('/', "~1")),
+                    "label": label(&field.name),
+                })
+            })
+            .collect();
+        forms.insert(
+            schema.name.clone(),
+            json!({
+                "schema": json_schema,
+                "uischema": { "type": "VerticalLayout", "elements": elements },
+            }),
+        );
+    }
+    Ok(serde_json::to_string_pretty(&Value::Object(forms))? + "\n")
+}
+
+/// JSON Schema of an object schema; the schemas it references are added to `definitions`
+fn object_schema(
+    schema_ir: &SchemaIR,
+    schema: &SchemaDefinition,
+    definitions: &mut Map<String, Value>,
+) -> Value {
+    let fields = super::all_of::flattened_fields(schema, &schema_ir.schemas);
+    let mut properties = Map::new();
+    for field in &fields {
+        let mut property = type_schema(schema_ir, &field.type_info, definitions);
+        property["title"] = json!(label(&field.name));
+        if let Some(description) = &field.description {
+            property["description"] = json!(description.trim());
+        }
+        let constraints = &field.constraints;
+        let keywords = [
+            ("minLength", constraints.min_length.map(|n| json!(n))),
+            ("maxLength", constraints.max_length.map(|n| json!(n))),
+            ("pattern", constraints.pattern.as_ref().map(|p| json!(p))),
+            ("multipleOf", constraints.multiple_of.map(|n| json!(n))),
+            ("minItems", constraints.min_items.map(|n| json!(n))),
+            ("maxItems", constraints.max_items.map(|n| json!(n))),
+        ];
+        for (keyword, value) in keywords {
+            if let Some(value) = value {
+                property[keyword] = value;
+            }
+        }
+        // OpenAPI 3.0's boolean exclusive bounds become draft-07's numeric ones
+        let bounds = [
+            (
+                constraints.minimum,
+                constraints.exclusive_minimum,
+                "minimum",
+                "exclusiveMinimum",
+            ),
+            (
+                constraints.maximum,
+                constraints.exclusive_maximum,
+                "maximum",
+                "exclusiveMaximum",
+            ),
+        ];
+        for (bound, exclusive, inclusive_keyword, exclusive_keyword) in bounds {
+            if let Some(bound) = bound {
+                property[if exclusive {
+                    exclusive_keyword
+                } else {
+                    inclusive_keyword
+                }] = json!(bound);
+            }
+        }
+        properties.insert(field.name.clone(), property);
+    }
+    let required: Vec<&str> = fields
+        .iter()
+        .filter(|f| f.required)
+        .map(|f| f.name.as_str())
+        .collect();
+
+    let mut object = json!({ "type": "object", "properties": properties });
+    if let Some(description) = &schema.description {
+        object["description"] = json!(description.trim());
+    }
+    if !required.is_empty() {
+        object["required"] = json!(required);
+    }
+    object
+}
+
+fn type_schema(
+    schema_ir: &SchemaIR,
+    type_info: &TypeInfo,
+    definitions: &mut Map<String, Value>,
+) -> Value {
+    if type_info.is_array {
+        let items = type_info.array_item_type.as_deref().map_or_else(
+            || json!({}),
+            |item| type_schema(schema_ir, item, definitions),
+        );
+        let mut array = json!({ "type": "array", "items": items });
+        if type_info
+            .array_item_type
+            .as_ref()
+            .is_some_and(|item| item.enum_values.is_some())
+        {
+            array["uniqueItems"] = json!(true);
+        }
+        return array;
+    }
+    if let Some(reference) = &type_info.reference {
+        let Some(referenced) = find(schema_ir, reference) else {
+            return json!({});
+        };
+        if !definitions.contains_key(reference) {
+            // Inserted before it is built, so cycles end at the reference
+            definitions.insert(reference.clone(), json!({}));
+            let built = object_schema(schema_ir, referenced, definitions);
+            definitions.insert(reference.clone(), built);
+        }
+        return json!({ "$ref": format!("#/definitions/{}", reference) });
+    }
+    if !type_info.union_members.is_empty() {
+        let members: Vec<Value> = type_info
+            .union_members
+            .iter()
+            .map(|member| type_schema(schema_ir, member, definitions))
+            .collect();
+        return json!({ "anyOf": members });
+    }
+    if let Some(values) = &type_info.map_values {
+        return json!({ "type": "object", "additionalProperties": type_schema(schema_ir, values, definitions) });
+    }
+
+    let mut schema = match type_info.openapi_type.as_str() {
+        kind @ ("string" | "integer" | "number" | "boolean" | "object") => json!({ "type": kind }),
+        _ => json!({}),
+    };
+    if let Some(format) = type_info
+        .format
+        .as_ref()
+        .filter(|_| type_info.openapi_type == "string")
+    {
+        schema["format"] = json!(format);
+    }
+    if let Some(values) = &type_info.enum_values {
+        schema["enum"] = json!(values);
+    }
+    schema
+}
@@ -1,9 +1,23 @@
+use super::conditional::Conditionals;
+use super::docs::{DocStyle, SeeAlso};
+use super::hypermedia::Hypermedia;
+use super::idempotency::{self, IdempotencyKey};
+use super::naming::{self, Language};
+use super::rate_limit::RateLimitOption;
+use super::readme;
+use super::request_builders::{self, RequestBuilders};
+use super::request_encoding;
+use super::templates;
+use super::type_overrides::TypeOverrides;
 use super::{GeneratedOutput, Generator};
 use crate::config::GenerationConfig;
-use crate::parsers::SchemaIR;
+use crate::parsers::{
+    BodyKind, FieldDefinition, OperationDefinition, ParameterLocation, ResponseDefinition,
+    SchemaDefinition, SchemaIR, TypeInfo,
+};
 use anyhow::Result;
 use std::collections::HashMap;
-use tera::{Context, Tera};
+use tera::Context;
 
 pub struct GolangGenerator;
 
@@ -21,15 +35,50 @@ impl Generator for GolangGenerator {
         schema_ir: &SchemaIR,
         config: &GenerationConfig,
     ) -> Result<GeneratedOutput> {
-        // Determine template path
-        let template_path = config
-            .template
-            .as_ref()
-            .and_then(|p| p.to_str())
-            .unwrap_or("templates/golang");
-
-        let tera = Tera::new(&format!("{}/**/*.tera", template_path))?;
+        let tera = templates::load(config, templates::GOLANG)?;
+        let context = self.context(schema_ir, config)?;
+
+        // Render template
+        let content = tera.render("client.go.tera", &context)?;
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content,
+            metadata: HashMap::new(),
+            companions: readme::companion(schema_ir, config, readme::Client::Go)
+                .into_iter()
+                .collect(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn template_context(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<Option<serde_json::Value>> {
+        Ok(Some(self.context(schema_ir, config)?.into_json()))
+    }
+
+    fn supports_inheritance(&self, _config: &GenerationConfig) -> bool {
+        true
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        OptionalStrategy::from_config(config)?;
+        DocStyle::from_config(config)?;
+        EnumStyle::from_config(config)?;
+        TypeOverrides::from_config(config)?;
+        RequestBuilders::from_config(config)?;
+        RateLimitOption::from_config(config).map(|_| ())
+    }
+}
+
+impl GolangGenerator {
+    /// The context `client.go.tera` is rendered with
+    fn context(&self, schema_ir: &SchemaIR, config: &GenerationConfig) -> Result<Context> {
         let mut context = Context::new();
+        context.insert("context_version", &templates::CONTEXT_VERSION);
 
         // Add metadata
         context.insert("api_title", &schema_ir.metadata.title);
@@ -43,66 +92,504 @@ impl Generator for GolangGenerator {
                 .unwrap_or_else(|| "http://localhost".to_string()),
         );
 
+        let doc_style = DocStyle::from_config(config)?;
+        let optional_strategy = OptionalStrategy::from_config(config)?;
+        let enum_style = EnumStyle::from_config(config)?;
+        let mut enums = Vec::new();
+        let schema_names: std::collections::HashSet<String> = schema_ir
+            .schemas
+            .iter()
+            .map(|schema| naming::type_name(&schema.name, Language::Go))
+            .collect();
+
         // Convert schemas for template
         let schemas_for_template: Vec<_> = schema_ir
             .schemas
             .iter()
-            .map(|schema| {
+            .filter_map(|schema| {
+                let name = naming::type_name(&schema.name, Language::Go);
+                let doc = doc_style.enabled().then(|| {
+                    doc_style
+                        .go_comment(&name, &[schema.description.as_deref()], "")
+                        .unwrap_or_else(|| format!("// {}", name))
+                });
+
+                // Enum schemas are named types either way; only `typed` validates them
+                if let Some((base, values)) = component_enum(schema) {
+                    enums.push(enum_type(&name, doc, &base, &values, enum_style == EnumStyle::Typed));
+                    return None;
+                }
+
+                let field_names = naming::field_names(schema.fields.iter().map(|f| f.name.as_str()), Language::Go);
                 let properties: Vec<_> = schema
                     .fields
                     .iter()
-                    .map(|field| {
+                    .zip(field_names)
+                    .map(|(field, field_name)| {
+                        let enum_name = field_enum(&field.type_info)
+                            .filter(|_| enum_style == EnumStyle::Typed)
+                            .map(|values| {
+                                let mut enum_name = format!("{}{}", name, field_name);
+                                if schema_names.contains(&enum_name) {
+                                    enum_name.push_str("Enum");
+                                }
+                                let doc = doc_style.enabled().then(|| format!("// {} - Values of {}.{}", enum_name, name, field_name));
+                                enums.push(enum_type(&enum_name, doc, "string", &string_literals(values), true));
+                                enum_name
+                            });
                         serde_json::json!({
-                            "name": field.name,
-                            "golang_type": field.type_info.to_golang(),
+                            "doc": doc_style.go_comment(&field_name, &[field.description.as_deref()], "\t"),
+                            "name": field_name,
+                            "golang_type": field_type(field, optional_strategy, enum_name.as_deref()),
                             "required": field.required,
-                            "json_tag": field.name,
+                            // A bare `-` tag would skip the field
+                            "json_tag": if field.name == "-" { "-," } else { field.name.as_str() },
                         })
                     })
                     .collect();
 
-                serde_json::json!({
-                    "name": schema.name,
+                // Embedded structs, whose fields encoding/json inlines
+                let embedded: Vec<String> = schema
+                    .parents
+                    .iter()
+                    .map(|parent| naming::type_name(parent, Language::Go))
+                    .collect();
+
+                Some(serde_json::json!({
+                    "doc": doc,
+                    "name": name,
+                    "embedded": embedded,
                     "properties": properties,
                     "description": schema.description,
-                })
+                }))
             })
             .collect();
 
         context.insert("schemas", &schemas_for_template);
+        context.insert("enums", &enums);
 
         // Convert operations
+        // Idempotency keys on unsafe calls are opt-in
+        let idempotency = IdempotencyKey::from_config(config)?;
+        // The plain method of each operation, and a wrapper for its ETag precondition
+        let conditionals = Conditionals::new(schema_ir, config);
+        context.insert("conditional_requests", &conditionals.any(schema_ir));
+        // Fluent setters for the optional parameters are opt-in
+        let builders = RequestBuilders::from_config(config)?;
         let operations_for_template: Vec<_> = schema_ir
             .operations
             .iter()
             .map(|op| {
+                let id = naming::function_name(&op.id, Language::Go);
+                let idents = naming::parameter_names(
+                    op.parameters.iter().map(|p| p.name.as_str()),
+                    Language::Go,
+                    naming::GO_METHOD_LOCALS,
+                );
+                let param_docs: Vec<_> = op
+                    .parameters
+                    .iter()
+                    .zip(&idents)
+                    .filter_map(|(p, ident)| Some(format!("  {}: {}", ident, p.description.as_deref()?)))
+                    .collect();
+                let param_section = (!param_docs.is_empty())
+                    .then(|| format!("Parameters:\n{}", param_docs.join("\n")));
+                let summary = op.summary.as_deref().unwrap_or(&op.id);
+
+                // Non-JSON bodies are passed through as raw strings
+                let request_body = op.request_content_type.as_ref().map(|content_type| {
+                    let kind = BodyKind::from_content_type(content_type);
+                    let body_type = match op.request_body.as_ref().filter(|_| kind == BodyKind::Json) {
+                        Some(b) if b.schema_type == "array" => {
+                            format!("[]{}", naming::type_name(&b.name, Language::Go))
+                        }
+                        Some(b) => format!("*{}", naming::type_name(&b.name, Language::Go)),
+                        None if kind == BodyKind::Json => "interface{}".to_string(),
+                        None => "string".to_string(),
+                    };
+                    serde_json::json!({
+                        "content_type": content_type,
+                        "kind": kind,
+                        "body_type": body_type,
+                    })
+                });
+
+                // Decoded success body; operations without one return only the response
+                let result = op.response_content_type.as_ref().map(|content_type| {
+                    let kind = BodyKind::from_content_type(content_type);
+                    let inline = op.returned_response().and_then(|r| r.inline_type.as_ref());
+                    let decode_type = match op.response.as_ref().filter(|_| kind == BodyKind::Json) {
+                        Some(r) if r.schema_type == "array" => {
+                            format!("[]{}", naming::type_name(&r.name, Language::Go))
+                        }
+                        Some(r) => naming::type_name(&r.name, Language::Go),
+                        None if kind == BodyKind::Json => inline.map_or_else(|| "interface{}".to_string(), TypeInfo::to_golang),
+                        // Downloads are handed over unread, for the caller to stream and close
+                        None if kind == BodyKind::Binary => "io.ReadCloser".to_string(),
+                        None => "string".to_string(),
+                    };
+                    let named_struct = (op.response.as_ref().is_some_and(|r| r.schema_type != "array")
+                        || inline.is_some_and(|t| t.reference.is_some() && !t.is_array))
+                        && kind == BodyKind::Json;
+                    let (go_type, zero) = match (named_struct, kind) {
+                        (true, _) => (format!("*{}", decode_type), "nil".to_string()),
+                        (false, BodyKind::Json) => (decode_type.clone(), zero_value(&decode_type)),
+                        (false, BodyKind::Binary) => (decode_type.clone(), "nil".to_string()),
+                        (false, _) => (decode_type.clone(), "\"\"".to_string()),
+                    };
+                    serde_json::json!({
+                        "kind": kind,
+                        "go_type": go_type,
+                        "decode_type": decode_type,
+                        "zero": zero,
+                    })
+                });
+
+                let parameters: Vec<_> = op.parameters.iter().zip(&idents).map(|(p, ident)| {
+                    let encoding = request_encoding::Plan::of(p);
+                    // Optional scalars are pointers; a nil slice is an absent array
+                    let pointer = !p.required && !encoding.array && p.location != ParameterLocation::Path;
+                    serde_json::json!({
+                        "name": p.name,
+                        "ident": ident,
+                        "location": format!("{:?}", p.location).to_lowercase(),
+                        "required": p.required,
+                        "schema_type": p.schema_type,
+                        "type": format!("{}{}", if pointer { "*" } else { "" }, encoding.value_type(Language::Go)),
+                        "optional": pointer || (encoding.array && !p.required),
+                        "value": format!("{}{}", if pointer { "*" } else { "" }, ident),
+                        "encoding": encoding,
+                    })
+                }).collect();
+                let body_type = request_body.as_ref().and_then(|b| b["body_type"].as_str());
+                let builder = builders.applies(op).then(|| call_builder(op, &id, &parameters, body_type));
+
+                // Error bodies the spec declares, decoded into APIError.Payload by status
+                let errors: Vec<_> = op
+                    .error_responses()
+                    .into_iter()
+                    .filter_map(|response| {
+                        let condition = match response.status.as_str() {
+                            "default" => None,
+                            status => Some(match status.strip_suffix("XX") {
+                                Some(class) => format!("statusCode/100 == {}", class),
+                                None => format!("statusCode == {}", status),
+                            }),
+                        };
+                        Some(serde_json::json!({
+                            "status": response.status,
+                            "condition": condition,
+                            "payload_type": error_payload_type(response)?,
+                        }))
+                    })
+                    .collect();
+
                 serde_json::json!({
-                    "id": op.id,
+                    "request_body": request_body,
+                    "result": result,
+                    "errors": errors,
+                    // A `default` case ends the switch, with nothing left to return after it
+                    "errors_default": errors.iter().any(|e| e["condition"].is_null()),
+                    "download": op.downloads(),
+                    "doc": doc_style.go_comment_see(
+                        &id,
+                        &[Some(summary), op.description.as_deref(), param_section.as_deref()],
+                        &SeeAlso::of(schema_ir, op),
+                        "",
+                    ),
+                    "id": id,
+                    "operation_id": op.id,
+                    "rate_limit": op.rate_limit,
+                    "idempotency_key": idempotency.applies(op),
+                    "timeout_ms": op.timeout_ms,
+                    "feature_flag": op.feature_flag.as_deref().map(naming::quote),
+                    "variants": conditionals.variants(op),
+                    "builder": builder,
                     "method": format!("{:?}", op.method).to_uppercase(),
                     "path": op.path,
-                    "parameters": op.parameters.iter().map(|p| {
-                        serde_json::json!({
-                            "name": p.name,
-                            "location": format!("{:?}", p.location).to_lowercase(),
-                            "required": p.required,
-                            "schema_type": p.schema_type,
-                        })
-                    }).collect::<Vec<_>>(),
-                    "responses": serde_json::json!([]),  // TODO: populate from op.response
+                    "parameters": parameters,
                 })
             })
             .collect();
 
         context.insert("operations", &operations_for_template);
+        let typed_errors = operations_for_template
+            .iter()
+            .any(|op| op["errors"].as_array().is_some_and(|e| !e.is_empty()));
+        context.insert("typed_errors", &typed_errors);
+
+        // OpenTelemetry spans around every client call are opt-in
+        let open_telemetry = config
+            .options
+            .get("openTelemetry")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        context.insert("telemetry", &open_telemetry);
+        context.insert("tracer_name", &naming::quote(&schema_ir.metadata.title));
+
+        // Client-side throttling is opt-in; the spec's `x-rate-limit` supplies the defaults
+        let rate_limit = RateLimitOption::from_config(config)?;
+        context.insert("rate_limit", &rate_limit.enabled());
+        context.insert("client_rate_limit", &rate_limit.client_limit(schema_ir));
+        context.insert("idempotency_key", &idempotency.enabled());
+        context.insert("idempotency_header", &idempotency::header_name(config)?);
+        // Link helpers for the HAL / JSON:API conventions the spec follows
+        context.insert(
+            "hypermedia",
+            &Hypermedia::from_config(config)?.formats(schema_ir),
+        );
+        // Names of the schemas added for JSON Patch bodies and hypermedia links
+        context.insert("support_types", &naming::support_type_names(Language::Go));
+        // Packages of the external types standing in for schemas and formats
+        let type_imports: Vec<_> = TypeOverrides::from_config(config)?
+            .imports(schema_ir)
+            .into_keys()
+            .collect();
+        context.insert("type_imports", &type_imports);
+        // File downloads skip reading the body, and get the filename helper
+        context.insert(
+            "downloads",
+            &schema_ir.operations.iter().any(|op| op.downloads()),
+        );
+        // Flag checks before operations declaring `x-feature-flag`
+        context.insert(
+            "feature_flags",
+            &schema_ir
+                .operations
+                .iter()
+                .any(|op| op.feature_flag.is_some()),
+        );
         context.insert("options", &config.options);
 
-        // Render template
-        let content = tera.render("client.go.tera", &context)?;
+        Ok(context)
+    }
+}
 
-        Ok(GeneratedOutput {
-            filename: config.output_file.clone(),
-            content,
-            metadata: HashMap::new(),
+/// How optional and nullable struct fields are typed (`optionalStrategy` option)
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OptionalStrategy {
+    /// `*T`, so an absent value is distinguishable from the zero value
+    Pointer,
+    /// Plain `T`; absence collapses to the zero value
+    Value,
+}
+
+impl OptionalStrategy {
+    fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("optionalStrategy").map(|v| v.as_str()) {
+            None | Some(Some("pointer")) => Ok(Self::Pointer),
+            Some(Some("value")) => Ok(Self::Value),
+            _ => {
+                anyhow::bail!("golang: option 'optionalStrategy' must be \"pointer\" or \"value\"")
+            }
+        }
+    }
+}
+
+/// How enum schemas are typed (`enumStyle` option)
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EnumStyle {
+    /// Enum schemas as named types with a constant per value; inline field
+    /// enums as the underlying `string`
+    String,
+    /// Named type with a constant per value, `IsValid()`, and validating `UnmarshalJSON`
+    Typed,
+}
+
+impl EnumStyle {
+    fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("enumStyle").map(|v| v.as_str()) {
+            None | Some(Some("string")) => Ok(Self::String),
+            Some(Some("typed")) => Ok(Self::Typed),
+            _ => anyhow::bail!("golang: option 'enumStyle' must be \"string\" or \"typed\""),
+        }
+    }
+}
+
+/// Underlying Go type and value literals of a component schema that is an enum
+fn component_enum(schema: &SchemaDefinition) -> Option<(String, Vec<String>)> {
+    let values = schema.original.get("enum")?.as_array()?;
+    if !schema.fields.is_empty() || values.is_empty() {
+        return None;
+    }
+
+    let base = match schema.original.get("type").and_then(|t| t.as_str()) {
+        Some("integer") => "int",
+        Some("number") => "float64",
+        _ => "string",
+    };
+    let literals = values
+        .iter()
+        .map(|value| match value {
+            serde_json::Value::String(s) => naming::quote(s),
+            other => other.to_string(),
         })
+        .collect();
+    Some((base.to_string(), literals))
+}
+
+/// Inline enum values of a field or of its array items
+fn field_enum(type_info: &TypeInfo) -> Option<&Vec<String>> {
+    match &type_info.array_item_type {
+        Some(item) if type_info.is_array => item.enum_values.as_ref(),
+        _ => type_info.enum_values.as_ref(),
     }
+    .filter(|values| !values.is_empty())
+}
+
+fn string_literals(values: &[String]) -> Vec<String> {
+    values.iter().map(|v| naming::quote(v)).collect()
+}
+
+/// Template data for a named enum type with one constant per literal, and
+/// `IsValid()` plus a rejecting `UnmarshalJSON` when `validated`
+fn enum_type(
+    name: &str,
+    doc: Option<String>,
+    base: &str,
+    literals: &[String],
+    validated: bool,
+) -> serde_json::Value {
+    let mut taken = std::collections::HashSet::new();
+    let values: Vec<_> = literals
+        .iter()
+        .map(|literal| {
+            let base = naming::enum_member(name, literal.trim_matches('"'), Language::Go);
+            let mut constant = base.clone();
+            let mut n = 2;
+            while !taken.insert(constant.clone()) {
+                constant = format!("{}{}", base, n);
+                n += 1;
+            }
+            serde_json::json!({ "name": constant, "literal": literal })
+        })
+        .collect();
+
+    serde_json::json!({
+        "doc": doc,
+        "name": name,
+        "base": base,
+        "values": values,
+        "validated": validated,
+    })
+}
+
+/// Zero value of a decoded JSON body type, returned alongside an error
+fn zero_value(go_type: &str) -> String {
+    match go_type {
+        "string" => "\"\"".to_string(),
+        "bool" => "false".to_string(),
+        _ if go_type.starts_with("int") || go_type.starts_with("float") => "0".to_string(),
+        _ if ["[]", "map[", "*", "interface{}"]
+            .iter()
+            .any(|nilable| go_type.starts_with(nilable)) =>
+        {
+            "nil".to_string()
+        }
+        _ => format!("*new({})", go_type),
+    }
+}
+
+/// Type an error response's JSON body is decoded into; `None` without one
+fn error_payload_type(response: &ResponseDefinition) -> Option<String> {
+    if BodyKind::from_content_type(response.content_type.as_deref()?) != BodyKind::Json {
+        return None;
+    }
+    match (&response.schema, &response.inline_type) {
+        (Some(schema), _) if response.is_array() => Some(format!(
+            "[]{}",
+            naming::type_name(&schema.name, Language::Go)
+        )),
+        (Some(schema), _) => Some(naming::type_name(&schema.name, Language::Go)),
+        (None, Some(type_info)) => Some(type_info.to_golang()),
+        (None, None) => None,
+    }
+}
+
+/// Struct field type; slices, maps, and interfaces are already nilable
+fn field_type(
+    field: &FieldDefinition,
+    strategy: OptionalStrategy,
+    enum_type: Option<&str>,
+) -> String {
+    let golang_type = match enum_type {
+        Some(name) if field.type_info.is_array => format!("[]{}", name),
+        Some(name) => name.to_string(),
+        None => field.type_info.to_golang(),
+    };
+    let nilable = ["[]", "map[", "interface{}"]
+        .iter()
+        .any(|prefix| golang_type.starts_with(prefix));
+
+    if strategy == OptionalStrategy::Pointer && (!field.required || field.nullable) && !nilable {
+        format!("*{}", golang_type)
+    } else {
+        golang_type
+    }
+}
+
+/// The `{Operation}Call` builder of `op`: its fields (aligned as gofmt would),
+/// in the order the operation's method takes them, and a setter per optional
+/// parameter
+fn call_builder(
+    op: &OperationDefinition,
+    id: &str,
+    parameters: &[serde_json::Value],
+    body_type: Option<&str>,
+) -> serde_json::Value {
+    let ident = |param: &serde_json::Value| param["ident"].as_str().unwrap_or_default().to_string();
+    let mut client = "client".to_string();
+    while parameters.iter().any(|param| ident(param) == client) {
+        client.push('_');
+    }
+
+    let mut fields = vec![(client.clone(), "*ApiClient".to_string())];
+    for locations in [&["path"][..], &["query"], &["header", "cookie"]] {
+        for param in parameters
+            .iter()
+            .filter(|p| locations.contains(&p["location"].as_str().unwrap_or_default()))
+        {
+            fields.push((
+                ident(param),
+                param["type"].as_str().unwrap_or_default().to_string(),
+            ));
+        }
+    }
+    if let Some(body_type) = body_type {
+        fields.push(("body".to_string(), body_type.to_string()));
+    }
+    let width = fields
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|(name, field_type)| format!("{:width$} {}", name, field_type))
+        .collect();
+
+    let setters: Vec<_> = request_builders::optional_parameters(op)
+        .filter_map(|p| {
+            let param = parameters
+                .iter()
+                .find(|param| param["name"] == p.name.as_str())?;
+            let field_type = param["type"].as_str().unwrap_or_default();
+            Some(serde_json::json!({
+                "name": request_builders::setter_name(p, Language::Go),
+                "param": p.name,
+                "ident": ident(param),
+                "type": field_type.trim_start_matches('*'),
+                "pointer": field_type.starts_with('*'),
+            }))
+        })
+        .collect();
+
+    serde_json::json!({
+        "type": format!("{}Call", id),
+        "call": naming::identifier(&naming::camel_case(id), Language::Go),
+        "client": client,
+        "takes_params": body_type.is_some() || setters.len() < parameters.len(),
+        "fields": fields,
+        "setters": setters,
+    })
 }
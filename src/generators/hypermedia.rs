@@ -0,0 +1,287 @@
+//! Typed link structures for HAL and JSON:API responses (`hypermedia`
+//! generation option):
+//! - `auto` (default): each convention applies when an operation sends or
+//!   receives its media type (`application/hal+json`,
+//!   `application/vnd.api+json`)
+//! - `hal`, `jsonapi`: the convention applies regardless of media types
+//! - `none`: link fields stay as the spec declares them
+//!
+//! Fields the spec leaves as untyped objects are retyped against schemas this
+//! stage adds to the IR: HAL `_links` become a map of `HalLink` (or lists of
+//! them), JSON:API `links` a map of URLs or `JsonApiLink`, and `relationships`
+//! a map of `JsonApiRelationship`. A spec component of the same name is used
+//! instead of the added one. The `typescript`, `python`, and `golang` clients
+//! add helpers that follow links and resolve relationships on top.
+
+use crate::config::GenerationConfig;
+use crate::parsers::{Constraints, FieldDefinition, SchemaDefinition, SchemaIR, TypeInfo};
+use anyhow::Result;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// Schemas this stage adds, which the client helpers name
+pub const LINK_SCHEMAS: &[&str] = &[
+    "HalLink",
+    "JsonApiLink",
+    "JsonApiResourceIdentifier",
+    "JsonApiRelationship",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hypermedia {
+    Auto,
+    Hal,
+    JsonApi,
+    None,
+}
+
+/// Conventions an IR follows, as the client templates see them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Formats {
+    pub hal: bool,
+    pub json_api: bool,
+}
+
+impl Hypermedia {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("hypermedia").map(|v| v.as_str()) {
+            None | Some(Some("auto")) => Ok(Self::Auto),
+            Some(Some("hal")) => Ok(Self::Hal),
+            Some(Some("jsonapi")) => Ok(Self::JsonApi),
+            Some(Some("none")) => Ok(Self::None),
+            _ => anyhow::bail!(
+                "{}: option 'hypermedia' must be \"auto\", \"hal\", \"jsonapi\", or \"none\"",
+                config.generator
+            ),
+        }
+    }
+
+    /// Conventions that apply to `schema_ir` in this mode
+    pub fn formats(self, schema_ir: &SchemaIR) -> Formats {
+        let uses = |media_type: &str| {
+            schema_ir.operations.iter().any(|op| {
+                [&op.request_content_type, &op.response_content_type]
+                    .into_iter()
+                    .flatten()
+                    .any(|content_type| content_type.to_ascii_lowercase().starts_with(media_type))
+            })
+        };
+        match self {
+            Self::Auto => Formats {
+                hal: uses("application/hal+json"),
+                json_api: uses("application/vnd.api+json"),
+            },
+            Self::Hal => Formats {
+                hal: true,
+                json_api: false,
+            },
+            Self::JsonApi => Formats {
+                hal: false,
+                json_api: true,
+            },
+            Self::None => Formats::default(),
+        }
+    }
+
+    /// The IR with untyped link fields retyped and the link schemas added
+    pub fn apply(self, schema_ir: Cow<'_, SchemaIR>) -> Cow<'_, SchemaIR> {
+        let formats = self.formats(&schema_ir);
+        if formats == Formats::default() {
+            return schema_ir;
+        }
+
+        let mut prepared = schema_ir.into_owned();
+        for field in prepared
+            .schemas
+            .iter_mut()
+            .flat_map(|schema| schema.fields.iter_mut())
+        {
+            if !opaque(&field.type_info) {
+                continue;
+            }
+            let type_info = match field.name.as_str() {
+                "_links" if formats.hal => hal_links(),
+                "links" if formats.json_api => json_api_links(),
+                "relationships" if formats.json_api => map(reference("JsonApiRelationship")),
+                _ => continue,
+            };
+            field.type_info = type_info;
+        }
+
+        let mut added = Vec::new();
+        if formats.hal {
+            added.push(hal_link());
+        }
+        if formats.json_api {
+            added.extend([
+                json_api_link(),
+                json_api_resource_identifier(),
+                json_api_relationship(),
+            ]);
+        }
+        for schema in added {
+            if !prepared.schemas.iter().any(|s| s.name == schema.name) {
+                prepared.schemas.push(schema);
+            }
+        }
+        Cow::Owned(prepared)
+    }
+}
+
+/// An object the spec gives no properties, values, or schema
+fn opaque(type_info: &TypeInfo) -> bool {
+    type_info.openapi_type == "object"
+        && !type_info.is_array
+        && type_info.reference.is_none()
+        && type_info.map_values.is_none()
+}
+
+/// Relation name to a link, or to several when the relation has more than one
+fn hal_links() -> TypeInfo {
+    map(union(vec![
+        reference("HalLink"),
+        array(reference("HalLink")),
+    ]))
+}
+
+/// Link name to a URL or a link object
+fn json_api_links() -> TypeInfo {
+    map(union(vec![scalar("string"), reference("JsonApiLink")]))
+}
+
+fn hal_link() -> SchemaDefinition {
+    let mut fields = vec![
+        field("href", scalar("string"), true, false),
+        field("templated", scalar("boolean"), false, false),
+    ];
+    fields.extend(
+        [
+            "type",
+            "deprecation",
+            "name",
+            "profile",
+            "title",
+            "hreflang",
+        ]
+        .into_iter()
+        .map(|name| field(name, scalar("string"), false, false)),
+    );
+    schema(
+        "HalLink",
+        "HAL link object; `href` is a URI template when `templated` is set",
+        fields,
+    )
+}
+
+fn json_api_link() -> SchemaDefinition {
+    let mut fields = vec![field("href", scalar("string"), true, false)];
+    fields.extend(
+        ["rel", "describedby", "title", "type", "hreflang"]
+            .into_iter()
+            .map(|name| field(name, scalar("string"), false, false)),
+    );
+    fields.push(field("meta", scalar("object"), false, false));
+    schema("JsonApiLink", "JSON:API link object", fields)
+}
+
+fn json_api_resource_identifier() -> SchemaDefinition {
+    schema(
+        "JsonApiResourceIdentifier",
+        "JSON:API resource identifier; `lid` identifies resources created in the same request",
+        vec![
+            field("type", scalar("string"), true, false),
+            field("id", scalar("string"), false, false),
+            field("lid", scalar("string"), false, false),
+            field("meta", scalar("object"), false, false),
+        ],
+    )
+}
+
+fn json_api_relationship() -> SchemaDefinition {
+    let identifier = || reference("JsonApiResourceIdentifier");
+    schema(
+        "JsonApiRelationship",
+        "JSON:API relationship; `data` holds one identifier, a list of them, or null",
+        vec![
+            field(
+                "data",
+                union(vec![identifier(), array(identifier())]),
+                false,
+                true,
+            ),
+            field("links", json_api_links(), false, false),
+            field("meta", scalar("object"), false, false),
+        ],
+    )
+}
+
+fn schema(name: &str, description: &str, fields: Vec<FieldDefinition>) -> SchemaDefinition {
+    SchemaDefinition {
+        name: name.to_string(),
+        fields,
+        description: Some(description.to_string()),
+        translations: IndexMap::new(),
+        parents: Vec::new(),
+        merge_patch_of: None,
+        audiences: Vec::new(),
+        original: serde_json::Value::Null,
+    }
+}
+
+fn field(name: &str, type_info: TypeInfo, required: bool, nullable: bool) -> FieldDefinition {
+    FieldDefinition {
+        name: name.to_string(),
+        type_info,
+        required,
+        nullable,
+        constraints: Constraints::default(),
+        description: None,
+        translations: IndexMap::new(),
+        audiences: Vec::new(),
+        original: serde_json::Value::Null,
+    }
+}
+
+fn scalar(openapi_type: &str) -> TypeInfo {
+    TypeInfo {
+        openapi_type: openapi_type.to_string(),
+        format: None,
+        is_array: false,
+        array_item_type: None,
+        reference: None,
+        enum_values: None,
+        union_members: Vec::new(),
+        tuple_items: Vec::new(),
+        map_values: None,
+    }
+}
+
+fn reference(name: &str) -> TypeInfo {
+    TypeInfo {
+        reference: Some(name.to_string()),
+        ..scalar("object")
+    }
+}
+
+fn array(item: TypeInfo) -> TypeInfo {
+    TypeInfo {
+        is_array: true,
+        array_item_type: Some(Box::new(item)),
+        ..scalar("array")
+    }
+}
+
+fn union(members: Vec<TypeInfo>) -> TypeInfo {
+    TypeInfo {
+        union_members: members,
+        ..scalar("union")
+    }
+}
+
+fn map(values: TypeInfo) -> TypeInfo {
+    TypeInfo {
+        map_values: Some(Box::new(values)),
+        ..scalar("object")
+    }
+}
@@ -0,0 +1,69 @@
+//! Idempotency keys for the `typescript`, `python`, and `golang` clients
+//! (`idempotencyKey` option):
+//! - `false` (default): no key is sent
+//! - `flagged` (or `true`): operations declaring `x-idempotency: true`
+//! - `unsafe`: every POST, PUT, PATCH, and DELETE, except operations declaring
+//!   `x-idempotency: false`
+//!
+//! Those operations send a UUID v4 in the `idempotencyHeader` header
+//! (`Idempotency-Key` by default) unless the caller supplies a key, as a
+//! declared header parameter or through the client's per-call option. Retries
+//! of a call reuse its key.
+
+use crate::config::GenerationConfig;
+use crate::parsers::{HttpMethod, OperationDefinition};
+use anyhow::Result;
+use serde_yaml::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdempotencyKey {
+    Off,
+    Flagged,
+    Unsafe,
+}
+
+impl IdempotencyKey {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("idempotencyKey") {
+            None | Some(Value::Bool(false)) => Ok(Self::Off),
+            Some(Value::Bool(true)) => Ok(Self::Flagged),
+            Some(Value::String(mode)) if mode == "flagged" => Ok(Self::Flagged),
+            Some(Value::String(mode)) if mode == "unsafe" => Ok(Self::Unsafe),
+            _ => anyhow::bail!(
+                "{}: option 'idempotencyKey' must be a boolean, \"flagged\", or \"unsafe\"",
+                config.generator
+            ),
+        }
+    }
+
+    pub fn enabled(self) -> bool {
+        self != Self::Off
+    }
+
+    /// Whether calls of `operation` carry a key
+    pub fn applies(self, operation: &OperationDefinition) -> bool {
+        match self {
+            Self::Off => false,
+            Self::Flagged => operation.idempotency == Some(true),
+            Self::Unsafe => {
+                operation.idempotency != Some(false)
+                    && matches!(
+                        operation.method,
+                        HttpMethod::Post | HttpMethod::Put | HttpMethod::Patch | HttpMethod::Delete
+                    )
+            }
+        }
+    }
+}
+
+/// Header the key is sent in (`idempotencyHeader` option)
+pub fn header_name(config: &GenerationConfig) -> Result<String> {
+    match config.options.get("idempotencyHeader") {
+        None => Ok("Idempotency-Key".to_string()),
+        Some(Value::String(name)) if !name.is_empty() => Ok(name.clone()),
+        Some(_) => anyhow::bail!(
+            "{}: option 'idempotencyHeader' must be a header name",
+            config.generator
+        ),
+    }
+}
@@ -0,0 +1,172 @@
+//! Schemas used by a single field, declared at that field instead of as
+//! exported types (`inlineSingleUse` option of `typescript` and
+//! `typescript_adi_http`), so specs naming every nested object do not fill an
+//! SDK's exports with types nobody imports.
+//!
+//! A schema is inlined when it is an object with fields, exactly one field of
+//! another schema references it (directly or through arrays, maps, tuples, and
+//! unions), and nothing else does: no operation body, channel message,
+//! `allOf` child, or merge patch copy. Schemas on a reference cycle, schemas
+//! with `allOf` parents, and the link schemas the hypermedia helpers name
+//! stay declared.
+
+use super::hypermedia;
+use crate::config::GenerationConfig;
+use crate::parsers::{SchemaDefinition, SchemaIR, TypeInfo};
+use std::collections::{HashMap, HashSet};
+
+/// Rendered expressions of inlined schemas, by IR name
+pub type Inlined = HashMap<String, String>;
+
+/// Schemas to inline under `inlineSingleUse: true` (none otherwise), each
+/// after the inlined schemas its own fields use
+pub fn single_use<'a>(
+    schema_ir: &'a SchemaIR,
+    config: &GenerationConfig,
+) -> Vec<&'a SchemaDefinition> {
+    if !config
+        .options
+        .get("inlineSingleUse")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        return Vec::new();
+    }
+
+    // Field references per schema, and the schema holding the last one seen
+    let mut uses: HashMap<&str, usize> = HashMap::new();
+    let mut users: HashMap<&str, &str> = HashMap::new();
+    // Schemas named outside fields
+    let mut pinned: HashSet<&str> = hypermedia::LINK_SCHEMAS.iter().copied().collect();
+    for schema in &schema_ir.schemas {
+        for field in &schema.fields {
+            let mut names = Vec::new();
+            references(&field.type_info, &mut names);
+            for name in names {
+                *uses.entry(name).or_default() += 1;
+                users.insert(name, &schema.name);
+            }
+        }
+        pinned.extend(schema.parents.iter().map(String::as_str));
+        if let Some(source) = &schema.merge_patch_of {
+            pinned.insert(source);
+            pinned.insert(&schema.name);
+        }
+    }
+    for op in &schema_ir.operations {
+        pinned.extend(
+            [&op.request_body, &op.response]
+                .into_iter()
+                .flatten()
+                .map(|body| body.name.as_str()),
+        );
+        for response in &op.responses {
+            pinned.extend(response.schema.iter().map(|body| body.name.as_str()));
+            let mut names = Vec::new();
+            if let Some(type_info) = &response.inline_type {
+                references(type_info, &mut names);
+            }
+            pinned.extend(names);
+        }
+    }
+    for channel in &schema_ir.channels {
+        pinned.extend(
+            [&channel.send, &channel.receive]
+                .into_iter()
+                .flatten()
+                .map(String::as_str),
+        );
+    }
+
+    let mut candidates: HashMap<&str, &SchemaDefinition> = schema_ir
+        .schemas
+        .iter()
+        .filter(|schema| {
+            let name = schema.name.as_str();
+            !schema.fields.is_empty()
+                && schema.parents.is_empty()
+                && !pinned.contains(name)
+                && uses.get(name) == Some(&1)
+        })
+        .map(|schema| (schema.name.as_str(), schema))
+        .collect();
+
+    // Following a cycle's single users leads back to where it started
+    let cyclic: Vec<&str> = candidates
+        .keys()
+        .copied()
+        .filter(|&name| {
+            let mut user = users[name];
+            for _ in 0..candidates.len() {
+                if user == name {
+                    return true;
+                }
+                if !candidates.contains_key(user) {
+                    return false;
+                }
+                user = users[user];
+            }
+            false
+        })
+        .collect();
+    for name in cyclic {
+        candidates.remove(name);
+    }
+
+    let mut ordered = Vec::new();
+    let mut visited = HashSet::new();
+    for schema in &schema_ir.schemas {
+        visit(&schema.name, &candidates, &mut visited, &mut ordered);
+    }
+    ordered
+}
+
+/// Adds the candidate `name` to `ordered` after the candidates its fields use
+fn visit<'a>(
+    name: &str,
+    candidates: &HashMap<&str, &'a SchemaDefinition>,
+    visited: &mut HashSet<String>,
+    ordered: &mut Vec<&'a SchemaDefinition>,
+) {
+    let Some(schema) = candidates.get(name).copied() else {
+        return;
+    };
+    if !visited.insert(name.to_string()) {
+        return;
+    }
+    for field in &schema.fields {
+        let mut names = Vec::new();
+        references(&field.type_info, &mut names);
+        for used in names {
+            visit(used, candidates, visited, ordered);
+        }
+    }
+    ordered.push(schema);
+}
+
+/// The inlined schema `type_info` refers to directly, for its description
+pub fn direct<'a>(
+    type_info: &TypeInfo,
+    inlined: &[&'a SchemaDefinition],
+) -> Option<&'a SchemaDefinition> {
+    let name = type_info.reference.as_deref()?;
+    inlined.iter().copied().find(|schema| schema.name == name)
+}
+
+/// `expression` placed `indent` deeper: lines after its first are indented
+pub fn indent(expression: &str, indent: &str) -> String {
+    expression.replace('\n', &format!("\n{}", indent))
+}
+
+fn references<'a>(type_info: &'a TypeInfo, names: &mut Vec<&'a str>) {
+    names.extend(type_info.reference.as_deref());
+    if let Some(item) = &type_info.array_item_type {
+        references(item, names);
+    }
+    if let Some(values) = &type_info.map_values {
+        references(values, names);
+    }
+    for member in type_info.union_members.iter().chain(&type_info.tuple_items) {
+        references(member, names);
+    }
+}
@@ -0,0 +1,73 @@
+//! Docs in the team's language (`locale` option): descriptions the spec
+//! translates under `x-descriptions` replace `description` in doc comments and
+//! Markdown / HTML docs. `pt-BR` takes a `pt-BR` translation, else a `pt` one;
+//! untranslated nodes keep their `description`.
+
+use crate::config::GenerationConfig;
+use crate::parsers::SchemaIR;
+use anyhow::Result;
+use indexmap::IndexMap;
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, Default)]
+pub struct Locale {
+    /// Locales tried in turn, most specific first; empty for `description`
+    candidates: Vec<String>,
+}
+
+impl Locale {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        let locale = match config.options.get("locale").map(|v| v.as_str()) {
+            None => return Ok(Self::default()),
+            Some(Some(locale)) if !locale.trim().is_empty() => locale.trim().replace('_', "-"),
+            _ => anyhow::bail!(
+                "{}: option 'locale' must be a locale like \"de\" or \"pt-BR\"",
+                config.generator
+            ),
+        };
+        let mut candidates = vec![locale.clone()];
+        if let Some((language, _)) = locale.split_once('-') {
+            candidates.push(language.to_string());
+        }
+        Ok(Self { candidates })
+    }
+
+    /// The IR with every translated description in the locale
+    pub fn apply(self, schema_ir: Cow<'_, SchemaIR>) -> Cow<'_, SchemaIR> {
+        if self.candidates.is_empty() {
+            return schema_ir;
+        }
+
+        let mut localized = schema_ir.into_owned();
+        let metadata = &mut localized.metadata;
+        self.localize(&mut metadata.description, &metadata.translations);
+        for tag in &mut metadata.tags {
+            self.localize(&mut tag.description, &tag.translations);
+        }
+        for schema in &mut localized.schemas {
+            self.localize(&mut schema.description, &schema.translations);
+            for field in &mut schema.fields {
+                self.localize(&mut field.description, &field.translations);
+            }
+        }
+        for op in &mut localized.operations {
+            self.localize(&mut op.description, &op.translations);
+            for param in &mut op.parameters {
+                self.localize(&mut param.description, &param.translations);
+            }
+        }
+        Cow::Owned(localized)
+    }
+
+    fn localize(&self, description: &mut Option<String>, translations: &IndexMap<String, String>) {
+        let translated = self.candidates.iter().find_map(|candidate| {
+            translations
+                .iter()
+                .find(|(locale, _)| locale.replace('_', "-").eq_ignore_ascii_case(candidate))
+                .map(|(_, text)| text)
+        });
+        if let Some(text) = translated {
+            *description = Some(text.clone());
+        }
+    }
+}
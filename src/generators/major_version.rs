@@ -0,0 +1,123 @@
+//! Output keyed to the major of the spec's `info.version`, so an SDK repo can
+//! ship several majors side by side from the same pipeline:
+//! - `majorVersionDirectory: true` writes the generation's files under
+//!   `v{major}/`; the other majors' directories are not touched
+//! - `majorVersionAlias: true` also exports each client class (or the
+//!   `typescript_adi_http` client object) under a `V{major}` name
+//!   (`ApiClientV2`), so two majors can be imported together without renaming
+//!
+//! The major is the leading number of `info.version` (`2.1.0`, `v2`, `2024-01`).
+
+use super::GeneratedOutput;
+use crate::config::GenerationConfig;
+use crate::parsers::SchemaIR;
+use anyhow::Result;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MajorVersion {
+    directory: bool,
+    alias: bool,
+}
+
+impl MajorVersion {
+    pub fn from_config(config: &GenerationConfig) -> Self {
+        let flag = |name: &str| {
+            config
+                .options
+                .get(name)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        };
+        Self {
+            directory: flag("majorVersionDirectory"),
+            alias: flag("majorVersionAlias"),
+        }
+    }
+
+    /// `output` moved under the major's directory and with the client aliases added
+    pub fn apply(
+        self,
+        mut output: GeneratedOutput,
+        schema_ir: &SchemaIR,
+    ) -> Result<GeneratedOutput> {
+        if !self.directory && !self.alias {
+            return Ok(output);
+        }
+        let version = &schema_ir.metadata.version;
+        let digits: String = version
+            .trim_start_matches(['v', 'V'])
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        let major: u64 = digits.parse().map_err(|_| {
+            anyhow::anyhow!(
+                "majorVersionDirectory / majorVersionAlias: info.version '{}' does not start with a major version",
+                version
+            )
+        })?;
+
+        if self.alias {
+            output.content = alias(&output.filename, &output.content, major);
+            for companion in &mut output.companions {
+                companion.content = alias(&companion.filename, &companion.content, major);
+            }
+        }
+        if self.directory {
+            output.filename = format!("v{}/{}", major, output.filename);
+            for companion in &mut output.companions {
+                companion.filename = format!("v{}/{}", major, companion.filename);
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// `content` with a `V{major}` alias after it for each client it declares
+fn alias(filename: &str, content: &str, major: u64) -> String {
+    let typescript = filename.ends_with(".ts") || filename.ends_with(".js");
+    let python = filename.ends_with(".py");
+    let go = filename.ends_with(".go");
+
+    let mut aliases = Vec::new();
+    for line in content.lines() {
+        let declared = if typescript {
+            ["export declare class ", "export class ", "export const "]
+                .iter()
+                .find_map(|prefix| line.strip_prefix(prefix))
+        } else if python {
+            line.strip_prefix("class ")
+        } else if go {
+            line.strip_prefix("type ")
+                .or_else(|| line.strip_prefix("func New"))
+        } else {
+            None
+        };
+        let Some(rest) = declared else { continue };
+        let name = identifier(rest);
+        if !name.ends_with("Client") {
+            continue;
+        }
+        aliases.push(if typescript {
+            format!("export {{ {0} as {0}V{1} }};", name, major)
+        } else if python {
+            format!("{0}V{1} = {0}", name, major)
+        } else if line.starts_with("func ") {
+            format!("var New{0}V{1} = New{0}", name, major)
+        } else {
+            format!("type {0}V{1} = {0}", name, major)
+        });
+    }
+    if aliases.is_empty() {
+        return content.to_string();
+    }
+    // Two blank lines before them, as between Python's top-level definitions
+    let gap = if python { "\n\n\n" } else { "\n\n" };
+    format!("{}{}{}\n", content.trim_end(), gap, aliases.join("\n"))
+}
+
+fn identifier(text: &str) -> &str {
+    let end = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(text.len());
+    &text[..end]
+}
@@ -1,24 +1,150 @@
+pub mod all_of;
+pub mod any_types;
+pub mod audience;
+pub mod build_info;
+pub mod changelog;
+pub mod conditional;
+pub mod contract_tests;
+pub mod coverage_report;
+pub mod docs;
+pub mod fixtures;
+pub mod forms;
+pub mod golang;
+pub mod hypermedia;
+pub mod idempotency;
+pub mod inline_schemas;
+pub mod locale;
+pub mod major_version;
+pub mod naming;
+pub mod patch_bodies;
+pub mod path_params;
+pub mod path_prefix;
+pub mod post_process;
+pub mod python;
+pub mod rate_limit;
+pub mod readme;
+pub mod request_builders;
+pub mod request_encoding;
+pub mod response_cache;
+pub mod route_handlers;
+pub mod spec_split;
+pub mod template_lint;
+pub mod templates;
+pub mod ts_barrel;
+pub mod ts_declarations;
+pub mod ts_imports;
+pub mod ts_optional;
+pub mod type_overrides;
+pub mod typecheck;
 pub mod typescript;
 pub mod typescript_adi_http;
-pub mod python;
-pub mod golang;
+pub mod typescript_nestjs;
+pub mod validation_middleware;
+pub mod validators;
+pub mod verify;
+pub mod wiremock;
+pub mod zod;
 
-use anyhow::Result;
 use crate::config::GenerationConfig;
-use crate::parsers::SchemaIR;
+use crate::parsers::{FeatureHandling, FeatureUsage, SchemaIR};
+use anyhow::Result;
+use naming::NamingStrategy;
+use post_process::PostProcessor;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::Arc;
 
+pub use all_of::AllOfStrategy;
+pub use any_types::AnyTypes;
+pub use audience::Audience;
+pub use build_info::BuildInfo;
+pub use changelog::ChangelogGenerator;
+pub use contract_tests::ContractTestsGenerator;
+pub use coverage_report::CoverageReportGenerator;
+pub use fixtures::FixturesGenerator;
+pub use forms::FormsGenerator;
+pub use golang::GolangGenerator;
+pub use hypermedia::Hypermedia;
+pub use locale::Locale;
+pub use major_version::MajorVersion;
+pub use patch_bodies::PatchBodies;
+pub use path_prefix::PathPrefixMode;
+pub use post_process::PostProcessors;
+pub use python::PythonGenerator;
+pub use route_handlers::RouteHandlersGenerator;
+pub use spec_split::SpecSplitGenerator;
+pub use type_overrides::TypeOverrides;
 pub use typescript::TypeScriptGenerator;
 pub use typescript_adi_http::TypeScriptAdiHttpGenerator;
-pub use python::PythonGenerator;
-pub use golang::GolangGenerator;
+pub use typescript_nestjs::TypeScriptNestjsGenerator;
+pub use validation_middleware::ValidationMiddlewareGenerator;
+pub use wiremock::WireMockGenerator;
 
 /// Generated output from a generator
 #[derive(Debug)]
 pub struct GeneratedOutput {
     pub filename: String,
     pub content: String,
+    #[allow(dead_code)]
     pub metadata: HashMap<String, String>,
+    /// Extra files written next to `filename` (e.g. a `.d.ts` declaration file)
+    pub companions: Vec<GeneratedFile>,
+    /// What the generator left out or degraded, reported once the run is done
+    pub warnings: Vec<GeneratorWarning>,
+}
+
+/// Something a generator could not render faithfully, such as a skipped
+/// `oneOf` on a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratorWarning {
+    /// Stable kebab-case identifier (`no-example`), for filtering
+    pub code: String,
+    pub message: String,
+    /// IR location the warning is about (`operations.getUser`), if any
+    pub path: Option<String>,
+}
+
+impl GeneratorWarning {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            message: message.into(),
+            path: None,
+        }
+    }
+
+    pub fn at(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl std::fmt::Display for GeneratorWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "[{}] {}: {}", self.code, path, self.message),
+            None => write!(f, "[{}] {}", self.code, self.message),
+        }
+    }
+}
+
+/// Additional file produced by a generator
+#[derive(Debug)]
+pub struct GeneratedFile {
+    pub filename: String,
+    pub content: String,
+}
+
+impl GeneratedOutput {
+    /// The main file followed by its companions
+    pub fn into_files(self) -> Vec<GeneratedFile> {
+        let mut files = vec![GeneratedFile {
+            filename: self.filename,
+            content: self.content,
+        }];
+        files.extend(self.companions);
+        files
+    }
 }
 
 /// Generator trait - converts SchemaIR to target language code
@@ -27,8 +153,14 @@ pub trait Generator: Send + Sync {
     fn name(&self) -> &str;
 
     /// File extension for generated output (e.g., "ts", "py")
+    #[allow(dead_code)]
     fn file_extension(&self) -> &str;
 
+    /// Version of the generator, recorded in the generation manifest
+    fn version(&self) -> &str {
+        env!("CARGO_PKG_VERSION")
+    }
+
     /// Generate code from intermediate representation
     fn generate_from_ir(
         &self,
@@ -36,21 +168,130 @@ pub trait Generator: Send + Sync {
         config: &GenerationConfig,
     ) -> Result<GeneratedOutput>;
 
+    /// The Tera context the generator renders its templates with
+    /// (`--dump-context`); `None` for generators that do not use templates
+    fn template_context(
+        &self,
+        _schema_ir: &SchemaIR,
+        _config: &GenerationConfig,
+    ) -> Result<Option<serde_json::Value>> {
+        Ok(None)
+    }
+
     /// Validate generator-specific configuration
     fn validate_config(&self, _config: &GenerationConfig) -> Result<()> {
         Ok(())
     }
+
+    /// Whether the generator emits `allOf` parents itself (`allOfStrategy: inherit`)
+    fn supports_inheritance(&self, _config: &GenerationConfig) -> bool {
+        false
+    }
+
+    /// Whether `oneOf`/`anyOf` unions come out as union types rather than `any`
+    fn supports_unions(&self) -> bool {
+        false
+    }
+
+    /// What the generator emits as `any`, for `reportAny` and `maxAnyTypes`
+    fn any_fields(&self, schema_ir: &SchemaIR) -> Vec<String> {
+        any_types::any_fields(schema_ir, self.supports_unions())
+    }
+}
+
+/// What to do when the IR contains schema kinds mapped to `any`
+/// (`onUnsupported` generation option)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsupportedPolicy {
+    /// Generate with `any` silently
+    Any,
+    /// Generate with `any` and report the constructs in the run summary
+    Warn,
+    /// Fail the generation
+    Error,
+}
+
+impl UnsupportedPolicy {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("onUnsupported") {
+            None => Ok(Self::Any),
+            Some(value) => match value.as_str() {
+                Some("any") => Ok(Self::Any),
+                Some("warn") => Ok(Self::Warn),
+                Some("error") => Ok(Self::Error),
+                _ => anyhow::bail!(
+                    "{}: option 'onUnsupported' must be \"any\", \"warn\", or \"error\"",
+                    config.generator
+                ),
+            },
+        }
+    }
+
+    /// Fail under `Error` when the IR has unsupported constructs
+    pub fn enforce(self, config: &GenerationConfig, unsupported: &[&FeatureUsage]) -> Result<()> {
+        if self != Self::Error || unsupported.is_empty() {
+            return Ok(());
+        }
+        let details: Vec<_> = unsupported
+            .iter()
+            .map(|usage| format!("  - {}: {}", usage.construct, usage.locations.join(", ")))
+            .collect();
+        anyhow::bail!(
+            "'{}' does not support these schema constructs (onUnsupported: error):\n{}",
+            config.generator,
+            details.join("\n")
+        );
+    }
+}
+
+/// Constructs in the IR that generators will emit as `any`
+pub fn unsupported_constructs(schema_ir: &SchemaIR) -> Vec<&FeatureUsage> {
+    schema_ir
+        .coverage
+        .iter()
+        .filter(|usage| usage.handling == FeatureHandling::Unsupported)
+        .collect()
+}
+
+/// `schema_ir` as `generator` renders it with the options of `gen_config`
+pub fn prepare<'a>(
+    schema_ir: &'a SchemaIR,
+    generator: &dyn Generator,
+    gen_config: &GenerationConfig,
+) -> Result<Cow<'a, SchemaIR>> {
+    let all_of = AllOfStrategy::from_config(gen_config)?;
+    // Operations, schemas, and fields marked for other audiences left out, then
+    // allOf children flattened, or kept with their parents for generators that extend
+    let prepared_ir = match Audience::from_config(gen_config)?.select(schema_ir) {
+        Cow::Borrowed(selected_ir) => all_of.prepare(selected_ir, generator, gen_config)?,
+        Cow::Owned(selected_ir) => {
+            let flattened = match all_of.prepare(&selected_ir, generator, gen_config)? {
+                Cow::Owned(flattened) => Some(flattened),
+                Cow::Borrowed(_) => None,
+            };
+            Cow::Owned(flattened.unwrap_or(selected_ir))
+        }
+    };
+    // Server base path kept in the base URL or moved into operation paths
+    let prepared_ir = PathPrefixMode::from_config(gen_config)?.apply(prepared_ir);
+    // Untyped HAL / JSON:API link fields typed against the conventions' link schemas
+    let prepared_ir = Hypermedia::from_config(gen_config)?.apply(prepared_ir);
+    let prepared_ir = PatchBodies::from_config(gen_config).apply(prepared_ir);
+    let prepared_ir = TypeOverrides::from_config(gen_config)?.apply(prepared_ir);
+    Ok(Locale::from_config(gen_config)?.apply(prepared_ir))
 }
 
 /// Generator registry for managing available code generators
 pub struct GeneratorRegistry {
     generators: HashMap<String, Box<dyn Generator>>,
+    post_processors: HashMap<String, Box<dyn PostProcessor>>,
 }
 
 impl GeneratorRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
             generators: HashMap::new(),
+            post_processors: HashMap::new(),
         };
 
         // Register built-in generators
@@ -58,21 +299,207 @@ impl GeneratorRegistry {
         registry.register(Box::new(TypeScriptAdiHttpGenerator));
         registry.register(Box::new(PythonGenerator));
         registry.register(Box::new(GolangGenerator));
+        registry.register(Box::new(FixturesGenerator));
+        registry.register(Box::new(ContractTestsGenerator));
+        registry.register(Box::new(ValidationMiddlewareGenerator));
+        registry.register(Box::new(FormsGenerator));
+        registry.register(Box::new(RouteHandlersGenerator));
+        registry.register(Box::new(TypeScriptNestjsGenerator));
+        registry.register(Box::new(WireMockGenerator));
+        registry.register(Box::new(CoverageReportGenerator));
+        registry.register(Box::new(SpecSplitGenerator));
+        registry.register(Box::new(ChangelogGenerator));
+        for processor in post_process::builtins() {
+            registry.register_post_processor(processor);
+        }
 
         registry
     }
 
     pub fn register(&mut self, generator: Box<dyn Generator>) {
-        self.generators.insert(generator.name().to_string(), generator);
+        self.generators.insert(
+            generator.name().to_string(),
+            Box::new(Transliterating(generator)),
+        );
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Generator> {
+        self.generators.get(name).map(|g| g.as_ref())
     }
 
-    pub fn get(&self, name: &str) -> Option<&Box<dyn Generator>> {
-        self.generators.get(name)
+    /// Like [`get`](Self::get), with an error listing the available generators
+    pub fn require(&self, name: &str) -> Result<&dyn Generator> {
+        self.get(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown generator: {} (available: {})",
+                name,
+                self.available_generators().join(", ")
+            )
+        })
     }
 
     pub fn available_generators(&self) -> Vec<&str> {
         self.generators.keys().map(|s| s.as_str()).collect()
     }
+
+    /// Makes `processor` available to `postProcessors`, replacing a built-in
+    /// of the same name
+    pub fn register_post_processor(&mut self, processor: Box<dyn PostProcessor>) {
+        self.post_processors
+            .insert(processor.name().to_string(), processor);
+    }
+
+    pub fn post_processor(&self, name: &str) -> Option<&dyn PostProcessor> {
+        self.post_processors.get(name).map(|p| p.as_ref())
+    }
+
+    pub fn available_post_processors(&self) -> Vec<&str> {
+        self.post_processors.keys().map(|s| s.as_str()).collect()
+    }
+
+    /// Names in the output of every generator registered so far come from
+    /// `strategy` instead of the built-in rules
+    pub fn set_naming(&mut self, strategy: Arc<dyn NamingStrategy>) {
+        self.generators = std::mem::take(&mut self.generators)
+            .into_iter()
+            .map(|(name, inner)| {
+                let named: Box<dyn Generator> = Box::new(Named {
+                    inner,
+                    strategy: strategy.clone(),
+                });
+                (name, named)
+            })
+            .collect();
+    }
+}
+
+/// A generator rendering with a custom [`NamingStrategy`] in scope
+struct Named {
+    inner: Box<dyn Generator>,
+    strategy: Arc<dyn NamingStrategy>,
+}
+
+impl Generator for Named {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn file_extension(&self) -> &str {
+        self.inner.file_extension()
+    }
+
+    fn version(&self) -> &str {
+        self.inner.version()
+    }
+
+    fn generate_from_ir(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<GeneratedOutput> {
+        naming::scoped(&self.strategy, || {
+            self.inner.generate_from_ir(schema_ir, config)
+        })
+    }
+
+    fn template_context(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<Option<serde_json::Value>> {
+        naming::scoped(&self.strategy, || {
+            self.inner.template_context(schema_ir, config)
+        })
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        self.inner.validate_config(config)
+    }
+
+    fn supports_inheritance(&self, config: &GenerationConfig) -> bool {
+        self.inner.supports_inheritance(config)
+    }
+
+    fn supports_unions(&self) -> bool {
+        self.inner.supports_unions()
+    }
+
+    fn any_fields(&self, schema_ir: &SchemaIR) -> Vec<String> {
+        self.inner.any_fields(schema_ir)
+    }
+}
+
+/// A generator rendering with names transliterated to ASCII when its
+/// generation sets `asciiIdentifiers`, and type names wrapped in its
+/// `typePrefix` and `typeSuffix`
+struct Transliterating(Box<dyn Generator>);
+
+impl Transliterating {
+    fn scoped<T>(config: &GenerationConfig, f: impl FnOnce() -> T) -> T {
+        // Unparseable affixes were rejected by `validate_config`
+        let affix = naming::TypeAffix::from_config(config).unwrap_or_default();
+        let affixed = || match affix.is_empty() {
+            true => f(),
+            false => naming::affix_scoped(&affix, f),
+        };
+        if config
+            .options
+            .get("asciiIdentifiers")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            naming::ascii_scoped(affixed)
+        } else {
+            affixed()
+        }
+    }
+}
+
+impl Generator for Transliterating {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn file_extension(&self) -> &str {
+        self.0.file_extension()
+    }
+
+    fn version(&self) -> &str {
+        self.0.version()
+    }
+
+    fn generate_from_ir(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<GeneratedOutput> {
+        Self::scoped(config, || self.0.generate_from_ir(schema_ir, config))
+    }
+
+    fn template_context(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<Option<serde_json::Value>> {
+        Self::scoped(config, || self.0.template_context(schema_ir, config))
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        naming::TypeAffix::from_config(config)?;
+        self.0.validate_config(config)
+    }
+
+    fn supports_inheritance(&self, config: &GenerationConfig) -> bool {
+        self.0.supports_inheritance(config)
+    }
+
+    fn supports_unions(&self) -> bool {
+        self.0.supports_unions()
+    }
+
+    fn any_fields(&self, schema_ir: &SchemaIR) -> Vec<String> {
+        self.0.any_fields(schema_ir)
+    }
 }
 
 impl Default for GeneratorRegistry {
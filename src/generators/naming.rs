@@ -0,0 +1,906 @@
+//! Identifier case conversions and per-language sanitization shared by generators.
+//!
+//! Type, field, parameter, function, and enum member names go through a
+//! [`NamingStrategy`]: the built-in rules unless one is in scope via
+//! [`scoped`] (or [`GeneratorRegistry::set_naming`](super::GeneratorRegistry::set_naming)).
+//! Under the `asciiIdentifiers` generation option they get names
+//! [`transliterate`]d to ASCII first, and `typePrefix` / `typeSuffix` wrap the
+//! type names the strategy picks.
+
+use super::hypermedia::LINK_SCHEMAS;
+use super::patch_bodies::JSON_PATCH_OPERATION;
+use crate::config::GenerationConfig;
+use anyhow::Result;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+/// Target language for identifier rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    TypeScript,
+    Python,
+    Go,
+}
+
+const TYPESCRIPT_KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+    "await",
+];
+
+/// Names that cannot be used for type aliases in TypeScript
+const TYPESCRIPT_TYPE_NAMES: &[&str] = &[
+    "any",
+    "bigint",
+    "boolean",
+    "never",
+    "number",
+    "object",
+    "string",
+    "symbol",
+    "undefined",
+    "unknown",
+];
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Attributes of pydantic's BaseModel that fields must not shadow
+const PYTHON_MODEL_ATTRIBUTES: &[&str] = &[
+    "copy",
+    "dict",
+    "json",
+    "schema",
+    "model_config",
+    "model_fields",
+    "validate",
+];
+
+const GO_KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+];
+
+/// Predeclared Go identifiers that would shadow types used in signatures
+const GO_PREDECLARED: &[&str] = &[
+    "bool",
+    "byte",
+    "error",
+    "float32",
+    "float64",
+    "int",
+    "int32",
+    "int64",
+    "interface",
+    "nil",
+    "string",
+];
+
+/// How generators name what they declare. Every method defaults to the
+/// built-in rules, so a strategy overrides only the names it changes; the
+/// results must still be valid identifiers in `language`.
+pub trait NamingStrategy: Send + Sync {
+    /// Schema types: type aliases, classes, structs
+    fn type_name(&self, name: &str, language: Language) -> String {
+        builtin_type_name(name, language)
+    }
+
+    /// Model fields, for languages that map them to the wire name
+    /// (pydantic aliases, Go struct tags); TypeScript keys stay the wire name
+    fn field_name(&self, name: &str, language: Language) -> String {
+        match language {
+            Language::Go => go_exported(name),
+            // pydantic keeps `_name` attributes private, so they would never be
+            // filled (HAL `_links`, and `$ref` or `1st` once sanitized)
+            Language::Python => match replace_invalid_chars(name, language).trim_start_matches('_')
+            {
+                // Not `field`, which names dataclasses' `field()` in the class body
+                "" => "field_".to_string(),
+                rest if rest.starts_with(|c: char| c.is_ascii_digit()) => format!("field_{}", rest),
+                rest => identifier(rest, language),
+            },
+            _ => identifier(name, language),
+        }
+    }
+
+    /// Operation parameters and other local variables
+    fn parameter_name(&self, name: &str, language: Language) -> String {
+        identifier(name, language)
+    }
+
+    /// Client methods, from operation ids
+    fn function_name(&self, name: &str, language: Language) -> String {
+        match language {
+            Language::Go => go_exported(name),
+            _ => identifier(name, language),
+        }
+    }
+
+    /// Member of the enum `enum_name` for `value`: a Go constant
+    /// (`StatusActive`), a Python `Enum` member (`ACTIVE`). Generators number
+    /// duplicates themselves.
+    fn enum_member(&self, enum_name: &str, value: &str, language: Language) -> String {
+        match language {
+            Language::Go => match pascal_case(value) {
+                suffix if suffix.is_empty() => format!("{}Empty", enum_name),
+                suffix => format!("{}{}", enum_name, suffix),
+            },
+            Language::Python => {
+                let member = snake_case(&pascal_case(value)).to_uppercase();
+                match member.chars().next() {
+                    None => "EMPTY".to_string(),
+                    Some(c) if c.is_ascii_digit() => format!("VALUE_{}", member),
+                    Some(_) => member,
+                }
+            }
+            Language::TypeScript => identifier(value, language),
+        }
+    }
+}
+
+/// The built-in rules
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultNaming;
+
+impl NamingStrategy for DefaultNaming {}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<dyn NamingStrategy>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with `strategy` naming everything generated on this thread
+pub fn scoped<T>(strategy: &Arc<dyn NamingStrategy>, f: impl FnOnce() -> T) -> T {
+    /// Puts the outer strategy back, also when `f` unwinds
+    struct Restore(Option<Arc<dyn NamingStrategy>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(CURRENT.with(|current| current.borrow_mut().replace(strategy.clone())));
+    f()
+}
+
+fn with_current<T>(f: impl FnOnce(&dyn NamingStrategy) -> T) -> T {
+    CURRENT.with(|current| match current.borrow().as_deref() {
+        Some(strategy) => f(strategy),
+        None => f(&DefaultNaming),
+    })
+}
+
+/// Runs `f` with names transliterated to ASCII before the strategy in scope
+/// names them (`asciiIdentifiers` generation option)
+pub fn ascii_scoped<T>(f: impl FnOnce() -> T) -> T {
+    let current = CURRENT
+        .with(|current| current.borrow().clone())
+        .unwrap_or_else(|| Arc::new(DefaultNaming));
+    let ascii: Arc<dyn NamingStrategy> = Arc::new(Ascii(current));
+    scoped(&ascii, f)
+}
+
+/// `typePrefix` and `typeSuffix` generation options (`Api` → `ApiUser`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeAffix {
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl TypeAffix {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        let option = |name: &str| -> Result<String> {
+            let Some(value) = config.options.get(name) else {
+                return Ok(String::new());
+            };
+            match value.as_str() {
+                Some(affix) if affix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') => {
+                    Ok(affix.to_string())
+                }
+                _ => anyhow::bail!(
+                    "{}: option '{}' must be a string of ASCII letters, digits, and underscores",
+                    config.generator,
+                    name
+                ),
+            }
+        };
+        let affix = Self {
+            prefix: option("typePrefix")?,
+            suffix: option("typeSuffix")?,
+        };
+        if affix.prefix.starts_with(|c: char| c.is_ascii_digit()) {
+            anyhow::bail!(
+                "{}: option 'typePrefix' must not start with a digit",
+                config.generator
+            );
+        }
+        Ok(affix)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prefix.is_empty() && self.suffix.is_empty()
+    }
+}
+
+/// Runs `f` with the type names of the strategy in scope wrapped in `affix`
+pub fn affix_scoped<T>(affix: &TypeAffix, f: impl FnOnce() -> T) -> T {
+    let current = CURRENT
+        .with(|current| current.borrow().clone())
+        .unwrap_or_else(|| Arc::new(DefaultNaming));
+    let affixed: Arc<dyn NamingStrategy> = Arc::new(Affixed(current, affix.clone()));
+    scoped(&affixed, f)
+}
+
+/// Another strategy, with prefix and suffix around its type names
+struct Affixed(Arc<dyn NamingStrategy>, TypeAffix);
+
+impl NamingStrategy for Affixed {
+    fn type_name(&self, name: &str, language: Language) -> String {
+        let name = self.0.type_name(name, language);
+        let prefix = match language {
+            // A lowercase prefix would leave Go types unexported
+            Language::Go => pascal_case(&self.1.prefix),
+            _ => self.1.prefix.clone(),
+        };
+        format!("{}{}{}", prefix, name, self.1.suffix)
+    }
+
+    fn field_name(&self, name: &str, language: Language) -> String {
+        self.0.field_name(name, language)
+    }
+
+    fn parameter_name(&self, name: &str, language: Language) -> String {
+        self.0.parameter_name(name, language)
+    }
+
+    fn function_name(&self, name: &str, language: Language) -> String {
+        self.0.function_name(name, language)
+    }
+
+    fn enum_member(&self, enum_name: &str, value: &str, language: Language) -> String {
+        self.0.enum_member(enum_name, value, language)
+    }
+}
+
+/// Names of the schemas generators add themselves (JSON Patch operations,
+/// hypermedia links) as `language` types, for templates that refer to them
+pub fn support_type_names(language: Language) -> BTreeMap<&'static str, String> {
+    std::iter::once(JSON_PATCH_OPERATION)
+        .chain(LINK_SCHEMAS.iter().copied())
+        .map(|name| (name, type_name(name, language)))
+        .collect()
+}
+
+/// Another strategy, given names transliterated to ASCII
+struct Ascii(Arc<dyn NamingStrategy>);
+
+impl NamingStrategy for Ascii {
+    fn type_name(&self, name: &str, language: Language) -> String {
+        self.0.type_name(&transliterate(name), language)
+    }
+
+    fn field_name(&self, name: &str, language: Language) -> String {
+        self.0.field_name(&transliterate(name), language)
+    }
+
+    fn parameter_name(&self, name: &str, language: Language) -> String {
+        self.0.parameter_name(&transliterate(name), language)
+    }
+
+    fn function_name(&self, name: &str, language: Language) -> String {
+        self.0.function_name(&transliterate(name), language)
+    }
+
+    fn enum_member(&self, enum_name: &str, value: &str, language: Language) -> String {
+        self.0
+            .enum_member(enum_name, &transliterate(value), language)
+    }
+}
+
+/// [`NamingStrategy::type_name`] of the strategy in scope
+pub fn type_name(name: &str, language: Language) -> String {
+    with_current(|strategy| strategy.type_name(name, language))
+}
+
+/// [`NamingStrategy::field_name`] of the strategy in scope
+pub fn field_name(name: &str, language: Language) -> String {
+    with_current(|strategy| strategy.field_name(name, language))
+}
+
+/// [`NamingStrategy::parameter_name`] of the strategy in scope
+pub fn parameter_name(name: &str, language: Language) -> String {
+    with_current(|strategy| strategy.parameter_name(name, language))
+}
+
+/// [`NamingStrategy::function_name`] of the strategy in scope
+pub fn function_name(name: &str, language: Language) -> String {
+    with_current(|strategy| strategy.function_name(name, language))
+}
+
+/// [`NamingStrategy::enum_member`] of the strategy in scope
+pub fn enum_member(enum_name: &str, value: &str, language: Language) -> String {
+    with_current(|strategy| strategy.enum_member(enum_name, value, language))
+}
+
+/// [`field_name`]s of one model's fields, numbered where two would collide
+/// (`a.b` and `a_b`); a field whose name is already its wire name keeps it
+pub fn field_names<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+    language: Language,
+) -> Vec<String> {
+    let names = names
+        .into_iter()
+        .map(|name| (name, field_name(name, language)))
+        .collect();
+    numbered(names, &[], language)
+}
+
+/// Locals a `python` operation method declares or calls besides its parameters
+pub const PYTHON_METHOD_LOCALS: &[&str] = &[
+    "self",
+    "path",
+    "query",
+    "headers",
+    "cookies",
+    "body",
+    "result",
+    "timeout",
+    "etag",
+    "on_response",
+    "quote",
+    "uuid",
+];
+
+/// Locals, the receiver, and packages a `golang` operation method uses
+/// besides its parameters
+pub const GO_METHOD_LOCALS: &[&str] = &[
+    "c",
+    "ctx",
+    "path",
+    "query",
+    "headers",
+    "cookies",
+    "body",
+    "etag",
+    "result",
+    "resp",
+    "respBody",
+    "err",
+    "span",
+    "tracer",
+    "attribute",
+    "trace",
+    "context",
+    "errors",
+    "fmt",
+    "http",
+    "io",
+    "json",
+    "strings",
+    "time",
+    "url",
+];
+
+/// [`parameter_name`]s of one operation's parameters, numbered like
+/// [`field_names`] where two would collide (`thing-id` and `thing_id`) or one
+/// would shadow a name in `locals`, which the generated method declares
+pub fn parameter_names<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+    language: Language,
+    locals: &[&str],
+) -> Vec<String> {
+    let names = names
+        .into_iter()
+        .map(|name| (name, parameter_name(name, language)))
+        .collect();
+    numbered(names, locals, language)
+}
+
+/// The sanitized names of `(name, sanitized)` pairs, unique and outside
+/// `reserved`: names that are already their own sanitized form keep it, the
+/// others take the first free `_2`, `_3`, ... suffix (`2`, `3`, ... in Go)
+fn numbered(names: Vec<(&str, String)>, reserved: &[&str], language: Language) -> Vec<String> {
+    let mut taken: HashSet<String> = reserved.iter().map(|name| name.to_string()).collect();
+    let kept: Vec<bool> = names
+        .iter()
+        .map(|(name, base)| name == base && taken.insert(base.clone()))
+        .collect();
+    names
+        .into_iter()
+        .zip(kept)
+        .map(|((_, base), kept)| {
+            if kept {
+                return base;
+            }
+            let mut candidate = base.clone();
+            let mut n = 2;
+            while !taken.insert(candidate.clone()) {
+                candidate = match language {
+                    Language::Go => format!("{}{}", base, n),
+                    _ => format!("{}_{}", base, n),
+                };
+                n += 1;
+            }
+            candidate
+        })
+        .collect()
+}
+
+/// Valid identifier for a variable, parameter, field, or function.
+/// Invalid characters become `_`; reserved words get a trailing `_`.
+pub fn identifier(name: &str, language: Language) -> String {
+    let mut result = replace_invalid_chars(name, language);
+
+    let reserved: &[&[&str]] = match language {
+        Language::TypeScript => &[TYPESCRIPT_KEYWORDS],
+        Language::Python => &[PYTHON_KEYWORDS, PYTHON_MODEL_ATTRIBUTES],
+        Language::Go => &[GO_KEYWORDS, GO_PREDECLARED],
+    };
+    if reserved
+        .iter()
+        .any(|words| words.contains(&result.as_str()))
+    {
+        result.push('_');
+    }
+
+    result
+}
+
+/// Valid type name for a schema (type alias, class, or struct)
+fn builtin_type_name(name: &str, language: Language) -> String {
+    let mut result = match language {
+        Language::Go => go_exported(name),
+        // PEP 8 class names; schema names that differ only in case and
+        // separators (`user_profile`, `UserProfile`) were already told apart by
+        // the parser's collision renaming
+        Language::Python => replace_invalid_chars(&pascal_case(name), language),
+        Language::TypeScript => replace_invalid_chars(name, language),
+    };
+
+    let reserved: &[&[&str]] = match language {
+        Language::TypeScript => &[TYPESCRIPT_KEYWORDS, TYPESCRIPT_TYPE_NAMES],
+        Language::Python => &[PYTHON_KEYWORDS],
+        Language::Go => &[],
+    };
+    if reserved
+        .iter()
+        .any(|words| words.contains(&result.as_str()))
+    {
+        result.push('_');
+    }
+
+    result
+}
+
+/// Exported Go identifier: `created_at` / `createdAt` / `x-trace` → `CreatedAt` / `XTrace`
+pub fn go_exported(name: &str) -> String {
+    let result = pascal_case(name);
+
+    // Digits and uncased letters (`日本`) would leave the name unexported
+    match result.chars().next() {
+        None => "X".to_string(),
+        Some(c) if !c.is_uppercase() => format!("X{}", result),
+        Some(_) => result,
+    }
+}
+
+/// `list_users` / `listUsers` / `list-users` → `ListUsers`
+pub fn pascal_case(name: &str) -> String {
+    let mut result = String::new();
+
+    for segment in name.split(|c: char| !is_word_char(c)) {
+        let mut chars = segment.chars();
+        if let Some(first) = chars.next() {
+            result.extend(first.to_uppercase());
+            result.push_str(chars.as_str());
+        }
+    }
+
+    result
+}
+
+/// `List users` / `list_users` / `ListUsers` → `listUsers`
+pub fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => pascal,
+    }
+}
+
+/// TypeScript object key: bare when it is a valid identifier, otherwise quoted
+pub fn ts_property_key(name: &str) -> String {
+    if is_plain_identifier(name, Language::TypeScript) {
+        name.to_string()
+    } else {
+        quote(name)
+    }
+}
+
+/// TypeScript member access: `.name` or `["name"]`
+pub fn ts_property_access(name: &str) -> String {
+    if is_plain_identifier(name, Language::TypeScript) {
+        format!(".{}", name)
+    } else {
+        format!("[{}]", quote(name))
+    }
+}
+
+/// Double-quoted string literal valid in TypeScript, Python, and Go
+pub fn quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("\"{}\"", value))
+}
+
+/// `NewPet` / `newPet` → `new_pet`
+pub fn snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.extend(ch.to_lowercase());
+        } else {
+            result.push(ch);
+        }
+    }
+    result
+}
+
+fn is_plain_identifier(name: &str, language: Language) -> bool {
+    !name.is_empty() && replace_invalid_chars(name, language) == name
+}
+
+/// ASCII letters and digits, and letters of other scripts (`é`, `日`), which
+/// all three languages accept in identifiers; not other digits (`²`)
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || (!c.is_ascii() && c.is_alphabetic())
+}
+
+fn replace_invalid_chars(name: &str, language: Language) -> String {
+    let mut result: String = name
+        .chars()
+        .map(|c| {
+            let valid =
+                is_word_char(c) || c == '_' || (c == '$' && language == Language::TypeScript);
+            if valid {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    match result.chars().next() {
+        None => result.push('_'),
+        Some(c) if c.is_ascii_digit() => result.insert(0, '_'),
+        Some(_) => {}
+    }
+
+    result
+}
+
+/// `name` with Latin and Cyrillic letters spelled in ASCII (`größe` →
+/// `groesse`, `имя` → `imya`) and other non-ASCII letters as their code
+/// points (`日本` → `u65e5_u672c`)
+pub fn transliterate(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    // A code point is kept apart from the letters around it
+    let mut after_code_point = false;
+    for c in name.chars() {
+        let code_point = !c.is_ascii() && c.is_alphabetic() && ascii_letter(c).is_none();
+        if (code_point || after_code_point)
+            && !result.is_empty()
+            && !result.ends_with('_')
+            && c != '_'
+        {
+            result.push('_');
+        }
+        match ascii_letter(c) {
+            Some(ascii) => result.push_str(ascii),
+            None if code_point => result.push_str(&format!("u{:04x}", c as u32)),
+            None => result.push(c),
+        }
+        after_code_point = code_point;
+    }
+    result
+}
+
+/// ASCII spelling of a Latin letter with diacritics or a Cyrillic letter
+fn ascii_letter(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ä' | 'æ' => "ae",
+        'Ä' | 'Æ' => "Ae",
+        'ö' | 'œ' => "oe",
+        'Ö' | 'Œ' => "Oe",
+        'ü' => "ue",
+        'Ü' => "Ue",
+        'ß' => "ss",
+        'þ' => "th",
+        'Þ' => "Th",
+        'à' | 'á' | 'â' | 'ã' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'À' | 'Á' | 'Â' | 'Ã' | 'Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'Ç' | 'Ć' | 'Ĉ' | 'Ċ' | 'Č' => "C",
+        'ð' | 'ď' | 'đ' => "d",
+        'Ð' | 'Ď' | 'Đ' => "D",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => "G",
+        'ĥ' | 'ħ' => "h",
+        'Ĥ' | 'Ħ' => "H",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' => "I",
+        'ĵ' => "j",
+        'Ĵ' => "J",
+        'ķ' => "k",
+        'Ķ' => "K",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'Ĺ' | 'Ļ' | 'Ľ' | 'Ŀ' | 'Ł' => "L",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'Ñ' | 'Ń' | 'Ņ' | 'Ň' => "N",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ø' | 'Ō' | 'Ŏ' | 'Ő' => "O",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'Ŕ' | 'Ŗ' | 'Ř' => "R",
+        'ś' | 'ŝ' | 'ş' | 'š' => "s",
+        'Ś' | 'Ŝ' | 'Ş' | 'Š' => "S",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'Ţ' | 'Ť' | 'Ŧ' => "T",
+        'ù' | 'ú' | 'û' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'Ù' | 'Ú' | 'Û' | 'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => "U",
+        'ŵ' => "w",
+        'Ŵ' => "W",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'Ý' | 'Ÿ' | 'Ŷ' => "Y",
+        'ź' | 'ż' | 'ž' => "z",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' | 'ґ' => "g",
+        'д' => "d",
+        'е' | 'ё' | 'є' => "e",
+        'ж' => "zh",
+        'з' => "z",
+        'и' | 'і' => "i",
+        'ї' => "yi",
+        'й' => "y",
+        'к' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' => "u",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ы' => "y",
+        'э' => "e",
+        'ю' => "yu",
+        'я' => "ya",
+        'А' => "A",
+        'Б' => "B",
+        'В' => "V",
+        'Г' | 'Ґ' => "G",
+        'Д' => "D",
+        'Е' | 'Ё' | 'Є' => "E",
+        'Ж' => "Zh",
+        'З' => "Z",
+        'И' | 'І' => "I",
+        'Ї' => "Yi",
+        'Й' => "Y",
+        'К' => "K",
+        'Л' => "L",
+        'М' => "M",
+        'Н' => "N",
+        'О' => "O",
+        'П' => "P",
+        'Р' => "R",
+        'С' => "S",
+        'Т' => "T",
+        'У' => "U",
+        'Ф' => "F",
+        'Х' => "Kh",
+        'Ц' => "Ts",
+        'Ч' => "Ch",
+        'Ш' => "Sh",
+        'Щ' => "Shch",
+        'Ъ' | 'Ь' => "",
+        'Ы' => "Y",
+        'Э' => "E",
+        'Ю' => "Yu",
+        'Я' => "Ya",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameter_names_are_numbered_past_each_other() {
+        let names = ["thing-id", "thing_id", "params", "path"];
+        assert_eq!(
+            parameter_names(names, Language::Python, PYTHON_METHOD_LOCALS),
+            vec!["thing_id_2", "thing_id", "params", "path_2"]
+        );
+        assert_eq!(
+            parameter_names(names, Language::Go, GO_METHOD_LOCALS),
+            vec!["thing_id2", "thing_id", "params", "path2"]
+        );
+    }
+
+    #[test]
+    fn parameter_names_avoid_method_locals() {
+        for local in PYTHON_METHOD_LOCALS {
+            let names = parameter_names([*local], Language::Python, PYTHON_METHOD_LOCALS);
+            assert_eq!(names, vec![format!("{}_2", local)]);
+        }
+        for local in GO_METHOD_LOCALS {
+            let names = parameter_names([*local], Language::Go, GO_METHOD_LOCALS);
+            assert_eq!(names, vec![format!("{}2", local)]);
+        }
+        // A suffixed name the spec already uses is skipped
+        assert_eq!(
+            parameter_names(["path", "path_2"], Language::Python, PYTHON_METHOD_LOCALS),
+            vec!["path_3", "path_2"]
+        );
+    }
+
+    #[test]
+    fn repeated_wire_names_in_different_locations_are_numbered() {
+        assert_eq!(
+            parameter_names(["id", "id"], Language::Python, &[]),
+            vec!["id", "id_2"]
+        );
+        assert_eq!(
+            parameter_names(["user-id", "user.id"], Language::Python, &[]),
+            vec!["user_id", "user_id_2"]
+        );
+    }
+
+    #[test]
+    fn colliding_field_names_are_numbered() {
+        assert_eq!(
+            field_names(["first-name", "first_name"], Language::Go),
+            vec!["FirstName", "FirstName2"]
+        );
+        assert_eq!(
+            field_names(["first-name", "first_name"], Language::Python),
+            vec!["first_name_2", "first_name"]
+        );
+        // HAL `_links` loses its underscore, so `links` keeps the plain name
+        assert_eq!(
+            field_names(["_links", "links"], Language::Python),
+            vec!["links_2", "links"]
+        );
+        assert_eq!(
+            field_names(["_links", "links"], Language::Go),
+            vec!["Links", "Links2"]
+        );
+    }
+
+    #[test]
+    fn field_names_avoid_keywords_and_model_attributes() {
+        assert_eq!(
+            field_name("model_config", Language::Python),
+            "model_config_"
+        );
+        assert_eq!(field_name("class", Language::Python), "class_");
+        assert_eq!(field_name("type", Language::Go), "Type");
+        assert_eq!(field_name("delete", Language::TypeScript), "delete_");
+        assert_eq!(parameter_name("type", Language::Go), "type_");
+    }
+
+    #[test]
+    fn invalid_field_names_become_identifiers() {
+        assert_eq!(field_name("123", Language::Python), "field_123");
+        assert_eq!(field_name("123", Language::Go), "X123");
+        assert_eq!(field_name("_", Language::Python), "field_");
+        assert_eq!(field_name("$ref", Language::Python), "ref");
+        assert_eq!(field_name("@type", Language::Go), "Type");
+        assert_eq!(field_name("user.id", Language::Python), "user_id");
+        // Letters of other scripts are kept; uncased ones cannot export in Go
+        assert_eq!(field_name("größe", Language::Python), "größe");
+        assert_eq!(field_name("日本", Language::Go), "X日本");
+    }
+
+    #[test]
+    fn ascii_identifiers_transliterate_first() {
+        assert_eq!(transliterate("größe"), "groesse");
+        assert_eq!(transliterate("имя"), "imya");
+        assert_eq!(transliterate("日本"), "u65e5_u672c");
+        assert_eq!(
+            ascii_scoped(|| field_name("größe", Language::Python)),
+            "groesse"
+        );
+        assert_eq!(
+            ascii_scoped(|| field_name("日本", Language::Go)),
+            "U65e5U672c"
+        );
+        assert_eq!(ascii_scoped(|| type_name("café", Language::Go)), "Cafe");
+    }
+}
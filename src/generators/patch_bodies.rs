@@ -0,0 +1,239 @@
+//! Bodies of operations sending JSON Merge Patch (RFC 7396) or JSON Patch
+//! (RFC 6902) documents (`patchBodies` generation option, on by default):
+//! - `application/merge-patch+json`: the body schema is replaced by a
+//!   deep-partial copy, `UserPatch`. Every field is optional, those the
+//!   original does not require may be null (which removes them), and fields
+//!   referencing another object schema take its copy in turn. Arrays and maps
+//!   are replaced whole by a merge patch, so their values keep the full schema.
+//! - `application/json-patch+json`: the body becomes a list of
+//!   `JsonPatchOperation`, which the `typescript` and `python` clients build
+//!   with typed helpers (`jsonPatch<User>()`, `patch_replace(...)`)
+//!
+//! A spec component named `JsonPatchOperation` is used instead of the added
+//! one; copies whose name is taken get a numeric suffix (`UserPatch2`).
+
+use super::all_of;
+use crate::config::GenerationConfig;
+use crate::parsers::{
+    Constraints, FieldDefinition, SchemaDefinition, SchemaIR, SchemaReference, TypeInfo,
+};
+use indexmap::IndexMap;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Name of the schema JSON Patch bodies are lists of
+pub const JSON_PATCH_OPERATION: &str = "JsonPatchOperation";
+
+pub struct PatchBodies {
+    enabled: bool,
+}
+
+impl PatchBodies {
+    pub fn from_config(config: &GenerationConfig) -> Self {
+        let enabled = config
+            .options
+            .get("patchBodies")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        Self { enabled }
+    }
+
+    /// The IR with patch bodies retyped and the schemas they need added
+    pub fn apply(self, schema_ir: Cow<'_, SchemaIR>) -> Cow<'_, SchemaIR> {
+        let patched = |media_type: &str| {
+            schema_ir.operations.iter().any(|op| {
+                op.request_content_type
+                    .as_deref()
+                    .is_some_and(|c| is_media_type(c, media_type))
+            })
+        };
+        if !self.enabled || !(patched(MERGE_PATCH) || patched(JSON_PATCH)) {
+            return schema_ir;
+        }
+
+        let mut prepared = schema_ir.into_owned();
+        let mut copies = HashMap::new();
+        let mut operations = std::mem::take(&mut prepared.operations);
+        for op in &mut operations {
+            let Some(content_type) = op.request_content_type.as_deref() else {
+                continue;
+            };
+            if is_media_type(content_type, JSON_PATCH) {
+                op.request_body = Some(SchemaReference {
+                    name: JSON_PATCH_OPERATION.to_string(),
+                    schema_type: "array".to_string(),
+                });
+                if !prepared
+                    .schemas
+                    .iter()
+                    .any(|s| s.name == JSON_PATCH_OPERATION)
+                {
+                    prepared.schemas.push(json_patch_operation());
+                }
+            } else if is_media_type(content_type, MERGE_PATCH) {
+                let Some(body) = op
+                    .request_body
+                    .as_mut()
+                    .filter(|b| b.schema_type == "object")
+                else {
+                    continue;
+                };
+                if let Some(copy) = merge_patch(&mut prepared.schemas, &mut copies, &body.name) {
+                    body.name = copy;
+                }
+            }
+        }
+        prepared.operations = operations;
+        Cow::Owned(prepared)
+    }
+}
+
+const MERGE_PATCH: &str = "application/merge-patch+json";
+const JSON_PATCH: &str = "application/json-patch+json";
+
+fn is_media_type(content_type: &str, media_type: &str) -> bool {
+    content_type.to_ascii_lowercase().starts_with(media_type)
+}
+
+/// Name of the merge patch copy of `name`, added to `schemas` with those of
+/// the schemas it references; `None` when `name` is not an object schema
+fn merge_patch(
+    schemas: &mut Vec<SchemaDefinition>,
+    copies: &mut HashMap<String, String>,
+    name: &str,
+) -> Option<String> {
+    if let Some(copy) = copies.get(name) {
+        return Some(copy.clone());
+    }
+    let schema = schemas.iter().find(|s| s.name == name)?;
+    let fields = all_of::flattened_fields(schema, schemas);
+    if fields.is_empty() {
+        return None;
+    }
+    let audiences = schema.audiences.clone();
+
+    let base_name = format!("{}Patch", name);
+    let mut copy_name = base_name.clone();
+    let mut suffix = 2;
+    while schemas.iter().any(|s| s.name == copy_name) {
+        copy_name = format!("{}{}", base_name, suffix);
+        suffix += 1;
+    }
+    copies.insert(name.to_string(), copy_name.clone());
+    // Added before its fields, so references back to `name` find the copy
+    let index = schemas.len();
+    schemas.push(SchemaDefinition {
+        name: copy_name.clone(),
+        fields: Vec::new(),
+        description: Some(format!(
+            "Merge patch of `{}`: fields left out stay unchanged, null removes an optional field",
+            name
+        )),
+        translations: IndexMap::new(),
+        parents: Vec::new(),
+        merge_patch_of: Some(name.to_string()),
+        audiences,
+        original: serde_json::Value::Null,
+    });
+
+    let fields = fields
+        .into_iter()
+        .map(|mut field| {
+            let nested = field
+                .type_info
+                .reference
+                .clone()
+                .filter(|_| !field.type_info.is_array);
+            if let Some(copy) = nested.and_then(|nested| merge_patch(schemas, copies, &nested)) {
+                field.type_info.reference = Some(copy);
+            }
+            field.nullable |= !field.required;
+            field.required = false;
+            field
+        })
+        .collect();
+    schemas[index].fields = fields;
+    Some(copy_name)
+}
+
+fn json_patch_operation() -> SchemaDefinition {
+    let string = TypeInfo {
+        openapi_type: "string".to_string(),
+        format: None,
+        is_array: false,
+        array_item_type: None,
+        reference: None,
+        enum_values: None,
+        union_members: Vec::new(),
+        tuple_items: Vec::new(),
+        map_values: None,
+    };
+    let ops = ["add", "remove", "replace", "move", "copy", "test"];
+    let field =
+        |name: &str, type_info: TypeInfo, required: bool, description: &str| FieldDefinition {
+            name: name.to_string(),
+            type_info,
+            required,
+            nullable: false,
+            constraints: Constraints::default(),
+            description: Some(description.to_string()),
+            translations: IndexMap::new(),
+            audiences: Vec::new(),
+            original: serde_json::Value::Null,
+        };
+    SchemaDefinition {
+        name: JSON_PATCH_OPERATION.to_string(),
+        fields: vec![
+            field(
+                "op",
+                TypeInfo {
+                    enum_values: Some(ops.iter().map(|op| op.to_string()).collect()),
+                    ..string.clone()
+                },
+                true,
+                "What the operation does",
+            ),
+            field(
+                "path",
+                string.clone(),
+                true,
+                "JSON Pointer to the location it applies to",
+            ),
+            field(
+                "from",
+                string.clone(),
+                false,
+                "JSON Pointer to the source of `move` and `copy`",
+            ),
+            field(
+                "value",
+                TypeInfo {
+                    openapi_type: "any".to_string(),
+                    ..string
+                },
+                false,
+                "Value of `add`, `replace`, and `test`",
+            ),
+        ],
+        description: Some("One operation of a JSON Patch document (RFC 6902)".to_string()),
+        translations: IndexMap::new(),
+        parents: Vec::new(),
+        merge_patch_of: None,
+        audiences: Vec::new(),
+        original: serde_json::Value::Null,
+    }
+}
+
+/// Whether an operation sends `JsonPatchOperation` lists, so the clients add
+/// their builders
+pub fn json_patch(schema_ir: &SchemaIR) -> bool {
+    schema_ir.operations.iter().any(|op| {
+        op.request_content_type
+            .as_deref()
+            .is_some_and(|c| is_media_type(c, JSON_PATCH))
+            && op
+                .request_body
+                .as_ref()
+                .is_some_and(|b| b.name == JSON_PATCH_OPERATION)
+    })
+}
@@ -0,0 +1,54 @@
+//! Path parameter syntax of emitted route templates (`pathParamStyle`
+//! generation option), matching the server framework the routes are mounted
+//! in:
+//! - `braces` (default): `/users/{id}`, as the spec writes them
+//! - `colon`: `/users/:id` (Express, Fastify, Koa, Hono)
+//! - `angle`: `/users/<id>` (Flask, Werkzeug)
+
+use crate::config::GenerationConfig;
+use anyhow::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathParamStyle {
+    Braces,
+    Colon,
+    Angle,
+}
+
+impl PathParamStyle {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("pathParamStyle").map(|v| v.as_str()) {
+            None | Some(Some("braces")) => Ok(Self::Braces),
+            Some(Some("colon")) => Ok(Self::Colon),
+            Some(Some("angle")) => Ok(Self::Angle),
+            _ => anyhow::bail!(
+                "{}: option 'pathParamStyle' must be \"braces\", \"colon\", or \"angle\"",
+                config.generator
+            ),
+        }
+    }
+
+    /// `path` with its `{name}` parameters in this style
+    pub fn template(self, path: &str) -> String {
+        if self == Self::Braces {
+            return path.to_string();
+        }
+
+        let mut template = String::with_capacity(path.len());
+        let mut rest = path;
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+            let name = &rest[start + 1..start + end];
+            template.push_str(&rest[..start]);
+            match self {
+                Self::Colon => template.push_str(&format!(":{}", name)),
+                _ => template.push_str(&format!("<{}>", name)),
+            }
+            rest = &rest[start + end + 1..];
+        }
+        template.push_str(rest);
+        template
+    }
+}
@@ -0,0 +1,62 @@
+//! Where the server's base path ends up in generated code (`pathPrefixMode`
+//! generation option):
+//! - `server` (default): the base path stays in the base URL and operation
+//!   paths are kept as the spec declares them
+//! - `path`: the base path is prepended to every operation path and removed
+//!   from the base URL and server URLs, so routes mounted at the origin and
+//!   clients configured with a bare host agree on the full path
+
+use crate::config::GenerationConfig;
+use crate::parsers::SchemaIR;
+use anyhow::Result;
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathPrefixMode {
+    Server,
+    Path,
+}
+
+impl PathPrefixMode {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("pathPrefixMode").map(|v| v.as_str()) {
+            None | Some(Some("server")) => Ok(Self::Server),
+            Some(Some("path")) => Ok(Self::Path),
+            _ => anyhow::bail!(
+                "{}: option 'pathPrefixMode' must be \"server\" or \"path\"",
+                config.generator
+            ),
+        }
+    }
+
+    /// The IR with the base path moved where this mode puts it
+    pub fn apply(self, schema_ir: Cow<'_, SchemaIR>) -> Cow<'_, SchemaIR> {
+        let Some(base_path) = schema_ir.metadata.base_path.clone() else {
+            return schema_ir;
+        };
+        if self == Self::Server {
+            return schema_ir;
+        }
+
+        let mut prepared = schema_ir.into_owned();
+        for operation in &mut prepared.operations {
+            operation.path = format!("{}{}", base_path, operation.path);
+        }
+        if let Some(base_url) = &mut prepared.metadata.base_url {
+            *base_url = strip_base_path(base_url, &base_path);
+        }
+        for server in &mut prepared.metadata.servers {
+            server.url = strip_base_path(&server.url, &base_path);
+        }
+        Cow::Owned(prepared)
+    }
+}
+
+/// `url` without a trailing `base_path`; servers deployed under another path are kept
+fn strip_base_path(url: &str, base_path: &str) -> String {
+    let trimmed = url.trim_end_matches('/');
+    match trimmed.strip_suffix(base_path) {
+        Some(origin) => origin.to_string(),
+        None => url.to_string(),
+    }
+}
@@ -0,0 +1,607 @@
+//! Post-processors a generation's files go through before they are written
+//! (`postProcessors` option), in the order listed. Each entry is a name, or a
+//! map from the name to its argument (`- lineEndings: crlf`):
+//! - `lineEndings`: `lf` (default) or `crlf`
+//! - `trailingNewline`: exactly one newline at the end of non-empty files
+//! - `tabsToSpaces` / `spacesToTabs`: leading indentation converted, with a
+//!   tab as wide as the argument's number of spaces (default 4); lines inside
+//!   multi-line strings are converted too
+//! - `sortImports`: runs of consecutive import statements in TypeScript and
+//!   Python files sorted by module, packages before relative paths (Python's
+//!   `import x` before `from x import y`); a blank line, comment, or
+//!   side-effect import (`import './polyfills'`, `from __future__`) ends a run
+//!
+//! Library users add their own by registering a [`PostProcessor`] with
+//! [`GeneratorRegistry::register_post_processor`](super::GeneratorRegistry::register_post_processor).
+
+use super::{GeneratedOutput, GeneratorRegistry};
+use crate::config::GenerationConfig;
+use anyhow::{Context, Result};
+use serde_yaml::Value;
+
+/// A transformation of generated files, named in `postProcessors`
+pub trait PostProcessor: Send + Sync {
+    /// Name generations list it under
+    fn name(&self) -> &str;
+
+    /// Rejects an `argument` it cannot use, before anything is generated;
+    /// `Null` when the entry gives none
+    fn validate(&self, argument: &Value) -> Result<()> {
+        match argument {
+            Value::Null => Ok(()),
+            _ => anyhow::bail!("takes no argument"),
+        }
+    }
+
+    /// `content` of the file `filename` (relative to the output directory), processed
+    fn process(&self, filename: &str, content: &str, argument: &Value) -> Result<String>;
+}
+
+/// The built-in post-processors, as a registry starts out with
+pub(super) fn builtins() -> Vec<Box<dyn PostProcessor>> {
+    vec![
+        Box::new(LineEndings),
+        Box::new(TrailingNewline),
+        Box::new(Indentation::TabsToSpaces),
+        Box::new(Indentation::SpacesToTabs),
+        Box::new(SortImports),
+    ]
+}
+
+/// The post-processors of a generation, with their arguments
+pub struct PostProcessors<'a> {
+    steps: Vec<(&'a dyn PostProcessor, Value)>,
+}
+
+impl<'a> PostProcessors<'a> {
+    pub fn from_config(config: &GenerationConfig, registry: &'a GeneratorRegistry) -> Result<Self> {
+        let entries = match config.options.get("postProcessors") {
+            None => return Ok(Self { steps: Vec::new() }),
+            Some(Value::Sequence(entries)) => entries,
+            Some(_) => anyhow::bail!(
+                "{}: option 'postProcessors' must be a list of post-processor names",
+                config.generator
+            ),
+        };
+
+        let mut steps = Vec::new();
+        for entry in entries {
+            let (name, argument) = match entry {
+                Value::String(name) => (name.as_str(), Value::Null),
+                Value::Mapping(mapping) if mapping.len() == 1 => match mapping.iter().next() {
+                    Some((Value::String(name), argument)) => (name.as_str(), argument.clone()),
+                    _ => anyhow::bail!("{}: 'postProcessors' entries must be named", config.generator),
+                },
+                _ => anyhow::bail!(
+                    "{}: 'postProcessors' entries must be a name or a map from a name to its argument",
+                    config.generator
+                ),
+            };
+            let processor = registry.post_processor(name).ok_or_else(|| {
+                let mut available = registry.available_post_processors();
+                available.sort();
+                anyhow::anyhow!(
+                    "{}: unknown post-processor '{}' (available: {})",
+                    config.generator,
+                    name,
+                    available.join(", ")
+                )
+            })?;
+            processor
+                .validate(&argument)
+                .with_context(|| format!("{}: post-processor '{}'", config.generator, name))?;
+            steps.push((processor, argument));
+        }
+        Ok(Self { steps })
+    }
+
+    /// `output` with every file run through the post-processors in order
+    pub fn apply(&self, mut output: GeneratedOutput) -> Result<GeneratedOutput> {
+        for (processor, argument) in &self.steps {
+            let process = |filename: &str, content: &str| {
+                processor
+                    .process(filename, content, argument)
+                    .with_context(|| {
+                        format!(
+                            "Post-processor '{}' failed on {}",
+                            processor.name(),
+                            filename
+                        )
+                    })
+            };
+            output.content = process(&output.filename, &output.content)?;
+            for companion in &mut output.companions {
+                companion.content = process(&companion.filename, &companion.content)?;
+            }
+        }
+        Ok(output)
+    }
+}
+
+struct LineEndings;
+
+impl PostProcessor for LineEndings {
+    fn name(&self) -> &str {
+        "lineEndings"
+    }
+
+    fn validate(&self, argument: &Value) -> Result<()> {
+        match argument {
+            Value::Null => Ok(()),
+            Value::String(ending) if ending == "lf" || ending == "crlf" => Ok(()),
+            _ => anyhow::bail!("takes \"lf\" or \"crlf\""),
+        }
+    }
+
+    fn process(&self, _filename: &str, content: &str, argument: &Value) -> Result<String> {
+        let lf = content.replace("\r\n", "\n").replace('\r', "\n");
+        Ok(match argument.as_str() {
+            Some("crlf") => lf.replace('\n', "\r\n"),
+            _ => lf,
+        })
+    }
+}
+
+struct TrailingNewline;
+
+impl PostProcessor for TrailingNewline {
+    fn name(&self) -> &str {
+        "trailingNewline"
+    }
+
+    fn process(&self, _filename: &str, content: &str, _argument: &Value) -> Result<String> {
+        let body = content.trim_end_matches(['\n', '\r']);
+        if body.is_empty() {
+            return Ok(String::new());
+        }
+        // Files with CRLF line endings keep them
+        let newline = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        Ok(format!("{}{}", body, newline))
+    }
+}
+
+enum Indentation {
+    TabsToSpaces,
+    SpacesToTabs,
+}
+
+impl PostProcessor for Indentation {
+    fn name(&self) -> &str {
+        match self {
+            Self::TabsToSpaces => "tabsToSpaces",
+            Self::SpacesToTabs => "spacesToTabs",
+        }
+    }
+
+    fn validate(&self, argument: &Value) -> Result<()> {
+        match argument {
+            Value::Null => Ok(()),
+            _ if argument
+                .as_u64()
+                .is_some_and(|width| (1..=16).contains(&width)) =>
+            {
+                Ok(())
+            }
+            _ => anyhow::bail!("takes the number of spaces a tab is wide (1 to 16)"),
+        }
+    }
+
+    fn process(&self, _filename: &str, content: &str, argument: &Value) -> Result<String> {
+        let width = argument.as_u64().unwrap_or(4) as usize;
+        let mut processed = String::with_capacity(content.len());
+        for line in content.split_inclusive('\n') {
+            let rest = line.trim_start_matches([' ', '\t']);
+            let indent = &line[..line.len() - rest.len()];
+            // Columns of the indentation, with tabs advancing to the next stop
+            let columns = indent.chars().fold(0, |column, c| match c {
+                '\t' => (column / width + 1) * width,
+                _ => column + 1,
+            });
+            match self {
+                Self::TabsToSpaces => processed.push_str(&" ".repeat(columns)),
+                Self::SpacesToTabs => {
+                    processed.push_str(&"\t".repeat(columns / width));
+                    processed.push_str(&" ".repeat(columns % width));
+                }
+            }
+            processed.push_str(rest);
+        }
+        Ok(processed)
+    }
+}
+
+struct SortImports;
+
+impl PostProcessor for SortImports {
+    fn name(&self) -> &str {
+        "sortImports"
+    }
+
+    fn process(&self, filename: &str, content: &str, _argument: &Value) -> Result<String> {
+        let syntax = match filename
+            .rsplit_once('.')
+            .map_or("", |(_, extension)| extension)
+        {
+            "ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs" => ImportSyntax::TypeScript,
+            "py" | "pyi" => ImportSyntax::Python,
+            _ => return Ok(content.to_string()),
+        };
+
+        let lines: Vec<&str> = content.split_inclusive('\n').collect();
+        let mut sorted = String::with_capacity(content.len());
+        let mut run: Vec<(ImportKey, String)> = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let statement = syntax
+                .starts_statement(lines[i])
+                .then(|| syntax.statement_end(&lines[i..]))
+                .flatten();
+            let Some(end) = statement else {
+                flush(&mut run, &mut sorted);
+                sorted.push_str(lines[i]);
+                i += 1;
+                continue;
+            };
+            let statement = lines[i..=i + end].concat();
+            run.push((syntax.key(&statement), statement));
+            i += end + 1;
+        }
+        flush(&mut run, &mut sorted);
+        Ok(sorted)
+    }
+}
+
+/// Where a statement sorts: packages before relative paths, then (Python)
+/// `import x` before `from x import y`, then by module
+type ImportKey = (bool, bool, String);
+
+/// The statements of `run` sorted onto `sorted`; a run ending a file without
+/// a final newline still ends without one
+fn flush(run: &mut Vec<(ImportKey, String)>, sorted: &mut String) {
+    let Some((_, last)) = run.last() else {
+        return;
+    };
+    let unterminated = !last.ends_with('\n');
+    run.sort_by(|a, b| a.0.cmp(&b.0));
+    for (_, statement) in run.drain(..) {
+        sorted.push_str(&statement);
+        if !statement.ends_with('\n') {
+            sorted.push('\n');
+        }
+    }
+    if unterminated {
+        sorted.pop();
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ImportSyntax {
+    TypeScript,
+    Python,
+}
+
+impl ImportSyntax {
+    /// Whether `line` opens an import statement that may be moved
+    fn starts_statement(self, line: &str) -> bool {
+        match self {
+            Self::TypeScript => {
+                line.starts_with("import ")
+                    && !line["import ".len()..]
+                        .trim_start()
+                        .starts_with(['\'', '"'])
+            }
+            Self::Python => {
+                (line.starts_with("import ")
+                    || (line.starts_with("from ") && line.contains(" import ")))
+                    && !line.starts_with("from __future__ ")
+            }
+        }
+    }
+
+    /// Index into `lines` of the last line of the statement opening `lines`;
+    /// `None` for one never closed
+    fn statement_end(self, lines: &[&str]) -> Option<usize> {
+        let mut open_parens = 0i32;
+        for (index, line) in lines.iter().enumerate() {
+            let line = line.trim_end();
+            let ended = match self {
+                Self::TypeScript => line.ends_with(';') || ends_with_module(line),
+                Self::Python => {
+                    open_parens +=
+                        line.matches('(').count() as i32 - line.matches(')').count() as i32;
+                    open_parens <= 0 && !line.ends_with('\\')
+                }
+            };
+            if ended {
+                return Some(index);
+            }
+        }
+        None
+    }
+
+    fn key(self, statement: &str) -> ImportKey {
+        let module = match self {
+            // The specifier: the last quoted string
+            Self::TypeScript => {
+                let quote = statement.rfind(['\'', '"']).unwrap_or(0);
+                let start = statement[..quote]
+                    .rfind(['\'', '"'])
+                    .map_or(0, |start| start + 1);
+                statement.get(start..quote).unwrap_or("").to_string()
+            }
+            Self::Python => statement
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or("")
+                .trim_end_matches(',')
+                .to_string(),
+        };
+        let from = matches!(self, Self::Python) && statement.starts_with("from ");
+        (module.starts_with('.'), from, module.to_lowercase())
+    }
+}
+
+/// Whether a line without a semicolon ends in `from 'module'`
+fn ends_with_module(line: &str) -> bool {
+    let Some(quote) = line.chars().last().filter(|c| *c == '\'' || *c == '"') else {
+        return false;
+    };
+    line[..line.len() - 1].rfind(quote).is_some_and(|start| {
+        line[..start]
+            .trim_end()
+            .strip_suffix("from")
+            .is_some_and(|before| before.is_empty() || before.ends_with([' ', '\t', '}']))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::GeneratedFile;
+    use std::collections::HashMap;
+
+    fn config(post_processors: &str) -> GenerationConfig {
+        serde_yaml::from_str(&format!(
+            "{{ generator: python, outputFile: client.py, options: {{ postProcessors: {} }} }}",
+            post_processors
+        ))
+        .unwrap()
+    }
+
+    fn output(content: &str, companion: &str) -> GeneratedOutput {
+        GeneratedOutput {
+            filename: "client.py".to_string(),
+            content: content.to_string(),
+            metadata: HashMap::new(),
+            companions: vec![GeneratedFile {
+                filename: "models.ts".to_string(),
+                content: companion.to_string(),
+            }],
+            warnings: Vec::new(),
+        }
+    }
+
+    fn run(post_processors: &str, registry: &GeneratorRegistry, content: &str) -> Result<String> {
+        let processors = PostProcessors::from_config(&config(post_processors), registry)?;
+        Ok(processors.apply(output(content, ""))?.content)
+    }
+
+    /// Fails on files named `client.py`, leaving others alone
+    struct RejectsClient;
+
+    impl PostProcessor for RejectsClient {
+        fn name(&self) -> &str {
+            "rejectsClient"
+        }
+
+        fn process(&self, filename: &str, content: &str, _argument: &Value) -> Result<String> {
+            match filename {
+                "client.py" => anyhow::bail!("refusing to touch the client"),
+                _ => Ok(content.to_string()),
+            }
+        }
+    }
+
+    /// Appends its argument to every file
+    struct Append;
+
+    impl PostProcessor for Append {
+        fn name(&self) -> &str {
+            "append"
+        }
+
+        fn validate(&self, argument: &Value) -> Result<()> {
+            match argument {
+                Value::String(_) => Ok(()),
+                _ => anyhow::bail!("takes the text to append"),
+            }
+        }
+
+        fn process(&self, _filename: &str, content: &str, argument: &Value) -> Result<String> {
+            Ok(format!("{}{}", content, argument.as_str().unwrap_or("")))
+        }
+    }
+
+    fn registry() -> GeneratorRegistry {
+        let mut registry = GeneratorRegistry::new();
+        registry.register_post_processor(Box::new(RejectsClient));
+        registry.register_post_processor(Box::new(Append));
+        registry
+    }
+
+    #[test]
+    fn processors_run_in_the_listed_order() {
+        let registry = registry();
+        assert_eq!(
+            run("[{ append: a }, { append: b }]", &registry, "x").unwrap(),
+            "xab"
+        );
+        assert_eq!(
+            run("[{ append: b }, { append: a }]", &registry, "x").unwrap(),
+            "xba"
+        );
+        // `crlf` only converts what earlier steps produced; `trailingNewline` after it follows the file's endings
+        assert_eq!(
+            run(
+                "[trailingNewline, { lineEndings: crlf }]",
+                &registry,
+                "a\nb"
+            )
+            .unwrap(),
+            "a\r\nb\r\n"
+        );
+        assert_eq!(
+            run(
+                "[{ lineEndings: crlf }, { append: \"\\n\" }, trailingNewline]",
+                &registry,
+                "a\nb"
+            )
+            .unwrap(),
+            "a\r\nb\r\n"
+        );
+        assert_eq!(
+            run(
+                "[{ lineEndings: crlf }, { append: \"\\n\" }]",
+                &registry,
+                "a\nb"
+            )
+            .unwrap(),
+            "a\r\nb\n"
+        );
+    }
+
+    #[test]
+    fn indentation_conversions_compose() {
+        let registry = registry();
+        let content = "def f():\n\tif x:\n\t\treturn 1\n";
+        assert_eq!(
+            run("[{ tabsToSpaces: 2 }]", &registry, content).unwrap(),
+            "def f():\n  if x:\n    return 1\n"
+        );
+        assert_eq!(
+            run(
+                "[{ tabsToSpaces: 2 }, { spacesToTabs: 2 }]",
+                &registry,
+                content
+            )
+            .unwrap(),
+            content
+        );
+        // Spaces short of a tab stop stay spaces
+        assert_eq!(
+            run("[spacesToTabs]", &registry, "      x\n").unwrap(),
+            "\t  x\n"
+        );
+    }
+
+    #[test]
+    fn sort_imports_then_other_processors() {
+        let registry = registry();
+        let content = "from .models import User\nimport json\nfrom typing import Any\n\nx = 1";
+        assert_eq!(
+            run("[sortImports, trailingNewline]", &registry, content).unwrap(),
+            "import json\nfrom typing import Any\nfrom .models import User\n\nx = 1\n"
+        );
+    }
+
+    #[test]
+    fn companions_are_processed_too() {
+        let registry = registry();
+        let processors =
+            PostProcessors::from_config(&config("[{ append: '!' }]"), &registry).unwrap();
+        let processed = processors.apply(output("a", "b")).unwrap();
+        assert_eq!(processed.content, "a!");
+        assert_eq!(processed.companions[0].content, "b!");
+    }
+
+    #[test]
+    fn a_failing_processor_names_itself_and_the_file() {
+        let registry = registry();
+        let processors =
+            PostProcessors::from_config(&config("[rejectsClient]"), &registry).unwrap();
+        let error = processors.apply(output("a", "b")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Post-processor 'rejectsClient' failed on client.py"
+        );
+        assert_eq!(
+            error.root_cause().to_string(),
+            "refusing to touch the client"
+        );
+
+        // Companions fail the same way
+        let mut output = output("a", "b");
+        std::mem::swap(&mut output.filename, &mut output.companions[0].filename);
+        let error = processors.apply(output).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Post-processor 'rejectsClient' failed on client.py"
+        );
+    }
+
+    #[test]
+    fn unknown_names_and_bad_arguments_are_rejected_up_front() {
+        let registry = registry();
+        let error = PostProcessors::from_config(&config("[prettier]"), &registry)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(
+            error.starts_with("python: unknown post-processor 'prettier' (available: append, "),
+            "{}",
+            error
+        );
+
+        let error = PostProcessors::from_config(&config("[{ lineEndings: cr }]"), &registry)
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "python: post-processor 'lineEndings'");
+        assert_eq!(error.root_cause().to_string(), "takes \"lf\" or \"crlf\"");
+
+        for (entries, message) in [
+            ("[append]", "takes the text to append"),
+            ("[{ sortImports: true }]", "takes no argument"),
+            (
+                "[{ tabsToSpaces: 0 }]",
+                "takes the number of spaces a tab is wide (1 to 16)",
+            ),
+        ] {
+            let error = PostProcessors::from_config(&config(entries), &registry)
+                .err()
+                .unwrap();
+            assert_eq!(error.root_cause().to_string(), message, "{}", entries);
+        }
+
+        let error = PostProcessors::from_config(&config("lineEndings"), &registry)
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "python: option 'postProcessors' must be a list of post-processor names"
+        );
+        let error = PostProcessors::from_config(&config("[{ a: 1, b: 2 }]"), &registry)
+            .err()
+            .unwrap();
+        assert!(
+            error.to_string().contains("must be a name or a map"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn no_processors_leave_the_output_alone() {
+        let registry = registry();
+        let config: GenerationConfig =
+            serde_yaml::from_str("{ generator: python, outputFile: client.py }").unwrap();
+        let processors = PostProcessors::from_config(&config, &registry).unwrap();
+        assert_eq!(
+            processors.apply(output("a\r\n", "")).unwrap().content,
+            "a\r\n"
+        );
+    }
+}
@@ -1,9 +1,25 @@
+use super::conditional::Conditionals;
+use super::docs::{DocStyle, SeeAlso};
+use super::hypermedia::Hypermedia;
+use super::idempotency::{self, IdempotencyKey};
+use super::naming::{self, Language};
+use super::patch_bodies;
+use super::rate_limit::RateLimitOption;
+use super::readme;
+use super::request_encoding;
+use super::response_cache::{self, CacheDefaults};
+use super::templates;
+use super::type_overrides::{self, TypeOverrides};
 use super::{GeneratedOutput, Generator};
 use crate::config::GenerationConfig;
-use crate::parsers::SchemaIR;
+use crate::parsers::gateway;
+use crate::parsers::{
+    BodyKind, Constraints, ResponseDefinition, SchemaDefinition, SchemaIR, TypeInfo,
+};
 use anyhow::Result;
-use std::collections::HashMap;
-use tera::{Context, Tera};
+use std::cell::Cell;
+use std::collections::{BTreeSet, HashMap};
+use tera::Context;
 
 pub struct PythonGenerator;
 
@@ -21,15 +37,63 @@ impl Generator for PythonGenerator {
         schema_ir: &SchemaIR,
         config: &GenerationConfig,
     ) -> Result<GeneratedOutput> {
-        // Determine template path
-        let template_path = config
-            .template
-            .as_ref()
-            .and_then(|p| p.to_str())
-            .unwrap_or("templates/python");
-
-        let tera = Tera::new(&format!("{}/**/*.tera", template_path))?;
+        let tera = templates::load(config, templates::PYTHON)?;
+        let context = self.context(schema_ir, config)?;
+
+        // Render template
+        let content = tera.render("client.py.tera", &context)?;
+        let client_mode = ClientMode::from_config(config)?;
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content,
+            metadata: HashMap::new(),
+            companions: readme::companion(
+                schema_ir,
+                config,
+                readme::Client::Python {
+                    is_async: client_mode == ClientMode::Async,
+                },
+            )
+            .into_iter()
+            .collect(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn template_context(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<Option<serde_json::Value>> {
+        Ok(Some(self.context(schema_ir, config)?.into_json()))
+    }
+
+    /// Pydantic models subclass their parents; dataclass field order and
+    /// functional TypedDicts do not allow it
+    fn supports_inheritance(&self, config: &GenerationConfig) -> bool {
+        matches!(
+            ModelBackend::from_config(config),
+            Ok(ModelBackend::PydanticV2)
+        )
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        ModelBackend::from_config(config)?;
+        DocStyle::from_config(config)?;
+        ClientMode::from_config(config)?;
+        RateLimitOption::from_config(config)?;
+        CacheDefaults::from_config(config)?;
+        TypeOverrides::from_config(config)?;
+        Ok(())
+    }
+}
+
+impl PythonGenerator {
+    /// The context `client.py.tera` is rendered with
+    fn context(&self, schema_ir: &SchemaIR, config: &GenerationConfig) -> Result<Context> {
         let mut context = Context::new();
+        context.insert("context_version", &templates::CONTEXT_VERSION);
 
         // Add metadata
         context.insert("api_title", &schema_ir.metadata.title);
@@ -43,27 +107,234 @@ impl Generator for PythonGenerator {
                 .unwrap_or_else(|| "http://localhost".to_string()),
         );
 
+        let doc_style = DocStyle::from_config(config)?;
+
+        // XML bodies are typed only when a (de)serializer module is provided
+        let xml_module = config.options.get("xmlModule").and_then(|v| v.as_str());
+        context.insert("xml_module", &xml_module);
+
+        // OpenTelemetry spans around every client call are opt-in
+        let open_telemetry = config
+            .options
+            .get("openTelemetry")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        context.insert("telemetry", &open_telemetry);
+        context.insert("tracer_name", &naming::quote(&schema_ir.metadata.title));
+
+        // Client-side throttling is opt-in; the spec's `x-rate-limit` supplies the defaults
+        let rate_limit = RateLimitOption::from_config(config)?;
+        context.insert("rate_limit", &rate_limit.enabled());
+        context.insert("client_rate_limit", &rate_limit.client_limit(schema_ir));
+        // Cached GET responses are opt-in
+        context.insert(
+            "cache",
+            &CacheDefaults::from_config(config)?.map(CacheDefaults::context),
+        );
+        // Idempotency keys on unsafe calls are opt-in
+        let idempotency = IdempotencyKey::from_config(config)?;
+        context.insert("idempotency_key", &idempotency.enabled());
+        context.insert("idempotency_header", &idempotency::header_name(config)?);
+        // Link helpers for the HAL / JSON:API conventions the spec follows
+        context.insert(
+            "hypermedia",
+            &Hypermedia::from_config(config)?.formats(schema_ir),
+        );
+        // Names of the schemas added for JSON Patch bodies and hypermedia links
+        context.insert(
+            "support_types",
+            &naming::support_type_names(Language::Python),
+        );
+        // External types standing in for schemas and formats: `import decimal`
+        // for `decimal.Decimal`, `from money import Money` for `Money`
+        let mut type_imports = Vec::new();
+        for (module, types) in TypeOverrides::from_config(config)?.imports(schema_ir) {
+            let (qualified, bare): (BTreeSet<_>, BTreeSet<_>) = types
+                .into_iter()
+                .partition(|t| t.starts_with(&format!("{}.", module)));
+            if !qualified.is_empty() {
+                type_imports.push(format!("import {}", module));
+            }
+            if !bare.is_empty() {
+                type_imports.push(format!(
+                    "from {} import {}",
+                    module,
+                    type_overrides::imported_names(&bare).join(", ")
+                ));
+            }
+        }
+        context.insert("type_imports", &type_imports);
+        // File downloads, returned as bytes or streamed into a file
+        context.insert(
+            "downloads",
+            &schema_ir.operations.iter().any(|op| op.downloads()),
+        );
+        // Flag checks before operations declaring `x-feature-flag`
+        context.insert(
+            "feature_flags",
+            &schema_ir
+                .operations
+                .iter()
+                .any(|op| op.feature_flag.is_some()),
+        );
+
+        let model_backend = ModelBackend::from_config(config)?;
+        context.insert("model_backend", model_backend.name());
+        // Builders for JSON Patch bodies, and a base for merge patch models
+        context.insert("json_patch", &patch_bodies::json_patch(schema_ir));
+        let merge_patch = model_backend == ModelBackend::PydanticV2
+            && schema_ir
+                .schemas
+                .iter()
+                .any(|schema| schema.merge_patch_of.is_some());
+        context.insert("merge_patch", &merge_patch);
+
+        // Sync and async clients share the models and differ only in transport
+        let client_mode = ClientMode::from_config(config)?;
+        let clients: Vec<_> = [("ApiClient", false), ("AsyncApiClient", true)]
+            .into_iter()
+            .filter(|(_, is_async)| client_mode.includes(*is_async))
+            .map(|(name, is_async)| serde_json::json!({ "name": name, "is_async": is_async }))
+            .collect();
+        context.insert("clients", &clients);
+
         // Convert schemas for template
         let schemas_for_template: Vec<_> = schema_ir
             .schemas
             .iter()
             .map(|schema| {
-                let properties: Vec<_> = schema
+                let schema_name = naming::type_name(&schema.name, Language::Python);
+                // Enum schemas are the enum class (a `Literal` alias for TypedDict) only
+                if let Some((base, literals)) = component_enum(schema) {
+                    let alias: Vec<_> = literals.iter().map(|(_, literal)| literal.clone()).collect();
+                    return serde_json::json!({
+                        "name": schema_name,
+                        "enum": enum_class_of(&schema_name, base, literals),
+                        "literals": alias.join(", "),
+                        "bases": "",
+                        "enums": [],
+                        "properties": [],
+                        "functional": false,
+                        "has_checks": false,
+                        "description": schema.description,
+                        "doc": serde_json::Value::Null,
+                    });
+                }
+                let mut enums = Vec::new();
+
+                let idents = naming::field_names(schema.fields.iter().map(|f| f.name.as_str()), Language::Python);
+                let mut properties: Vec<_> = schema
                     .fields
                     .iter()
-                    .map(|field| {
+                    .zip(&idents)
+                    .map(|(field, ident)| {
+                        // Serialization alias keeps the wire name when the attribute is renamed
+                        let alias = (*ident != field.name).then(|| naming::quote(&field.name));
+                        let description = field
+                            .description
+                            .as_deref()
+                            .filter(|_| doc_style.enabled())
+                            .map(|description| naming::quote(&doc_style.plain(description)));
+
+                        let enum_values = field
+                            .type_info
+                            .enum_values
+                            .as_ref()
+                            .or_else(|| field.type_info.array_item_type.as_ref()?.enum_values.as_ref());
+                        let python_type = match enum_values {
+                            Some(values) => {
+                                let enum_type = if model_backend == ModelBackend::TypedDict {
+                                    let literals: Vec<_> = values.iter().map(|v| naming::quote(v)).collect();
+                                    format!("Literal[{}]", literals.join(", "))
+                                } else {
+                                    let enum_name = format!("{}{}", schema_name, naming::pascal_case(ident));
+                                    enums.push(enum_class(&enum_name, values));
+                                    enum_name
+                                };
+                                if field.type_info.is_array {
+                                    format!("List[{}]", enum_type)
+                                } else {
+                                    enum_type
+                                }
+                            }
+                            None => field.type_info.to_python(),
+                        };
+                        let optional = !field.required || field.nullable;
+
+                        let value = match model_backend {
+                            ModelBackend::PydanticV2 => {
+                                // `= None` / `= Field(...)`, or a full `Field(...)` when metadata is attached
+                                let default = if field.required { "..." } else { "None" };
+                                let mut field_args = vec![default.to_string()];
+                                field_args.extend(alias.as_ref().map(|a| format!("alias={}", a)));
+                                field_args.extend(description.map(|d| format!("description={}", d)));
+                                field_args.extend(pydantic_constraints(&field.constraints));
+                                match (field.required, field_args.len()) {
+                                    (true, 1) => None,
+                                    (false, 1) => Some("None".to_string()),
+                                    _ => Some(format!("Field({})", field_args.join(", "))),
+                                }
+                            }
+                            ModelBackend::Dataclass => {
+                                let metadata = alias.as_ref().map(|a| format!("metadata={{\"alias\": {}}}", a));
+                                match (field.required, metadata) {
+                                    (true, None) => None,
+                                    (true, Some(metadata)) => Some(format!("field({})", metadata)),
+                                    (false, None) => Some("None".to_string()),
+                                    (false, Some(metadata)) => Some(format!("field(default=None, {})", metadata)),
+                                }
+                            }
+                            ModelBackend::TypedDict => None,
+                        };
+
                         serde_json::json!({
-                            "name": field.name,
-                            "python_type": field.type_info.to_python(),
+                            "name": ident,
+                            "key": naming::quote(&field.name),
+                            "python_type": if optional { format!("Optional[{}]", python_type) } else { python_type },
                             "required": field.required,
+                            "value": value,
+                            "checks": if model_backend == ModelBackend::Dataclass {
+                                dataclass_checks(ident, &field.constraints)
+                            } else {
+                                Vec::new()
+                            },
                         })
                     })
                     .collect();
 
+                // Dataclass fields without defaults must come first; the sort is
+                // stable, so both groups keep the spec's order
+                if model_backend == ModelBackend::Dataclass {
+                    properties.sort_by_key(|p| p["required"] != true);
+                }
+
+                // TypedDict's class syntax needs identifier keys
+                let functional = model_backend == ModelBackend::TypedDict
+                    && schema.fields.iter().zip(&idents).any(|(f, ident)| *ident != f.name);
+
+                let mut bases: Vec<String> = schema
+                    .parents
+                    .iter()
+                    .map(|parent| naming::type_name(parent, Language::Python))
+                    .collect();
+                if merge_patch && schema.merge_patch_of.is_some() {
+                    bases.push("MergePatch".to_string());
+                }
+
                 serde_json::json!({
-                    "name": schema.name,
+                    "name": schema_name,
+                    "enum": serde_json::Value::Null,
+                    "bases": bases.join(", "),
+                    "enums": enums,
                     "properties": properties,
+                    "functional": functional,
+                    "has_checks": properties.iter().any(|p| p["checks"].as_array().is_some_and(|c| !c.is_empty())),
                     "description": schema.description,
+                    "doc": doc_style.python_docstring(
+                        &[Some(schema.description.as_deref().unwrap_or(&schema.name))],
+                        &[],
+                        "    ",
+                    ),
                 })
             })
             .collect();
@@ -71,37 +342,664 @@ impl Generator for PythonGenerator {
         context.insert("schemas", &schemas_for_template);
 
         // Convert operations
+        // The plain method of each operation, and a wrapper for its ETag precondition
+        let conditionals = Conditionals::new(schema_ir, config);
+        context.insert("conditional_requests", &conditionals.any(schema_ir));
+        let body = ResponseBodies {
+            schema_ir,
+            model_backend,
+            xml: xml_module.is_some(),
+            type_adapter: Cell::new(false),
+        };
+        let schema_names: BTreeSet<_> = schema_ir
+            .schemas
+            .iter()
+            .map(|schema| naming::type_name(&schema.name, Language::Python))
+            .collect();
         let operations_for_template: Vec<_> = schema_ir
             .operations
             .iter()
             .map(|op| {
+                let idents = naming::parameter_names(
+                    op.parameters.iter().map(|p| p.name.as_str()),
+                    Language::Python,
+                    naming::PYTHON_METHOD_LOCALS,
+                );
+                let arg_docs: Vec<_> = op
+                    .parameters
+                    .iter()
+                    .zip(&idents)
+                    .filter_map(|(p, ident)| Some((ident.clone(), p.description.as_deref()?)))
+                    .collect();
+                let summary = op.summary.as_deref().unwrap_or(&op.id);
+
+                let request_body = op.request_content_type.as_ref().map(|content_type| {
+                    let kind = BodyKind::from_content_type(content_type);
+                    let typed = kind == BodyKind::Json || (kind == BodyKind::Xml && xml_module.is_some());
+                    let body_type = match op.request_body.as_ref().filter(|_| typed) {
+                        Some(b) if b.schema_type == "array" => {
+                            format!("List[{}]", naming::type_name(&b.name, Language::Python))
+                        }
+                        Some(b) => naming::type_name(&b.name, Language::Python),
+                        None if kind == BodyKind::Json => "Any".to_string(),
+                        None => "str".to_string(),
+                    };
+                    serde_json::json!({
+                        "content_type": content_type,
+                        "kind": kind,
+                        "body_type": body_type,
+                    })
+                });
+
+                // The method returns the first success response with a body
+                let returned = op.responses.iter().position(|r| r.is_success() && r.content_type.is_some());
+                let responses: Vec<_> = op.responses.iter().enumerate().map(|(index, response)| {
+                    let kind = response.content_type.as_deref().map(BodyKind::from_content_type);
+                    let typed = kind == Some(BodyKind::Json) || (kind == Some(BodyKind::Xml) && xml_module.is_some());
+                    let schema_ref = response
+                        .schema
+                        .as_ref()
+                        .filter(|_| typed)
+                        .map(|b| naming::type_name(&b.name, Language::Python));
+                    let (python_type, decode) = body.of(response);
+                    serde_json::json!({
+                        "status_code": response.status,
+                        "content_type": response.content_type,
+                        "schema_ref": schema_ref,
+                        "python_type": python_type,
+                        "decode": decode,
+                        "is_array": response.is_array(),
+                        "returned": Some(index) == returned,
+                        "description": response.description,
+                    })
+                }).collect();
+
+                // Error responses with a body raise a subclass of `ApiError` decoding it
+                let errors: Vec<_> = op.error_responses().into_iter().map(|r| (r, body.of(r))).collect();
+                let error = (!errors.is_empty()).then(|| {
+                    let mut name = naming::type_name(&format!("{}Error", naming::pascal_case(&op.id)), Language::Python);
+                    if schema_names.contains(&name) {
+                        name.push_str("Response");
+                    }
+                    let mut types: Vec<&str> = Vec::new();
+                    for (_, (python_type, _)) in &errors {
+                        if !types.contains(&python_type.as_str()) {
+                            types.push(python_type);
+                        }
+                    }
+                    let statuses: Vec<_> = errors
+                        .iter()
+                        .map(|(response, (python_type, _))| format!("{} ({})", response.status, python_type))
+                        .collect();
+                    serde_json::json!({
+                        "name": name,
+                        "statuses": statuses.join(", "),
+                        "body_type": match types.as_slice() {
+                            [single] => single.to_string(),
+                            _ => format!("Union[{}]", types.join(", ")),
+                        },
+                        "bodies": errors
+                            .iter()
+                            .filter_map(|(response, (_, decode))| {
+                                let decode = decode.as_ref()?;
+                                Some(serde_json::json!({ "status": response.status, "decode": decode }))
+                            })
+                            .collect::<Vec<_>>(),
+                    })
+                });
+
                 serde_json::json!({
-                    "id": op.id,
+                    "id": naming::function_name(&op.id, Language::Python),
+                    "operation_id": op.id,
+                    "rate_limit": op.rate_limit,
+                    "cached": response_cache::applies(op),
+                    "idempotency_key": idempotency.applies(op),
+                    // Seconds, as the `timeout` argument takes them
+                    "timeout": op.timeout_ms.map(|ms| number(ms as f64 / 1000.0)),
+                    "feature_flag": op.feature_flag.as_deref().map(naming::quote),
+                    "variants": conditionals.variants(op),
+                    // A declared `idempotency-key` header already is the caller's way in
+                    "idempotency_argument": !idents.iter().any(|ident| ident == "idempotency_key"),
+                    "request_body": request_body,
+                    "download": op.downloads(),
+                    // Likewise for a declared `destination` parameter, which keeps downloads in memory
+                    "destination_argument": !idents.iter().any(|ident| ident == "destination"),
+                    "doc": doc_style.python_docstring_see(
+                        &[Some(summary), op.description.as_deref()],
+                        &arg_docs,
+                        &SeeAlso::of(schema_ir, op),
+                        "        ",
+                    ),
                     "method": format!("{:?}", op.method).to_uppercase(),
                     "path": op.path,
-                    "parameters": op.parameters.iter().map(|p| {
+                    "parameters": op.parameters.iter().zip(&idents).map(|(p, ident)| {
+                        let encoding = request_encoding::Plan::of(p);
                         serde_json::json!({
                             "name": p.name,
+                            "ident": ident,
                             "location": format!("{:?}", p.location).to_lowercase(),
                             "required": p.required,
                             "schema_type": p.schema_type,
+                            "type": encoding.value_type(Language::Python),
+                            "encoding": encoding,
                         })
                     }).collect::<Vec<_>>(),
-                    "responses": serde_json::json!([]),  // TODO: populate from op.response
+                    "responses": responses,
+                    "error": error,
                 })
             })
             .collect();
 
         context.insert("operations", &operations_for_template);
+        let typed_errors = operations_for_template
+            .iter()
+            .any(|op| !op["error"].is_null());
+        context.insert("typed_errors", &typed_errors);
+
+        // Gateway services, each a namespace of its operations' methods
+        let namespaces: Vec<_> = gateway::namespaces(schema_ir)
+            .into_iter()
+            .map(|(name, operations)| {
+                let members: Vec<_> = operations
+                    .into_iter()
+                    .flat_map(|(member, op)| {
+                        let member = naming::function_name(member, Language::Python);
+                        let target = naming::function_name(&op.id, Language::Python);
+                        conditionals.variants(op).into_iter().map(move |precondition| {
+                            let suffix = if precondition.is_some() { "Conditional" } else { "" };
+                            serde_json::json!({
+                                "name": format!("{}{}", member, suffix),
+                                "target": format!("{}{}", target, suffix),
+                            })
+                        })
+                    })
+                    .collect();
+                serde_json::json!({ "name": naming::function_name(name, Language::Python), "members": members })
+            })
+            .collect();
+        context.insert("namespaces", &namespaces);
+
+        // WebSocket channels are opt-in
+        let include_websockets = config
+            .options
+            .get("websockets")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let channels: Vec<_> = schema_ir
+            .channels
+            .iter()
+            .filter(|_| include_websockets)
+            .map(|channel| {
+                serde_json::json!({
+                    "name": naming::function_name(&channel.name, Language::Python),
+                    "path": channel.path,
+                    "description": channel.description,
+                    "doc": doc_style.python_docstring(
+                        &[Some(channel.description.as_deref().unwrap_or(&channel.name))],
+                        &[],
+                        "        ",
+                    ),
+                    "send": channel.send.as_ref().map(|s| naming::type_name(s, Language::Python)),
+                    "receive": channel.receive.as_ref().map(|s| naming::type_name(s, Language::Python)),
+                })
+            })
+            .collect();
+        context.insert(
+            "type_adapter",
+            &(!channels.is_empty() || body.type_adapter.get()),
+        );
+        context.insert("channels", &channels);
         context.insert("options", &config.options);
 
-        // Render template
-        let content = tera.render("client.py.tera", &context)?;
+        Ok(context)
+    }
+}
 
-        Ok(GeneratedOutput {
-            filename: config.output_file.clone(),
-            content,
-            metadata: HashMap::new(),
+/// Model classes emitted for schemas (`modelBackend` option)
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ModelBackend {
+    PydanticV2,
+    Dataclass,
+    TypedDict,
+}
+
+impl ModelBackend {
+    fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("modelBackend").map(|v| v.as_str()) {
+            None | Some(Some("pydantic_v2")) => Ok(Self::PydanticV2),
+            Some(Some("dataclass")) => Ok(Self::Dataclass),
+            Some(Some("typeddict")) => Ok(Self::TypedDict),
+            _ => anyhow::bail!(
+                "python: option 'modelBackend' must be \"pydantic_v2\", \"dataclass\", or \"typeddict\""
+            ),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::PydanticV2 => "pydantic_v2",
+            Self::Dataclass => "dataclass",
+            Self::TypedDict => "typeddict",
+        }
+    }
+}
+
+/// How response bodies are typed and decoded, per model backend
+struct ResponseBodies<'a> {
+    schema_ir: &'a SchemaIR,
+    model_backend: ModelBackend,
+    xml: bool,
+    /// Whether a decoder uses pydantic's `TypeAdapter`
+    type_adapter: Cell<bool>,
+}
+
+impl ResponseBodies<'_> {
+    /// Annotation of the body of `response`, and the expression decoding its
+    /// JSON (`{value}`) into that type; `None` when the JSON already is one
+    fn of(&self, response: &ResponseDefinition) -> (String, Option<String>) {
+        let kind = response
+            .content_type
+            .as_deref()
+            .map(BodyKind::from_content_type);
+        let typed = kind == Some(BodyKind::Json) || (kind == Some(BodyKind::Xml) && self.xml);
+        if let Some(reference) = response.schema.as_ref().filter(|_| typed) {
+            let name = naming::type_name(&reference.name, Language::Python);
+            let is_array = response.is_array();
+            let python_type = match is_array {
+                true => format!("List[{}]", name),
+                false => name.clone(),
+            };
+            let is_enum = self
+                .schema_ir
+                .schemas
+                .iter()
+                .find(|schema| schema.name == reference.name)
+                .is_some_and(|schema| component_enum(schema).is_some());
+            let decode = match (self.model_backend, is_enum, is_array) {
+                (ModelBackend::TypedDict, _, _) => None,
+                (_, true, false) => Some(format!("{}({{value}})", name)),
+                (_, true, true) => Some(format!("[{}(item) for item in {{value}}]", name)),
+                (ModelBackend::PydanticV2, _, false) => {
+                    Some(format!("{}.model_validate({{value}})", name))
+                }
+                (ModelBackend::PydanticV2, _, true) => Some(format!(
+                    "[{}.model_validate(item) for item in {{value}}]",
+                    name
+                )),
+                (ModelBackend::Dataclass, _, _) => {
+                    Some(format!("_from_wire({}, {{value}})", python_type))
+                }
+            };
+            return (python_type, decode);
+        }
+
+        match &response.inline_type {
+            Some(type_info) if typed => {
+                let python_type = type_info.to_python();
+                let decode = match self.model_backend {
+                    _ if !needs_decoding(type_info) => None,
+                    ModelBackend::PydanticV2 => {
+                        self.type_adapter.set(true);
+                        Some(format!(
+                            "TypeAdapter({}).validate_python({{value}})",
+                            python_type
+                        ))
+                    }
+                    ModelBackend::Dataclass => {
+                        Some(format!("_from_wire({}, {{value}})", python_type))
+                    }
+                    ModelBackend::TypedDict => None,
+                };
+                (python_type, decode)
+            }
+            // Text, and XML without `xmlModule`, come back as the text sent
+            _ if matches!(kind, Some(BodyKind::Text | BodyKind::Xml)) && !typed => {
+                ("str".to_string(), None)
+            }
+            _ => ("Any".to_string(), None),
+        }
+    }
+}
+
+/// Whether JSON of the type needs converting to it: it holds models or dates
+fn needs_decoding(type_info: &TypeInfo) -> bool {
+    type_info.reference.is_some()
+        || (type_info.openapi_type == "string"
+            && matches!(type_info.format.as_deref(), Some("date" | "date-time")))
+        || type_info
+            .array_item_type
+            .as_deref()
+            .is_some_and(needs_decoding)
+        || type_info.map_values.as_deref().is_some_and(needs_decoding)
+}
+
+/// Which API clients are emitted (`clientMode` option)
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ClientMode {
+    /// `ApiClient` over `requests`
+    Sync,
+    /// `AsyncApiClient` over `httpx.AsyncClient`
+    Async,
+    Both,
+}
+
+impl ClientMode {
+    fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("clientMode").map(|v| v.as_str()) {
+            None | Some(Some("sync")) => Ok(Self::Sync),
+            Some(Some("async")) => Ok(Self::Async),
+            Some(Some("both")) => Ok(Self::Both),
+            _ => anyhow::bail!(
+                "python: option 'clientMode' must be \"sync\", \"async\", or \"both\""
+            ),
+        }
+    }
+
+    fn includes(self, is_async: bool) -> bool {
+        match self {
+            Self::Sync => !is_async,
+            Self::Async => is_async,
+            Self::Both => true,
+        }
+    }
+}
+
+/// `(str, Enum)` class with UPPER_SNAKE members
+fn enum_class(name: &str, values: &[String]) -> serde_json::Value {
+    let literals = values
+        .iter()
+        .map(|value| (value.clone(), naming::quote(value)))
+        .collect();
+    enum_class_of(name, "str", literals)
+}
+
+/// Template data for an enum class over `base`, from `(value, literal)` pairs
+fn enum_class_of(name: &str, base: &str, literals: Vec<(String, String)>) -> serde_json::Value {
+    let mut members: Vec<(String, String)> = Vec::new();
+    for (value, literal) in literals {
+        let base = naming::enum_member(name, &value, Language::Python);
+        let mut member = base.clone();
+        let mut suffix = 2;
+        while members.iter().any(|(existing, _)| *existing == member) {
+            member = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        members.push((member, literal));
+    }
+
+    serde_json::json!({
+        "name": name,
+        "base": base,
+        "members": members
+            .into_iter()
+            .map(|(name, value)| serde_json::json!({ "name": name, "value": value }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Enum class base and `(value, literal)` pairs of a component schema that is
+/// an enum; `null` members are left to the fields' `Optional`
+fn component_enum(schema: &SchemaDefinition) -> Option<(&'static str, Vec<(String, String)>)> {
+    let values = schema.original.get("enum")?.as_array()?;
+    if !schema.fields.is_empty() || values.iter().all(serde_json::Value::is_null) {
+        return None;
+    }
+
+    let base = match schema.original.get("type").and_then(|t| t.as_str()) {
+        Some("integer") => "int",
+        Some("number") => "float",
+        _ => "str",
+    };
+    let literals = values
+        .iter()
+        .filter(|value| !value.is_null())
+        .map(|value| match value {
+            serde_json::Value::String(s) => (s.clone(), naming::quote(s)),
+            other => (other.to_string(), other.to_string()),
         })
+        .collect();
+    Some((base, literals))
+}
+
+/// `Field(...)` keyword arguments enforcing the schema's validation keywords
+fn pydantic_constraints(constraints: &Constraints) -> Vec<String> {
+    let mut args = Vec::new();
+    let length = (constraints.min_length, constraints.max_length);
+    let items = (constraints.min_items, constraints.max_items);
+    for (min, max) in [length, items] {
+        args.extend(min.map(|v| format!("min_length={}", v)));
+        args.extend(max.map(|v| format!("max_length={}", v)));
+    }
+    if let Some(pattern) = &constraints.pattern {
+        args.push(format!("pattern={}", naming::quote(pattern)));
+    }
+    if let Some(minimum) = constraints.minimum {
+        let op = if constraints.exclusive_minimum {
+            "gt"
+        } else {
+            "ge"
+        };
+        args.push(format!("{}={}", op, number(minimum)));
+    }
+    if let Some(maximum) = constraints.maximum {
+        let op = if constraints.exclusive_maximum {
+            "lt"
+        } else {
+            "le"
+        };
+        args.push(format!("{}={}", op, number(maximum)));
+    }
+    if let Some(multiple_of) = constraints.multiple_of {
+        args.push(format!("multiple_of={}", number(multiple_of)));
+    }
+    args
+}
+
+/// `(failure condition, message)` pairs checked in a dataclass `__post_init__`
+fn dataclass_checks(ident: &str, constraints: &Constraints) -> Vec<serde_json::Value> {
+    let value = format!("self.{}", ident);
+    let mut checks = Vec::new();
+    let mut check = |condition: String, message: String| {
+        checks.push(serde_json::json!({
+            "condition": format!("{} is not None and {}", value, condition),
+            "message": naming::quote(&format!("{}: {}", ident, message)),
+        }));
+    };
+
+    let min_len = constraints.min_length.or(constraints.min_items);
+    let max_len = constraints.max_length.or(constraints.max_items);
+    if let Some(min) = min_len {
+        check(
+            format!("len({}) < {}", value, min),
+            format!("length must be at least {}", min),
+        );
+    }
+    if let Some(max) = max_len {
+        check(
+            format!("len({}) > {}", value, max),
+            format!("length must be at most {}", max),
+        );
+    }
+    if let Some(pattern) = &constraints.pattern {
+        check(
+            format!("re.search({}, {}) is None", naming::quote(pattern), value),
+            format!("must match {}", pattern),
+        );
+    }
+    if let Some(minimum) = constraints.minimum {
+        let (op, word) = if constraints.exclusive_minimum {
+            ("<=", "greater than")
+        } else {
+            ("<", "at least")
+        };
+        check(
+            format!("{} {} {}", value, op, number(minimum)),
+            format!("must be {} {}", word, number(minimum)),
+        );
+    }
+    if let Some(maximum) = constraints.maximum {
+        let (op, word) = if constraints.exclusive_maximum {
+            (">=", "less than")
+        } else {
+            (">", "at most")
+        };
+        check(
+            format!("{} {} {}", value, op, number(maximum)),
+            format!("must be {} {}", word, number(maximum)),
+        );
+    }
+    if let Some(multiple_of) = constraints.multiple_of {
+        check(
+            format!("{} % {} != 0", value, number(multiple_of)),
+            format!("must be a multiple of {}", number(multiple_of)),
+        );
+    }
+    checks
+}
+
+/// Python literal for a bound, without a fraction for whole numbers
+fn number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{InputParser, OpenApiParser};
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /users/{id}:
+    get:
+      operationId: getUser
+      parameters: [{ name: id, in: path, required: true, schema: { type: string } }]
+      responses:
+        "200": { description: ok, content: { application/json: { schema: { $ref: "#/components/schemas/User" } } } }
+        "409": { description: conflict, content: { application/json: { schema: { type: array, items: { $ref: "#/components/schemas/User" } } } } }
+        "410": { description: gone, content: { application/json: { schema: { $ref: "#/components/schemas/Status" } } } }
+  /seen:
+    get:
+      operationId: listSeen
+      responses:
+        "200": { description: ok, content: { application/json: { schema: { type: array, items: { type: string, format: date-time } } } } }
+        "400": { description: bad, content: { application/json: { schema: { type: array, items: { type: string } } } } }
+        "500": { description: broken, content: { text/plain: { schema: { type: string } } } }
+components:
+  schemas:
+    Status: { type: string, enum: [active, disabled] }
+    User:
+      type: object
+      properties:
+        user.name: { type: string }
+"##;
+
+    /// `(annotation, decoder)` of each response of the spec, in order
+    fn bodies(model_backend: ModelBackend) -> Vec<(String, Option<String>)> {
+        let ir = OpenApiParser.parse_str(SPEC, &HashMap::new()).unwrap();
+        let bodies = ResponseBodies {
+            schema_ir: &ir,
+            model_backend,
+            xml: false,
+            type_adapter: Cell::new(false),
+        };
+        ir.operations
+            .iter()
+            .flat_map(|op| &op.responses)
+            .map(|r| bodies.of(r))
+            .collect()
+    }
+
+    fn body(annotation: &str, decode: Option<&str>) -> (String, Option<String>) {
+        (annotation.to_string(), decode.map(str::to_string))
+    }
+
+    #[test]
+    fn pydantic_bodies_are_validated() {
+        assert_eq!(
+            bodies(ModelBackend::PydanticV2),
+            vec![
+                body("User", Some("User.model_validate({value})")),
+                body(
+                    "List[User]",
+                    Some("[User.model_validate(item) for item in {value}]")
+                ),
+                body("Status", Some("Status({value})")),
+                body(
+                    "List[datetime]",
+                    Some("TypeAdapter(List[datetime]).validate_python({value})")
+                ),
+                body("List[str]", None),
+                body("str", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn dataclass_bodies_are_built_from_wire_names() {
+        assert_eq!(
+            bodies(ModelBackend::Dataclass),
+            vec![
+                body("User", Some("_from_wire(User, {value})")),
+                body("List[User]", Some("_from_wire(List[User], {value})")),
+                body("Status", Some("Status({value})")),
+                body(
+                    "List[datetime]",
+                    Some("_from_wire(List[datetime], {value})")
+                ),
+                body("List[str]", None),
+                body("str", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn typeddict_bodies_stay_as_decoded() {
+        let decoders: Vec<_> = bodies(ModelBackend::TypedDict)
+            .into_iter()
+            .map(|(_, decode)| decode)
+            .collect();
+        assert_eq!(decoders, vec![None; 6]);
+    }
+
+    #[test]
+    fn snake_case_schemas_become_pascal_case_classes_without_colliding() {
+        let spec = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /profiles:
+    get:
+      operationId: getProfile
+      responses:
+        "200": { description: ok, content: { application/json: { schema: { $ref: "#/components/schemas/user_profile" } } } }
+components:
+  schemas:
+    user_profile:
+      type: object
+      properties:
+        account: { $ref: "#/components/schemas/UserProfile" }
+    UserProfile:
+      type: object
+      properties: { id: { type: string } }
+"##;
+        let mut ir = OpenApiParser.parse_str(spec, &HashMap::new()).unwrap();
+        crate::parsers::resolve_name_collisions(&mut ir);
+        let config: GenerationConfig =
+            serde_yaml::from_str("{ generator: python, outputFile: client.py }").unwrap();
+        let content = PythonGenerator
+            .generate_from_ir(&ir, &config)
+            .unwrap()
+            .content;
+
+        assert!(content.contains("class UserProfile(BaseModel):"));
+        assert!(content.contains("class UserProfile2(BaseModel):"));
+        assert!(content.contains("account: Optional[UserProfile2]"));
+        assert!(content.contains("-> UserProfile:"));
     }
 }
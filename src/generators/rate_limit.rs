@@ -0,0 +1,51 @@
+//! Client-side rate limiting for the `typescript`, `python`, and `golang`
+//! clients (`rateLimit` option).
+//!
+//! `true` enables the limiter with the spec's top-level `x-rate-limit` as the
+//! client-wide default; `{ requestsPerSecond, burst }` sets that default
+//! instead. Operations declaring their own `x-rate-limit` get a second bucket.
+//! Either way the clients wait out `Retry-After` and exhausted
+//! `X-RateLimit-Remaining` windows, retrying a 429 once.
+
+use crate::config::GenerationConfig;
+use crate::parsers::{RateLimit, SchemaIR};
+use anyhow::{Context, Result};
+use serde_yaml::Value;
+
+pub enum RateLimitOption {
+    Disabled,
+    /// Client default from the spec, if it declares one
+    FromSpec,
+    Explicit(RateLimit),
+}
+
+impl RateLimitOption {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("rateLimit") {
+            None | Some(Value::Bool(false)) => Ok(Self::Disabled),
+            Some(Value::Bool(true)) => Ok(Self::FromSpec),
+            Some(value @ Value::Mapping(_)) => serde_json::to_value(value)
+                .map_err(anyhow::Error::from)
+                .and_then(|value| RateLimit::from_value(&value))
+                .map(Self::Explicit)
+                .with_context(|| format!("{}: invalid option 'rateLimit'", config.generator)),
+            Some(_) => anyhow::bail!(
+                "{}: option 'rateLimit' must be a boolean or {{ requestsPerSecond, burst }}",
+                config.generator
+            ),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !matches!(self, Self::Disabled)
+    }
+
+    /// Budget shared by every call of a client, unless the caller overrides it
+    pub fn client_limit(&self, schema_ir: &SchemaIR) -> Option<RateLimit> {
+        match self {
+            Self::Disabled => None,
+            Self::FromSpec => schema_ir.metadata.rate_limit,
+            Self::Explicit(limit) => Some(*limit),
+        }
+    }
+}
@@ -0,0 +1,644 @@
+//! `README.md` for the generated client package (`readmeFile` option on the
+//! `typescript`, `python`, and `golang` generators).
+//!
+//! Rendered from the IR: install instructions for `readmePackage` (defaults to
+//! the API title in kebab case), authentication from the spec's security
+//! schemes, and one usage example per tag.
+
+use super::docs;
+use super::naming::{self, Language};
+use super::request_builders::RequestBuilders;
+use super::request_encoding;
+use super::GeneratedFile;
+use crate::config::GenerationConfig;
+use crate::parsers::{
+    BodyKind, ExternalDocs, OperationDefinition, Parameter, ParameterLocation, SchemaIR,
+    TagDefinition,
+};
+use serde_json::Value as JsonValue;
+
+/// The client the README documents
+#[derive(Debug, Clone, Copy)]
+pub enum Client {
+    TypeScript,
+    /// `AsyncApiClient` when the sync client isn't generated
+    Python {
+        is_async: bool,
+    },
+    Go,
+}
+
+/// README requested by the `readmeFile` option, if any
+pub fn companion(
+    schema_ir: &SchemaIR,
+    config: &GenerationConfig,
+    client: Client,
+) -> Option<GeneratedFile> {
+    let filename = config.options.get("readmeFile").and_then(|v| v.as_str())?;
+    let package = config
+        .options
+        .get("readmePackage")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| kebab_case(&schema_ir.metadata.title));
+    Some(GeneratedFile {
+        filename: filename.to_string(),
+        content: render(schema_ir, config, client, &package),
+    })
+}
+
+fn render(
+    schema_ir: &SchemaIR,
+    config: &GenerationConfig,
+    client: Client,
+    package: &str,
+) -> String {
+    let metadata = &schema_ir.metadata;
+    let mut output = format!("# {}\n\n", metadata.title);
+    if let Some(description) = metadata
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        output.push_str(&format!("{}\n\n", description));
+    }
+    if let Some(docs) = &metadata.external_docs {
+        output.push_str(&format!("More documentation: {}.\n\n", markdown_link(docs)));
+    }
+    output.push_str(&format!(
+        "Generated {} client for version {} of the API.",
+        client.language_name(),
+        metadata.version
+    ));
+    if let Some(base_url) = &metadata.base_url {
+        output.push_str(&format!(
+            " Requests go to `{}` unless configured otherwise.",
+            base_url
+        ));
+    }
+    output.push_str("\n\n");
+
+    output.push_str("## Installation\n\n");
+    output.push_str(&format!(
+        "```sh\n{}\n```\n",
+        client.install(config, package)
+    ));
+
+    let schemes = security_schemes(schema_ir);
+    output.push_str("\n## Authentication\n\n");
+    if schemes.is_empty() {
+        output.push_str("The API declares no security schemes.\n");
+    } else {
+        for scheme in &schemes {
+            output.push_str(&format!("- `{}`: {}", scheme.name, scheme.summary));
+            if let Some(description) = &scheme.description {
+                output.push_str(&format!(" — {}", description));
+            }
+            output.push('\n');
+        }
+        let headers: Vec<(&str, &str)> = schemes
+            .iter()
+            .filter_map(|scheme| scheme.header.as_ref())
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        if !headers.is_empty() {
+            output.push_str("\nCredentials sent as headers are set once on the client:\n\n");
+            output.push_str(&client.fence(&client.construct(&headers)));
+        }
+    }
+
+    // Validated with the client's other options
+    let builders = RequestBuilders::from_config(config).unwrap_or_default();
+    output.push_str("\n## Usage\n\n");
+    output.push_str(&client.fence(&client.setup(package)));
+    let groups = tag_groups(schema_ir);
+    if groups.is_empty() {
+        if let Some(operation) = schema_ir.operations.first() {
+            output.push('\n');
+            output.push_str(&client.fence(&client.call(operation, builders)));
+            output.push_str(&references(schema_ir, operation, client));
+        }
+    }
+    for (tag, declared, operation) in groups {
+        output.push_str(&format!("\n### {}\n\n", tag));
+        if let Some(description) = declared.and_then(|tag| tag.description.as_deref()) {
+            output.push_str(&format!("{}\n\n", description));
+        }
+        if let Some(docs) = declared.and_then(|tag| tag.external_docs.as_ref()) {
+            output.push_str(&format!("See also: {}.\n\n", markdown_link(docs)));
+        }
+        match &operation.summary {
+            Some(summary) => output.push_str(&format!(
+                "`{}`: {}\n\n",
+                client.method_name(operation),
+                summary
+            )),
+            None => output.push_str(&format!("`{}`\n\n", client.method_name(operation))),
+        }
+        output.push_str(&client.fence(&client.call(operation, builders)));
+        output.push_str(&references(schema_ir, operation, client));
+    }
+
+    output
+}
+
+/// `[description](url)`, or the bare URL in angle brackets
+fn markdown_link(docs: &ExternalDocs) -> String {
+    match docs
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        Some(description) => format!("[{}]({})", description, docs.url),
+        None => format!("<{}>", docs.url),
+    }
+}
+
+/// The operation's `externalDocs` and response links, as a list after its example
+fn references(schema_ir: &SchemaIR, operation: &OperationDefinition, client: Client) -> String {
+    let mut items = Vec::new();
+    if let Some(docs) = &operation.external_docs {
+        items.push(markdown_link(docs));
+    }
+    for link in &operation.links {
+        let target = link
+            .operation
+            .as_deref()
+            .and_then(|id| schema_ir.operations.iter().find(|op| op.id == id))
+            .map(|target| client.method_name(target))
+            .unwrap_or_else(|| link.target.clone());
+        items.push(format!("`{}` {}", target, docs::link_note(link)));
+    }
+    match items.is_empty() {
+        true => String::new(),
+        false => format!(
+            "\nSee also:\n\n{}\n",
+            items
+                .iter()
+                .map(|item| format!("- {}\n", item))
+                .collect::<String>()
+        ),
+    }
+}
+
+impl Client {
+    fn language_name(self) -> &'static str {
+        match self {
+            Self::TypeScript => "TypeScript",
+            Self::Python { .. } => "Python",
+            Self::Go => "Go",
+        }
+    }
+
+    fn fence(self, code: &str) -> String {
+        let language = match self {
+            Self::TypeScript => "ts",
+            Self::Python { .. } => "python",
+            Self::Go => "go",
+        };
+        format!("```{}\n{}\n```\n", language, code.trim_end())
+    }
+
+    /// Install command for the package and the client's runtime dependencies
+    fn install(self, config: &GenerationConfig, package: &str) -> String {
+        let option = |key: &str| config.options.get(key).and_then(|v| v.as_str());
+        let telemetry = config
+            .options
+            .get("openTelemetry")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let mut packages = vec![package.to_string()];
+        match self {
+            Self::TypeScript => {
+                packages.push(option("validationLibrary").unwrap_or("zod").to_string());
+                if telemetry {
+                    packages.push("@opentelemetry/api".to_string());
+                }
+                format!("npm install {}", packages.join(" "))
+            }
+            Self::Python { .. } => {
+                let client_mode = option("clientMode").unwrap_or("sync");
+                if client_mode != "async" {
+                    packages.push("requests".to_string());
+                }
+                if client_mode != "sync" {
+                    packages.push("httpx".to_string());
+                }
+                if option("modelBackend").unwrap_or("pydantic_v2") == "pydantic_v2" {
+                    packages.push("pydantic".to_string());
+                }
+                if telemetry {
+                    packages.push("opentelemetry-api".to_string());
+                }
+                format!("pip install {}", packages.join(" "))
+            }
+            Self::Go => format!("go get {}", package),
+        }
+    }
+
+    /// Client configured with `headers` on every request
+    fn construct(self, headers: &[(&str, &str)]) -> String {
+        match self {
+            Self::TypeScript if headers.is_empty() => "const client = new ApiClient();".to_string(),
+            Self::TypeScript => {
+                let entries: Vec<String> = headers
+                    .iter()
+                    .map(|(name, value)| {
+                        format!(
+                            "    {}: {},",
+                            naming::ts_property_key(name),
+                            naming::quote(value)
+                        )
+                    })
+                    .collect();
+                format!(
+                    "const client = new ApiClient({{\n  headers: {{\n{}\n  }},\n}});",
+                    entries.join("\n")
+                )
+            }
+            Self::Python { is_async } => {
+                let class = if is_async {
+                    "AsyncApiClient"
+                } else {
+                    "ApiClient"
+                };
+                if headers.is_empty() {
+                    return format!("client = {}()", class);
+                }
+                let entries: Vec<String> = headers
+                    .iter()
+                    .map(|(name, value)| {
+                        format!("    {}: {},", naming::quote(name), naming::quote(value))
+                    })
+                    .collect();
+                format!(
+                    "client = {}(ApiClientConfig(headers={{\n{}\n}}))",
+                    class,
+                    entries.join("\n")
+                )
+            }
+            Self::Go if headers.is_empty() => "api := client.NewApiClient()".to_string(),
+            Self::Go => {
+                let options: Vec<String> = headers
+                    .iter()
+                    .map(|(name, value)| {
+                        format!(
+                            "\tclient.WithHeader({}, {}),",
+                            naming::quote(name),
+                            naming::quote(value)
+                        )
+                    })
+                    .collect();
+                format!("api := client.NewApiClient(\n{}\n)", options.join("\n"))
+            }
+        }
+    }
+
+    /// Imports and a client the per-tag examples call
+    fn setup(self, package: &str) -> String {
+        match self {
+            Self::TypeScript => format!(
+                "import {{ ApiClient }} from {};\n\n{}",
+                naming::quote(package),
+                self.construct(&[])
+            ),
+            Self::Python { is_async } => format!(
+                "from {} import {}, ApiClientConfig\n\n{}",
+                package.replace('-', "_"),
+                if is_async {
+                    "AsyncApiClient"
+                } else {
+                    "ApiClient"
+                },
+                self.construct(&[])
+            ),
+            Self::Go => format!(
+                "import client {}\n\nctx := context.Background()\n{}",
+                naming::quote(package),
+                self.construct(&[])
+            ),
+        }
+    }
+
+    fn method_name(self, operation: &OperationDefinition) -> String {
+        match self {
+            Self::TypeScript => naming::function_name(&operation.id, Language::TypeScript),
+            Self::Python { .. } => naming::function_name(&operation.id, Language::Python),
+            Self::Go => naming::function_name(&operation.id, Language::Go),
+        }
+    }
+
+    /// Call of `operation` with placeholder arguments
+    fn call(self, operation: &OperationDefinition, builders: RequestBuilders) -> String {
+        let method = self.method_name(operation);
+        // The Python client has no builders
+        let builder = !matches!(self, Self::Python { .. }) && builders.applies(operation);
+        let mut lines = Vec::new();
+        if let Some(body) = self.body_placeholder(operation) {
+            lines.push(body);
+        }
+        let returns = operation.response_content_type.is_some();
+
+        match self {
+            Self::TypeScript => {
+                let mut args: Vec<String> = required(operation)
+                    .map(|p| {
+                        format!(
+                            "{}: {}",
+                            naming::ts_property_key(&p.name),
+                            self.placeholder(p)
+                        )
+                    })
+                    .collect();
+                if operation.request_content_type.is_some() {
+                    args.push("body".to_string());
+                }
+                let args = match args.is_empty() {
+                    true => String::new(),
+                    false => format!("{{ {} }}", args.join(", ")),
+                };
+                let binding = if returns { "const result = " } else { "" };
+                let send = if builder { ".send()" } else { "" };
+                lines.push(format!(
+                    "{}await client.{}({}){};",
+                    binding, method, args, send
+                ));
+            }
+            Self::Python { is_async } => {
+                let idents = naming::parameter_names(
+                    operation.parameters.iter().map(|p| p.name.as_str()),
+                    Language::Python,
+                    naming::PYTHON_METHOD_LOCALS,
+                );
+                let mut args: Vec<String> = operation
+                    .parameters
+                    .iter()
+                    .zip(&idents)
+                    .filter(|(p, _)| p.required || p.location == ParameterLocation::Path)
+                    .map(|(p, ident)| format!("{}={}", ident, self.placeholder(p)))
+                    .collect();
+                if operation.request_content_type.is_some() {
+                    args.push("body=body".to_string());
+                }
+                lines.push(format!(
+                    "{}{}client.{}({})",
+                    if returns { "result = " } else { "" },
+                    if is_async { "await " } else { "" },
+                    method,
+                    args.join(", ")
+                ));
+            }
+            Self::Go => {
+                // Positional, in the generated signature's order; optional parameters are pointers.
+                // A builder takes only the required ones, and the context when sent
+                let mut args = match builder {
+                    true => Vec::new(),
+                    false => vec!["ctx".to_string()],
+                };
+                for locations in [
+                    &[ParameterLocation::Path][..],
+                    &[ParameterLocation::Query],
+                    &[ParameterLocation::Header, ParameterLocation::Cookie],
+                ] {
+                    for param in operation
+                        .parameters
+                        .iter()
+                        .filter(|p| locations.contains(&p.location))
+                    {
+                        match param.required || param.location == ParameterLocation::Path {
+                            true => args.push(self.placeholder(param)),
+                            false if !builder => args.push("nil".to_string()),
+                            false => {}
+                        }
+                    }
+                }
+                if operation.request_content_type.is_some() {
+                    args.push("body".to_string());
+                }
+                lines.push(format!(
+                    "{}, err := api.{}({}){}",
+                    if returns { "result, _" } else { "_" },
+                    method,
+                    args.join(", "),
+                    if builder { ".Send(ctx)" } else { "" }
+                ));
+                lines.push("if err != nil {\n\tlog.Fatal(err)\n}".to_string());
+                if returns {
+                    lines.push("fmt.Println(result)".to_string());
+                }
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Example argument for `param`, typed like the generated signature
+    fn placeholder(self, param: &Parameter) -> String {
+        let plan = request_encoding::Plan::of(param);
+        let item = match (self, plan.scalar) {
+            (_, "integer" | "number") => "1".to_string(),
+            (Self::Python { .. }, "boolean") => "True".to_string(),
+            (_, "boolean") => "true".to_string(),
+            (Self::Go, "date" | "date-time") => "time.Now()".to_string(),
+            _ => naming::quote(&format!("<{}>", param.name)),
+        };
+        match (self, plan.array) {
+            (_, false) => item,
+            (Self::Go, true) => format!("{}{{{}}}", plan.value_type(Language::Go), item),
+            (_, true) => format!("[{}]", item),
+        }
+    }
+
+    /// Declaration of the `body` argument, typed like the generated signature
+    fn body_placeholder(self, operation: &OperationDefinition) -> Option<String> {
+        let content_type = operation.request_content_type.as_deref()?;
+        if BodyKind::from_content_type(content_type) != BodyKind::Json {
+            // Non-JSON bodies are passed as raw strings
+            return Some(
+                match self {
+                    Self::TypeScript => "const body = \"...\";",
+                    Self::Python { .. } => "body = \"...\"",
+                    Self::Go => "body := \"...\"",
+                }
+                .to_string(),
+            );
+        }
+
+        let Some(body) = &operation.request_body else {
+            return Some(
+                match self {
+                    Self::TypeScript => "const body = { /* ... */ };",
+                    Self::Python { .. } => "body = {}",
+                    Self::Go => "body := map[string]interface{}{}",
+                }
+                .to_string(),
+            );
+        };
+        let is_array = body.schema_type == "array";
+        Some(match self {
+            Self::TypeScript => {
+                let name = naming::type_name(&body.name, Language::TypeScript);
+                match is_array {
+                    true => format!("const body: {}[] = [];", name),
+                    false => format!("const body: {} = {{ /* ... */ }};", name),
+                }
+            }
+            Self::Python { .. } => {
+                let name = naming::type_name(&body.name, Language::Python);
+                match is_array {
+                    true => format!("body = [{}(...)]", name),
+                    false => format!("body = {}(...)", name),
+                }
+            }
+            Self::Go => {
+                let name = naming::type_name(&body.name, Language::Go);
+                match is_array {
+                    true => format!("body := []client.{}{{}}", name),
+                    false => format!("body := &client.{}{{ /* ... */ }}", name),
+                }
+            }
+        })
+    }
+}
+
+/// Parameters a call must pass; path parameters are always required
+fn required(operation: &OperationDefinition) -> impl Iterator<Item = &Parameter> {
+    operation
+        .parameters
+        .iter()
+        .filter(|p| p.required || p.location == ParameterLocation::Path)
+}
+
+/// Each tag's declaration, if any, and first operation, declared tags first
+fn tag_groups(schema_ir: &SchemaIR) -> Vec<(&str, Option<&TagDefinition>, &OperationDefinition)> {
+    let mut tags: Vec<&str> = schema_ir
+        .metadata
+        .tags
+        .iter()
+        .map(|tag| tag.name.as_str())
+        .collect();
+    for operation in &schema_ir.operations {
+        for tag in &operation.tags {
+            if !tags.contains(&tag.as_str()) {
+                tags.push(tag);
+            }
+        }
+    }
+
+    tags.into_iter()
+        .filter_map(|tag| {
+            let operation = schema_ir
+                .operations
+                .iter()
+                .find(|op| op.tags.iter().any(|t| t == tag))?;
+            let declared = schema_ir
+                .metadata
+                .tags
+                .iter()
+                .find(|declared| declared.name == tag);
+            Some((tag, declared, operation))
+        })
+        .collect()
+}
+
+struct SecurityScheme {
+    name: String,
+    summary: String,
+    description: Option<String>,
+    /// Header and placeholder value the client sends, for header-based schemes
+    header: Option<(String, String)>,
+}
+
+/// `components.securitySchemes` of the source document, in spec order
+fn security_schemes(schema_ir: &SchemaIR) -> Vec<SecurityScheme> {
+    let Some(schemes) = schema_ir
+        .original
+        .data
+        .pointer("/components/securitySchemes")
+        .and_then(|v| v.as_object())
+    else {
+        return Vec::new();
+    };
+
+    let mut result: Vec<SecurityScheme> = Vec::new();
+    for (name, scheme) in schemes {
+        let field = |key: &str| scheme.get(key).and_then(JsonValue::as_str);
+        let (summary, header) = match field("type") {
+            Some("http") => match field("scheme").map(|s| s.to_ascii_lowercase()).as_deref() {
+                Some("bearer") => (
+                    match field("bearerFormat") {
+                        Some(format) => format!("HTTP bearer token ({})", format),
+                        None => "HTTP bearer token".to_string(),
+                    },
+                    Some(("Authorization".to_string(), "Bearer <token>".to_string())),
+                ),
+                Some("basic") => (
+                    "HTTP basic authentication".to_string(),
+                    Some((
+                        "Authorization".to_string(),
+                        "Basic <base64 of user:password>".to_string(),
+                    )),
+                ),
+                scheme => {
+                    let scheme = naming::pascal_case(scheme.unwrap_or("custom"));
+                    (
+                        format!("HTTP `{}` authentication", scheme),
+                        Some((
+                            "Authorization".to_string(),
+                            format!("{} <credentials>", scheme),
+                        )),
+                    )
+                }
+            },
+            Some("apiKey") => {
+                let key = field("name").unwrap_or(name);
+                match field("in") {
+                    Some("header") => (
+                        format!("API key in the `{}` header", key),
+                        Some((key.to_string(), "<api key>".to_string())),
+                    ),
+                    Some("query") => (format!("API key in the `{}` query parameter", key), None),
+                    Some(location) => (format!("API key in the `{}` {}", key, location), None),
+                    None => ("API key".to_string(), None),
+                }
+            }
+            Some("oauth2") | Some("openIdConnect") => (
+                "OAuth 2.0 access token, sent as a bearer token".to_string(),
+                Some((
+                    "Authorization".to_string(),
+                    "Bearer <access token>".to_string(),
+                )),
+            ),
+            _ => continue,
+        };
+
+        // Schemes sharing a header (bearer and OAuth) are set once
+        let header = header.filter(|(header, _)| {
+            !result.iter().any(|other| {
+                other
+                    .header
+                    .as_ref()
+                    .is_some_and(|(h, _)| h.eq_ignore_ascii_case(header))
+            })
+        });
+        result.push(SecurityScheme {
+            name: name.clone(),
+            summary,
+            description: field("description")
+                .map(|d| d.trim().replace('\n', " "))
+                .filter(|d| !d.is_empty()),
+            header,
+        });
+    }
+    result
+}
+
+/// `Rich API` → `rich-api`
+fn kebab_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| segment.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
@@ -0,0 +1,63 @@
+//! Builder-style calls in the `typescript` and `golang` clients
+//! (`requestBuilders` option), for operations with many optional parameters:
+//! - `true` gives every operation with an optional parameter a builder
+//! - a number gives one to operations with at least that many
+//!
+//! The operation's method then takes only the required parameters (and the
+//! body) and returns a builder with one setter per optional parameter, ending
+//! in `send`: `client.searchUsers().query('x').limit(10).send()` in
+//! TypeScript, `api.SearchUsers().Query("x").Limit(10).Send(ctx)` in Go. The
+//! ETag-conditional variant keeps taking every parameter at once.
+
+use super::naming::{self, Language};
+use crate::config::GenerationConfig;
+use crate::parsers::{OperationDefinition, Parameter, ParameterLocation};
+use anyhow::Result;
+use serde_yaml::Value;
+
+/// Operations with at least this many optional parameters get a builder
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestBuilders {
+    min_optional: Option<usize>,
+}
+
+impl RequestBuilders {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        let min_optional = match config.options.get("requestBuilders") {
+            None | Some(Value::Bool(false)) => None,
+            Some(Value::Bool(true)) => Some(1),
+            Some(value) => match value.as_u64().filter(|count| *count > 0) {
+                Some(count) => Some(count as usize),
+                None => anyhow::bail!(
+                    "{}: option 'requestBuilders' must be a boolean or a positive number of optional parameters",
+                    config.generator
+                ),
+            },
+        };
+        Ok(Self { min_optional })
+    }
+
+    /// Whether calls of `operation` go through a builder
+    pub fn applies(self, operation: &OperationDefinition) -> bool {
+        self.min_optional
+            .is_some_and(|min| optional_parameters(operation).count() >= min)
+    }
+}
+
+/// Parameters a builder sets; path parameters are always required
+pub fn optional_parameters(operation: &OperationDefinition) -> impl Iterator<Item = &Parameter> {
+    operation
+        .parameters
+        .iter()
+        .filter(|p| !p.required && p.location != ParameterLocation::Path)
+}
+
+/// Name of the builder method setting `param` (`xTraceId` / `XTraceId` for
+/// `X-Trace-Id`), clear of the `send` method
+pub fn setter_name(param: &Parameter, language: Language) -> String {
+    let name = naming::function_name(&naming::camel_case(&param.name), language);
+    match name.eq_ignore_ascii_case("send") {
+        true => format!("{}Param", name),
+        false => name,
+    }
+}
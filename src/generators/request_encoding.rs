@@ -0,0 +1,199 @@
+//! How parameter values go on the wire, planned once here so the TypeScript,
+//! Python, and Go clients encode them the same way:
+//! - booleans as `true` / `false`, numbers in decimal
+//! - `date` as `2024-01-31`; `date-time` as RFC 3339 in UTC
+//!   (`2024-01-31T12:00:00Z`, fractional seconds only when non-zero)
+//! - arrays item by item: one `name=item` pair each in a `form` query with
+//!   `explode` (the default), otherwise joined with `,` (unexploded `form`,
+//!   headers, paths, cookies), ` ` (`spaceDelimited`), or `|` (`pipeDelimited`)
+//!
+//! Client templates render the [`Plan`] of each parameter through a small
+//! runtime helper of their own. `label`, `matrix`, and `deepObject` styles are
+//! encoded like `simple` and `form`.
+
+use super::naming::Language;
+use crate::parsers::{Parameter, ParameterLocation};
+use serde::Serialize;
+
+/// Encoding of one parameter
+#[derive(Debug, Clone, Serialize)]
+pub struct Plan {
+    /// Kind of the value, or of each item of an array: `string`, `integer`,
+    /// `number`, `boolean`, `date`, or `date-time`
+    pub scalar: &'static str,
+    pub array: bool,
+    /// Array items go out as one query pair each instead of joined
+    pub repeat: bool,
+    /// Joins the items of arrays that are not repeated
+    pub separator: &'static str,
+}
+
+impl Plan {
+    pub fn of(param: &Parameter) -> Self {
+        let array = param.schema_type == "array";
+        let (kind, format) = if array {
+            (
+                param.item_type.as_deref().unwrap_or("string"),
+                param.item_format.as_deref(),
+            )
+        } else {
+            (param.schema_type.as_str(), param.format.as_deref())
+        };
+        let scalar = match (kind, format) {
+            ("string", Some("date")) => "date",
+            ("string", Some("date-time")) => "date-time",
+            ("integer", _) => "integer",
+            ("number", _) => "number",
+            ("boolean", _) => "boolean",
+            _ => "string",
+        };
+
+        let style = param.style.as_deref().unwrap_or(match param.location {
+            ParameterLocation::Query | ParameterLocation::Cookie => "form",
+            ParameterLocation::Path | ParameterLocation::Header => "simple",
+        });
+        let explode = param.explode.unwrap_or(style == "form");
+
+        Self {
+            scalar,
+            array,
+            repeat: array
+                && param.location == ParameterLocation::Query
+                && style == "form"
+                && explode,
+            separator: match style {
+                "spaceDelimited" => " ",
+                "pipeDelimited" => "|",
+                _ => ",",
+            },
+        }
+    }
+
+    /// Type of the value a client method takes for the parameter, without
+    /// the language's marker for optional values
+    pub fn value_type(&self, language: Language) -> String {
+        let scalar = match (language, self.scalar) {
+            (Language::TypeScript, "integer" | "number") => "number",
+            (Language::TypeScript, "boolean") => "boolean",
+            (Language::TypeScript, "date" | "date-time") => "string | Date",
+            (Language::TypeScript, _) => "string",
+            (Language::Python, "integer") => "int",
+            (Language::Python, "number") => "float",
+            (Language::Python, "boolean") => "bool",
+            (Language::Python, "date") => "date",
+            (Language::Python, "date-time") => "datetime",
+            (Language::Python, _) => "str",
+            (Language::Go, "integer") => "int",
+            (Language::Go, "number") => "float64",
+            (Language::Go, "boolean") => "bool",
+            (Language::Go, "date" | "date-time") => "time.Time",
+            (Language::Go, _) => "string",
+        };
+        match (language, self.array) {
+            (_, false) => scalar.to_string(),
+            (Language::TypeScript, true) if scalar.contains('|') => format!("({})[]", scalar),
+            (Language::TypeScript, true) => format!("{}[]", scalar),
+            (Language::Python, true) => format!("List[{}]", scalar),
+            (Language::Go, true) => format!("[]{}", scalar),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{InputParser, OpenApiParser};
+
+    const SPEC: &str = r#"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /users/{ids}:
+    get:
+      operationId: getUsers
+      parameters:
+        - { name: ids, in: path, required: true, schema: { type: array, items: { type: integer } } }
+        - { name: roles, in: query, schema: { type: array, items: { type: string } } }
+        - { name: tags, in: query, explode: false, schema: { type: array, items: { type: string } } }
+        - { name: words, in: query, style: spaceDelimited, explode: false, schema: { type: array, items: { type: string } } }
+        - { name: flags, in: query, style: pipeDelimited, explode: false, schema: { type: array, items: { type: boolean } } }
+        - { name: X-Seen, in: header, explode: true, schema: { type: array, items: { type: string, format: date-time } } }
+        - { name: since, in: query, schema: { type: string, format: date } }
+        - { name: score, in: cookie, schema: { type: number } }
+      responses: { "204": { description: ok } }
+"#;
+
+    /// `(scalar, array, repeat, separator)` of a plan
+    type Fields = (&'static str, bool, bool, &'static str);
+
+    /// The fields of each parameter's plan, by name
+    fn plans() -> Vec<(String, Fields)> {
+        let ir = OpenApiParser.parse_str(SPEC, &Default::default()).unwrap();
+        ir.operations[0]
+            .parameters
+            .iter()
+            .map(|param| {
+                let plan = Plan::of(param);
+                (
+                    param.name.clone(),
+                    (plan.scalar, plan.array, plan.repeat, plan.separator),
+                )
+            })
+            .collect()
+    }
+
+    fn plan(name: &str) -> Fields {
+        plans().into_iter().find(|(n, _)| n == name).unwrap().1
+    }
+
+    #[test]
+    fn arrays_repeat_only_in_exploded_form_queries() {
+        assert_eq!(plan("roles"), ("string", true, true, ","));
+        assert_eq!(plan("tags"), ("string", true, false, ","));
+        // Paths and headers are `simple`, joined even when exploded
+        assert_eq!(plan("ids"), ("integer", true, false, ","));
+        assert_eq!(plan("X-Seen"), ("date-time", true, false, ","));
+    }
+
+    #[test]
+    fn delimited_styles_pick_their_separator() {
+        assert_eq!(plan("words"), ("string", true, false, " "));
+        assert_eq!(plan("flags"), ("boolean", true, false, "|"));
+    }
+
+    #[test]
+    fn scalars_take_their_kind_from_type_and_format() {
+        assert_eq!(plan("since"), ("date", false, false, ","));
+        assert_eq!(plan("score"), ("number", false, false, ","));
+    }
+
+    #[test]
+    fn value_types_per_language() {
+        let since = Plan {
+            scalar: "date",
+            array: false,
+            repeat: false,
+            separator: ",",
+        };
+        assert_eq!(since.value_type(Language::TypeScript), "string | Date");
+        assert_eq!(since.value_type(Language::Python), "date");
+        assert_eq!(since.value_type(Language::Go), "time.Time");
+
+        let seen = Plan {
+            scalar: "date-time",
+            array: true,
+            ..since.clone()
+        };
+        assert_eq!(seen.value_type(Language::TypeScript), "(string | Date)[]");
+        assert_eq!(seen.value_type(Language::Python), "List[datetime]");
+        assert_eq!(seen.value_type(Language::Go), "[]time.Time");
+
+        let ids = Plan {
+            scalar: "integer",
+            ..seen
+        };
+        assert_eq!(ids.value_type(Language::TypeScript), "number[]");
+        assert_eq!(ids.value_type(Language::Python), "List[int]");
+        assert_eq!(ids.value_type(Language::Go), "[]int");
+    }
+}
@@ -0,0 +1,157 @@
+//! Cached GET responses in the `typescript` and `python` clients (`cache`
+//! option), with stale-while-revalidate semantics:
+//! - `true` caches for 60 seconds, without a stale window
+//! - `{ ttlSeconds, staleWhileRevalidateSeconds, varyHeaders }` sets both
+//!   durations and the headers that tell responses apart
+//!
+//! A response is served from the cache for `ttlSeconds`, then for another
+//! `staleWhileRevalidateSeconds` while a background call refreshes it. The
+//! server's `Cache-Control` wins: `max-age` and `stale-while-revalidate`
+//! replace the two durations, and `no-store` or `no-cache` keeps a response
+//! out. Entries are keyed by operation, path, and query, plus the values of
+//! the `varyHeaders` a call sends (none unless listed, e.g. `[Authorization,
+//! Accept]`); other headers, like a per-request `X-Request-Id`, would make
+//! every call miss. File downloads and conditional variants are never cached.
+
+use crate::config::GenerationConfig;
+use crate::parsers::{HttpMethod, OperationDefinition};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+/// Client defaults, in seconds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CacheDefaults {
+    #[serde(default = "default_ttl")]
+    pub ttl_seconds: f64,
+    #[serde(default)]
+    pub stale_while_revalidate_seconds: f64,
+    /// Headers whose values are part of the cache key
+    #[serde(default)]
+    pub vary_headers: Vec<String>,
+}
+
+fn default_ttl() -> f64 {
+    60.0
+}
+
+impl CacheDefaults {
+    pub fn from_config(config: &GenerationConfig) -> Result<Option<Self>> {
+        let defaults = match config.options.get("cache") {
+            None | Some(Value::Bool(false)) => return Ok(None),
+            Some(Value::Bool(true)) => Self {
+                ttl_seconds: default_ttl(),
+                stale_while_revalidate_seconds: 0.0,
+                vary_headers: Vec::new(),
+            },
+            Some(value @ Value::Mapping(_)) => serde_yaml::from_value(value.clone())
+                .with_context(|| format!("{}: invalid option 'cache'", config.generator))?,
+            Some(_) => anyhow::bail!(
+                "{}: option 'cache' must be a boolean or {{ ttlSeconds, staleWhileRevalidateSeconds, varyHeaders }}",
+                config.generator
+            ),
+        };
+        let valid = |seconds: f64| seconds.is_finite() && seconds >= 0.0;
+        if !valid(defaults.ttl_seconds) || !valid(defaults.stale_while_revalidate_seconds) {
+            anyhow::bail!(
+                "{}: option 'cache' durations must be non-negative seconds",
+                config.generator
+            );
+        }
+        if let Some(name) = defaults
+            .vary_headers
+            .iter()
+            .find(|name| !is_header_name(name))
+        {
+            anyhow::bail!(
+                "{}: option 'cache' varyHeaders: '{}' is not a header name",
+                config.generator,
+                name
+            );
+        }
+        Ok(Some(defaults))
+    }
+
+    /// The durations as template literals, and the vary headers
+    pub fn context(self) -> serde_json::Value {
+        serde_json::json!({
+            "ttl_seconds": self.ttl_seconds.to_string(),
+            "stale_while_revalidate_seconds": self.stale_while_revalidate_seconds.to_string(),
+            "vary_headers": self.vary_headers,
+        })
+    }
+}
+
+/// Whether `name` is an HTTP header name (an RFC 9110 token), and so safe to
+/// quote in generated code
+fn is_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&*+-.^_|~".contains(c))
+}
+
+/// Whether calls of `operation` go through the cache
+pub fn applies(operation: &OperationDefinition) -> bool {
+    matches!(operation.method, HttpMethod::Get) && !operation.downloads()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults(cache: &str) -> Result<Option<CacheDefaults>> {
+        let config: GenerationConfig = serde_yaml::from_str(&format!(
+            "{{ generator: typescript, outputFile: client.ts, options: {{ cache: {} }} }}",
+            cache
+        ))
+        .unwrap();
+        CacheDefaults::from_config(&config)
+    }
+
+    #[test]
+    fn no_headers_vary_unless_listed() {
+        let cache = defaults("true").unwrap().unwrap();
+        assert!(cache.vary_headers.is_empty());
+        let cache = defaults("{ ttlSeconds: 30 }").unwrap().unwrap();
+        assert!(cache.vary_headers.is_empty());
+        assert!(defaults("false").unwrap().is_none());
+    }
+
+    #[test]
+    fn listed_vary_headers_reach_the_templates() {
+        let cache =
+            defaults("{ staleWhileRevalidateSeconds: 300, varyHeaders: [Authorization, Accept] }")
+                .unwrap()
+                .unwrap();
+        assert_eq!(
+            cache.context(),
+            serde_json::json!({
+                "ttl_seconds": "60",
+                "stale_while_revalidate_seconds": "300",
+                "vary_headers": ["Authorization", "Accept"],
+            })
+        );
+    }
+
+    #[test]
+    fn vary_headers_must_be_header_names() {
+        for name in ["''", "\"X Request\"", "\"it's\"", "\"a:b\""] {
+            let error = defaults(&format!("{{ varyHeaders: [{}] }}", name))
+                .err()
+                .unwrap()
+                .to_string();
+            assert!(
+                error.contains("is not a header name"),
+                "{}: {}",
+                name,
+                error
+            );
+        }
+        assert!(defaults("{ varyHeaders: Authorization }").is_err());
+        assert!(defaults("{ varyHeaders: [X-Tenant_Id.v2] }")
+            .unwrap()
+            .is_some());
+    }
+}
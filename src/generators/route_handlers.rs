@@ -0,0 +1,613 @@
+//! Server route scaffolding in the frameworks' own file routing
+//! (`route_handlers` generator), a TypeScript-native alternative to the
+//! `typescript_adi_http` router:
+//! - `nextjs` (default): an App Router `route.ts` per path under `routesDir`
+//!   (default `app`), exporting a function per method
+//! - `tanstack-start`: a server route per path under `routesDir` (default
+//!   `src/routes`), as an index route with `server.handlers`
+//!
+//! Each route validates the request's parameters and JSON body against the
+//! `{operation}Config` schemas of the `typescript` client at `clientImport`
+//! (same `validationLibrary`), answering 400 with a `ValidationErrorBody` when
+//! they do not match, and otherwise calls the user's implementation of the
+//! generated `ApiHandlers` interface, exported as `handlers` from
+//! `handlersImport`. The main file holds that interface and the shared
+//! runtime; routes sit at `basePath` under the routes directory.
+
+use super::naming::{self, Language};
+use super::ts_imports::ImportStyle;
+use super::validators::{self, Dialect, ValidationLibrary};
+use super::{GeneratedFile, GeneratedOutput, Generator};
+use crate::config::GenerationConfig;
+use crate::parsers::{BodyKind, OperationDefinition, ParameterLocation, SchemaIR};
+use anyhow::Result;
+use std::collections::HashMap;
+
+pub struct RouteHandlersGenerator;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Framework {
+    NextJs,
+    TanStackStart,
+}
+
+impl Framework {
+    fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("framework").map(|v| v.as_str()) {
+            None | Some(Some("nextjs")) => Ok(Self::NextJs),
+            Some(Some("tanstack-start")) => Ok(Self::TanStackStart),
+            _ => anyhow::bail!(
+                "{}: option 'framework' must be \"nextjs\" or \"tanstack-start\"",
+                config.generator
+            ),
+        }
+    }
+
+    fn default_routes_dir(self) -> &'static str {
+        match self {
+            Self::NextJs => "app",
+            Self::TanStackStart => "src/routes",
+        }
+    }
+}
+
+const GROUPS: [(&str, ParameterLocation); 4] = [
+    ("query", ParameterLocation::Query),
+    ("path", ParameterLocation::Path),
+    ("headers", ParameterLocation::Header),
+    ("cookies", ParameterLocation::Cookie),
+];
+
+impl Generator for RouteHandlersGenerator {
+    fn name(&self) -> &str {
+        "route_handlers"
+    }
+
+    fn file_extension(&self) -> &str {
+        "ts"
+    }
+
+    fn generate_from_ir(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<GeneratedOutput> {
+        let framework = Framework::from_config(config)?;
+        let dialect = Dialect::from_config(config)?;
+        let import_style = ImportStyle::from_config(config)?;
+        let option = |name: &str| config.options.get(name).and_then(|v| v.as_str());
+        let routes_dir = option("routesDir")
+            .unwrap_or(framework.default_routes_dir())
+            .trim_end_matches('/');
+        let base_path = option("basePath").unwrap_or("").trim_end_matches('/');
+        let handlers_import = option("handlersImport").unwrap_or("@/api-handlers");
+
+        let content = generate_main(schema_ir, config, &dialect, &import_style, handlers_import);
+
+        let mut companions = Vec::new();
+        for (path, operations) in by_path(schema_ir)? {
+            let segments = route_segments(&path, framework)?;
+            let mut route_path = format!("{}{}", base_path, segments.join(""));
+            if route_path.is_empty() {
+                route_path.push('/');
+            }
+            let filename = match framework {
+                Framework::NextJs => format!(
+                    "{}{}/route.ts",
+                    routes_dir,
+                    route_path.trim_end_matches('/')
+                ),
+                Framework::TanStackStart => format!(
+                    "{}{}/index.ts",
+                    routes_dir,
+                    route_path.trim_end_matches('/')
+                ),
+            };
+            let main = relative(&filename, config.output_file.trim_end_matches(".ts"));
+            let handlers = match handlers_import.starts_with('.') {
+                true => relative(
+                    &filename,
+                    &join(parent(&config.output_file), handlers_import),
+                ),
+                false => handlers_import.to_string(),
+            };
+
+            let mut route = format!(
+                "// Generated Route Handler for {}\n// Version: {}\n\n",
+                path, schema_ir.metadata.version
+            );
+            if framework == Framework::TanStackStart {
+                route
+                    .push_str(&import_style.values(&["createFileRoute"], "@tanstack/react-router"));
+            }
+            route.push_str(&import_style.values(&["handle", "operations"], &main));
+            route.push_str(&import_style.values(&["handlers"], &handlers));
+            route.push('\n');
+            route.push_str(&match framework {
+                Framework::NextJs => nextjs_route(&operations),
+                Framework::TanStackStart => tanstack_route(&route_path, &operations),
+            });
+            companions.push(GeneratedFile {
+                filename,
+                content: route,
+            });
+        }
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content,
+            metadata: HashMap::new(),
+            companions,
+            warnings: Vec::new(),
+        })
+    }
+
+    fn supports_unions(&self) -> bool {
+        true
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        Framework::from_config(config)?;
+        Dialect::from_config(config)?;
+        ImportStyle::from_config(config)?;
+        Ok(())
+    }
+}
+
+/// Operations grouped by path, in spec order; one route file serves each path
+fn by_path(schema_ir: &SchemaIR) -> Result<Vec<(String, Vec<&OperationDefinition>)>> {
+    let mut paths: Vec<(String, Vec<&OperationDefinition>)> = Vec::new();
+    for operation in &schema_ir.operations {
+        match paths.iter_mut().find(|(path, _)| *path == operation.path) {
+            Some((_, operations)) => operations.push(operation),
+            None => paths.push((operation.path.clone(), vec![operation])),
+        }
+    }
+
+    // File routing cannot tell `/users/{id}` from `/users/{userId}/posts` apart
+    let mut dynamic: HashMap<String, (&str, &str)> = HashMap::new();
+    for (path, _) in &paths {
+        let mut prefix = String::new();
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            if let Some(name) = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                let (seen_name, seen_path) = *dynamic.entry(prefix.clone()).or_insert((name, path));
+                if seen_name != name {
+                    anyhow::bail!(
+                        "route_handlers: '{}' and '{}' name the same path segment differently ({{{}}} and {{{}}})",
+                        seen_path,
+                        path,
+                        seen_name,
+                        name
+                    );
+                }
+            }
+            prefix.push('/');
+            prefix.push_str(if segment.starts_with('{') {
+                "{}"
+            } else {
+                segment
+            });
+        }
+    }
+    Ok(paths)
+}
+
+/// `/users/{id}` → `["/users", "/[id]"]` (Next.js) or `["/users", "/$id"]` (TanStack)
+fn route_segments(path: &str, framework: Framework) -> Result<Vec<String>> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let parameter = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}'));
+            match parameter {
+                Some(name) if !name.contains(['{', '}']) => Ok(match framework {
+                    Framework::NextJs => format!("/[{}]", name),
+                    Framework::TanStackStart => format!("/${}", name),
+                }),
+                _ if segment.contains(['{', '}']) => anyhow::bail!(
+                    "route_handlers: '{}' mixes a parameter with text in one segment, which file routing cannot match",
+                    path
+                ),
+                _ => Ok(format!("/{}", segment)),
+            }
+        })
+        .collect()
+}
+
+fn parent(file: &str) -> &str {
+    file.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// `dir` joined with the relative `specifier`, `..` resolved
+fn join(dir: &str, specifier: &str) -> String {
+    let mut parts: Vec<&str> = dir.split('/').filter(|p| !p.is_empty()).collect();
+    for part in specifier.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// Relative specifier of the module `to` (no extension) from the file `from`
+fn relative(from: &str, to: &str) -> String {
+    let from: Vec<&str> = parent(from).split('/').filter(|p| !p.is_empty()).collect();
+    let to: Vec<&str> = to.split('/').filter(|p| !p.is_empty()).collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut parts = vec![".."; from.len() - common];
+    parts.extend(&to[common..]);
+    match parts.first() {
+        Some(&"..") => parts.join("/"),
+        _ => format!("./{}", parts.join("/")),
+    }
+}
+
+/// Whether the request body is JSON, the only kind validated and passed on parsed
+fn json_body(operation: &OperationDefinition) -> bool {
+    operation
+        .request_content_type
+        .as_deref()
+        .is_some_and(|content_type| BodyKind::from_content_type(content_type) == BodyKind::Json)
+}
+
+fn function_name(operation: &OperationDefinition) -> String {
+    naming::function_name(&operation.id, Language::TypeScript)
+}
+
+fn generate_main(
+    schema_ir: &SchemaIR,
+    config: &GenerationConfig,
+    dialect: &Dialect,
+    import_style: &ImportStyle,
+    handlers_import: &str,
+) -> String {
+    let client_import = config
+        .options
+        .get("clientImport")
+        .and_then(|v| v.as_str())
+        .unwrap_or("./client");
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        "// Generated Route Handlers for {}\n",
+        schema_ir.metadata.title
+    ));
+    output.push_str(&format!("// Version: {}\n\n", schema_ir.metadata.version));
+
+    let (schema_type, check) = match dialect.library {
+        ValidationLibrary::Zod => {
+            output.push_str(&import_style.types(&["z"], "zod"));
+            ("z.ZodType", ZOD_CHECK)
+        }
+        ValidationLibrary::Valibot => {
+            output.push_str(&import_style.namespace("v", "valibot"));
+            ("v.GenericSchema", VALIBOT_CHECK)
+        }
+        ValidationLibrary::Arktype => {
+            output.push_str(&import_style.values(&["type"], "arktype"));
+            ("(value: unknown) => unknown", ARKTYPE_CHECK)
+        }
+    };
+    let mut configs: Vec<String> = schema_ir
+        .operations
+        .iter()
+        .map(|op| format!("{}Config", function_name(op)))
+        .collect();
+    configs.sort();
+    if !configs.is_empty() {
+        let names: Vec<&str> = configs.iter().map(String::as_str).collect();
+        output.push_str(&import_style.values(&names, client_import));
+    }
+    let mut types: Vec<String> = schema_ir
+        .operations
+        .iter()
+        .filter_map(|op| op.response.as_ref())
+        .map(|response| naming::type_name(&response.name, Language::TypeScript))
+        .collect();
+    types.sort();
+    types.dedup();
+    if !types.is_empty() {
+        let names: Vec<&str> = types.iter().map(String::as_str).collect();
+        output.push_str(&import_style.types(&names, client_import));
+    }
+
+    output.push_str(TYPES);
+    output.push_str(&format!("\ntype Schema = {};\n", schema_type));
+
+    // Validated input of each operation
+    for operation in &schema_ir.operations {
+        let name = function_name(operation);
+        output.push_str(&format!(
+            "\n/** Validated input of `{}` ({} {}) */\nexport interface {}Input {{\n  request: Request;\n",
+            name,
+            format!("{:?}", operation.method).to_uppercase(),
+            operation.path,
+            naming::pascal_case(&name)
+        ));
+        for (group, location) in GROUPS {
+            if operation.parameters.iter().any(|p| p.location == location) {
+                let schema = format!("{}Config.parameters.{}.schema", name, group);
+                output.push_str(&format!(
+                    "  {}: {};\n",
+                    group,
+                    validators::fill(dialect.infer, &schema)
+                ));
+            }
+        }
+        if json_body(operation) {
+            let schema = format!("{}Config.requestBody.schema", name);
+            output.push_str(&format!(
+                "  body: {};\n",
+                validators::fill(dialect.infer, &schema)
+            ));
+        }
+        output.push_str("}\n");
+    }
+
+    output.push_str(&format!(
+        "\n/**\n * What the routes delegate to; export an implementation as `handlers` from\n * `{}`.\n */\nexport interface ApiHandlers {{\n",
+        handlers_import
+    ));
+    for operation in &schema_ir.operations {
+        let name = function_name(operation);
+        let result = match &operation.response {
+            Some(response) if response.schema_type == "array" => {
+                format!(
+                    "{}[]",
+                    naming::type_name(&response.name, Language::TypeScript)
+                )
+            }
+            Some(response) => naming::type_name(&response.name, Language::TypeScript),
+            None => "unknown".to_string(),
+        };
+        if let Some(doc) = operation
+            .summary
+            .as_deref()
+            .or(operation.description.as_deref())
+        {
+            let doc = doc.trim().replace("*/", "*\\/");
+            output.push_str(&format!(
+                "  /** {} */\n",
+                doc.lines().next().unwrap_or_default()
+            ));
+        }
+        output.push_str(&format!(
+            "  {}(input: {}Input): HandlerResult<{}>;\n",
+            name,
+            naming::pascal_case(&name),
+            result
+        ));
+    }
+    output.push_str("}\n");
+
+    output.push_str(
+        "\n/** How each operation is validated, by handler name */\nexport const operations = {\n",
+    );
+    for operation in &schema_ir.operations {
+        let header_names: Vec<String> = operation
+            .parameters
+            .iter()
+            .filter(|p| p.location == ParameterLocation::Header)
+            .map(|p| naming::quote(&p.name))
+            .collect();
+        output.push_str(&format!(
+            "  {name}: {{ config: {name}Config as Config, headerNames: [{}], jsonBody: {} }},\n",
+            header_names.join(", "),
+            json_body(operation),
+            name = function_name(operation),
+        ));
+    }
+    output.push_str("} satisfies Record<keyof ApiHandlers, Operation>;\n");
+
+    output.push('\n');
+    output.push_str(check);
+    output.push_str(HANDLE);
+    output
+}
+
+fn handler_call(operation: &OperationDefinition) -> String {
+    let name = function_name(operation);
+    format!(
+        "handle(request, params, operations.{0}, handlers.{0}.bind(handlers))",
+        name
+    )
+}
+
+fn nextjs_route(operations: &[&OperationDefinition]) -> String {
+    let mut route = String::new();
+    for (i, operation) in operations.iter().enumerate() {
+        if i > 0 {
+            route.push('\n');
+        }
+        let names: Vec<String> = operation
+            .path
+            .split('/')
+            .filter_map(|s| s.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+            .map(|name| format!("{}: string", naming::ts_property_key(name)))
+            .collect();
+        let method = format!("{:?}", operation.method).to_uppercase();
+        if names.is_empty() {
+            route.push_str(&format!(
+                "export async function {}(request: Request) {{\n  const params = {{}};\n  return {};\n}}\n",
+                method,
+                handler_call(operation)
+            ));
+        } else {
+            route.push_str(&format!(
+                "export async function {}(request: Request, context: {{ params: Promise<{{ {} }}> }}) {{\n  const params = await context.params;\n  return {};\n}}\n",
+                method,
+                names.join("; "),
+                handler_call(operation)
+            ));
+        }
+    }
+    route
+}
+
+fn tanstack_route(route_path: &str, operations: &[&OperationDefinition]) -> String {
+    let mut route = format!(
+        "export const Route = createFileRoute('{}/')({{\n  server: {{\n    handlers: {{\n",
+        route_path.trim_end_matches('/')
+    );
+    for operation in operations {
+        route.push_str(&format!(
+            "      {}: ({{ request, params }}) => {},\n",
+            format!("{:?}", operation.method).to_uppercase(),
+            handler_call(operation)
+        ));
+    }
+    route.push_str("    },\n  },\n});\n");
+    route
+}
+
+const TYPES: &str = r#"
+/** One way a request does not match its operation */
+export interface ValidationIssue {
+  location: 'path' | 'query' | 'header' | 'cookie' | 'body';
+  /** Dot-separated path to the value inside the location; the parameter name for parameters */
+  path: string;
+  message: string;
+}
+
+/** Body of the 400 responses sent for invalid requests */
+export interface ValidationErrorBody {
+  error: 'ValidationError';
+  issues: ValidationIssue[];
+}
+
+/**
+ * What a handler answers with: a `Response` as is, or a value sent as JSON
+ * with status 200 (`undefined` as an empty 204)
+ */
+export type HandlerResult<T> = Response | T | Promise<Response | T>;
+"#;
+
+const ZOD_CHECK: &str = r#"function check(schema: Schema, value: unknown, location: ValidationIssue['location'], issues: ValidationIssue[]): unknown {
+  const result = schema.safeParse(value);
+  if (result.success) return result.data;
+  for (const issue of result.error.issues) {
+    issues.push({ location, path: issue.path.map(String).join('.'), message: issue.message });
+  }
+  return undefined;
+}
+"#;
+
+const VALIBOT_CHECK: &str = r#"function check(schema: Schema, value: unknown, location: ValidationIssue['location'], issues: ValidationIssue[]): unknown {
+  const result = v.safeParse(schema, value);
+  if (result.success) return result.output;
+  for (const issue of result.issues) {
+    issues.push({ location, path: v.getDotPath(issue) ?? '', message: issue.message });
+  }
+  return undefined;
+}
+"#;
+
+const ARKTYPE_CHECK: &str = r#"function check(schema: Schema, value: unknown, location: ValidationIssue['location'], issues: ValidationIssue[]): unknown {
+  const result = schema(value);
+  if (!(result instanceof type.errors)) return result;
+  for (const error of result) {
+    issues.push({ location, path: error.path.map(String).join('.'), message: error.message });
+  }
+  return undefined;
+}
+"#;
+
+const HANDLE: &str = r#"
+type Group = 'query' | 'path' | 'headers' | 'cookies';
+
+interface Config {
+  parameters?: { [group in Group]?: { schema: Schema } };
+  requestBody?: { schema: Schema };
+}
+
+interface Operation {
+  config: Config;
+  headerNames: string[];
+  jsonBody: boolean;
+}
+
+const groups: [Group, ValidationIssue['location']][] = [
+  ['query', 'query'],
+  ['path', 'path'],
+  ['headers', 'header'],
+  ['cookies', 'cookie'],
+];
+
+function json(body: unknown, status: number): Response {
+  return new Response(JSON.stringify(body), { status, headers: { 'content-type': 'application/json' } });
+}
+
+function queryValues(url: URL): Record<string, string | string[]> {
+  const query: Record<string, string | string[]> = {};
+  for (const name of new Set(url.searchParams.keys())) {
+    const values = url.searchParams.getAll(name);
+    query[name] = values.length === 1 ? values[0]! : values;
+  }
+  return query;
+}
+
+function cookieValues(header: string | null): Record<string, string> {
+  const cookies: Record<string, string> = {};
+  for (const pair of (header ?? '').split(';')) {
+    const index = pair.indexOf('=');
+    if (index < 0) continue;
+    const value = pair.slice(index + 1).trim();
+    try {
+      cookies[pair.slice(0, index).trim()] = decodeURIComponent(value);
+    } catch {
+      cookies[pair.slice(0, index).trim()] = value;
+    }
+  }
+  return cookies;
+}
+
+/**
+ * Validate `request` (with its framework-decoded path `params`) against
+ * `operation`, answering 400 with a `ValidationErrorBody` when it does not
+ * match, and otherwise answer with what `handler` returns for the parsed input.
+ */
+export async function handle<I, T>(
+  request: Request,
+  params: Record<string, string | string[] | undefined>,
+  operation: Operation,
+  handler: (input: I) => HandlerResult<T>,
+): Promise<Response> {
+  const values: Record<Group, unknown> = {
+    query: queryValues(new URL(request.url)),
+    path: params,
+    headers: Object.fromEntries(
+      operation.headerNames.flatMap((name) => {
+        const value = request.headers.get(name);
+        return value === null ? [] : [[name, value]];
+      }),
+    ),
+    cookies: cookieValues(request.headers.get('cookie')),
+  };
+
+  const issues: ValidationIssue[] = [];
+  const input: Record<string, unknown> = { request };
+  for (const [group, location] of groups) {
+    const parameters = operation.config.parameters?.[group];
+    if (parameters) input[group] = check(parameters.schema, values[group], location, issues);
+  }
+  if (operation.jsonBody && operation.config.requestBody) {
+    let body: unknown;
+    try {
+      body = await request.json();
+    } catch {
+      issues.push({ location: 'body', path: '', message: 'Invalid JSON' });
+    }
+    if (body !== undefined) input.body = check(operation.config.requestBody.schema, body, 'body', issues);
+  }
+  if (issues.length) {
+    return json({ error: 'ValidationError', issues } satisfies ValidationErrorBody, 400);
+  }
+
+  const result = await handler(input as I);
+  if (result instanceof Response) return result;
+  return result === undefined ? new Response(null, { status: 204 }) : json(result, 200);
+}
+"#;
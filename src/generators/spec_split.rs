@@ -0,0 +1,234 @@
+//! Splits the parsed spec into a directory of files referencing each other,
+//! the inverse of `generator bundle`.
+//!
+//! The main output file keeps the top-level document with every path item and
+//! component replaced by a ref to its own file (`paths/users_{id}.yaml`,
+//! `components/schemas/User.yaml`), and local refs in all files are rewritten
+//! to point across files. Files are JSON when `outputFile` ends in `.json`,
+//! YAML otherwise.
+
+use super::{GeneratedFile, GeneratedOutput, Generator};
+use crate::config::GenerationConfig;
+use crate::parsers::SchemaIR;
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+pub struct SpecSplitGenerator;
+
+impl Generator for SpecSplitGenerator {
+    fn name(&self) -> &str {
+        "spec_split"
+    }
+
+    fn file_extension(&self) -> &str {
+        "yaml"
+    }
+
+    fn generate_from_ir(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<GeneratedOutput> {
+        if schema_ir.original.format != "openapi" {
+            anyhow::bail!(
+                "spec_split: input format '{}' has no spec to split",
+                schema_ir.original.format
+            );
+        }
+        if schema_ir.original.low_memory {
+            anyhow::bail!("spec_split: the input was parsed with 'lowMemory', which drops the paths and schemas to split");
+        }
+
+        let mut root = schema_ir
+            .original
+            .data
+            .as_object()
+            .cloned()
+            .context("spec_split: original spec is not an object")?;
+        let (dir, extension) = match config.output_file.rsplit_once('/') {
+            Some((dir, file)) => (format!("{}/", dir), extension(file)),
+            None => (String::new(), extension(&config.output_file)),
+        };
+        let layout = Layout::new(&root, &config.output_file, extension);
+
+        let mut files = Vec::new();
+
+        if let Some(Value::Object(paths)) = root.get_mut("paths") {
+            for (path, item) in paths.iter_mut() {
+                let file = layout.paths[path].clone();
+                files.push((file.clone(), std::mem::replace(item, ref_object(&file))));
+            }
+        }
+
+        if let Some(Value::Object(components)) = root.get_mut("components") {
+            for (section, entries) in components.iter_mut() {
+                let Value::Object(entries) = entries else {
+                    continue;
+                };
+                for (name, entry) in entries.iter_mut() {
+                    let file = Layout::component(section, name, extension);
+                    files.push((file.clone(), std::mem::replace(entry, ref_object(&file))));
+                }
+            }
+        }
+
+        let mut companions = Vec::new();
+        for (file, mut value) in files {
+            layout.rewrite(&mut value, &file);
+            companions.push(GeneratedFile {
+                filename: format!("{}{}", dir, file),
+                content: serialize(&value, extension)?,
+            });
+        }
+
+        let mut root = Value::Object(root);
+        layout.rewrite(&mut root, "");
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content: serialize(&root, extension)?,
+            metadata: HashMap::new(),
+            companions,
+            warnings: Vec::new(),
+        })
+    }
+}
+
+/// Where each piece of the spec lives, relative to the main file's directory
+struct Layout {
+    /// Main file name
+    root: String,
+    /// Path template → file
+    paths: HashMap<String, String>,
+    extension: &'static str,
+}
+
+impl Layout {
+    fn new(root: &Map<String, Value>, output_file: &str, extension: &'static str) -> Self {
+        let mut paths = HashMap::new();
+        let mut taken = HashSet::new();
+        if let Some(Value::Object(items)) = root.get("paths") {
+            for path in items.keys() {
+                let stem = match path.trim_matches('/') {
+                    "" => "root".to_string(),
+                    trimmed => trimmed.replace('/', "_"),
+                };
+                let mut file = format!("paths/{}.{}", stem, extension);
+                let mut n = 2;
+                while !taken.insert(file.clone()) {
+                    file = format!("paths/{}{}.{}", stem, n, extension);
+                    n += 1;
+                }
+                paths.insert(path.clone(), file);
+            }
+        }
+
+        Self {
+            root: output_file
+                .rsplit('/')
+                .next()
+                .unwrap_or(output_file)
+                .to_string(),
+            paths,
+            extension,
+        }
+    }
+
+    fn component(section: &str, name: &str, extension: &str) -> String {
+        format!("components/{}/{}.{}", section, name, extension)
+    }
+
+    /// File and remaining pointer holding the target of the local ref `#pointer`
+    fn locate(&self, pointer: &str) -> (String, String) {
+        let segments: Vec<String> = pointer
+            .split('/')
+            .skip(1)
+            .map(|s| s.replace("~1", "/").replace("~0", "~"))
+            .collect();
+        let rest = |n: usize| {
+            pointer
+                .split('/')
+                .skip(n + 1)
+                .fold(String::new(), |acc, s| acc + "/" + s)
+        };
+
+        match segments.as_slice() {
+            [components, section, name, ..] if components == "components" => {
+                (Self::component(section, name, self.extension), rest(3))
+            }
+            [paths, path, ..] if paths == "paths" && self.paths.contains_key(path) => {
+                (self.paths[path].clone(), rest(2))
+            }
+            _ => (self.root.clone(), pointer.to_string()),
+        }
+    }
+
+    /// Rewrite local refs in `value`, which is written to `file`
+    fn rewrite(&self, value: &mut Value, file: &str) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                    if let Some(pointer) = reference.strip_prefix('#') {
+                        let (target, rest) = self.locate(pointer);
+                        let mut relative = relative_path(file, &target);
+                        if !rest.is_empty() {
+                            relative = format!("{}#{}", relative, rest);
+                        }
+                        *reference = relative;
+                    }
+                    return;
+                }
+                for (key, child) in map.iter_mut() {
+                    if matches!(key.as_str(), "example" | "examples" | "default" | "enum") {
+                        continue;
+                    }
+                    self.rewrite(child, file);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.rewrite(item, file);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `to` relative to the directory of `from` (both relative to the main file's directory)
+fn relative_path(from: &str, to: &str) -> String {
+    let from_parts: Vec<&str> = from.split('/').collect();
+    let from_dir = &from_parts[..from_parts.len() - 1];
+    let to_parts: Vec<&str> = to.split('/').collect();
+
+    let common = from_dir
+        .iter()
+        .zip(&to_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut parts: Vec<&str> = vec![".."; from_dir.len() - common];
+    parts.extend(&to_parts[common..]);
+    parts.join("/")
+}
+
+fn extension(file: &str) -> &'static str {
+    if file.ends_with(".json") {
+        "json"
+    } else {
+        "yaml"
+    }
+}
+
+fn serialize(value: &Value, extension: &str) -> Result<String> {
+    Ok(match extension {
+        "json" => serde_json::to_string_pretty(value)? + "\n",
+        _ => serde_yaml::to_string(value)?,
+    })
+}
+
+fn ref_object(file: &str) -> Value {
+    let mut map = Map::new();
+    map.insert("$ref".to_string(), Value::String(file.to_string()));
+    Value::Object(map)
+}
@@ -0,0 +1,676 @@
+//! Static checks of a template pack (`generator template lint <dir>`), so a
+//! custom template's mistakes surface before a generation renders it:
+//! - syntax errors and missing `include` / `extends` targets, when loading
+//! - variables the context does not have (`{{ operation.nmae }}`), checked
+//!   against the context of a sample spec through `for` loops, `set`,
+//!   includes, and macro arguments
+//! - filters, tests, and functions Tera does not know
+//!
+//! Variables behind `default(...)` or tested with `is defined` may be
+//! missing. Values that are `null` or empty lists in the sample context are
+//! not looked into, so a spec exercising more of the context checks more.
+
+use super::templates::{self, Builtin};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+// Hidden from Tera's docs and free to change in minor releases; Cargo.lock pins it
+use tera::ast::{Expr, ExprVal, FunctionCall, Node};
+use tera::Tera;
+
+/// A problem found in one template
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub template: String,
+    pub message: String,
+}
+
+/// What linting a template pack found
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackReport {
+    /// The pack's own templates, by name in order
+    pub templates: Vec<String>,
+    /// The pack's templates no built-in template is named like or uses
+    pub unrendered: Vec<String>,
+    pub issues: Vec<Issue>,
+}
+
+/// The generator a pack in `dir` is for: the first whose built-in templates
+/// it overrides (a `client.ts.tera` is for `typescript`)
+pub fn pack_generator(dir: &Path) -> Option<&'static str> {
+    ["typescript", "python", "golang"].into_iter().find(|name| {
+        templates::builtin(name)
+            .is_some_and(|builtin| builtin.iter().any(|(file, _)| dir.join(file).is_file()))
+    })
+}
+
+/// Lint the templates of `dir`, loaded into `tera` over the `builtin` ones;
+/// the built-in templates are only walked through for the pack's partials
+pub fn lint_pack(tera: &Tera, context: &Value, dir: &Path, builtin: Builtin) -> PackReport {
+    let mut templates: Vec<String> = tera
+        .get_template_names()
+        .filter(|file| dir.join(file).is_file())
+        .map(str::to_string)
+        .collect();
+    templates.sort();
+    let issues = lint(tera, context)
+        .into_iter()
+        .filter(|issue| templates.contains(&issue.template))
+        .collect();
+    let used = referenced(tera);
+    let unrendered = templates
+        .iter()
+        .filter(|file| {
+            !builtin.iter().any(|(builtin_file, _)| builtin_file == file) && !used.contains(*file)
+        })
+        .cloned()
+        .collect();
+    PackReport {
+        templates,
+        unrendered,
+        issues,
+    }
+}
+
+/// Every issue in the templates of `tera` rendered with a context shaped like `context`
+pub fn lint(tera: &Tera, context: &Value) -> Vec<Issue> {
+    let globals = match Shape::of(context) {
+        Shape::Object(fields) => fields.into_iter().collect(),
+        _ => HashMap::new(),
+    };
+    let mut linter = Linter {
+        tera,
+        probe: tera.clone(),
+        known: HashMap::new(),
+        issues: Vec::new(),
+        template: String::new(),
+        including: Vec::new(),
+        guarded: Vec::new(),
+    };
+
+    // Included templates are checked where they are included, with the includer's variables
+    let included = included(tera);
+    let mut names: Vec<&str> = tera
+        .get_template_names()
+        .filter(|name| !included.contains(*name))
+        .collect();
+    names.sort_unstable();
+    for name in names {
+        if let Ok(template) = tera.get_template(name) {
+            linter.template = name.to_string();
+            let mut scope = vec![globals.clone()];
+            linter.nodes(&template.ast, &mut scope);
+        }
+    }
+
+    let mut issues = Vec::new();
+    for issue in linter.issues {
+        if !issues.contains(&issue) {
+            issues.push(issue);
+        }
+    }
+    issues
+}
+
+/// Names of the templates another template includes
+pub fn included(tera: &Tera) -> HashSet<String> {
+    fn collect(nodes: &[Node], names: &mut HashSet<String>) {
+        for node in nodes {
+            match node {
+                Node::Include(_, targets, _) => names.extend(targets.iter().cloned()),
+                Node::MacroDefinition(_, definition, _) => collect(&definition.body, names),
+                Node::FilterSection(_, section, _) => collect(&section.body, names),
+                Node::Block(_, block, _) => collect(&block.body, names),
+                Node::Forloop(_, forloop, _) => {
+                    collect(&forloop.body, names);
+                    collect(forloop.empty_body.as_deref().unwrap_or_default(), names);
+                }
+                Node::If(branches, _) => {
+                    for (_, _, body) in &branches.conditions {
+                        collect(body, names);
+                    }
+                    if let Some((_, body)) = &branches.otherwise {
+                        collect(body, names);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    let mut names = HashSet::new();
+    for name in tera.get_template_names() {
+        if let Ok(template) = tera.get_template(name) {
+            collect(&template.ast, &mut names);
+        }
+    }
+    names
+}
+
+/// Names of the templates another template includes, imports macros from, or extends
+pub fn referenced(tera: &Tera) -> HashSet<String> {
+    let mut names = included(tera);
+    for name in tera.get_template_names() {
+        if let Ok(template) = tera.get_template(name) {
+            names.extend(template.parent.iter().cloned());
+            names.extend(
+                template
+                    .imported_macro_files
+                    .iter()
+                    .map(|(file, _)| file.clone()),
+            );
+        }
+    }
+    names
+}
+
+/// What is known of a context value: its keys and element type, not its contents
+#[derive(Debug, Clone)]
+enum Shape {
+    /// `null`, an empty list, or a computed value: not checked
+    Any,
+    Scalar,
+    Array(Box<Shape>),
+    Object(BTreeMap<String, Shape>),
+}
+
+impl Shape {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Any,
+            Value::Bool(_) | Value::Number(_) | Value::String(_) => Self::Scalar,
+            Value::Array(items) => match items.iter().map(Self::of).reduce(Self::merge) {
+                Some(item) => Self::Array(Box::new(item)),
+                None => Self::Any,
+            },
+            Value::Object(fields) => Self::Object(
+                fields
+                    .iter()
+                    .map(|(k, v)| (k.clone(), Self::of(v)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The shape covering both, e.g. list items with different optional fields set
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Any, shape) | (shape, Self::Any) => shape,
+            (Self::Scalar, Self::Scalar) => Self::Scalar,
+            (Self::Array(a), Self::Array(b)) => Self::Array(Box::new(a.merge(*b))),
+            (Self::Object(mut a), Self::Object(b)) => {
+                for (key, shape) in b {
+                    let merged = match a.remove(&key) {
+                        Some(existing) => existing.merge(shape),
+                        None => shape,
+                    };
+                    a.insert(key, merged);
+                }
+                Self::Object(a)
+            }
+            _ => Self::Any,
+        }
+    }
+
+    /// What a `for` loop over this binds its value to
+    fn element(&self) -> Self {
+        match self {
+            Self::Array(item) => (**item).clone(),
+            Self::Object(fields) => fields
+                .values()
+                .cloned()
+                .reduce(Self::merge)
+                .unwrap_or(Self::Any),
+            _ => Self::Any,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Callable {
+    Filter,
+    Test,
+    Function,
+}
+
+type Scope = Vec<HashMap<String, Shape>>;
+
+struct Linter<'a> {
+    tera: &'a Tera,
+    /// A copy of `tera` that unknown names are tried against
+    probe: Tera,
+    known: HashMap<(Callable, String), bool>,
+    issues: Vec<Issue>,
+    /// Template the nodes being checked are from
+    template: String,
+    including: Vec<String>,
+    /// Paths an enclosing `if ... is defined` checked for
+    guarded: Vec<String>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, message: String) {
+        self.issues.push(Issue {
+            template: self.template.clone(),
+            message,
+        });
+    }
+
+    fn nodes(&mut self, nodes: &[Node], scope: &mut Scope) {
+        for node in nodes {
+            self.node(node, scope);
+        }
+    }
+
+    /// `nodes` in a `for` loop's scope, whose `set` variables end with it
+    fn nested(&mut self, nodes: &[Node], scope: &mut Scope, frame: HashMap<String, Shape>) {
+        scope.push(frame);
+        self.nodes(nodes, scope);
+        scope.pop();
+    }
+
+    fn node(&mut self, node: &Node, scope: &mut Scope) {
+        match node {
+            Node::VariableBlock(_, expr) => {
+                self.expr(expr, scope);
+            }
+            // Macros only see their arguments
+            Node::MacroDefinition(_, definition, _) => {
+                let mut frame = HashMap::new();
+                for (name, default) in &definition.args {
+                    if let Some(default) = default {
+                        self.expr(default, scope);
+                    }
+                    frame.insert(name.clone(), Shape::Any);
+                }
+                let mut macro_scope = vec![frame];
+                self.nodes(&definition.body, &mut macro_scope);
+            }
+            Node::Include(_, targets, ignore_missing) => {
+                let Some(target) = targets
+                    .iter()
+                    .find(|target| self.tera.get_template(target).is_ok())
+                else {
+                    if !ignore_missing {
+                        self.report(format!(
+                            "includes missing template `{}`",
+                            targets.join("`, `")
+                        ));
+                    }
+                    return;
+                };
+                if self.including.contains(target) {
+                    return;
+                }
+                let Ok(template) = self.tera.get_template(target) else {
+                    return;
+                };
+                let includer = std::mem::replace(&mut self.template, target.clone());
+                self.including.push(target.clone());
+                self.nodes(&template.ast, scope);
+                self.including.pop();
+                self.template = includer;
+            }
+            Node::Set(_, set) => {
+                let shape = self.expr(&set.value, scope);
+                let frame = if set.global {
+                    scope.first_mut()
+                } else {
+                    scope.last_mut()
+                };
+                if let Some(frame) = frame {
+                    frame.insert(set.key.clone(), shape);
+                }
+            }
+            Node::FilterSection(_, section, _) => {
+                self.callable(Callable::Filter, &section.filter, scope);
+                self.nodes(&section.body, scope);
+            }
+            Node::Block(_, block, _) => self.nodes(&block.body, scope),
+            Node::Forloop(_, forloop, _) => {
+                let container = self.expr(&forloop.container, scope);
+                let mut frame = HashMap::new();
+                frame.insert(
+                    "loop".to_string(),
+                    Shape::Object(
+                        ["index", "index0", "first", "last"]
+                            .iter()
+                            .map(|key| (key.to_string(), Shape::Scalar))
+                            .collect(),
+                    ),
+                );
+                if let Some(key) = &forloop.key {
+                    frame.insert(key.clone(), Shape::Scalar);
+                }
+                frame.insert(forloop.value.clone(), container.element());
+                self.nested(&forloop.body, scope, frame);
+                if let Some(empty) = &forloop.empty_body {
+                    self.nodes(empty, scope);
+                }
+            }
+            Node::If(branches, _) => {
+                for (_, condition, body) in &branches.conditions {
+                    self.expr(condition, scope);
+                    let guarded = self.guarded.len();
+                    self.guarded.extend(defined(condition));
+                    self.nodes(body, scope);
+                    self.guarded.truncate(guarded);
+                }
+                if let Some((_, body)) = &branches.otherwise {
+                    self.nodes(body, scope);
+                }
+            }
+            Node::Super
+            | Node::Text(_)
+            | Node::Extends(..)
+            | Node::ImportMacro(..)
+            | Node::Raw(..)
+            | Node::Break(_)
+            | Node::Continue(_)
+            | Node::Comment(..) => {}
+        }
+    }
+
+    /// Checks `expr`, returning the shape of its value
+    fn expr(&mut self, expr: &Expr, scope: &Scope) -> Shape {
+        // `missing | default(value=...)` is how templates handle absent variables
+        let defaulted = expr
+            .filters
+            .first()
+            .is_some_and(|filter| filter.name == "default");
+        let mut shape = self.value(&expr.val, scope, defaulted);
+        for filter in &expr.filters {
+            self.callable(Callable::Filter, filter, scope);
+            shape = match filter.name.as_str() {
+                "filter" | "sort" | "reverse" | "slice" | "unique" | "concat" => shape,
+                "first" | "last" | "nth" => shape.element(),
+                "length" | "join" | "upper" | "lower" | "trim" | "replace" | "json_encode"
+                | "as_str" => Shape::Scalar,
+                _ => Shape::Any,
+            };
+        }
+        if expr.negated {
+            Shape::Scalar
+        } else {
+            shape
+        }
+    }
+
+    fn value(&mut self, value: &ExprVal, scope: &Scope, defaulted: bool) -> Shape {
+        match value {
+            ExprVal::String(_) | ExprVal::Int(_) | ExprVal::Float(_) | ExprVal::Bool(_) => {
+                Shape::Scalar
+            }
+            ExprVal::Ident(path) => self.lookup(path, scope, defaulted),
+            ExprVal::Math(math) => {
+                self.expr(&math.lhs, scope);
+                self.expr(&math.rhs, scope);
+                Shape::Scalar
+            }
+            ExprVal::Logic(logic) => {
+                self.expr(&logic.lhs, scope);
+                self.expr(&logic.rhs, scope);
+                Shape::Scalar
+            }
+            ExprVal::In(membership) => {
+                self.expr(&membership.lhs, scope);
+                self.expr(&membership.rhs, scope);
+                Shape::Scalar
+            }
+            ExprVal::Test(test) => {
+                if !self.is_known(Callable::Test, &test.name) {
+                    self.report(format!("unknown test `{}`", test.name));
+                }
+                // `is defined` / `is undefined` ask whether it exists
+                if !matches!(test.name.as_str(), "defined" | "undefined") {
+                    self.lookup(&test.ident, scope, false);
+                }
+                for arg in &test.args {
+                    self.expr(arg, scope);
+                }
+                Shape::Scalar
+            }
+            ExprVal::MacroCall(call) => {
+                for arg in call.args.values() {
+                    self.expr(arg, scope);
+                }
+                Shape::Any
+            }
+            ExprVal::FunctionCall(call) => {
+                self.callable(Callable::Function, call, scope);
+                Shape::Any
+            }
+            ExprVal::Array(items) => {
+                let shapes: Vec<Shape> = items.iter().map(|item| self.expr(item, scope)).collect();
+                match shapes.into_iter().reduce(Shape::merge) {
+                    Some(item) => Shape::Array(Box::new(item)),
+                    None => Shape::Any,
+                }
+            }
+            ExprVal::StringConcat(concat) => {
+                for value in &concat.values {
+                    self.value(value, scope, false);
+                }
+                Shape::Scalar
+            }
+        }
+    }
+
+    /// The shape at `path` (`operation.parameters[0].name`), reporting where it leaves the context
+    fn lookup(&mut self, path: &str, scope: &Scope, defaulted: bool) -> Shape {
+        let segments = segments(path);
+        let Some((Segment::Key(root), rest)) = segments.split_first() else {
+            return Shape::Any;
+        };
+        if root == "__tera_context" {
+            return Shape::Any;
+        }
+        let guarded = self.guarded.iter().any(|guarded| {
+            path == guarded
+                || path
+                    .strip_prefix(guarded.as_str())
+                    .is_some_and(|rest| rest.starts_with(['.', '[']))
+        });
+        let defaulted = defaulted || guarded;
+        let Some(mut shape) = scope
+            .iter()
+            .rev()
+            .find_map(|frame| frame.get(root.as_str()))
+            .cloned()
+        else {
+            if !defaulted {
+                self.report(format!("unknown variable `{}`", root));
+            }
+            return Shape::Any;
+        };
+
+        let mut prefix = root.clone();
+        for segment in rest {
+            let key = match segment {
+                Segment::Key(key) => key.clone(),
+                Segment::Variable(variable) => {
+                    self.lookup(variable, scope, false);
+                    return Shape::Any;
+                }
+            };
+            shape = match (&shape, key.parse::<usize>().is_ok()) {
+                (Shape::Any, _) => return Shape::Any,
+                (Shape::Array(item), true) => (**item).clone(),
+                (Shape::Object(fields), _) if fields.contains_key(&key) => fields[&key].clone(),
+                _ => {
+                    if !defaulted {
+                        self.report(format!(
+                            "unknown variable `{}`: `{}` has no `{}`",
+                            path, prefix, key
+                        ));
+                    }
+                    return Shape::Any;
+                }
+            };
+            prefix = format!("{}.{}", prefix, key);
+        }
+        shape
+    }
+
+    fn callable(&mut self, kind: Callable, call: &FunctionCall, scope: &Scope) {
+        if !self.is_known(kind, &call.name) {
+            let kind = match kind {
+                Callable::Filter => "filter",
+                Callable::Test => "test",
+                Callable::Function => "function",
+            };
+            self.report(format!("unknown {} `{}`", kind, call.name));
+        }
+        for arg in call.args.values() {
+            self.expr(arg, scope);
+        }
+    }
+
+    /// Whether Tera has `name`, by rendering a call to it and looking for a "not found" error
+    fn is_known(&mut self, kind: Callable, name: &str) -> bool {
+        // Handled by Tera itself rather than registered
+        if kind == Callable::Filter && name == "safe" {
+            return true;
+        }
+        if let Some(known) = self.known.get(&(kind, name.to_string())) {
+            return *known;
+        }
+        let probe = match kind {
+            Callable::Filter => format!("{{{{ 0 | {} }}}}", name),
+            // Tests only apply to variables
+            Callable::Test => format!("{{% set x = 0 %}}{{% if x is {} %}}{{% endif %}}", name),
+            Callable::Function => format!("{{{{ {}() }}}}", name),
+        };
+        let known = match self.probe.render_str(&probe, &tera::Context::new()) {
+            Ok(_) => true,
+            Err(error) => !not_found(&error),
+        };
+        self.known.insert((kind, name.to_string()), known);
+        known
+    }
+}
+
+/// Paths `condition` requires to be defined (`a is defined and b.c is defined`)
+fn defined(condition: &Expr) -> Vec<String> {
+    if condition.negated {
+        return Vec::new();
+    }
+    match &condition.val {
+        ExprVal::Test(test) if test.name == "defined" && !test.negated => vec![test.ident.clone()],
+        ExprVal::Logic(logic) if logic.operator == tera::ast::LogicOperator::And => {
+            let mut paths = defined(&logic.lhs);
+            paths.extend(defined(&logic.rhs));
+            paths
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn not_found(error: &tera::Error) -> bool {
+    let mut cause: Option<&(dyn std::error::Error + 'static)> = Some(error);
+    while let Some(error) = cause {
+        if let Some(tera_error) = error.downcast_ref::<tera::Error>() {
+            if matches!(
+                tera_error.kind,
+                tera::ErrorKind::FilterNotFound(_)
+                    | tera::ErrorKind::TestNotFound(_)
+                    | tera::ErrorKind::FunctionNotFound(_)
+            ) {
+                return true;
+            }
+        }
+        cause = error.source();
+    }
+    false
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    /// `[name]`: a key held in another variable
+    Variable(String),
+}
+
+/// `a.b[0]["c"][d]` split into `a`, `b`, `0`, `c`, and the variable `d`
+fn segments(path: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut chars = path.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if !current.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut current)));
+                }
+            }
+            '[' => {
+                if !current.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut current)));
+                }
+                let mut inner = String::new();
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    inner.push(c);
+                }
+                let inner = inner.trim();
+                let quoted = inner
+                    .strip_prefix(['"', '\''])
+                    .and_then(|rest| rest.strip_suffix(['"', '\'']));
+                segments.push(match quoted {
+                    Some(key) => Segment::Key(key.to_string()),
+                    None if inner.parse::<usize>().is_ok() => Segment::Key(inner.to_string()),
+                    None => Segment::Variable(inner.to_string()),
+                });
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        segments.push(Segment::Key(current));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GenerationConfig;
+
+    /// A pack overriding the `typescript` client template, with one more template
+    fn pack() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("generator-lint-pack-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("client.ts.tera"),
+            "{{ metadata.title }} {{ metadata.nmae }}",
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.tera"), "notes").unwrap();
+        dir
+    }
+
+    #[test]
+    fn packs_are_linted_for_the_generator_they_override() {
+        let dir = pack();
+        assert_eq!(pack_generator(&dir), Some("typescript"));
+        assert_eq!(pack_generator(&dir.join("missing")), None);
+
+        let config: GenerationConfig =
+            serde_yaml::from_str("{ generator: typescript, outputFile: client.ts }").unwrap();
+        let config = GenerationConfig {
+            template_dirs: vec![dir.clone()],
+            ..config
+        };
+        let tera = templates::load(&config, templates::TYPESCRIPT).unwrap();
+        let context = serde_json::json!({ "metadata": { "title": "T" } });
+        let report = lint_pack(&tera, &context, &dir, templates::TYPESCRIPT);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.templates, ["client.ts.tera", "notes.tera"]);
+        assert_eq!(report.unrendered, ["notes.tera"]);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].template, "client.ts.tera");
+        assert!(report.issues[0].message.contains("metadata.nmae"));
+    }
+}
@@ -0,0 +1,96 @@
+//! Tera templates for the template-driven generators.
+//!
+//! The built-in templates are compiled into the binary, so generating needs
+//! no files on disk; a generation's `template` directory replaces them with
+//! every `*.tera` file under it. `templateDirs` instead layers directories
+//! over them (project overrides, then an organization's pack), so a file
+//! replaces only the template of the same name.
+
+use crate::config::GenerationConfig;
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use std::fs;
+use std::path::Path;
+use tera::Tera;
+
+/// Version of the context shape the template-driven generators render with,
+/// exposed to templates as `context_version`. It changes when a key is
+/// removed or renamed or its type changes; additions keep it.
+pub const CONTEXT_VERSION: u32 = 1;
+
+/// `(name, source)` pairs a generator renders from
+pub type Builtin = &'static [(&'static str, &'static str)];
+
+pub const TYPESCRIPT: Builtin = &[(
+    "client.ts.tera",
+    include_str!("../../templates/typescript/client.ts.tera"),
+)];
+
+pub const PYTHON: Builtin = &[(
+    "client.py.tera",
+    include_str!("../../templates/python/client.py.tera"),
+)];
+
+pub const GOLANG: Builtin = &[(
+    "client.go.tera",
+    include_str!("../../templates/golang/client.go.tera"),
+)];
+
+/// The built-in templates of `generator`, for the template-driven generators
+pub fn builtin(generator: &str) -> Option<Builtin> {
+    match generator {
+        "typescript" => Some(TYPESCRIPT),
+        "python" => Some(PYTHON),
+        "golang" => Some(GOLANG),
+        _ => None,
+    }
+}
+
+/// Templates from the configured `template` directory, or the built-in set
+/// with the `templateDirs` layered over it
+pub fn load(config: &GenerationConfig, builtin: Builtin) -> Result<Tera> {
+    if let Some(dir) = &config.template {
+        if !config.template_dirs.is_empty() {
+            anyhow::bail!(
+                "{}: set either 'template' or 'templateDirs', not both",
+                config.generator
+            );
+        }
+        let glob = format!("{}/**/*.tera", dir.display());
+        return Tera::new(&glob)
+            .with_context(|| format!("Failed to load templates from {:?}", dir));
+    }
+
+    // Lowest precedence first, so each layer replaces the files it redefines
+    let mut sources: IndexMap<String, String> = builtin
+        .iter()
+        .map(|(name, source)| (name.to_string(), source.to_string()))
+        .collect();
+    for dir in config.template_dirs.iter().rev() {
+        read_dir(dir, dir, &mut sources)
+            .with_context(|| format!("Failed to load templates from {:?}", dir))?;
+    }
+
+    let mut tera = Tera::default();
+    tera.add_raw_templates(sources)
+        .with_context(|| format!("{}: failed to load templates", config.generator))?;
+    Ok(tera)
+}
+
+/// Every `*.tera` file under `dir`, named by its path from `root`
+fn read_dir(root: &Path, dir: &Path, sources: &mut IndexMap<String, String>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            read_dir(root, &path, sources)?;
+        } else if path.extension().is_some_and(|ext| ext == "tera") {
+            let name: Vec<_> = path
+                .strip_prefix(root)?
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect();
+            sources.insert(name.join("/"), fs::read_to_string(&path)?);
+        }
+    }
+    Ok(())
+}
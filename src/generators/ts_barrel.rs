@@ -0,0 +1,164 @@
+//! The barrel file of the `typescript_adi_http` module-per-tag output
+//! (`splitByTag`), for bundlers whose tree-shaking rules conflict with
+//! blanket `export *`:
+//! - `barrel: false` leaves it out; the modules are imported directly
+//! - `barrelExports`: `star` (default) re-exports each module with
+//!   `export *`; `named` lists every symbol, types with `export type`
+//! - `barrelAllowlist`: re-exports only the listed symbols, where `*` matches
+//!   any run of characters (`"*Client"`); implies `named`
+
+use super::ts_imports::ImportStyle;
+use crate::config::GenerationConfig;
+use anyhow::Result;
+
+#[derive(Debug, Clone, Default)]
+pub struct Barrel {
+    omitted: bool,
+    named: bool,
+    allowlist: Option<Vec<String>>,
+}
+
+impl Barrel {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        let named = match config.options.get("barrelExports").map(|v| v.as_str()) {
+            None | Some(Some("star")) => false,
+            Some(Some("named")) => true,
+            _ => anyhow::bail!(
+                "{}: option 'barrelExports' must be \"star\" or \"named\"",
+                config.generator
+            ),
+        };
+        let allowlist = match config.options.get("barrelAllowlist") {
+            None => None,
+            Some(value) => Some(
+                value
+                    .as_sequence()
+                    .and_then(|entries| {
+                        entries
+                            .iter()
+                            .map(|e| e.as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "{}: option 'barrelAllowlist' must be a list of symbol names",
+                            config.generator
+                        )
+                    })?,
+            ),
+        };
+        Ok(Self {
+            omitted: !config
+                .options
+                .get("barrel")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true),
+            named,
+            allowlist,
+        })
+    }
+
+    /// Whether any barrel option is set, for generations that have no barrel
+    pub fn configured(config: &GenerationConfig) -> bool {
+        ["barrel", "barrelExports", "barrelAllowlist"]
+            .iter()
+            .any(|option| config.options.contains_key(*option))
+    }
+
+    /// Whether the barrel file is written
+    pub fn enabled(&self) -> bool {
+        !self.omitted
+    }
+
+    /// Lines re-exporting from `specifier` what the module `content` exports
+    pub fn reexport(&self, style: &ImportStyle, specifier: &str, content: &str) -> String {
+        if !self.named && self.allowlist.is_none() {
+            return style.reexport(specifier);
+        }
+        let allowed = |name: &str| {
+            self.allowlist
+                .as_ref()
+                .is_none_or(|allowlist| allowlist.iter().any(|pattern| matches(pattern, name)))
+        };
+        let exported: Vec<(&str, bool)> = exports(content)
+            .into_iter()
+            .filter(|(name, _)| allowed(name))
+            .collect();
+        let names = |types: bool| -> Vec<&str> {
+            exported
+                .iter()
+                .filter(|(_, is_type)| *is_type == types)
+                .map(|(name, _)| *name)
+                .collect()
+        };
+
+        let mut lines = String::new();
+        let values = names(false);
+        if !values.is_empty() {
+            lines.push_str(&style.reexport_values(&values, specifier));
+        }
+        let types = names(true);
+        if !types.is_empty() {
+            lines.push_str(&style.reexport_types(&types, specifier));
+        }
+        lines
+    }
+
+    /// `barrelAllowlist` entries matching none of `contents`' exports, likely typos
+    pub fn unmatched<'a>(&'a self, contents: &[&str]) -> Vec<&'a str> {
+        let Some(allowlist) = &self.allowlist else {
+            return Vec::new();
+        };
+        let exported: Vec<&str> = contents
+            .iter()
+            .flat_map(|content| exports(content))
+            .map(|(name, _)| name)
+            .collect();
+        allowlist
+            .iter()
+            .filter(|pattern| !exported.iter().any(|name| matches(pattern, name)))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Names a module declares with a top-level `export`, and whether each is a type
+fn exports(content: &str) -> Vec<(&str, bool)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.strip_prefix("export ")?.split_whitespace();
+            let is_type = match words.next()? {
+                "type" | "interface" => true,
+                "const" | "let" | "function" | "class" | "enum" => false,
+                _ => return None,
+            };
+            let name = words.next()?;
+            let end = name
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(name.len());
+            (end > 0).then(|| (&name[..end], is_type))
+        })
+        .collect()
+}
+
+/// Whether `name` matches `pattern`, whose `*` stands for any run of characters
+fn matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*`: the whole name
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
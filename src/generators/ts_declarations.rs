@@ -0,0 +1,228 @@
+//! Type-only `.d.ts` output for the TypeScript generators (`declarationFile`
+//! option).
+//!
+//! Every schema becomes an interface matching `z.infer` of the zod schema the
+//! generator emits from the same IR, so consumers can import the types without
+//! bundling zod.
+
+use super::docs::DocStyle;
+use super::inline_schemas::{self, Inlined};
+use super::naming::{self, Language};
+use super::ts_optional::OptionalStrategy;
+use super::type_overrides::{self, TypeOverrides};
+use super::validators;
+use super::GeneratedFile;
+use crate::config::GenerationConfig;
+use crate::parsers::{FieldDefinition, SchemaIR, TypeInfo};
+
+/// How a generator's zod schemas map IR types, where generators differ
+pub struct Flavor {
+    /// Type inferred for `date` / `date-time` strings
+    pub date: &'static str,
+    /// Type inferred for free-form objects
+    pub object: &'static str,
+    /// Whether spec `nullable` fields are emitted with `.nullable()`
+    pub nullable: bool,
+}
+
+/// `typescript` generator: `z.date().or(z.string())`, `z.any()`
+pub const TYPESCRIPT: Flavor = Flavor {
+    date: "Date | string",
+    object: "any",
+    nullable: true,
+};
+
+/// `typescript` generator with `validationLibrary: arktype`, which has no `any`
+pub const TYPESCRIPT_ARKTYPE: Flavor = Flavor {
+    date: "Date | string",
+    object: "unknown",
+    nullable: true,
+};
+
+/// `typescript_adi_http` generator: `z.string().datetime()`, `z.record(z.any())`
+pub const ADI_HTTP: Flavor = Flavor {
+    date: "string",
+    object: "Record<string, any>",
+    nullable: false,
+};
+
+/// Declaration file requested by the `declarationFile` option, if any
+pub fn companion(
+    schema_ir: &SchemaIR,
+    config: &GenerationConfig,
+    flavor: &Flavor,
+) -> Option<GeneratedFile> {
+    let filename = config
+        .options
+        .get("declarationFile")
+        .and_then(|v| v.as_str())?;
+    Some(GeneratedFile {
+        filename: filename.to_string(),
+        content: render(schema_ir, config, flavor),
+    })
+}
+
+fn render(schema_ir: &SchemaIR, config: &GenerationConfig, flavor: &Flavor) -> String {
+    // Validated with the generator's other options
+    let doc_style = DocStyle::from_config(config).unwrap_or_default();
+    // Validated with the generator's other options
+    let optional_strategy =
+        OptionalStrategy::from_config(config).unwrap_or(OptionalStrategy::Optional);
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "// Generated TypeScript declarations for {}\n",
+        schema_ir.metadata.title
+    ));
+    output.push_str(&format!("// Version: {}\n", schema_ir.metadata.version));
+    // Validated with the generator's other options
+    let overrides = TypeOverrides::from_config(config).unwrap_or_default();
+    let imports = overrides.imports(schema_ir);
+    if !imports.is_empty() {
+        output.push('\n');
+    }
+    for (module, types) in imports {
+        output.push_str(&format!(
+            "import type {{ {} }} from '{}';\n",
+            type_overrides::imported_names(&types).join(", "),
+            module
+        ));
+    }
+
+    // Object literal types for the schemas the generator inlines
+    let single_use = inline_schemas::single_use(schema_ir, config);
+    let property = |field: &FieldDefinition, inlined: &Inlined| {
+        let mut line = String::new();
+        let description = field.description.as_deref().or_else(|| {
+            inline_schemas::direct(&field.type_info, &single_use)
+                .and_then(|schema| schema.description.as_deref())
+        });
+        if let Some(doc) = doc_style.jsdoc(&[description], &[], "  ") {
+            line.push_str(&format!("  {}\n", doc));
+        }
+
+        let presence =
+            optional_strategy.presence(field.required, field.nullable && flavor.nullable);
+        let mut field_type = type_expr(&field.type_info, flavor, inlined);
+        if presence.nullable() {
+            field_type.push_str(" | null");
+        }
+        line.push_str(&format!(
+            "  {}{}: {};\n",
+            naming::ts_property_key(&field.name),
+            if presence.optional() { "?" } else { "" },
+            inline_schemas::indent(&field_type, "  ")
+        ));
+        line
+    };
+    let mut inlined = Inlined::new();
+    for schema in &single_use {
+        let properties: String = schema
+            .fields
+            .iter()
+            .map(|field| property(field, &inlined))
+            .collect();
+        inlined.insert(schema.name.clone(), format!("{{\n{}}}", properties));
+    }
+
+    for schema in schema_ir
+        .schemas
+        .iter()
+        .filter(|schema| !inlined.contains_key(&schema.name))
+    {
+        output.push('\n');
+        if let Some(doc) = doc_style.jsdoc(&[schema.description.as_deref()], &[], "") {
+            output.push_str(&format!("{}\n", doc));
+        }
+        if let Some(type_info) = validators::enum_schema(schema) {
+            output.push_str(&format!(
+                "export type {} = {};\n",
+                naming::type_name(&schema.name, Language::TypeScript),
+                type_expr(&type_info, flavor, &inlined)
+            ));
+            continue;
+        }
+        let parents: Vec<String> = schema
+            .parents
+            .iter()
+            .map(|parent| naming::type_name(parent, Language::TypeScript))
+            .collect();
+        let extends = match parents.as_slice() {
+            [] => String::new(),
+            parents => format!(" extends {}", parents.join(", ")),
+        };
+        output.push_str(&format!(
+            "export interface {}{} {{\n",
+            naming::type_name(&schema.name, Language::TypeScript),
+            extends
+        ));
+
+        for field in &schema.fields {
+            output.push_str(&property(field, &inlined));
+        }
+
+        output.push_str("}\n");
+    }
+
+    output
+}
+
+fn type_expr(type_info: &TypeInfo, flavor: &Flavor, inlined: &Inlined) -> String {
+    if !type_info.tuple_items.is_empty() {
+        let items: Vec<String> = type_info
+            .tuple_items
+            .iter()
+            .map(|t| type_expr(t, flavor, inlined))
+            .collect();
+        return format!("[{}]", items.join(", "));
+    }
+
+    if type_info.is_array {
+        let item = match &type_info.array_item_type {
+            Some(item_type) => type_expr(item_type, flavor, inlined),
+            None => "any".to_string(),
+        };
+        if item.contains(' ') {
+            return format!("Array<{}>", item);
+        }
+        return format!("{}[]", item);
+    }
+
+    if let Some(ref_name) = &type_info.reference {
+        if let Some(object) = inlined.get(ref_name) {
+            return object.clone();
+        }
+        return naming::type_name(ref_name, Language::TypeScript);
+    }
+
+    if let Some(enum_vals) = &type_info.enum_values {
+        let values: Vec<String> = enum_vals.iter().map(|v| naming::quote(v)).collect();
+        return values.join(" | ");
+    }
+
+    match type_info.openapi_type.as_str() {
+        "string" => match type_info.format.as_deref() {
+            Some("date" | "date-time") => flavor.date.to_string(),
+            _ => "string".to_string(),
+        },
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "external" => type_info
+            .format
+            .clone()
+            .unwrap_or_else(|| "any".to_string()),
+        "object" => match &type_info.map_values {
+            Some(values) => format!("Record<string, {}>", type_expr(values, flavor, inlined)),
+            None => flavor.object.to_string(),
+        },
+        "union" => {
+            let members: Vec<String> = type_info
+                .union_members
+                .iter()
+                .map(|m| type_expr(m, flavor, inlined))
+                .collect();
+            members.join(" | ")
+        }
+        _ => "any".to_string(),
+    }
+}
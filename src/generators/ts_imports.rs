@@ -0,0 +1,152 @@
+//! Import statements for the TypeScript outputs.
+//!
+//! Shaped by the module options so the files compile under the consumer's
+//! tsconfig:
+//! - `moduleFormat`: `esm` (default) emits `import { a } from 'm'`; `cjs` emits
+//!   `import m = require('m')` with one `import a = m.a` alias per name, which
+//!   keeps `z` usable as both a value and a namespace
+//! - `importExtension`: suffix appended to relative specifiers (`.js` for
+//!   `NodeNext` resolution)
+//! - `typeImports`: `import type` for names used only as types (default true)
+
+use super::naming;
+use crate::config::GenerationConfig;
+use anyhow::Result;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ModuleFormat {
+    Esm,
+    Cjs,
+}
+
+pub struct ImportStyle {
+    format: ModuleFormat,
+    extension: Option<String>,
+    type_imports: bool,
+}
+
+impl ImportStyle {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        let format = match config.options.get("moduleFormat").map(|v| v.as_str()) {
+            None | Some(Some("esm")) => ModuleFormat::Esm,
+            Some(Some("cjs")) => ModuleFormat::Cjs,
+            _ => anyhow::bail!(
+                "{}: option 'moduleFormat' must be \"esm\" or \"cjs\"",
+                config.generator
+            ),
+        };
+
+        let extension = match config.options.get("importExtension") {
+            None => None,
+            Some(value) => match value.as_str() {
+                Some("") => None,
+                Some(ext) if ext.starts_with('.') => Some(ext.to_string()),
+                _ => anyhow::bail!(
+                    "{}: option 'importExtension' must be a suffix starting with '.' (e.g. \".js\")",
+                    config.generator
+                ),
+            },
+        };
+
+        let type_imports = config
+            .options
+            .get("typeImports")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        Ok(Self {
+            format,
+            extension,
+            type_imports,
+        })
+    }
+
+    /// Import of runtime values, one statement per line, newline-terminated
+    pub fn values(&self, names: &[&str], specifier: &str) -> String {
+        let specifier = self.specifier(specifier);
+        match self.format {
+            ModuleFormat::Esm => {
+                format!("import {{ {} }} from '{}';\n", names.join(", "), specifier)
+            }
+            ModuleFormat::Cjs => {
+                let module = module_binding(&specifier);
+                let mut lines = format!("import {} = require('{}');\n", module, specifier);
+                for name in names {
+                    lines.push_str(&format!("import {} = {}.{};\n", name, module, name));
+                }
+                lines
+            }
+        }
+    }
+
+    /// Namespace import (`import * as v from 'm'`)
+    pub fn namespace(&self, name: &str, specifier: &str) -> String {
+        let specifier = self.specifier(specifier);
+        match self.format {
+            ModuleFormat::Esm => format!("import * as {} from '{}';\n", name, specifier),
+            ModuleFormat::Cjs => format!("import {} = require('{}');\n", name, specifier),
+        }
+    }
+
+    /// Import of names used only in type positions
+    pub fn types(&self, names: &[&str], specifier: &str) -> String {
+        if !self.type_imports {
+            return self.values(names, specifier);
+        }
+        format!(
+            "import type {{ {} }} from '{}';\n",
+            names.join(", "),
+            self.specifier(specifier)
+        )
+    }
+
+    /// Re-export of everything a module exports, for barrel files
+    pub fn reexport(&self, specifier: &str) -> String {
+        format!("export * from '{}';\n", self.specifier(specifier))
+    }
+
+    /// Re-export of runtime values by name
+    pub fn reexport_values(&self, names: &[&str], specifier: &str) -> String {
+        format!(
+            "export {{ {} }} from '{}';\n",
+            names.join(", "),
+            self.specifier(specifier)
+        )
+    }
+
+    /// Re-export of names that are only types
+    pub fn reexport_types(&self, names: &[&str], specifier: &str) -> String {
+        if !self.type_imports {
+            return self.reexport_values(names, specifier);
+        }
+        format!(
+            "export type {{ {} }} from '{}';\n",
+            names.join(", "),
+            self.specifier(specifier)
+        )
+    }
+
+    /// Relative specifiers get `importExtension` unless they already end with it
+    fn specifier(&self, specifier: &str) -> String {
+        match &self.extension {
+            Some(ext)
+                if (specifier.starts_with("./") || specifier.starts_with("../"))
+                    && !specifier.ends_with(ext.as_str()) =>
+            {
+                format!("{}{}", specifier, ext)
+            }
+            _ => specifier.to_string(),
+        }
+    }
+}
+
+/// `zodModule` for `zod`, `httpModule` for `@adi-family/http`, `typesModule` for `./types.js`
+fn module_binding(specifier: &str) -> String {
+    let last = specifier.rsplit('/').next().unwrap_or(specifier);
+    let stem = last.split('.').next().unwrap_or(last);
+    let mut binding = naming::camel_case(stem);
+    if !binding.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        binding.insert_str(0, "imported");
+    }
+    format!("{}Module", binding)
+}
@@ -0,0 +1,70 @@
+//! How fields the spec leaves out of `required` are typed by the TypeScript
+//! generators (`optionalStrategy` option):
+//! - `optional` (default): `field?: T`, zod `.optional()`
+//! - `nullable`: `field: T | null`, zod `.nullable()`
+//! - `nullish`: `field?: T | null`, zod `.nullish()`
+//!
+//! Fields marked `nullable` in the spec additionally accept `null` either way.
+
+use crate::config::GenerationConfig;
+use anyhow::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionalStrategy {
+    Optional,
+    Nullable,
+    Nullish,
+}
+
+/// What a field accepts besides its type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Presence {
+    Required,
+    Nullable,
+    Optional,
+    /// Spec-optional and spec-nullable under the `optional` strategy (`.optional().nullable()`)
+    OptionalNullable,
+    Nullish,
+}
+
+impl OptionalStrategy {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("optionalStrategy").map(|v| v.as_str()) {
+            None | Some(Some("optional")) => Ok(Self::Optional),
+            Some(Some("nullable")) => Ok(Self::Nullable),
+            Some(Some("nullish")) => Ok(Self::Nullish),
+            _ => anyhow::bail!(
+                "{}: option 'optionalStrategy' must be \"optional\", \"nullable\", or \"nullish\"",
+                config.generator
+            ),
+        }
+    }
+
+    pub fn presence(self, required: bool, nullable: bool) -> Presence {
+        match (required, nullable, self) {
+            (true, false, _) => Presence::Required,
+            (true, true, _) | (false, _, Self::Nullable) => Presence::Nullable,
+            (false, false, Self::Optional) => Presence::Optional,
+            (false, true, Self::Optional) => Presence::OptionalNullable,
+            (false, _, Self::Nullish) => Presence::Nullish,
+        }
+    }
+}
+
+impl Presence {
+    /// Whether the key may be absent
+    pub fn optional(self) -> bool {
+        matches!(
+            self,
+            Self::Optional | Self::OptionalNullable | Self::Nullish
+        )
+    }
+
+    /// Whether the value may be `null`
+    pub fn nullable(self) -> bool {
+        matches!(
+            self,
+            Self::Nullable | Self::OptionalNullable | Self::Nullish
+        )
+    }
+}
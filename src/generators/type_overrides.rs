@@ -0,0 +1,227 @@
+//! Types supplied by the user instead of generated (`typeOverrides` option):
+//! a component schema, or every value of a `format`, maps to an external type
+//! and the import that brings it in.
+//!
+//! ```yaml
+//! typeOverrides:
+//!   Money: { type: Money, import: "@corp/money" }
+//!   "format:decimal": { type: decimal.Decimal, import: decimal }
+//! ```
+//!
+//! The overridden schemas are dropped, and the fields using them or the format
+//! become `external` types carrying the type as written, which the
+//! `typescript`, `python`, and `golang` generators emit verbatim. Bodies that
+//! are an overridden schema as a whole lose their typing.
+
+use crate::config::GenerationConfig;
+use crate::parsers::{SchemaIR, TypeInfo};
+use anyhow::{Context, Result};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Clone, Default)]
+pub struct TypeOverrides {
+    /// Overrides of component schemas, by schema name
+    schemas: BTreeMap<String, Override>,
+    /// Overrides of `format` values, by format
+    formats: BTreeMap<String, Override>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Override {
+    /// Type as written in the target language (`Money`, `decimal.Decimal`)
+    pub type_name: String,
+    /// Module, package, or import path the type comes from; `None` for built-ins
+    pub import: Option<String>,
+}
+
+impl TypeOverrides {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        let mut overrides = Self::default();
+        let Some(value) = config.options.get("typeOverrides") else {
+            return Ok(overrides);
+        };
+        let entries = value.as_mapping().with_context(|| {
+            format!(
+                "{}: option 'typeOverrides' must map schema names and \"format:<name>\" keys to types",
+                config.generator
+            )
+        })?;
+        for (key, entry) in entries {
+            let key = key.as_str().unwrap_or_default();
+            let field = |name: &str| {
+                entry
+                    .get(name)
+                    .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+            };
+            let Some(type_name) = field("type") else {
+                anyhow::bail!(
+                    "{}: option 'typeOverrides': '{}' needs a `type`, optionally with an `import`",
+                    config.generator,
+                    key
+                );
+            };
+            let entry = Override {
+                type_name: type_name.to_string(),
+                import: field("import").map(str::to_string),
+            };
+            match key.strip_prefix("format:") {
+                Some(format) => overrides.formats.insert(format.to_string(), entry),
+                None => overrides.schemas.insert(key.to_string(), entry),
+            };
+        }
+        Ok(overrides)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty() && self.formats.is_empty()
+    }
+
+    /// The IR with overridden schemas dropped and their uses made external
+    pub fn apply(self, schema_ir: Cow<'_, SchemaIR>) -> Cow<'_, SchemaIR> {
+        if self.is_empty() {
+            return schema_ir;
+        }
+
+        let mut prepared = schema_ir.into_owned();
+        prepared
+            .schemas
+            .retain(|schema| !self.schemas.contains_key(&schema.name));
+        for schema in &mut prepared.schemas {
+            for field in &mut schema.fields {
+                self.rewrite(&mut field.type_info);
+            }
+        }
+        let overridden = |name: &str| self.schemas.contains_key(name);
+        for op in &mut prepared.operations {
+            if op
+                .request_body
+                .as_ref()
+                .is_some_and(|body| overridden(&body.name))
+            {
+                op.request_body = None;
+            }
+            if op
+                .response
+                .as_ref()
+                .is_some_and(|response| overridden(&response.name))
+            {
+                op.response = None;
+            }
+            for response in &mut op.responses {
+                if response
+                    .schema
+                    .as_ref()
+                    .is_some_and(|body| overridden(&body.name))
+                {
+                    response.schema = None;
+                }
+                if let Some(type_info) = &mut response.inline_type {
+                    self.rewrite(type_info);
+                }
+            }
+        }
+        for channel in &mut prepared.channels {
+            channel.send = channel.send.take().filter(|name| !overridden(name));
+            channel.receive = channel.receive.take().filter(|name| !overridden(name));
+        }
+        Cow::Owned(prepared)
+    }
+
+    fn rewrite(&self, type_info: &mut TypeInfo) {
+        let matched = match &type_info.reference {
+            Some(reference) => self.schemas.get(reference),
+            None => type_info
+                .format
+                .as_ref()
+                .and_then(|format| self.formats.get(format)),
+        };
+        if let Some(entry) = matched {
+            if type_info.is_array {
+                // Arrays also name their items' schema; the items are rewritten below
+                type_info.reference = None;
+            } else {
+                *type_info = TypeInfo {
+                    openapi_type: "external".to_string(),
+                    format: Some(entry.type_name.clone()),
+                    is_array: false,
+                    array_item_type: None,
+                    reference: None,
+                    enum_values: None,
+                    union_members: Vec::new(),
+                    tuple_items: Vec::new(),
+                    map_values: None,
+                };
+                return;
+            }
+        }
+        let nested = type_info
+            .array_item_type
+            .iter_mut()
+            .chain(type_info.map_values.iter_mut())
+            .map(|boxed| boxed.as_mut())
+            .chain(type_info.union_members.iter_mut())
+            .chain(type_info.tuple_items.iter_mut());
+        for inner in nested {
+            self.rewrite(inner);
+        }
+    }
+
+    /// Overrides with an import that `schema_ir` uses, by import; their types
+    /// are sorted and deduplicated
+    pub fn imports(&self, schema_ir: &SchemaIR) -> BTreeMap<String, BTreeSet<String>> {
+        let mut used = BTreeSet::new();
+        for schema in &schema_ir.schemas {
+            for field in &schema.fields {
+                collect_external(&field.type_info, &mut used);
+            }
+        }
+
+        let mut imports: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for entry in self.schemas.values().chain(self.formats.values()) {
+            if let Some(import) = entry
+                .import
+                .as_ref()
+                .filter(|_| used.contains(&entry.type_name))
+            {
+                imports
+                    .entry(import.clone())
+                    .or_default()
+                    .insert(entry.type_name.clone());
+            }
+        }
+        imports
+    }
+}
+
+fn collect_external(type_info: &TypeInfo, used: &mut BTreeSet<String>) {
+    if type_info.openapi_type == "external" {
+        used.extend(type_info.format.clone());
+    }
+    let nested = type_info
+        .array_item_type
+        .iter()
+        .chain(type_info.map_values.iter())
+        .map(|boxed| boxed.as_ref())
+        .chain(type_info.union_members.iter())
+        .chain(type_info.tuple_items.iter());
+    for inner in nested {
+        collect_external(inner, used);
+    }
+}
+
+/// Names an import brings into scope for `types`: the leading identifier of
+/// each (`Money` of `Money<'EUR'>`, `Temporal` of `Temporal.PlainDate`)
+pub fn imported_names(types: &BTreeSet<String>) -> Vec<&str> {
+    let names: BTreeSet<&str> = types
+        .iter()
+        .map(|type_name| {
+            let end = type_name
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(type_name.len());
+            &type_name[..end]
+        })
+        .collect();
+    names.into_iter().collect()
+}
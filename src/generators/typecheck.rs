@@ -0,0 +1,352 @@
+//! Type check of a generation's files once written (`typecheck: true`
+//! generation option), so output that parses but does not type-check (a wrong
+//! zod call, a missing import, a mistyped field) fails the run:
+//! - `.ts`: `tsc --noEmit` over the files together, under a minimal strict
+//!   tsconfig written to the temp directory; `tsc` comes from the nearest
+//!   `node_modules/.bin` above the output directory, else `PATH`
+//! - `.py`: `mypy`, or `pyright` with `pythonTypeChecker: pyright`, from the
+//!   nearest `.venv/bin` above the output directory, else `PATH`
+//! - `.go`: `go build ./...`, and `go vet ./...` with `goVet: true`, over a
+//!   copy of the files in a temporary module of their own (the client only
+//!   imports the standard library); `go` comes from `PATH`
+//!
+//! Imports (`zod`, `pydantic`, ...) resolve as in the consumer's build, from
+//! the packages installed above the output directory.
+
+use super::GeneratedFile;
+use crate::config::GenerationConfig;
+use crate::diagnostics::{ErrorGroup, SourceError};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum PythonChecker {
+    #[default]
+    Mypy,
+    Pyright,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TypeCheck {
+    enabled: bool,
+    python: PythonChecker,
+    go_vet: bool,
+}
+
+impl TypeCheck {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        let python = match config.options.get("pythonTypeChecker").map(|v| v.as_str()) {
+            None | Some(Some("mypy")) => PythonChecker::Mypy,
+            Some(Some("pyright")) => PythonChecker::Pyright,
+            _ => anyhow::bail!(
+                "{}: option 'pythonTypeChecker' must be \"mypy\" or \"pyright\"",
+                config.generator
+            ),
+        };
+        Ok(Self {
+            enabled: config
+                .options
+                .get("typecheck")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            python,
+            go_vet: config
+                .options
+                .get("goVet")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        })
+    }
+
+    /// Whether `files`, written under `output_dir`, type-check; files other
+    /// than `.ts`, `.py`, and `.go` are skipped
+    pub fn check(&self, output_dir: &Path, files: &[GeneratedFile]) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let output_dir = output_dir
+            .canonicalize()
+            .with_context(|| format!("typecheck: cannot resolve {:?}", output_dir))?;
+        let of = |extension: &str| -> Vec<&GeneratedFile> {
+            files
+                .iter()
+                .filter(|file| file.filename.ends_with(extension))
+                .collect()
+        };
+        let (typescript, python, go) = (of(".ts"), of(".py"), of(".go"));
+
+        let mut errors = Vec::new();
+        if !typescript.is_empty() {
+            errors.extend(check_typescript(&output_dir, &typescript)?);
+        }
+        if !python.is_empty() {
+            errors.extend(match self.python {
+                PythonChecker::Mypy => check_mypy(&output_dir, &python)?,
+                PythonChecker::Pyright => check_pyright(&output_dir, &python)?,
+            });
+        }
+        if !go.is_empty() {
+            errors.extend(check_go(&go, self.go_vet)?);
+        }
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            count => Err(ErrorGroup::new(format!("{} type errors", count), errors).into()),
+        }
+    }
+}
+
+/// `tsc` errors in `files`
+fn check_typescript(output_dir: &Path, files: &[&GeneratedFile]) -> Result<Vec<anyhow::Error>> {
+    let tsconfig = serde_json::json!({
+        "compilerOptions": {
+            "noEmit": true,
+            "strict": true,
+            "skipLibCheck": true,
+            "target": "ES2020",
+            "module": "ESNext",
+            "moduleResolution": "Bundler",
+            "lib": ["ES2020", "DOM"],
+            "types": [],
+            "esModuleInterop": true,
+        },
+        "files": files
+            .iter()
+            .map(|file| output_dir.join(&file.filename).display().to_string())
+            .collect::<Vec<_>>(),
+    });
+    let tsconfig_path =
+        std::env::temp_dir().join(format!("generator-typecheck-{}.json", std::process::id()));
+    std::fs::write(&tsconfig_path, serde_json::to_string_pretty(&tsconfig)?)
+        .with_context(|| format!("typecheck: failed to write {:?}", tsconfig_path))?;
+
+    let program = tool(output_dir, "node_modules/.bin", "tsc");
+    let output = Command::new(&program)
+        .args(["--noEmit", "--pretty", "false", "-p"])
+        .arg(&tsconfig_path)
+        .current_dir(output_dir)
+        .output();
+    let _ = std::fs::remove_file(&tsconfig_path);
+    let output = output.with_context(|| {
+        format!(
+            "typecheck: failed to run {:?}; is TypeScript installed?",
+            program
+        )
+    })?;
+    if output.status.success() {
+        return Ok(Vec::new());
+    }
+
+    // `zod.ts(12,5): error TS2322: Type 'string' is not assignable to type 'number'.`
+    let text = String::from_utf8_lossy(&output.stdout).into_owned()
+        + &String::from_utf8_lossy(&output.stderr);
+    let mut errors: Vec<anyhow::Error> = Vec::new();
+    // Lines that are not `file(line,col): ...` continue the message before them
+    for line in text.lines() {
+        if let Some((file, row, column, message)) = locate(line, files) {
+            errors.push(
+                SourceError::at_position(
+                    &file.filename,
+                    &file.content,
+                    row,
+                    column,
+                    message.to_string(),
+                )
+                .into(),
+            );
+        }
+    }
+    if errors.is_empty() {
+        anyhow::bail!("typecheck: tsc failed: {}", text.trim());
+    }
+    Ok(errors)
+}
+
+/// The generated file, line, column, and message of a `tsc --pretty false` error line
+fn locate<'a, 'f>(
+    line: &'a str,
+    files: &[&'f GeneratedFile],
+) -> Option<(&'f GeneratedFile, usize, usize, &'a str)> {
+    let (location, message) = line.split_once("): ")?;
+    let (path, position) = location.rsplit_once('(')?;
+    let (row, column) = position.split_once(',')?;
+    let path = path.trim().replace('\\', "/");
+    let file = files.iter().copied().find(|file| file.filename == path)?;
+    Some((
+        file,
+        row.parse().ok()?,
+        column.parse().ok()?,
+        message.trim(),
+    ))
+}
+
+/// `mypy` errors in `files`
+fn check_mypy(output_dir: &Path, files: &[&GeneratedFile]) -> Result<Vec<anyhow::Error>> {
+    let program = tool(output_dir, ".venv/bin", "mypy");
+    let output = Command::new(&program)
+        .args(["--show-column-numbers", "--no-error-summary", "--no-pretty"])
+        .args(files.iter().map(|file| &file.filename))
+        .current_dir(output_dir)
+        .output()
+        .with_context(|| format!("typecheck: failed to run {:?}; is mypy installed?", program))?;
+    if output.status.success() {
+        return Ok(Vec::new());
+    }
+
+    // `client.py:12:5: error: Incompatible return value type  [return-value]`
+    let text = String::from_utf8_lossy(&output.stdout).into_owned()
+        + &String::from_utf8_lossy(&output.stderr);
+    let errors: Vec<anyhow::Error> = text
+        .lines()
+        .filter_map(|line| {
+            let (location, message) = line.split_once(": error: ")?;
+            let mut parts = location.rsplitn(3, ':');
+            let column = parts.next()?.parse().ok()?;
+            let row = parts.next()?.parse().ok()?;
+            let path = parts.next()?.replace('\\', "/");
+            let file = files.iter().find(|file| file.filename == path)?;
+            Some(
+                SourceError::at_position(
+                    &file.filename,
+                    &file.content,
+                    row,
+                    column,
+                    message.trim().to_string(),
+                )
+                .into(),
+            )
+        })
+        .collect();
+    if errors.is_empty() {
+        anyhow::bail!("typecheck: mypy failed: {}", text.trim());
+    }
+    Ok(errors)
+}
+
+/// `pyright` errors in `files`
+fn check_pyright(output_dir: &Path, files: &[&GeneratedFile]) -> Result<Vec<anyhow::Error>> {
+    let program = tool(output_dir, ".venv/bin", "pyright");
+    let output = Command::new(&program)
+        .arg("--outputjson")
+        .args(files.iter().map(|file| &file.filename))
+        .current_dir(output_dir)
+        .output()
+        .with_context(|| {
+            format!(
+                "typecheck: failed to run {:?}; is pyright installed?",
+                program
+            )
+        })?;
+    if output.status.success() {
+        return Ok(Vec::new());
+    }
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).map_err(|_| {
+        anyhow::anyhow!(
+            "typecheck: pyright failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    })?;
+    let diagnostics = report["generalDiagnostics"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let errors: Vec<anyhow::Error> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic["severity"] == "error")
+        .filter_map(|diagnostic| {
+            let path = Path::new(diagnostic["file"].as_str()?);
+            let file = files
+                .iter()
+                .find(|file| output_dir.join(&file.filename) == path)?;
+            // Zero-based positions
+            let start = &diagnostic["range"]["start"];
+            let row = start["line"].as_u64()? as usize + 1;
+            let column = start["character"].as_u64()? as usize + 1;
+            let message = diagnostic["message"].as_str()?.to_string();
+            Some(
+                SourceError::at_position(&file.filename, &file.content, row, column, message)
+                    .into(),
+            )
+        })
+        .collect();
+    if errors.is_empty() {
+        anyhow::bail!(
+            "typecheck: pyright failed: {}",
+            String::from_utf8_lossy(&output.stdout).trim()
+        );
+    }
+    Ok(errors)
+}
+
+/// `go build` (and `go vet`) errors in `files`, built as a module of their own
+fn check_go(files: &[&GeneratedFile], vet: bool) -> Result<Vec<anyhow::Error>> {
+    let module =
+        std::env::temp_dir().join(format!("generator-typecheck-go-{}", std::process::id()));
+    let result = build_go_module(&module, files, vet);
+    let _ = std::fs::remove_dir_all(&module);
+    result
+}
+
+fn build_go_module(
+    module: &Path,
+    files: &[&GeneratedFile],
+    vet: bool,
+) -> Result<Vec<anyhow::Error>> {
+    std::fs::create_dir_all(module)
+        .with_context(|| format!("typecheck: failed to create {:?}", module))?;
+    std::fs::write(module.join("go.mod"), "module generated.check\n\ngo 1.21\n")?;
+    for file in files {
+        let path = module.join(&file.filename);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, &file.content)?;
+    }
+
+    let commands: &[&str] = if vet { &["build", "vet"] } else { &["build"] };
+    for command in commands {
+        let output = Command::new("go")
+            .args([command, "./..."])
+            .current_dir(module)
+            .env("GOWORK", "off")
+            .output()
+            .context("typecheck: failed to run \"go\"; is Go installed?")?;
+        if output.status.success() {
+            continue;
+        }
+
+        // `./client.go:12:5: undefined: foo`, after a `# generated.check` line
+        let text = String::from_utf8_lossy(&output.stdout).into_owned()
+            + &String::from_utf8_lossy(&output.stderr);
+        let errors: Vec<anyhow::Error> = text
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.trim_start_matches("./").splitn(4, ':');
+                let path = parts.next()?.replace('\\', "/");
+                let row = parts.next()?.parse().ok()?;
+                let column = parts.next()?.trim().parse().ok()?;
+                let message = format!("go {}: {}", command, parts.next()?.trim());
+                let file = files.iter().find(|file| file.filename == path)?;
+                Some(
+                    SourceError::at_position(&file.filename, &file.content, row, column, message)
+                        .into(),
+                )
+            })
+            .collect();
+        if errors.is_empty() {
+            anyhow::bail!("typecheck: go {} failed: {}", command, text.trim());
+        }
+        return Ok(errors);
+    }
+    Ok(Vec::new())
+}
+
+/// `name` from the nearest `bin_dir` (`node_modules/.bin`) at or above `dir`, else from `PATH`
+fn tool(dir: &Path, bin_dir: &str, name: &str) -> PathBuf {
+    dir.ancestors()
+        .map(|dir| dir.join(bin_dir).join(name))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| PathBuf::from(name))
+}
@@ -1,9 +1,32 @@
+use super::any_types;
+use super::conditional::Conditionals;
+use super::docs::{DocStyle, SeeAlso};
+use super::hypermedia::Hypermedia;
+use super::idempotency::{self, IdempotencyKey};
+use super::inline_schemas::{self, Inlined};
+use super::naming::{self, Language};
+use super::patch_bodies;
+use super::rate_limit::RateLimitOption;
+use super::readme;
+use super::request_builders::{self, RequestBuilders};
+use super::request_encoding;
+use super::response_cache::{self, CacheDefaults};
+use super::templates;
+use super::ts_declarations;
+use super::ts_imports::ImportStyle;
+use super::ts_optional::OptionalStrategy;
+use super::type_overrides::{self, TypeOverrides};
+use super::validators::{self, Dialect, ResponseValidation, ValidationLibrary};
 use super::{GeneratedOutput, Generator};
 use crate::config::GenerationConfig;
-use crate::parsers::SchemaIR;
+use crate::parsers::gateway;
+use crate::parsers::{
+    BodyKind, FieldDefinition, OperationDefinition, ParameterLocation, ResponseDefinition,
+    SchemaIR, TypeInfo,
+};
 use anyhow::Result;
-use std::collections::HashMap;
-use tera::{Context, Tera};
+use std::collections::{BTreeSet, HashMap};
+use tera::Context;
 
 pub struct TypeScriptGenerator;
 
@@ -21,15 +44,95 @@ impl Generator for TypeScriptGenerator {
         schema_ir: &SchemaIR,
         config: &GenerationConfig,
     ) -> Result<GeneratedOutput> {
-        // Determine template path
-        let template_path = config
-            .template
-            .as_ref()
-            .and_then(|p| p.to_str())
-            .unwrap_or("templates/typescript");
-
-        let tera = Tera::new(&format!("{}/**/*.tera", template_path))?;
+        let tera = templates::load(config, templates::TYPESCRIPT)?;
+        let context = self.context(schema_ir, config)?;
+
+        // Render template
+        let content = tera.render("client.ts.tera", &context)?;
+
+        let flavor = match Dialect::from_config(config)?.library {
+            ValidationLibrary::Arktype => &ts_declarations::TYPESCRIPT_ARKTYPE,
+            _ => &ts_declarations::TYPESCRIPT,
+        };
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content,
+            metadata: HashMap::new(),
+            companions: ts_declarations::companion(schema_ir, config, flavor)
+                .into_iter()
+                .chain(readme::companion(
+                    schema_ir,
+                    config,
+                    readme::Client::TypeScript,
+                ))
+                .collect(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn template_context(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<Option<serde_json::Value>> {
+        Ok(Some(self.context(schema_ir, config)?.into_json()))
+    }
+
+    fn supports_inheritance(&self, _config: &GenerationConfig) -> bool {
+        true
+    }
+
+    fn supports_unions(&self) -> bool {
+        true
+    }
+
+    fn any_fields(&self, schema_ir: &SchemaIR) -> Vec<String> {
+        let mut fields = any_types::any_fields(schema_ir, self.supports_unions());
+        // Array parameters are `runtime_any` in the operation schemas
+        fields.extend(any_types::any_parameters(schema_ir, |param| {
+            param.schema_type == "array"
+        }));
+        fields
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        ImportStyle::from_config(config)?;
+        DocStyle::from_config(config)?;
+        OptionalStrategy::from_config(config)?;
+        RateLimitOption::from_config(config)?;
+        CacheDefaults::from_config(config)?;
+        RequestBuilders::from_config(config)?;
+        ResponseValidation::from_config(config)?;
+        binary_responses(config)?;
+        let overrides = TypeOverrides::from_config(config)?;
+        let dialect = Dialect::from_config(config)?;
+        if dialect.library == ValidationLibrary::Arktype && !overrides.is_empty() {
+            anyhow::bail!(
+                "typescript: option 'typeOverrides' needs validationLibrary zod or valibot"
+            );
+        }
+        // Arktype's string definitions cannot hold an object
+        if dialect.library == ValidationLibrary::Arktype
+            && config
+                .options
+                .get("inlineSingleUse")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        {
+            anyhow::bail!(
+                "typescript: option 'inlineSingleUse' needs validationLibrary zod or valibot"
+            );
+        }
+        Ok(())
+    }
+}
+
+impl TypeScriptGenerator {
+    /// The context `client.ts.tera` is rendered with
+    fn context(&self, schema_ir: &SchemaIR, config: &GenerationConfig) -> Result<Context> {
         let mut context = Context::new();
+        context.insert("context_version", &templates::CONTEXT_VERSION);
 
         // Add metadata
         context.insert("api_title", &schema_ir.metadata.title);
@@ -43,28 +146,164 @@ impl Generator for TypeScriptGenerator {
                 .unwrap_or_else(|| "http://localhost".to_string()),
         );
 
+        let doc_style = DocStyle::from_config(config)?;
+
+        // XML bodies are typed only when a (de)serializer module is provided
+        let xml_module = config.options.get("xmlModule").and_then(|v| v.as_str());
+        context.insert("xml_module", &xml_module);
+
+        let import_style = ImportStyle::from_config(config)?;
+        let dialect = Dialect::from_config(config)?;
+        let optional_strategy = OptionalStrategy::from_config(config)?;
+        let mut imports = dialect.imports(&import_style);
+        if let Some(xml_module) = xml_module {
+            imports.push_str(&import_style.values(&["toXml", "fromXml"], xml_module));
+        }
+        // External types standing in for schemas and formats
+        for (module, types) in TypeOverrides::from_config(config)?.imports(schema_ir) {
+            imports.push_str(&import_style.types(&type_overrides::imported_names(&types), &module));
+        }
+        // OpenTelemetry spans around every client call are opt-in
+        let open_telemetry = config
+            .options
+            .get("openTelemetry")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        context.insert("telemetry", &open_telemetry);
+        context.insert("tracer_name", &naming::quote(&schema_ir.metadata.title));
+
+        // Client-side throttling is opt-in; the spec's `x-rate-limit` supplies the defaults
+        let rate_limit = RateLimitOption::from_config(config)?;
+        context.insert("rate_limit", &rate_limit.enabled());
+        context.insert("client_rate_limit", &rate_limit.client_limit(schema_ir));
+        // Cached GET responses are opt-in
+        context.insert(
+            "cache",
+            &CacheDefaults::from_config(config)?.map(CacheDefaults::context),
+        );
+        // Idempotency keys on unsafe calls are opt-in
+        let idempotency = IdempotencyKey::from_config(config)?;
+        context.insert("idempotency_key", &idempotency.enabled());
+        context.insert("idempotency_header", &idempotency::header_name(config)?);
+        // Link helpers for the HAL / JSON:API conventions the spec follows
+        context.insert(
+            "hypermedia",
+            &Hypermedia::from_config(config)?.formats(schema_ir),
+        );
+        // Names of the schemas added for JSON Patch bodies and hypermedia links
+        context.insert(
+            "support_types",
+            &naming::support_type_names(Language::TypeScript),
+        );
+        // Builders for JSON Patch bodies
+        context.insert("json_patch", &patch_bodies::json_patch(schema_ir));
+        // `Response` method file downloads read their body with, when there are any
+        let downloads = schema_ir.operations.iter().any(|op| op.downloads());
+        context.insert(
+            "downloads",
+            &downloads.then(|| binary_responses(config)).transpose()?,
+        );
+        // Flag checks before operations declaring `x-feature-flag`
+        context.insert(
+            "feature_flags",
+            &schema_ir
+                .operations
+                .iter()
+                .any(|op| op.feature_flag.is_some()),
+        );
+        if open_telemetry {
+            imports
+                .push_str(&import_style.values(&["SpanStatusCode", "trace"], "@opentelemetry/api"));
+        }
+        context.insert("imports", &imports);
+
+        // Validator snippets the template assembles itself
+        let (object_open, object_close) = dialect.object.split_once("{}").unwrap_or(("", ""));
+        context.insert("object_open", object_open);
+        context.insert("object_close", object_close);
+        context.insert("v", &dialect);
+        let response_validation = ResponseValidation::from_config(config)?;
+        context.insert("validate_responses", &response_validation);
+
+        // Arktype schemas live in one scope, so their properties sit one level deeper
+        let property_indent = if dialect.library == ValidationLibrary::Arktype {
+            "    "
+        } else {
+            "  "
+        };
+
+        // Schemas used by one field only are declared at that field (`inlineSingleUse`)
+        let single_use = inline_schemas::single_use(schema_ir, config);
+        let property = |field: &FieldDefinition, inlined: &Inlined, indent: &str| {
+            let (key, value) = dialect.property(
+                &field.name,
+                &field.type_info,
+                optional_strategy.presence(field.required, field.nullable),
+                inlined,
+            );
+            let description = field.description.as_deref().or_else(|| {
+                inline_schemas::direct(&field.type_info, &single_use)
+                    .and_then(|schema| schema.description.as_deref())
+            });
+            (
+                key,
+                inline_schemas::indent(&value, indent),
+                doc_style.jsdoc(&[description], &[], indent),
+            )
+        };
+        let mut inlined = Inlined::new();
+        for schema in &single_use {
+            let mut expression = format!("{}{{\n", object_open);
+            for field in &schema.fields {
+                let (key, value, doc) = property(field, &inlined, "  ");
+                if let Some(doc) = doc {
+                    expression.push_str(&format!("  {}\n", doc));
+                }
+                expression.push_str(&format!("  {}: {},\n", key, value));
+            }
+            expression.push_str(&format!("}}{}", object_close));
+            inlined.insert(schema.name.clone(), expression);
+        }
+
         // Convert schemas for template
         let schemas_for_template: Vec<_> = schema_ir
             .schemas
             .iter()
+            .filter(|schema| !inlined.contains_key(&schema.name))
             .map(|schema| {
                 let properties: Vec<_> = schema
                     .fields
                     .iter()
                     .map(|field| {
+                        let (key, value, doc) = property(field, &inlined, property_indent);
                         serde_json::json!({
                             "name": field.name,
-                            "typescript_type": field.type_info.to_typescript_zod(),
+                            "key": key,
+                            "value": value,
                             "required": field.required,
-                            "nullable": false,
+                            "nullable": field.nullable,
+                            "doc": doc,
                         })
                     })
                     .collect();
 
+                let parents: Vec<String> = schema
+                    .parents
+                    .iter()
+                    .map(|parent| naming::type_name(parent, Language::TypeScript))
+                    .collect();
+                let (object_open, object_close, spreads) = dialect.extension(&parents);
+
                 serde_json::json!({
-                    "name": schema.name,
+                    "name": naming::type_name(&schema.name, Language::TypeScript),
+                    // Enum schemas are their literal union rather than an object
+                    "definition": validators::enum_schema(schema).map(|t| dialect.definition(&t, &inlined)),
+                    "object_open": object_open,
+                    "object_close": object_close,
+                    "spreads": spreads,
                     "properties": properties,
                     "description": schema.description,
+                    "doc": doc_style.jsdoc(&[schema.description.as_deref()], &[], ""),
                 })
             })
             .collect();
@@ -72,39 +311,471 @@ impl Generator for TypeScriptGenerator {
         context.insert("schemas", &schemas_for_template);
 
         // Convert operations for template
+        // The plain method of each operation, and a wrapper for its ETag precondition
+        let conditionals = Conditionals::new(schema_ir, config);
+        context.insert("conditional_requests", &conditionals.any(schema_ir));
+        // Fluent setters for the optional parameters are opt-in
+        let builders = RequestBuilders::from_config(config)?;
+        context.insert(
+            "request_builders",
+            &schema_ir.operations.iter().any(|op| builders.applies(op)),
+        );
+        let schema_names: BTreeSet<_> = schema_ir
+            .schemas
+            .iter()
+            .map(|schema| naming::type_name(&schema.name, Language::TypeScript))
+            .collect();
         let operations_for_template: Vec<_> = schema_ir
             .operations
             .iter()
             .map(|op| {
+                let param_docs: Vec<_> = op
+                    .parameters
+                    .iter()
+                    .filter_map(|p| {
+                        let description = p.description.as_deref()?;
+                        Some((format!("params.{}", p.name), description))
+                    })
+                    .collect();
+                let summary = op.summary.as_deref().unwrap_or(&op.id);
+
+                let request_body = op.request_content_type.as_ref().map(|content_type| {
+                    let kind = BodyKind::from_content_type(content_type);
+                    let typed = kind == BodyKind::Json || (kind == BodyKind::Xml && xml_module.is_some());
+                    let schema = op.request_body.as_ref().filter(|_| typed);
+                    let schema_ref = schema.map(|b| naming::type_name(&b.name, Language::TypeScript));
+                    let is_array = schema.is_some_and(|b| b.schema_type == "array");
+                    let body_type = match (&schema_ref, kind) {
+                        (Some(name), _) if is_array => format!("{}[]", name),
+                        (Some(name), _) => name.clone(),
+                        (None, BodyKind::Json) => "any".to_string(),
+                        (None, _) => "string".to_string(),
+                    };
+                    let validator = match (&schema_ref, kind) {
+                        (Some(name), _) if is_array => {
+                            validators::fill(dialect.runtime_array, &format!("{}Schema", name))
+                        }
+                        (Some(name), _) => format!("{}Schema", name),
+                        (None, BodyKind::Json) => dialect.runtime_any.to_string(),
+                        (None, _) => dialect.runtime_string.to_string(),
+                    };
+                    serde_json::json!({
+                        "content_type": content_type,
+                        "kind": kind,
+                        "schema_ref": schema_ref,
+                        "is_array": is_array,
+                        "body_type": body_type,
+                        "validator": validator,
+                    })
+                });
+
+                // The method returns the first success response with a body
+                let returned = op.responses.iter().position(|r| r.is_success() && r.content_type.is_some());
+                let responses: Vec<_> = op.responses.iter().enumerate().map(|(index, response)| {
+                    let kind = response.content_type.as_deref().map(BodyKind::from_content_type);
+                    let typed = kind == Some(BodyKind::Json) || (kind == Some(BodyKind::Xml) && xml_module.is_some());
+                    let schema_ref = response
+                        .schema
+                        .as_ref()
+                        .filter(|_| typed)
+                        .map(|b| naming::type_name(&b.name, Language::TypeScript));
+                    serde_json::json!({
+                        "status_code": response.status,
+                        "content_type": response.content_type,
+                        "schema_ref": schema_ref,
+                        "body_type": body_type(response, xml_module.is_some()),
+                        "is_array": response.is_array(),
+                        "returned": Some(index) == returned,
+                        "description": response.description,
+                    })
+                }).collect();
+
+                // Error responses with a body, as the `ApiError`s a call rejects with
+                let error = (!op.error_responses().is_empty()).then(|| {
+                    let mut name = naming::type_name(&format!("{}Error", naming::pascal_case(&op.id)), Language::TypeScript);
+                    if schema_names.contains(&name) {
+                        name.push_str("Response");
+                    }
+                    let members: Vec<_> = op
+                        .error_responses()
+                        .into_iter()
+                        .map(|response| {
+                            let status = match response.status.parse::<u16>() {
+                                Ok(code) => code.to_string(),
+                                Err(_) => "number".to_string(),
+                            };
+                            let body = body_type(response, xml_module.is_some()).unwrap_or_else(|| "unknown".to_string());
+                            format!("ApiError<{}, {}>", status, body)
+                        })
+                        .collect();
+                    let statuses: Vec<_> = op.error_responses().iter().map(|r| r.status.as_str()).collect();
+                    serde_json::json!({
+                        "name": name,
+                        "statuses": statuses.join(", "),
+                        "type": members.join(" | "),
+                    })
+                });
+
+                let id = naming::function_name(&op.id, Language::TypeScript);
+                let builder = builders.applies(op).then(|| {
+                    let setters: Vec<_> = request_builders::optional_parameters(op)
+                        .map(|p| {
+                            serde_json::json!({
+                                "name": request_builders::setter_name(p, Language::TypeScript),
+                                "key": naming::ts_property_key(&p.name),
+                                "type": request_encoding::Plan::of(p).value_type(Language::TypeScript),
+                            })
+                        })
+                        .collect();
+                    serde_json::json!({
+                        "call": format!("send{}", naming::pascal_case(&id)),
+                        "takes_params": op.request_content_type.is_some()
+                            || request_builders::optional_parameters(op).count() < op.parameters.len(),
+                        "setters": setters,
+                    })
+                });
+
                 serde_json::json!({
-                    "id": op.id,
+                    "id": id,
+                    "operation_id": op.id,
+                    "rate_limit": op.rate_limit,
+                    "cached": response_cache::applies(op),
+                    "idempotency_key": idempotency.applies(op),
+                    "timeout_ms": op.timeout_ms,
+                    "feature_flag": op.feature_flag.as_deref().map(naming::quote),
+                    "variants": conditionals.variants(op),
+                    "builder": builder,
+                    "parameter_groups": parameter_groups(&dialect, op),
+                    "request_body": request_body,
+                    "download": op.downloads(),
+                    "response_validator": response_validator(&dialect, response_validation, op),
+                    "doc": doc_style.jsdoc_see(
+                        &[Some(summary), op.description.as_deref()],
+                        &param_docs,
+                        &SeeAlso::of(schema_ir, op),
+                        "  ",
+                    ),
                     "method": format!("{:?}", op.method).to_uppercase(),
                     "path": op.path,
                     "parameters": op.parameters.iter().map(|p| {
+                        let encoding = request_encoding::Plan::of(p);
                         serde_json::json!({
                             "name": p.name,
+                            "key": naming::ts_property_key(&p.name),
+                            "access": naming::ts_property_access(&p.name),
                             "location": format!("{:?}", p.location).to_lowercase(),
                             "required": p.required,
                             "schema_type": p.schema_type,
+                            "type": encoding.value_type(Language::TypeScript),
+                            "encoding": encoding,
                         })
                     }).collect::<Vec<_>>(),
-                    "responses": serde_json::json!([]),  // TODO: populate from op.response
+                    "responses": responses,
+                    "error": error,
                 })
             })
             .collect();
 
         context.insert("operations", &operations_for_template);
 
+        // Gateway services, each an object of its operations' methods
+        let namespaces: Vec<_> = gateway::namespaces(schema_ir)
+            .into_iter()
+            .map(|(name, operations)| {
+                let members: Vec<_> = operations
+                    .into_iter()
+                    .flat_map(|(member, op)| {
+                        let member = naming::function_name(member, Language::TypeScript);
+                        let target = naming::function_name(&op.id, Language::TypeScript);
+                        conditionals
+                            .variants(op)
+                            .into_iter()
+                            .map(move |precondition| {
+                                let suffix = if precondition.is_some() {
+                                    "Conditional"
+                                } else {
+                                    ""
+                                };
+                                serde_json::json!({
+                                    "name": format!("{}{}", member, suffix),
+                                    "target": format!("{}{}", target, suffix),
+                                })
+                            })
+                    })
+                    .collect();
+                serde_json::json!({ "name": name, "members": members })
+            })
+            .collect();
+        context.insert("namespaces", &namespaces);
+
+        // WebSocket channels are opt-in
+        let include_websockets = config
+            .options
+            .get("websockets")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let channels: Vec<_> = schema_ir
+            .channels
+            .iter()
+            .filter(|_| include_websockets)
+            .map(|channel| {
+                serde_json::json!({
+                    "name": naming::function_name(&channel.name, Language::TypeScript),
+                    "path": channel.path,
+                    "description": channel.description,
+                    "doc": doc_style.jsdoc(&[Some(channel.description.as_deref().unwrap_or(&channel.name))], &[], "  "),
+                    "send": channel.send.as_ref().map(|s| naming::type_name(s, Language::TypeScript)),
+                    "receive": channel.receive.as_ref().map(|s| naming::type_name(s, Language::TypeScript)),
+                })
+            })
+            .collect();
+        context.insert("channels", &channels);
+
         // Add generator options
         context.insert("options", &config.options);
 
-        // Render template
-        let content = tera.render("client.ts.tera", &context)?;
+        Ok(context)
+    }
+}
 
-        Ok(GeneratedOutput {
-            filename: config.output_file.clone(),
-            content,
-            metadata: HashMap::new(),
-        })
+/// What file downloads carry their body as (`binaryResponses` option): the
+/// `Response` method reading it, `blob` by default or `arrayBuffer`
+fn binary_responses(config: &GenerationConfig) -> Result<&'static str> {
+    match config.options.get("binaryResponses") {
+        None => Ok("blob"),
+        Some(value) => match value.as_str() {
+            Some("blob") => Ok("blob"),
+            Some("arrayBuffer") => Ok("arrayBuffer"),
+            _ => anyhow::bail!(
+                "{}: option 'binaryResponses' must be \"blob\" or \"arrayBuffer\"",
+                config.generator
+            ),
+        },
+    }
+}
+
+/// Schema client methods parse JSON success responses through, when
+/// `validateResponses` is on and the response references one
+fn response_validator(
+    dialect: &Dialect,
+    validation: ResponseValidation,
+    op: &OperationDefinition,
+) -> Option<String> {
+    let json = op
+        .response_content_type
+        .as_deref()
+        .is_none_or(|content_type| BodyKind::from_content_type(content_type) == BodyKind::Json);
+    let response = op
+        .response
+        .as_ref()
+        .filter(|_| json && validation != ResponseValidation::Off)?;
+    let schema = format!(
+        "{}Schema",
+        naming::type_name(&response.name, Language::TypeScript)
+    );
+    Some(if response.schema_type == "array" {
+        validators::fill(dialect.runtime_array, &schema)
+    } else {
+        schema
+    })
+}
+
+/// Parameter schemas of an operation's config object, as `(name, schema)`
+/// in query, path, headers, cookies order
+/// TypeScript type of the body of `response`; `None` when it isn't known
+fn body_type(response: &ResponseDefinition, xml: bool) -> Option<String> {
+    let kind = BodyKind::from_content_type(response.content_type.as_deref()?);
+    let typed = kind == BodyKind::Json || (kind == BodyKind::Xml && xml);
+    match (&response.schema, &response.inline_type) {
+        (Some(schema), _) if typed => {
+            let name = naming::type_name(&schema.name, Language::TypeScript);
+            Some(if response.is_array() {
+                format!("{}[]", name)
+            } else {
+                name
+            })
+        }
+        (None, Some(type_info)) if typed => Some(inline_type(type_info)),
+        // Text, and XML without `xmlModule`, come back as the text sent
+        _ if matches!(kind, BodyKind::Text | BodyKind::Xml) && !typed => Some("string".to_string()),
+        _ => None,
+    }
+}
+
+/// TypeScript type of a body schema the spec declares inline
+fn inline_type(type_info: &TypeInfo) -> String {
+    if type_info.is_array {
+        let item = type_info
+            .array_item_type
+            .as_deref()
+            .map_or_else(|| "any".to_string(), inline_type);
+        return match item.contains(' ') {
+            true => format!("({})[]", item),
+            false => format!("{}[]", item),
+        };
+    }
+    if let Some(name) = &type_info.reference {
+        return naming::type_name(name, Language::TypeScript);
+    }
+    if let Some(values) = &type_info.enum_values {
+        return values
+            .iter()
+            .map(|value| naming::quote(value))
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    match type_info.openapi_type.as_str() {
+        "string" => "string".to_string(),
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "object" => match &type_info.map_values {
+            Some(values) => format!("Record<string, {}>", inline_type(values)),
+            None => "Record<string, any>".to_string(),
+        },
+        "union" if !type_info.union_members.is_empty() => type_info
+            .union_members
+            .iter()
+            .map(inline_type)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        _ => "any".to_string(),
+    }
+}
+
+fn parameter_groups(dialect: &Dialect, op: &OperationDefinition) -> Vec<serde_json::Value> {
+    let groups = [
+        ("query", ParameterLocation::Query),
+        ("path", ParameterLocation::Path),
+        ("headers", ParameterLocation::Header),
+        ("cookies", ParameterLocation::Cookie),
+    ];
+
+    let mut result = Vec::new();
+    for (name, location) in groups {
+        let params: Vec<_> = op
+            .parameters
+            .iter()
+            .filter(|p| p.location == location)
+            .collect();
+        let Some(first) = params.first() else {
+            continue;
+        };
+
+        let mut properties = String::from("{");
+        for param in &params {
+            let schema = match (location, param.schema_type.as_str()) {
+                (ParameterLocation::Query | ParameterLocation::Path, "integer" | "number") => {
+                    dialect.coerce_number
+                }
+                (ParameterLocation::Query, "boolean") => dialect.coerce_boolean,
+                // One string or several, depending on how the items were sent
+                (_, "array") => dialect.runtime_any,
+                _ => dialect.runtime_string,
+            };
+            // Path parameters are always required
+            let required = param.required || location == ParameterLocation::Path;
+            let (key, value) = dialect.runtime_property(&param.name, schema, required);
+            properties.push_str(&format!("\n        {}: {},", key, value));
+        }
+        properties.push_str("\n      }");
+
+        let mut schema = validators::fill(dialect.object, &properties);
+        // The whole query object may be omitted when its first parameter is optional
+        if location == ParameterLocation::Query && !first.required {
+            schema = validators::fill(dialect.optional_object, &schema);
+        }
+        result.push(serde_json::json!({ "name": name, "schema": schema }));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{InputParser, OpenApiParser};
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /users:
+    get:
+      operationId: getUser
+      responses:
+        "200": { description: ok, content: { application/json: { schema: { $ref: "#/components/schemas/User" } } } }
+components:
+  schemas:
+    Status: { type: string, enum: [active, "it's", disabled] }
+    Level: { type: integer, enum: [1, 2] }
+    User:
+      type: object
+      required: [status]
+      properties:
+        status: { $ref: "#/components/schemas/Status" }
+        level: { $ref: "#/components/schemas/Level" }
+        nickname: { type: string, nullable: true }
+"##;
+
+    fn generate(library: &str) -> GeneratedOutput {
+        let ir = OpenApiParser.parse_str(SPEC, &HashMap::new()).unwrap();
+        let config: GenerationConfig = serde_yaml::from_str(&format!(
+            "{{ generator: typescript, outputFile: client.ts, \
+             options: {{ validationLibrary: {}, declarationFile: client.d.ts }} }}",
+            library
+        ))
+        .unwrap();
+        TypeScriptGenerator.generate_from_ir(&ir, &config).unwrap()
+    }
+
+    #[test]
+    fn string_enum_schemas_are_enums_in_each_library() {
+        let zod = generate("zod").content;
+        assert!(
+            zod.contains(r#"export const StatusSchema = z.enum(["active", "it's", "disabled"]);"#)
+        );
+        assert!(zod.contains("export const LevelSchema = z.number();"));
+        assert!(zod.contains("status: z.lazy(() => StatusSchema),"));
+
+        let valibot = generate("valibot").content;
+        assert!(valibot.contains(
+            r#"export const StatusSchema = v.picklist(["active", "it's", "disabled"]);"#
+        ));
+
+        let arktype = generate("arktype").content;
+        assert!(arktype.contains(r#"Status: "'active' | 'it\\'s' | 'disabled'","#));
+        assert!(arktype.contains(r#"status: "Status","#));
+    }
+
+    #[test]
+    fn optional_nullable_fields_are_nullish_in_valibot() {
+        assert!(generate("valibot")
+            .content
+            .contains("nickname: v.nullish(v.string()),"));
+        assert!(generate("zod")
+            .content
+            .contains("nickname: z.string().optional().nullable(),"));
+    }
+
+    #[test]
+    fn string_enum_schemas_are_literal_unions_in_the_declaration_file() {
+        let output = generate("zod");
+        let declarations = &output.companions[0];
+        assert_eq!(declarations.filename, "client.d.ts");
+        assert!(declarations
+            .content
+            .contains(r#"export type Status = "active" | "it's" | "disabled";"#));
+        assert!(declarations.content.contains("export type Level = number;"));
+        assert!(declarations.content.contains("  status: Status;"));
+    }
+
+    #[test]
+    fn object_schemas_are_not_enums() {
+        let ir = OpenApiParser.parse_str(SPEC, &HashMap::new()).unwrap();
+        let user = ir.schemas.iter().find(|s| s.name == "User").unwrap();
+        assert!(validators::enum_schema(user).is_none());
+        let level = ir.schemas.iter().find(|s| s.name == "Level").unwrap();
+        let level = validators::enum_schema(level).unwrap();
+        assert_eq!(level.openapi_type, "integer");
+        assert!(level.enum_values.is_none());
     }
 }
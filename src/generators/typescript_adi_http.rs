@@ -1,8 +1,21 @@
-use super::{GeneratedOutput, Generator};
+use super::docs::DocStyle;
+use super::inline_schemas::{self, Inlined};
+use super::naming::{self, Language};
+use super::path_params::PathParamStyle;
+use super::ts_barrel::Barrel;
+use super::ts_declarations;
+use super::ts_imports::ImportStyle;
+use super::ts_optional::{OptionalStrategy, Presence};
+use super::validators;
+use super::zod::ZodVersion;
+use super::{GeneratedFile, GeneratedOutput, Generator};
 use crate::config::GenerationConfig;
-use crate::parsers::{ParameterLocation, SchemaIR, TypeInfo};
+use crate::parsers::{
+    BodyKind, FieldDefinition, OperationDefinition, ParameterLocation, SchemaIR, SchemaReference,
+    TypeInfo,
+};
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 pub struct TypeScriptAdiHttpGenerator;
 
@@ -20,6 +33,16 @@ impl Generator for TypeScriptAdiHttpGenerator {
         schema_ir: &SchemaIR,
         config: &GenerationConfig,
     ) -> Result<GeneratedOutput> {
+        let import_style = ImportStyle::from_config(config)?;
+        let zod = ZodVersion::from_config(config)?;
+        let sections = Sections::from_config(config);
+        let companions = ts_declarations::companion(schema_ir, config, &ts_declarations::ADI_HTTP);
+        if split_by_tag(config) {
+            let mut output = split(schema_ir, config, &import_style, zod, &sections)?;
+            output.companions.extend(companions);
+            return Ok(output);
+        }
+
         let mut output = String::new();
 
         // Header
@@ -29,253 +52,803 @@ impl Generator for TypeScriptAdiHttpGenerator {
         ));
         output.push_str(&format!("// Version: {}\n\n", schema_ir.metadata.version));
 
-        output.push_str("import { z } from 'zod';\n");
-        output.push_str(
-            "import { createRoute, createRouter, createClient } from '@adi-family/http';\n\n",
-        );
+        output.push_str(&import_style.values(&["z"], "zod"));
+        output.push_str(&import_style.values(
+            &["createRoute", "createRouter", "createClient"],
+            "@adi-family/http",
+        ));
+        output.push('\n');
 
-        // Generate schemas
-        output.push_str(
-            "// ============================================================================\n",
-        );
-        output.push_str("// Schema Definitions\n");
-        output.push_str(
-            "// ============================================================================\n\n",
+        output.push_str(&schema_definitions(schema_ir, config, zod)?);
+
+        let operations: Vec<&OperationDefinition> = schema_ir.operations.iter().collect();
+        output.push_str(&route_definitions(&operations, "routes", config));
+
+        if sections.server {
+            let router_name = config
+                .options
+                .get("routerName")
+                .and_then(|v| v.as_str())
+                .unwrap_or("apiRouter");
+            output.push_str(&router(&operations, "routes", router_name));
+        }
+
+        if sections.client {
+            let client_name = config
+                .options
+                .get("clientName")
+                .and_then(|v| v.as_str())
+                .unwrap_or("apiClient");
+            output.push_str(&client(
+                schema_ir,
+                &operations,
+                "routes",
+                client_name,
+                config,
+                true,
+            )?);
+        }
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content: output,
+            metadata: HashMap::new(),
+            companions: companions.into_iter().collect(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn supports_inheritance(&self, _config: &GenerationConfig) -> bool {
+        true
+    }
+
+    fn supports_unions(&self) -> bool {
+        true
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        BaseUrlStrategy::from_config(config)?;
+        DocStyle::from_config(config)?;
+        PathParamStyle::from_config(config)?;
+        ImportStyle::from_config(config)?;
+        OptionalStrategy::from_config(config)?;
+        Barrel::from_config(config)?;
+        if Barrel::configured(config) && !split_by_tag(config) {
+            anyhow::bail!("typescript_adi_http: barrel options need splitByTag, the only layout with a barrel file");
+        }
+        ZodVersion::from_config(config).map(|_| ())
+    }
+}
+
+/// Whether routes, routers, and clients get a module per tag (`splitByTag` option)
+fn split_by_tag(config: &GenerationConfig) -> bool {
+    config
+        .options
+        .get("splitByTag")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Operations by their first tag, in spec order; untagged ones are `default`
+fn tag_groups(schema_ir: &SchemaIR) -> Vec<(&str, Vec<&OperationDefinition>)> {
+    let mut groups: Vec<(&str, Vec<&OperationDefinition>)> = Vec::new();
+    for operation in &schema_ir.operations {
+        let tag = operation
+            .tags
+            .first()
+            .map(String::as_str)
+            .unwrap_or("default");
+        match groups.iter_mut().find(|(name, _)| *name == tag) {
+            Some((_, operations)) => operations.push(operation),
+            None => groups.push((tag, vec![operation])),
+        }
+    }
+    groups
+}
+
+/// `splitByTag` layout next to the output file: component schemas in
+/// `schemas.ts`, `environments` in `environments.ts`, and each tag's routes,
+/// router, and client in a module of its own (`pets.ts` with `petsRoutes`,
+/// `petsRouter`, `petsClient`); the output file re-exports them as the
+/// barrel options say
+fn split(
+    schema_ir: &SchemaIR,
+    config: &GenerationConfig,
+    import_style: &ImportStyle,
+    zod: ZodVersion,
+    sections: &Sections,
+) -> Result<GeneratedOutput> {
+    let (dir, main_file) = match config.output_file.rsplit_once('/') {
+        Some((dir, file)) => (format!("{}/", dir), file),
+        None => (String::new(), config.output_file.as_str()),
+    };
+    let header = |title: String| {
+        format!(
+            "// Generated ADI HTTP {}\n// Version: {}\n\n",
+            title, schema_ir.metadata.version
+        )
+    };
+    let environments = sections.client
+        && matches!(
+            BaseUrlStrategy::from_config(config)?,
+            BaseUrlStrategy::Environments
         );
 
-        for schema in &schema_ir.schemas {
-            if let Some(desc) = &schema.description {
-                output.push_str(&format!("// {}\n", desc));
-            }
+    let mut companions = Vec::new();
+    let mut barrel = header(format!("Routes for {}", schema_ir.metadata.title));
+    let mut taken = vec![
+        main_file.trim_end_matches(".ts").to_string(),
+        "schemas".to_string(),
+    ];
+
+    let mut schemas = header(format!("Schemas for {}", schema_ir.metadata.title));
+    schemas.push_str(&import_style.values(&["z"], "zod"));
+    schemas.push('\n');
+    schemas.push_str(&schema_definitions(schema_ir, config, zod)?);
+    companions.push(GeneratedFile {
+        filename: format!("{}schemas.ts", dir),
+        content: schemas,
+    });
+
+    if environments {
+        taken.push("environments".to_string());
+        let mut module = header(format!("Environments for {}", schema_ir.metadata.title));
+        module.push_str(&environment_definitions(&server_environments(
+            schema_ir,
+            default_base_url(schema_ir),
+        )));
+        companions.push(GeneratedFile {
+            filename: format!("{}environments.ts", dir),
+            content: module,
+        });
+    }
 
-            output.push_str(&format!(
-                "export const {}Schema = z.object({{\n",
-                schema.name
+    for (tag, operations) in tag_groups(schema_ir) {
+        // Always followed by `Routes`, so keywords need no escaping
+        let mut base = naming::camel_case(tag);
+        if !base.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            base.insert_str(0, "tag");
+        }
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while taken.contains(&name) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        taken.push(name.clone());
+
+        let routes_name = format!("{}Routes", name);
+        let mut body = route_definitions(&operations, &routes_name, config);
+        if sections.server {
+            body.push_str(&router(
+                &operations,
+                &routes_name,
+                &format!("{}Router", name),
             ));
+        }
+        if sections.client {
+            let client_name = format!("{}Client", name);
+            body.push_str(&client(
+                schema_ir,
+                &operations,
+                &routes_name,
+                &client_name,
+                config,
+                false,
+            )?);
+        }
 
-            for field in &schema.fields {
-                let zod_type = type_info_to_zod(&field.type_info);
-                let optional_suffix = if field.required { "" } else { ".optional()" };
+        let mut module = header(format!("Routes for {}: {}", schema_ir.metadata.title, tag));
+        if body.contains("z.") {
+            module.push_str(&import_style.values(&["z"], "zod"));
+        }
+        let mut http = vec!["createRoute"];
+        if sections.server {
+            http.push("createRouter");
+        }
+        if sections.client {
+            http.push("createClient");
+        }
+        module.push_str(&import_style.values(&http, "@adi-family/http"));
+        let schema_names = body_schemas(&operations);
+        if !schema_names.is_empty() {
+            let names: Vec<&str> = schema_names.iter().map(String::as_str).collect();
+            module.push_str(&import_style.values(&names, "./schemas"));
+        }
+        if environments {
+            module.push_str(&import_style.values(&["environments"], "./environments"));
+            module.push_str(&import_style.types(&["Environment"], "./environments"));
+        }
+        module.push('\n');
+        module.push_str(&body);
 
-                if let Some(desc) = &field.description {
-                    output.push_str(&format!("  /** {} */\n", desc));
-                }
+        companions.push(GeneratedFile {
+            filename: format!("{}{}.ts", dir, name),
+            content: module,
+        });
+    }
 
-                output.push_str(&format!(
-                    "  {}: {}{},\n",
-                    field.name, zod_type, optional_suffix
+    // Validated with the generator's other options
+    let barrel_options = Barrel::from_config(config).unwrap_or_default();
+    let contents: Vec<&str> = companions
+        .iter()
+        .map(|module| module.content.as_str())
+        .collect();
+    let unmatched = barrel_options.unmatched(&contents);
+    if !unmatched.is_empty() {
+        anyhow::bail!(
+            "typescript_adi_http: option 'barrelAllowlist' has entries matching no export: {}",
+            unmatched.join(", ")
+        );
+    }
+    // Without a barrel, `schemas.ts` leads the output
+    if !barrel_options.enabled() {
+        let schemas = companions.remove(0);
+        return Ok(GeneratedOutput {
+            filename: schemas.filename,
+            content: schemas.content,
+            metadata: HashMap::new(),
+            companions,
+            warnings: Vec::new(),
+        });
+    }
+    for module in &companions {
+        let stem = module.filename[dir.len()..].trim_end_matches(".ts");
+        barrel.push_str(&barrel_options.reexport(
+            import_style,
+            &format!("./{}", stem),
+            &module.content,
+        ));
+    }
+
+    Ok(GeneratedOutput {
+        filename: config.output_file.clone(),
+        content: barrel,
+        metadata: HashMap::new(),
+        companions,
+        warnings: Vec::new(),
+    })
+}
+
+/// Zod schemas the routes of `operations` validate bodies with, sorted
+fn body_schemas(operations: &[&OperationDefinition]) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    for operation in operations {
+        let bodies = [
+            (&operation.request_body, &operation.request_content_type),
+            (&operation.response, &operation.response_content_type),
+        ];
+        for (body, content_type) in bodies {
+            let (Some(body), Some(content_type)) = (body, content_type) else {
+                continue;
+            };
+            if BodyKind::from_content_type(content_type) == BodyKind::Json {
+                names.insert(format!(
+                    "{}Schema",
+                    naming::type_name(&body.name, Language::TypeScript)
                 ));
             }
+        }
+    }
+    names
+}
 
-            output.push_str("});\n\n");
-            output.push_str(&format!(
-                "export type {} = z.infer<typeof {}Schema>;\n\n",
-                schema.name, schema.name
-            ));
+/// Which parts besides routes are generated (`includeServer`, `includeClient`)
+struct Sections {
+    server: bool,
+    client: bool,
+}
+
+impl Sections {
+    fn from_config(config: &GenerationConfig) -> Self {
+        let enabled = |name: &str| {
+            config
+                .options
+                .get(name)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true)
+        };
+        Self {
+            server: enabled("includeServer"),
+            client: enabled("includeClient"),
         }
+    }
+}
 
-        // Generate routes
-        output.push_str(
-            "// ============================================================================\n",
-        );
-        output.push_str("// Route Definitions\n");
-        output.push_str(
-            "// ============================================================================\n\n",
-        );
+fn section_header(title: &str) -> String {
+    format!(
+        "// ============================================================================\n// {}\n// ============================================================================\n\n",
+        title
+    )
+}
 
-        output.push_str("export const routes = {\n");
+fn schema_definitions(
+    schema_ir: &SchemaIR,
+    config: &GenerationConfig,
+    zod: ZodVersion,
+) -> Result<String> {
+    let mut output = section_header("Schema Definitions");
+
+    let doc_style = DocStyle::from_config(config)?;
+    let optional_strategy = OptionalStrategy::from_config(config)?;
+
+    // Schemas used by one field only are declared at that field (`inlineSingleUse`)
+    let single_use = inline_schemas::single_use(schema_ir, config);
+    let property = |field: &FieldDefinition, inlined: &Inlined| {
+        let mut line = String::new();
+        let zod_type = type_info_to_zod(&field.type_info, zod, inlined);
+        let optional_suffix = match optional_strategy.presence(field.required, field.nullable) {
+            Presence::Optional => ".optional()",
+            Presence::Nullable => ".nullable()",
+            Presence::OptionalNullable => ".optional().nullable()",
+            Presence::Nullish => ".nullish()",
+            Presence::Required => "",
+        };
+
+        let description = field.description.as_deref().or_else(|| {
+            inline_schemas::direct(&field.type_info, &single_use)
+                .and_then(|schema| schema.description.as_deref())
+        });
+        if let Some(doc) = doc_style.jsdoc(&[description], &[], "  ") {
+            line.push_str(&format!("  {}\n", doc));
+        }
 
-        for operation in &schema_ir.operations {
-            if let Some(desc) = &operation.description {
-                output.push_str(&format!("  // {}\n", desc));
-            }
+        line.push_str(&format!(
+            "  {}: {}{},\n",
+            naming::ts_property_key(&field.name),
+            inline_schemas::indent(&zod_type, "  "),
+            optional_suffix
+        ));
+        line
+    };
+    let mut inlined = Inlined::new();
+    for schema in &single_use {
+        let properties: String = schema
+            .fields
+            .iter()
+            .map(|field| property(field, &inlined))
+            .collect();
+        inlined.insert(
+            schema.name.clone(),
+            format!("z.object({{\n{}}})", properties),
+        );
+    }
 
-            output.push_str(&format!("  {}: createRoute({{\n", operation.id));
-            output.push_str(&format!(
-                "    method: '{}',\n",
-                format!("{:?}", operation.method).to_uppercase()
-            ));
-            output.push_str(&format!("    path: '{}',\n", operation.path));
+    for schema in schema_ir
+        .schemas
+        .iter()
+        .filter(|schema| !inlined.contains_key(&schema.name))
+    {
+        let schema_name = naming::type_name(&schema.name, Language::TypeScript);
 
-            // Query parameters
-            let query_params: Vec<_> = operation
-                .parameters
-                .iter()
-                .filter(|p| matches!(p.location, ParameterLocation::Query))
-                .collect();
+        let schema_doc = doc_style.jsdoc(&[schema.description.as_deref()], &[], "");
+        if let Some(doc) = &schema_doc {
+            output.push_str(&format!("{}\n", doc));
+        }
+
+        match validators::enum_schema(schema) {
+            // Enum schemas are the enum itself rather than an object
+            Some(type_info) => output.push_str(&format!(
+                "export const {}Schema = {};\n\n",
+                schema_name,
+                type_info_to_zod(&type_info, zod, &inlined)
+            )),
+            None => {
+                // Parents are declared first (`allOfStrategy: inherit`)
+                let mut object_open = "z.object(".to_string();
+                if let Some((first, rest)) = schema.parents.split_first() {
+                    object_open =
+                        format!("{}Schema", naming::type_name(first, Language::TypeScript));
+                    for parent in rest {
+                        object_open.push_str(&format!(
+                            ".extend({}Schema.shape)",
+                            naming::type_name(parent, Language::TypeScript)
+                        ));
+                    }
+                    object_open.push_str(".extend(");
+                }
+                output.push_str(&format!(
+                    "export const {}Schema = {}{{\n",
+                    schema_name, object_open
+                ));
 
-            if !query_params.is_empty() {
-                output.push_str("    query: z.object({\n");
-                for param in query_params {
-                    let param_type = param_type_to_zod(&param.schema_type);
-                    let optional = if param.required { "" } else { ".optional()" };
-                    output.push_str(&format!(
-                        "      {}: {}{},\n",
-                        param.name, param_type, optional
-                    ));
+                for field in &schema.fields {
+                    output.push_str(&property(field, &inlined));
                 }
-                output.push_str("    }).optional(),\n");
+
+                output.push_str("});\n\n");
             }
+        }
+        if let Some(doc) = &schema_doc {
+            output.push_str(&format!("{}\n", doc));
+        }
+        output.push_str(&format!(
+            "export type {} = z.infer<typeof {}Schema>;\n\n",
+            schema_name, schema_name
+        ));
+    }
+    Ok(output)
+}
 
-            // Path parameters
-            let path_params: Vec<_> = operation
-                .parameters
-                .iter()
-                .filter(|p| matches!(p.location, ParameterLocation::Path))
-                .collect();
+/// `export const <routes_name> = { ... }` for `operations`
+fn route_definitions(
+    operations: &[&OperationDefinition],
+    routes_name: &str,
+    config: &GenerationConfig,
+) -> String {
+    // Validated with the generator's other options
+    let doc_style = DocStyle::from_config(config).unwrap_or_default();
+    let path_style = PathParamStyle::from_config(config).unwrap_or(PathParamStyle::Braces);
+    let mut output = section_header("Route Definitions");
+
+    output.push_str(&format!("export const {} = {{\n", routes_name));
+
+    for operation in operations {
+        if let Some(doc) = doc_style.jsdoc(
+            &[
+                operation.summary.as_deref(),
+                operation.description.as_deref(),
+            ],
+            &[],
+            "  ",
+        ) {
+            output.push_str(&format!("  {}\n", doc));
+        }
+
+        output.push_str(&format!(
+            "  {}: createRoute({{\n",
+            naming::function_name(&operation.id, Language::TypeScript)
+        ));
+        output.push_str(&format!(
+            "    method: '{}',\n",
+            format!("{:?}", operation.method).to_uppercase()
+        ));
+        output.push_str(&format!(
+            "    path: '{}',\n",
+            path_style.template(&operation.path)
+        ));
 
-            if !path_params.is_empty() {
-                output.push_str("    params: z.object({\n");
-                for param in path_params {
-                    let param_type = param_type_to_zod(&param.schema_type);
-                    output.push_str(&format!("      {}: {},\n", param.name, param_type));
+        // Query parameters
+        let query_params: Vec<_> = operation
+            .parameters
+            .iter()
+            .filter(|p| matches!(p.location, ParameterLocation::Query))
+            .collect();
+
+        if !query_params.is_empty() {
+            output.push_str("    query: z.object({\n");
+            for param in query_params {
+                if let Some(doc) = doc_style.jsdoc(&[param.description.as_deref()], &[], "      ") {
+                    output.push_str(&format!("      {}\n", doc));
                 }
-                output.push_str("    }),\n");
+                let param_type = param_type_to_zod(&param.schema_type);
+                let optional = if param.required { "" } else { ".optional()" };
+                output.push_str(&format!(
+                    "      {}: {}{},\n",
+                    naming::ts_property_key(&param.name),
+                    param_type,
+                    optional
+                ));
             }
+            output.push_str("    }).optional(),\n");
+        }
 
-            // Request body (if POST/PUT/PATCH)
-            if let Some(request_body) = &operation.request_body {
-                output.push_str(&format!("    body: {}Schema,\n", request_body.name));
+        // Path parameters
+        let path_params: Vec<_> = operation
+            .parameters
+            .iter()
+            .filter(|p| matches!(p.location, ParameterLocation::Path))
+            .collect();
+
+        if !path_params.is_empty() {
+            output.push_str("    params: z.object({\n");
+            for param in path_params {
+                if let Some(doc) = doc_style.jsdoc(&[param.description.as_deref()], &[], "      ") {
+                    output.push_str(&format!("      {}\n", doc));
+                }
+                let param_type = param_type_to_zod(&param.schema_type);
+                output.push_str(&format!(
+                    "      {}: {},\n",
+                    naming::ts_property_key(&param.name),
+                    param_type
+                ));
             }
+            output.push_str("    }),\n");
+        }
 
-            // Response
-            if let Some(response) = &operation.response {
-                output.push_str(&format!("    response: {}Schema,\n", response.name));
-            } else {
-                output.push_str("    response: z.void(),\n");
+        // Header parameters (keys lowercased as received by HTTP servers)
+        let header_params: Vec<_> = operation
+            .parameters
+            .iter()
+            .filter(|p| matches!(p.location, ParameterLocation::Header))
+            .collect();
+
+        if !header_params.is_empty() {
+            output.push_str("    headers: z.object({\n");
+            for param in header_params {
+                if let Some(doc) = doc_style.jsdoc(&[param.description.as_deref()], &[], "      ") {
+                    output.push_str(&format!("      {}\n", doc));
+                }
+                let optional = if param.required { "" } else { ".optional()" };
+                output.push_str(&format!(
+                    "      {}: z.string(){},\n",
+                    naming::ts_property_key(&param.name.to_lowercase()),
+                    optional
+                ));
             }
-
-            output.push_str("  }),\n\n");
+            output.push_str("    }),\n");
         }
 
-        output.push_str("};\n\n");
+        // Request body (if POST/PUT/PATCH)
+        if let Some(content_type) = &operation.request_content_type {
+            output.push_str(&format!(
+                "    body: {},\n",
+                body_to_zod(operation.request_body.as_ref(), content_type)
+            ));
+        }
 
-        // Generate server router (if enabled)
-        let include_server = config
-            .options
-            .get("includeServer")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(true);
+        // Response
+        match &operation.response_content_type {
+            Some(content_type) => output.push_str(&format!(
+                "    response: {},\n",
+                body_to_zod(operation.response.as_ref(), content_type)
+            )),
+            None => output.push_str("    response: z.void(),\n"),
+        }
 
-        if include_server {
-            let router_name = config
-                .options
-                .get("routerName")
-                .and_then(|v| v.as_str())
-                .unwrap_or("apiRouter");
+        output.push_str("  }),\n\n");
+    }
 
-            output.push_str(
-                "// ============================================================================\n",
-            );
-            output.push_str("// Server-side Router\n");
-            output.push_str("// ============================================================================\n\n");
+    output.push_str("};\n\n");
+    output
+}
 
-            output.push_str(&format!(
-                "export const {} = createRouter(routes, {{\n",
-                router_name
-            ));
+/// Server router stub over `routes_name`, one handler per operation
+fn router(operations: &[&OperationDefinition], routes_name: &str, router_name: &str) -> String {
+    let mut output = section_header("Server-side Router");
 
-            for operation in &schema_ir.operations {
-                output.push_str(&format!("  {}: async (req) => {{\n", operation.id));
-                output.push_str("    // TODO: Implement handler\n");
-
-                // Add type hints in comments
-                if !operation.parameters.is_empty() {
-                    output.push_str("    // Request parameters:\n");
-                    for param in &operation.parameters {
-                        output.push_str(&format!(
-                            "    //   req.{}: {}\n",
-                            format!("{:?}", param.location).to_lowercase(),
-                            param.schema_type
-                        ));
-                    }
-                }
+    output.push_str(&format!(
+        "export const {} = createRouter({}, {{\n",
+        router_name, routes_name
+    ));
 
-                if let Some(response) = &operation.response {
-                    output.push_str(&format!("    // Must return: {}\n", response.name));
-                }
+    for operation in operations {
+        output.push_str(&format!(
+            "  {}: async (req) => {{\n",
+            naming::function_name(&operation.id, Language::TypeScript)
+        ));
+        output.push_str("    // TODO: Implement handler\n");
 
-                output.push_str("    throw new Error('Not implemented');\n");
-                output.push_str("  },\n\n");
+        // Add type hints in comments
+        if !operation.parameters.is_empty() {
+            output.push_str("    // Request parameters:\n");
+            for param in &operation.parameters {
+                output.push_str(&format!(
+                    "    //   req.{}: {}\n",
+                    format!("{:?}", param.location).to_lowercase(),
+                    param.schema_type
+                ));
             }
+        }
 
-            output.push_str("});\n\n");
+        if let Some(response) = &operation.response {
+            let suffix = if response.schema_type == "array" {
+                "[]"
+            } else {
+                ""
+            };
+            output.push_str(&format!(
+                "    // Must return: {}{}\n",
+                naming::type_name(&response.name, Language::TypeScript),
+                suffix
+            ));
         }
 
-        // Generate client (if enabled)
-        let include_client = config
-            .options
-            .get("includeClient")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(true);
+        output.push_str("    throw new Error('Not implemented');\n");
+        output.push_str("  },\n\n");
+    }
 
-        if include_client {
-            let client_name = config
-                .options
-                .get("clientName")
-                .and_then(|v| v.as_str())
-                .unwrap_or("apiClient");
+    output.push_str("});\n\n");
+    output
+}
 
+/// Client over `routes_name` as `baseUrlStrategy` creates it, with usage
+/// examples; `environments` is left to the caller unless `with_environments`
+fn client(
+    schema_ir: &SchemaIR,
+    operations: &[&OperationDefinition],
+    routes_name: &str,
+    client_name: &str,
+    config: &GenerationConfig,
+    with_environments: bool,
+) -> Result<String> {
+    let mut output = section_header("Client-side API");
+
+    let default_base_url = default_base_url(schema_ir);
+    let factory_name = format!("create{}", naming::pascal_case(client_name));
+
+    match BaseUrlStrategy::from_config(config)? {
+        BaseUrlStrategy::Env => {
             let base_url_env = config
                 .options
                 .get("baseUrlEnvVar")
                 .and_then(|v| v.as_str())
                 .unwrap_or("API_BASE_URL");
 
-            output.push_str(
-                "// ============================================================================\n",
-            );
-            output.push_str("// Client-side API\n");
-            output.push_str("// ============================================================================\n\n");
-
             output.push_str(&format!(
-                "export const {} = createClient(routes, {{\n",
-                client_name
+                "export const {} = createClient({}, {{\n",
+                client_name, routes_name
             ));
             output.push_str(&format!(
-                "  baseUrl: process.env.{} || '{}',\n",
+                "  baseUrl: process.env.{} || {},\n",
                 base_url_env,
-                schema_ir
-                    .metadata
-                    .base_url
-                    .as_deref()
-                    .unwrap_or("http://localhost:3000")
+                naming::quote(default_base_url)
             ));
             output.push_str("});\n\n");
+        }
+        BaseUrlStrategy::Constructor => {
+            output.push_str(&format!(
+                "export function {}(baseUrl: string = {}) {{\n",
+                factory_name,
+                naming::quote(default_base_url)
+            ));
+            output.push_str(&format!(
+                "  return createClient({}, {{ baseUrl }});\n",
+                routes_name
+            ));
+            output.push_str("}\n\n");
+            output.push_str(&format!(
+                "// const {} = {}({});\n",
+                client_name,
+                factory_name,
+                naming::quote(default_base_url)
+            ));
+        }
+        BaseUrlStrategy::Environments => {
+            let environments = server_environments(schema_ir, default_base_url);
+            if with_environments {
+                output.push_str(&environment_definitions(&environments));
+            }
 
-            // Add usage examples
-            output.push_str("// Usage examples:\n");
-            for operation in schema_ir.operations.iter().take(2) {
-                output.push_str(&format!(
-                    "// const result = await {}.{}(",
-                    client_name, operation.id
-                ));
+            let default_environment = naming::quote(&environments[0].0);
+            output.push_str(&format!(
+                "export function {}(environment: Environment = {}) {{\n",
+                factory_name, default_environment
+            ));
+            output.push_str(&format!(
+                "  return createClient({}, {{ baseUrl: environments[environment] }});\n",
+                routes_name
+            ));
+            output.push_str("}\n\n");
+            output.push_str(&format!(
+                "// const {} = {}({});\n",
+                client_name, factory_name, default_environment
+            ));
+        }
+        BaseUrlStrategy::Callback => {
+            output.push_str(
+                "/** Resolves the base URL when the client is created, e.g. from `window.location` */\n",
+            );
+            output.push_str(&format!(
+                "export function {}(resolveBaseUrl: () => string = () => {}) {{\n",
+                factory_name,
+                naming::quote(default_base_url)
+            ));
+            output.push_str(&format!(
+                "  return createClient({}, {{ baseUrl: resolveBaseUrl() }});\n",
+                routes_name
+            ));
+            output.push_str("}\n\n");
+            output.push_str(&format!(
+                "// const {} = {}(() => window.location.origin);\n",
+                client_name, factory_name
+            ));
+        }
+    }
 
-                let has_params = !operation.parameters.is_empty();
-                let has_body = operation.request_body.is_some();
+    // Add usage examples
+    output.push_str("// Usage examples:\n");
+    for operation in operations.iter().take(2) {
+        output.push_str(&format!(
+            "// const result = await {}.{}(",
+            client_name,
+            naming::function_name(&operation.id, Language::TypeScript)
+        ));
 
-                if has_params || has_body {
-                    output.push_str("{ ");
+        let has_params = !operation.parameters.is_empty();
+        let has_body = operation.request_body.is_some();
 
-                    if has_params {
-                        for param in operation.parameters.iter().take(1) {
-                            output.push_str(&format!("{}: value", param.name));
-                        }
-                    }
+        if has_params || has_body {
+            output.push_str("{ ");
 
-                    output.push_str(" }");
+            if has_params {
+                for param in operation.parameters.iter().take(1) {
+                    output.push_str(&format!("{}: value", naming::ts_property_key(&param.name)));
                 }
-
-                output.push_str(");\n");
             }
+
+            output.push_str(" }");
         }
 
-        Ok(GeneratedOutput {
-            filename: config.output_file.clone(),
-            content: output,
-            metadata: HashMap::new(),
-        })
+        output.push_str(");\n");
+    }
+    Ok(output)
+}
+
+fn default_base_url(schema_ir: &SchemaIR) -> &str {
+    schema_ir
+        .metadata
+        .base_url
+        .as_deref()
+        .unwrap_or("http://localhost:3000")
+}
+
+fn environment_definitions(environments: &[(String, String)]) -> String {
+    let mut output = "export const environments = {\n".to_string();
+    for (environment, url) in environments {
+        output.push_str(&format!(
+            "  {}: {},\n",
+            naming::ts_property_key(environment),
+            naming::quote(url)
+        ));
+    }
+    output.push_str("} as const;\n\n");
+    output.push_str("export type Environment = keyof typeof environments;\n\n");
+    output
+}
+
+/// How the generated client resolves its base URL (`baseUrlStrategy` option)
+enum BaseUrlStrategy {
+    /// `process.env[baseUrlEnvVar]`, falling back to the first server
+    Env,
+    /// Factory taking the base URL as an argument
+    Constructor,
+    /// Factory taking an environment name mapped to one of the spec servers
+    Environments,
+    /// Factory taking a resolver function
+    Callback,
+}
+
+impl BaseUrlStrategy {
+    fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("baseUrlStrategy").map(|v| v.as_str()) {
+            None | Some(Some("env")) => Ok(Self::Env),
+            Some(Some("constructor")) => Ok(Self::Constructor),
+            Some(Some("environments")) => Ok(Self::Environments),
+            Some(Some("callback")) => Ok(Self::Callback),
+            _ => anyhow::bail!(
+                "typescript_adi_http: option 'baseUrlStrategy' must be \"env\", \"constructor\", \"environments\", or \"callback\""
+            ),
+        }
+    }
+}
+
+/// Environment names paired with server URLs, in spec order; names come from
+/// `x-environment`, then the server description
+fn server_environments(schema_ir: &SchemaIR, default_base_url: &str) -> Vec<(String, String)> {
+    let mut environments: Vec<(String, String)> = Vec::new();
+    for (index, server) in schema_ir.metadata.servers.iter().enumerate() {
+        let base = server
+            .environment
+            .as_deref()
+            .or(server.description.as_deref())
+            .map(naming::camel_case)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("server{}", index + 1));
+
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while environments.iter().any(|(existing, _)| *existing == name) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        environments.push((name, server.url.clone()));
+    }
+
+    if environments.is_empty() {
+        environments.push(("default".to_string(), default_base_url.to_string()));
     }
+    environments
 }
 
 // Note: This function intentionally differs from TypeInfo::to_typescript_zod() for ADI HTTP-specific needs:
@@ -284,21 +857,38 @@ impl Generator for TypeScriptAdiHttpGenerator {
 // 3. Uses z.number().int() for integers (not just z.number())
 // 4. Uses z.record(z.any()) for objects (not z.any())
 // These differences are required for @adi-family/http compatibility.
-fn type_info_to_zod(type_info: &TypeInfo) -> String {
+// Validators whose spelling changed in zod 4 come from `ZodVersion`, and
+// references to `inlined` schemas are replaced by their object.
+fn type_info_to_zod(type_info: &TypeInfo, zod: ZodVersion, inlined: &Inlined) -> String {
+    if !type_info.tuple_items.is_empty() {
+        let items: Vec<String> = type_info
+            .tuple_items
+            .iter()
+            .map(|t| type_info_to_zod(t, zod, inlined))
+            .collect();
+        return format!("z.tuple([{}])", items.join(", "));
+    }
+
     if type_info.is_array {
         if let Some(item_type) = &type_info.array_item_type {
-            return format!("z.array({})", type_info_to_zod(item_type));
+            return format!("z.array({})", type_info_to_zod(item_type, zod, inlined));
         }
         return "z.array(z.any())".to_string();
     }
 
     // ADI HTTP-specific: Add "Schema" suffix to references
     if let Some(ref_name) = &type_info.reference {
-        return format!("{}Schema", ref_name);
+        if let Some(object) = inlined.get(ref_name) {
+            return object.clone();
+        }
+        return format!(
+            "z.lazy(() => {}Schema)",
+            naming::type_name(ref_name, Language::TypeScript)
+        );
     }
 
     if let Some(enum_vals) = &type_info.enum_values {
-        let values: Vec<String> = enum_vals.iter().map(|v| format!("\"{}\"", v)).collect();
+        let values: Vec<String> = enum_vals.iter().map(|v| naming::quote(v)).collect();
         return format!("z.enum([{}])", values.join(", "));
     }
 
@@ -306,10 +896,10 @@ fn type_info_to_zod(type_info: &TypeInfo) -> String {
         "string" => {
             if let Some(fmt) = &type_info.format {
                 match fmt.as_str() {
-                    "email" => "z.string().email()".to_string(),
-                    "uuid" => "z.string().uuid()".to_string(),
-                    "uri" | "url" => "z.string().url()".to_string(),
-                    "date" | "date-time" => "z.string().datetime()".to_string(),
+                    "email" => zod.email().to_string(),
+                    "uuid" => zod.uuid().to_string(),
+                    "uri" | "url" => zod.url().to_string(),
+                    "date" | "date-time" => zod.datetime().to_string(),
                     _ => "z.string()".to_string(),
                 }
             } else {
@@ -319,11 +909,40 @@ fn type_info_to_zod(type_info: &TypeInfo) -> String {
         "integer" => "z.number().int()".to_string(),
         "number" => "z.number()".to_string(),
         "boolean" => "z.boolean()".to_string(),
-        "object" => "z.record(z.any())".to_string(),
+        "object" => match &type_info.map_values {
+            Some(values) => zod.record(&type_info_to_zod(values, zod, inlined)),
+            None => zod.record("z.any()"),
+        },
+        "union" => {
+            let members: Vec<String> = type_info
+                .union_members
+                .iter()
+                .map(|m| type_info_to_zod(m, zod, inlined))
+                .collect();
+            format!("z.union([{}])", members.join(", "))
+        }
         _ => "z.any()".to_string(),
     }
 }
 
+fn schema_reference_to_zod(schema_ref: &SchemaReference) -> String {
+    let schema_name = naming::type_name(&schema_ref.name, Language::TypeScript);
+    if schema_ref.schema_type == "array" {
+        format!("z.array({}Schema)", schema_name)
+    } else {
+        format!("{}Schema", schema_name)
+    }
+}
+
+/// Zod schema for a body; non-JSON bodies are validated as raw strings
+fn body_to_zod(schema_ref: Option<&SchemaReference>, content_type: &str) -> String {
+    match (BodyKind::from_content_type(content_type), schema_ref) {
+        (BodyKind::Json, Some(schema_ref)) => schema_reference_to_zod(schema_ref),
+        (BodyKind::Json, None) => "z.any()".to_string(),
+        _ => "z.string()".to_string(),
+    }
+}
+
 fn param_type_to_zod(schema_type: &str) -> String {
     match schema_type {
         "integer" => "z.coerce.number().int()".to_string(),
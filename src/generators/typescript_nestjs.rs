@@ -0,0 +1,924 @@
+//! NestJS server scaffolding (`typescript_nestjs` generator):
+//! - a class-validator DTO per object schema (`UserDto`), with `@Type` from
+//!   class-transformer on nested objects so `ValidationPipe({ transform: true })`
+//!   validates them too; enum schemas become a `const` list and its type
+//! - a `@Param()` and a `@Query()` DTO per operation, converting numbers and
+//!   booleans from their string form
+//! - a `@Controller()` per tag (`DefaultController` for untagged operations)
+//!   with a route per operation, delegating to the abstract `{Tag}Service`
+//!   the application implements and provides:
+//!   `{ provide: UsersService, useClass: UsersServiceImpl }`
+//!
+//! Cookie parameters are read from `request.cookies`, as `cookie-parser` sets
+//! it. `allOfStrategy: inherit` makes a DTO with a single parent extend it.
+
+use super::all_of;
+use super::docs::DocStyle;
+use super::naming::{self, Language};
+use super::ts_imports::ImportStyle;
+use super::type_overrides::{self, TypeOverrides};
+use super::{GeneratedOutput, Generator};
+use crate::config::GenerationConfig;
+use crate::parsers::{
+    BodyKind, Constraints, HttpMethod, OperationDefinition, Parameter, ParameterLocation,
+    SchemaDefinition, SchemaIR, SchemaReference, TypeInfo,
+};
+use anyhow::Result;
+use indexmap::IndexMap;
+use std::collections::{BTreeSet, HashMap};
+
+pub struct TypeScriptNestjsGenerator;
+
+/// How a component schema is declared
+enum Kind {
+    /// `class {Name}Dto`
+    Class,
+    /// `const {Name}Values` and `type {Name}`
+    Enum,
+    /// `type {Name}` for another TypeScript type
+    Alias(String),
+}
+
+/// Decorators used, by the package they are imported from
+#[derive(Default)]
+struct Used {
+    nest: BTreeSet<&'static str>,
+    validator: BTreeSet<&'static str>,
+    transformer: BTreeSet<&'static str>,
+}
+
+impl Used {
+    fn validator(&mut self, name: &'static str, args: &[String]) -> String {
+        self.validator.insert(name);
+        format!("@{}({})", name, args.join(", "))
+    }
+
+    fn transformer(&mut self, name: &'static str, arg: &str) -> String {
+        self.transformer.insert(name);
+        format!("@{}({})", name, arg)
+    }
+}
+
+impl Generator for TypeScriptNestjsGenerator {
+    fn name(&self) -> &str {
+        "typescript_nestjs"
+    }
+
+    fn file_extension(&self) -> &str {
+        "ts"
+    }
+
+    fn generate_from_ir(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<GeneratedOutput> {
+        let import_style = ImportStyle::from_config(config)?;
+        let doc_style = DocStyle::from_config(config)?;
+        let kinds: HashMap<&str, Kind> = schema_ir
+            .schemas
+            .iter()
+            .map(|schema| (schema.name.as_str(), kind(schema)))
+            .collect();
+        let mut used = Used::default();
+
+        let schemas = generate_schemas(schema_ir, &kinds, &doc_style, &mut used);
+        let operations = generate_operations(schema_ir, &kinds, &doc_style, &mut used);
+
+        let mut content = format!(
+            "// Generated NestJS DTOs and controllers for {}\n// Version: {}\n\n",
+            schema_ir.metadata.title, schema_ir.metadata.version
+        );
+        let names = |set: &BTreeSet<&'static str>| set.iter().copied().collect::<Vec<_>>();
+        content.push_str(&import_style.values(&names(&used.nest), "@nestjs/common"));
+        if !used.transformer.is_empty() {
+            content.push_str(&import_style.values(&names(&used.transformer), "class-transformer"));
+        }
+        if !used.validator.is_empty() {
+            content.push_str(&import_style.values(&names(&used.validator), "class-validator"));
+        }
+        // External types standing in for schemas and formats
+        for (module, types) in TypeOverrides::from_config(config)?.imports(schema_ir) {
+            content.push_str(&import_style.types(&type_overrides::imported_names(&types), &module));
+        }
+        content.push('\n');
+        content.push_str(&schemas);
+        content.push_str(&operations);
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content: format!("{}\n", content.trim_end()),
+            metadata: HashMap::new(),
+            companions: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn supports_inheritance(&self, _config: &GenerationConfig) -> bool {
+        true
+    }
+
+    fn supports_unions(&self) -> bool {
+        true
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        ImportStyle::from_config(config)?;
+        DocStyle::from_config(config)?;
+        TypeOverrides::from_config(config)?;
+        Ok(())
+    }
+}
+
+fn kind(schema: &SchemaDefinition) -> Kind {
+    if schema.fields.is_empty() && schema.parents.is_empty() {
+        if schema
+            .original
+            .get("enum")
+            .and_then(|values| values.as_array())
+            .is_some()
+        {
+            return Kind::Enum;
+        }
+        let alias = match schema.original.get("type").and_then(|t| t.as_str()) {
+            None | Some("object") => None,
+            Some("string") => Some("string"),
+            Some("integer") | Some("number") => Some("number"),
+            Some("boolean") => Some("boolean"),
+            Some("array") => Some("unknown[]"),
+            Some(_) => Some("unknown"),
+        };
+        if let Some(alias) = alias {
+            return Kind::Alias(alias.to_string());
+        }
+    }
+    Kind::Class
+}
+
+/// Name a reference to a component schema is typed as
+fn reference_type(name: &str, kinds: &HashMap<&str, Kind>) -> String {
+    match kinds.get(name) {
+        Some(Kind::Class) | None => format!("{}Dto", naming::type_name(name, Language::TypeScript)),
+        Some(_) => naming::type_name(name, Language::TypeScript),
+    }
+}
+
+fn ts_type(type_info: &TypeInfo, kinds: &HashMap<&str, Kind>) -> String {
+    if type_info.is_array {
+        let item = type_info
+            .array_item_type
+            .as_deref()
+            .map(|item| ts_type(item, kinds))
+            .unwrap_or_else(|| "unknown".to_string());
+        return match item.contains(' ') {
+            true => format!("({})[]", item),
+            false => format!("{}[]", item),
+        };
+    }
+    if let Some(name) = &type_info.reference {
+        return reference_type(name, kinds);
+    }
+    if let Some(values) = &type_info.enum_values {
+        return values
+            .iter()
+            .map(|value| naming::quote(value))
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    match type_info.openapi_type.as_str() {
+        "string" => "string".to_string(),
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "external" => type_info
+            .format
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string()),
+        "object" => match &type_info.map_values {
+            Some(values) => format!("Record<string, {}>", ts_type(values, kinds)),
+            None => "Record<string, unknown>".to_string(),
+        },
+        "union" if !type_info.union_members.is_empty() => type_info
+            .union_members
+            .iter()
+            .map(|member| ts_type(member, kinds))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        _ => "unknown".to_string(),
+    }
+}
+
+/// `{ each: true }` for validators applied to array items
+fn options(each: bool) -> Vec<String> {
+    match each {
+        true => vec!["{ each: true }".to_string()],
+        false => Vec::new(),
+    }
+}
+
+/// Validators with leading arguments take a placeholder before the options
+fn with_options(placeholder: &str, each: bool) -> Vec<String> {
+    match each {
+        true => vec![placeholder.to_string(), "{ each: true }".to_string()],
+        false => Vec::new(),
+    }
+}
+
+/// Validator of a string of the given `format`
+fn string_validator(format: Option<&str>, each: bool, used: &mut Used) -> String {
+    match format {
+        Some("email") => used.validator("IsEmail", &with_options("{}", each)),
+        Some("uuid") => used.validator("IsUUID", &with_options("undefined", each)),
+        Some("uri") | Some("url") => used.validator("IsUrl", &with_options("{}", each)),
+        Some("date") | Some("date-time") => {
+            used.validator("IsDateString", &with_options("{}", each))
+        }
+        _ => used.validator("IsString", &options(each)),
+    }
+}
+
+/// Type validators of one value (or each item, with `each`); empty when
+/// anything is accepted
+fn value_validators(
+    type_info: &TypeInfo,
+    each: bool,
+    kinds: &HashMap<&str, Kind>,
+    used: &mut Used,
+) -> Vec<String> {
+    if let Some(name) = &type_info.reference {
+        return match kinds.get(name.as_str()) {
+            Some(Kind::Class) | None => vec![
+                used.validator("ValidateNested", &options(each)),
+                used.transformer("Type", &format!("() => {}", reference_type(name, kinds))),
+            ],
+            Some(Kind::Enum) => {
+                let mut args = vec![format!(
+                    "{}Values",
+                    naming::type_name(name, Language::TypeScript)
+                )];
+                args.extend(options(each));
+                vec![used.validator("IsIn", &args)]
+            }
+            Some(Kind::Alias(alias)) => match alias.as_str() {
+                "string" => vec![used.validator("IsString", &options(each))],
+                "number" => vec![used.validator("IsNumber", &with_options("{}", each))],
+                "boolean" => vec![used.validator("IsBoolean", &options(each))],
+                "unknown[]" => vec![used.validator("IsArray", &options(each))],
+                _ => Vec::new(),
+            },
+        };
+    }
+    if let Some(values) = &type_info.enum_values {
+        let quoted: Vec<_> = values.iter().map(|value| naming::quote(value)).collect();
+        let mut args = vec![format!("[{}]", quoted.join(", "))];
+        args.extend(options(each));
+        return vec![used.validator("IsIn", &args)];
+    }
+    match type_info.openapi_type.as_str() {
+        "string" => vec![string_validator(type_info.format.as_deref(), each, used)],
+        "integer" => vec![used.validator("IsInt", &options(each))],
+        "number" => vec![used.validator("IsNumber", &with_options("{}", each))],
+        "boolean" => vec![used.validator("IsBoolean", &options(each))],
+        "object" => vec![used.validator("IsObject", &options(each))],
+        _ => Vec::new(),
+    }
+}
+
+/// Validators of the spec's keywords; string and number bounds apply to each
+/// item of an array
+fn constraint_validators(constraints: &Constraints, each: bool, used: &mut Used) -> Vec<String> {
+    let with = |value: String| {
+        let mut args = vec![value];
+        args.extend(options(each));
+        args
+    };
+    let mut validators = Vec::new();
+    if let Some(min) = constraints.min_items {
+        validators.push(used.validator("ArrayMinSize", &[min.to_string()]));
+    }
+    if let Some(max) = constraints.max_items {
+        validators.push(used.validator("ArrayMaxSize", &[max.to_string()]));
+    }
+    if let Some(min) = constraints.min_length {
+        validators.push(used.validator("MinLength", &with(min.to_string())));
+    }
+    if let Some(max) = constraints.max_length {
+        validators.push(used.validator("MaxLength", &with(max.to_string())));
+    }
+    if let Some(pattern) = &constraints.pattern {
+        validators.push(used.validator(
+            "Matches",
+            &with(format!("new RegExp({})", naming::quote(pattern))),
+        ));
+    }
+    // class-validator bounds are inclusive; exclusive ones are left to the service
+    if let Some(min) = constraints
+        .minimum
+        .filter(|_| !constraints.exclusive_minimum)
+    {
+        validators.push(used.validator("Min", &with(min.to_string())));
+    }
+    if let Some(max) = constraints
+        .maximum
+        .filter(|_| !constraints.exclusive_maximum)
+    {
+        validators.push(used.validator("Max", &with(max.to_string())));
+    }
+    if let Some(divisor) = constraints.multiple_of {
+        validators.push(used.validator("IsDivisibleBy", &with(divisor.to_string())));
+    }
+    validators
+}
+
+struct Property {
+    key: String,
+    ts_type: String,
+    required: bool,
+    decorators: Vec<String>,
+    doc: Option<String>,
+}
+
+fn render_class(
+    name: &str,
+    extends: Option<&str>,
+    doc: Option<String>,
+    properties: &[Property],
+) -> String {
+    let mut class = String::new();
+    if let Some(doc) = doc {
+        class.push_str(&format!("{}\n", doc));
+    }
+    match extends {
+        Some(parent) => class.push_str(&format!("export class {} extends {} {{", name, parent)),
+        None => class.push_str(&format!("export class {} {{", name)),
+    }
+    if !properties.is_empty() {
+        class.push('\n');
+    }
+    for (i, property) in properties.iter().enumerate() {
+        if i > 0 {
+            class.push('\n');
+        }
+        if let Some(doc) = &property.doc {
+            class.push_str(&format!("  {}\n", doc));
+        }
+        for decorator in &property.decorators {
+            class.push_str(&format!("  {}\n", decorator));
+        }
+        let mark = if property.required { "!" } else { "?" };
+        class.push_str(&format!(
+            "  {}{}: {};\n",
+            property.key, mark, property.ts_type
+        ));
+    }
+    class.push_str("}\n\n");
+    class
+}
+
+/// Enums and aliases first, since validators refer to enum values as the
+/// classes are defined, then the DTO classes
+fn generate_schemas(
+    schema_ir: &SchemaIR,
+    kinds: &HashMap<&str, Kind>,
+    doc_style: &DocStyle,
+    used: &mut Used,
+) -> String {
+    let mut types = String::new();
+    let mut classes = String::new();
+    for schema in &schema_ir.schemas {
+        let name = naming::type_name(&schema.name, Language::TypeScript);
+        let doc = doc_style.jsdoc(&[schema.description.as_deref()], &[], "");
+        match &kinds[schema.name.as_str()] {
+            Kind::Enum => {
+                let values: Vec<_> = schema.original["enum"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|value| match value {
+                        serde_json::Value::String(s) => naming::quote(s),
+                        other => other.to_string(),
+                    })
+                    .collect();
+                if let Some(doc) = doc {
+                    types.push_str(&format!("{}\n", doc));
+                }
+                types.push_str(&format!(
+                    "export const {}Values = [{}] as const;\n",
+                    name,
+                    values.join(", ")
+                ));
+                types.push_str(&format!(
+                    "export type {0} = (typeof {0}Values)[number];\n\n",
+                    name
+                ));
+            }
+            Kind::Alias(alias) => {
+                if let Some(doc) = doc {
+                    types.push_str(&format!("{}\n", doc));
+                }
+                types.push_str(&format!("export type {} = {};\n\n", name, alias));
+            }
+            Kind::Class => {
+                // One parent is extended; several are merged into the class
+                let (extends, fields) = match schema.parents.as_slice() {
+                    [] => (None, schema.fields.clone()),
+                    [parent] if matches!(kinds.get(parent.as_str()), Some(Kind::Class)) => {
+                        (Some(reference_type(parent, kinds)), schema.fields.clone())
+                    }
+                    _ => (None, all_of::flattened_fields(schema, &schema_ir.schemas)),
+                };
+                let properties: Vec<Property> = fields
+                    .iter()
+                    .map(|field| {
+                        let mut decorators = Vec::new();
+                        if !field.required {
+                            decorators.push(used.validator("IsOptional", &[]));
+                        } else if field.nullable {
+                            let present = "(_, value) => value !== null".to_string();
+                            decorators.push(used.validator("ValidateIf", &[present]));
+                        }
+                        let type_info = &field.type_info;
+                        let values =
+                            match (type_info.is_array, type_info.array_item_type.as_deref()) {
+                                (true, item) => {
+                                    let mut values = vec![used.validator("IsArray", &[])];
+                                    if let Some(item) = item {
+                                        values.extend(value_validators(item, true, kinds, used));
+                                    }
+                                    values
+                                }
+                                (false, _) => value_validators(type_info, false, kinds, used),
+                            };
+                        if values.is_empty() {
+                            let name = if field.required { "IsDefined" } else { "Allow" };
+                            decorators.push(used.validator(name, &[]));
+                        }
+                        decorators.extend(values);
+                        decorators.extend(constraint_validators(
+                            &field.constraints,
+                            type_info.is_array,
+                            used,
+                        ));
+
+                        let mut field_type = ts_type(type_info, kinds);
+                        if field.nullable {
+                            field_type.push_str(" | null");
+                        }
+                        Property {
+                            key: naming::ts_property_key(&field.name),
+                            ts_type: field_type,
+                            required: field.required,
+                            decorators,
+                            doc: doc_style.jsdoc(&[field.description.as_deref()], &[], "  "),
+                        }
+                    })
+                    .collect();
+                classes.push_str(&render_class(
+                    &format!("{}Dto", name),
+                    extends.as_deref(),
+                    doc,
+                    &properties,
+                ));
+            }
+        }
+    }
+    types + &classes
+}
+
+fn param_type(param: &Parameter) -> String {
+    let scalar = |schema_type: Option<&str>| match schema_type {
+        Some("integer") | Some("number") => "number",
+        Some("boolean") => "boolean",
+        Some("object") => "Record<string, unknown>",
+        _ => "string",
+    };
+    match param.schema_type.as_str() {
+        "array" => format!("{}[]", scalar(param.item_type.as_deref())),
+        other => scalar(Some(other)).to_string(),
+    }
+}
+
+/// Validators of a path or query parameter, converting it from the string
+/// (or strings) the request carries
+fn param_decorators(param: &Parameter, used: &mut Used) -> Vec<String> {
+    let mut decorators = Vec::new();
+    if !param.required {
+        decorators.push(used.validator("IsOptional", &[]));
+    }
+    let (schema_type, format, each) = match param.schema_type.as_str() {
+        "array" => {
+            // A single `?tag=a` arrives as a string
+            decorators.push(used.transformer(
+                "Transform",
+                "({ value }) => (Array.isArray(value) ? value : [value])",
+            ));
+            decorators.push(used.validator("IsArray", &[]));
+            (
+                param.item_type.as_deref().unwrap_or("string"),
+                param.item_format.as_deref(),
+                true,
+            )
+        }
+        other => (other, param.format.as_deref(), false),
+    };
+    match schema_type {
+        "integer" | "number" => {
+            decorators.push(used.transformer("Type", "() => Number"));
+            decorators.push(match schema_type {
+                "integer" => used.validator("IsInt", &options(each)),
+                _ => used.validator("IsNumber", &with_options("{}", each)),
+            });
+        }
+        "boolean" => {
+            decorators.push(used.transformer(
+                "Transform",
+                "({ value }) => value === true || value === \"true\"",
+            ));
+            decorators.push(used.validator("IsBoolean", &options(each)));
+        }
+        "object" => decorators.push(used.validator("IsObject", &options(each))),
+        _ => decorators.push(string_validator(format, each, used)),
+    }
+    decorators
+}
+
+/// Tag an operation's controller is named after
+fn tag(op: &OperationDefinition) -> &str {
+    op.tags.first().map(String::as_str).unwrap_or("default")
+}
+
+fn schema_type(reference: &SchemaReference, kinds: &HashMap<&str, Kind>) -> String {
+    let name = reference_type(&reference.name, kinds);
+    match reference.schema_type.as_str() {
+        "array" => format!("{}[]", name),
+        _ => name,
+    }
+}
+
+/// `/users/{id}` as the `users/:id` route of a root `@Controller()`
+fn route(path: &str) -> String {
+    path.trim_start_matches('/')
+        .split('/')
+        .map(
+            |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => format!(":{}", name),
+                None => segment.to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn decorator_name(method: &HttpMethod) -> &'static str {
+    match method {
+        HttpMethod::Get => "Get",
+        HttpMethod::Post => "Post",
+        HttpMethod::Put => "Put",
+        HttpMethod::Delete => "Delete",
+        HttpMethod::Patch => "Patch",
+        HttpMethod::Head => "Head",
+        HttpMethod::Options => "Options",
+    }
+}
+
+/// Status of the first success response when it is not the one Nest sends by
+/// default (201 for POST, 200 otherwise), such as a 204 from PUT or PATCH
+fn success_status(op: &OperationDefinition) -> Option<u16> {
+    let status: u16 = op
+        .responses
+        .iter()
+        .find(|response| response.is_success())?
+        .status
+        .parse()
+        .ok()?;
+    let default = match op.method {
+        HttpMethod::Post => 201,
+        _ => 200,
+    };
+    (status != default).then_some(status)
+}
+
+/// Parameter DTOs and input interfaces, then a service and controller per tag
+fn generate_operations(
+    schema_ir: &SchemaIR,
+    kinds: &HashMap<&str, Kind>,
+    doc_style: &DocStyle,
+    used: &mut Used,
+) -> String {
+    let mut inputs = String::new();
+    let mut tags: IndexMap<&str, (String, String)> = IndexMap::new();
+    used.nest.insert("Controller");
+
+    for op in &schema_ir.operations {
+        let method = naming::function_name(&op.id, Language::TypeScript);
+        let pascal = naming::pascal_case(&method);
+        let located = |location: ParameterLocation| -> Vec<&Parameter> {
+            op.parameters
+                .iter()
+                .filter(|param| param.location == location)
+                .collect()
+        };
+
+        // Controller method parameters, the input members they fill, and their types
+        let mut args = Vec::new();
+        let mut members = Vec::new();
+        let mut fields = Vec::new();
+        for (group, location, decorator) in [
+            ("params", ParameterLocation::Path, "Param"),
+            ("query", ParameterLocation::Query, "Query"),
+        ] {
+            let params = located(location);
+            if params.is_empty() {
+                continue;
+            }
+            let dto = format!("{}{}Dto", pascal, naming::pascal_case(group));
+            let properties: Vec<Property> = params
+                .iter()
+                .map(|param| Property {
+                    key: naming::ts_property_key(&param.name),
+                    ts_type: param_type(param),
+                    required: param.required,
+                    decorators: param_decorators(param, used),
+                    doc: doc_style.jsdoc(&[param.description.as_deref()], &[], "  "),
+                })
+                .collect();
+            inputs.push_str(&render_class(&dto, None, None, &properties));
+            used.nest.insert(decorator);
+            args.push(format!("@{}() {}: {}", decorator, group, dto));
+            members.push(group.to_string());
+            fields.push(format!("  {}: {};", group, dto));
+        }
+
+        let headers = located(ParameterLocation::Header);
+        if !headers.is_empty() {
+            used.nest.insert("Headers");
+            let mut values = Vec::new();
+            let mut types = Vec::new();
+            for header in headers {
+                let variable = naming::camel_case(&header.name);
+                let key = naming::ts_property_key(&header.name);
+                let optional = if header.required { "" } else { " | undefined" };
+                // Node lowercases header names
+                let lookup = naming::quote(&header.name.to_ascii_lowercase());
+                args.push(format!(
+                    "@Headers({}) {}: string{}",
+                    lookup, variable, optional
+                ));
+                values.push(format!("{}: {}", key, variable));
+                types.push(format!(
+                    "{}{}: string",
+                    key,
+                    if header.required { "" } else { "?" }
+                ));
+            }
+            members.push(format!("headers: {{ {} }}", values.join(", ")));
+            fields.push(format!("  headers: {{ {} }};", types.join("; ")));
+        }
+
+        let cookies = located(ParameterLocation::Cookie);
+        if !cookies.is_empty() {
+            used.nest.insert("Req");
+            let mut values = Vec::new();
+            let mut types = Vec::new();
+            for cookie in cookies {
+                let key = naming::ts_property_key(&cookie.name);
+                values.push(format!(
+                    "{}: request.cookies?.[{}]",
+                    key,
+                    naming::quote(&cookie.name)
+                ));
+                types.push(format!("{}?: string", key));
+            }
+            args.push("@Req() request: { cookies?: Record<string, string> }".to_string());
+            members.push(format!("cookies: {{ {} }}", values.join(", ")));
+            fields.push(format!("  cookies: {{ {} }};", types.join("; ")));
+        }
+
+        if op.request_content_type.is_some() {
+            used.nest.insert("Body");
+            let kind = op
+                .request_content_type
+                .as_deref()
+                .map(BodyKind::from_content_type);
+            let json = kind == Some(BodyKind::Json);
+            let body_type = match &op.request_body {
+                Some(body) if json => schema_type(body, kinds),
+                _ if kind == Some(BodyKind::Text) => "string".to_string(),
+                _ => "unknown".to_string(),
+            };
+            // ValidationPipe checks a class, not an array of them
+            let pipe = match &op.request_body {
+                Some(body)
+                    if json
+                        && body.schema_type == "array"
+                        && matches!(kinds.get(body.name.as_str()), Some(Kind::Class)) =>
+                {
+                    used.nest.insert("ParseArrayPipe");
+                    format!(
+                        "new ParseArrayPipe({{ items: {} }})",
+                        reference_type(&body.name, kinds)
+                    )
+                }
+                _ => String::new(),
+            };
+            args.push(format!("@Body({}) body: {}", pipe, body_type));
+            members.push("body".to_string());
+            fields.push(format!("  body: {};", body_type));
+        }
+
+        if !fields.is_empty() {
+            inputs.push_str(&format!(
+                "export interface {}Input {{\n{}\n}}\n\n",
+                pascal,
+                fields.join("\n")
+            ));
+        }
+
+        let response = match &op.response {
+            _ if op.downloads() => {
+                used.nest.insert("StreamableFile");
+                "StreamableFile".to_string()
+            }
+            Some(response) => schema_type(response, kinds),
+            // Bodies without a named schema: text as sent, or their inline type
+            None => match op.returned_response() {
+                Some(returned)
+                    if returned
+                        .content_type
+                        .as_deref()
+                        .map(BodyKind::from_content_type)
+                        == Some(BodyKind::Text) =>
+                {
+                    "string".to_string()
+                }
+                Some(returned) => returned.inline_type.as_ref().map_or_else(
+                    || "unknown".to_string(),
+                    |type_info| ts_type(type_info, kinds),
+                ),
+                None => "void".to_string(),
+            },
+        };
+        let decorator = decorator_name(&op.method);
+        used.nest.insert(decorator);
+        let status = success_status(op);
+        if status.is_some() {
+            used.nest.insert("HttpCode");
+        }
+        let summary = op.summary.as_deref().unwrap_or(&op.id);
+        let doc = doc_style.jsdoc(&[Some(summary), op.description.as_deref()], &[], "  ");
+
+        let (service, controller) = tags.entry(tag(op)).or_default();
+        let (input, call) = match fields.is_empty() {
+            true => (String::new(), String::new()),
+            false => (
+                format!("input: {}Input", pascal),
+                format!("{{ {} }}", members.join(", ")),
+            ),
+        };
+        if !service.is_empty() {
+            service.push('\n');
+        }
+        if let Some(doc) = &doc {
+            service.push_str(&format!("  {}\n", doc));
+        }
+        service.push_str(&format!(
+            "  abstract {}({}): Promise<{}>;\n",
+            method, input, response
+        ));
+        if !controller.is_empty() {
+            controller.push('\n');
+        }
+        if op
+            .request_content_type
+            .as_deref()
+            .map(BodyKind::from_content_type)
+            == Some(BodyKind::Text)
+        {
+            controller.push_str(
+                "  // Nest parses only JSON and urlencoded bodies: register a text parser\n  \
+                 // (`app.use(express.text())`) or `body` is `{}` instead of the raw text\n",
+            );
+        }
+        if let Some(doc) = &doc {
+            controller.push_str(&format!("  {}\n", doc));
+        }
+        controller.push_str(&format!(
+            "  @{}({})\n",
+            decorator,
+            naming::quote(&route(&op.path))
+        ));
+        if let Some(status) = status {
+            controller.push_str(&format!("  @HttpCode({})\n", status));
+        }
+        let mut signature = args.join(", ");
+        // One decorated parameter per line once they stop fitting
+        if signature.len() > 80 {
+            signature = format!("\n    {},\n  ", args.join(",\n    "));
+        }
+        controller.push_str(&format!(
+            "  {}({}): Promise<{}> {{\n",
+            method, signature, response
+        ));
+        controller.push_str(&format!(
+            "    return this.service.{}({});\n  }}\n",
+            method, call
+        ));
+    }
+
+    let mut content = inputs;
+    for (tag, (service, controller)) in tags {
+        let name = naming::pascal_case(tag);
+        content.push_str(&format!(
+            "/** What `{0}Controller` calls; provide an implementation as `{0}Service` */\n",
+            name
+        ));
+        content.push_str(&format!(
+            "export abstract class {}Service {{\n{}}}\n\n",
+            name, service
+        ));
+        content.push_str(&format!(
+            "@Controller()\nexport class {0}Controller {{\n  constructor(private readonly service: {0}Service) {{}}\n\n",
+            name
+        ));
+        content.push_str(&format!("{}}}\n\n", controller));
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{InputParser, OpenApiParser};
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /notes/{id}:
+    parameters: [{ name: id, in: path, required: true, schema: { type: string } }]
+    put:
+      operationId: replaceNote
+      requestBody: { content: { text/plain: { schema: { type: string } } } }
+      responses: { "204": { description: done } }
+    patch:
+      operationId: patchNote
+      requestBody: { content: { application/json: { schema: { $ref: "#/components/schemas/Note" } } } }
+      responses: { "204": { description: done } }
+    get:
+      operationId: getNote
+      responses: { "200": { description: ok, content: { application/json: { schema: { $ref: "#/components/schemas/Note" } } } } }
+  /notes:
+    post:
+      operationId: createNote
+      requestBody: { content: { application/json: { schema: { $ref: "#/components/schemas/Note" } } } }
+      responses: { "201": { description: made } }
+  /search:
+    post:
+      operationId: search
+      responses: { "200": { description: ok, content: { application/json: { schema: { $ref: "#/components/schemas/Note" } } } } }
+components:
+  schemas:
+    Note: { type: object, properties: { text: { type: string } } }
+"##;
+
+    fn ir() -> SchemaIR {
+        OpenApiParser.parse_str(SPEC, &HashMap::new()).unwrap()
+    }
+
+    #[test]
+    fn success_status_is_set_only_when_nest_would_send_another() {
+        let ir = ir();
+        let statuses: Vec<(&str, Option<u16>)> = ir
+            .operations
+            .iter()
+            .map(|op| (op.id.as_str(), success_status(op)))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("getNote", None),
+                ("replaceNote", Some(204)),
+                ("patchNote", Some(204)),
+                ("createNote", None),
+                ("search", Some(200)),
+            ]
+        );
+    }
+
+    #[test]
+    fn controllers_declare_http_codes_and_note_text_bodies() {
+        let config: GenerationConfig =
+            serde_yaml::from_str("{ generator: typescript_nestjs, outputFile: nest.ts }").unwrap();
+        let content = TypeScriptNestjsGenerator
+            .generate_from_ir(&ir(), &config)
+            .unwrap()
+            .content;
+        assert!(content.contains("import { Body, Controller, Get, HttpCode,"));
+        assert!(content.contains("  @Patch(\"notes/:id\")\n  @HttpCode(204)\n"));
+        assert!(content.contains("  @Post(\"notes\")\n  createNote("));
+        assert!(content.contains(
+            "  // (`app.use(express.text())`) or `body` is `{}` instead of the raw text\n  \
+             /** replaceNote */\n  @Put(\"notes/:id\")\n  @HttpCode(204)\n"
+        ));
+        assert_eq!(content.matches("express.text()").count(), 1);
+    }
+}
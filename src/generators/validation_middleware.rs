@@ -0,0 +1,694 @@
+//! Request validation middleware for servers implementing the spec, hand-written
+//! or not (`validation_middleware` generator):
+//! - `express` (default), `fastify`: a TypeScript module validating against the
+//!   `{operation}Config` schemas of the `typescript` client at `clientImport`
+//! - `asgi`: a Python ASGI middleware validating parameters and JSON bodies
+//!   against the models of the `python` client at `modelsModule`
+//!
+//! Requests are matched to operations by method and path. Those that fail get
+//! a 400 with a JSON `{"error": "ValidationError", "issues": [...]}` body, each
+//! issue naming its `location` (`path`, `query`, `header`, `cookie`, `body`),
+//! the `path` to the value inside it, and a `message`. Requests no operation
+//! matches pass through untouched.
+
+use super::naming::{self, Language};
+use super::request_encoding::Plan;
+use super::ts_imports::ImportStyle;
+use super::validators::{Dialect, ValidationLibrary};
+use super::{GeneratedOutput, Generator};
+use crate::config::GenerationConfig;
+use crate::parsers::{BodyKind, OperationDefinition, ParameterLocation, SchemaIR};
+use anyhow::Result;
+use std::collections::HashMap;
+
+pub struct ValidationMiddlewareGenerator;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Framework {
+    Express,
+    Fastify,
+    Asgi,
+}
+
+impl Framework {
+    fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("framework").map(|v| v.as_str()) {
+            None | Some(Some("express")) => Ok(Self::Express),
+            Some(Some("fastify")) => Ok(Self::Fastify),
+            Some(Some("asgi")) => Ok(Self::Asgi),
+            _ => anyhow::bail!(
+                "{}: option 'framework' must be \"express\", \"fastify\", or \"asgi\"",
+                config.generator
+            ),
+        }
+    }
+}
+
+impl Generator for ValidationMiddlewareGenerator {
+    fn name(&self) -> &str {
+        "validation_middleware"
+    }
+
+    fn file_extension(&self) -> &str {
+        "ts"
+    }
+
+    fn generate_from_ir(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<GeneratedOutput> {
+        let content = match Framework::from_config(config)? {
+            Framework::Asgi => generate_asgi(schema_ir, config),
+            framework => generate_typescript(
+                schema_ir,
+                config,
+                framework,
+                &Dialect::from_config(config)?,
+                &ImportStyle::from_config(config)?,
+            ),
+        };
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content,
+            metadata: HashMap::new(),
+            companions: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn supports_unions(&self) -> bool {
+        true
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        if Framework::from_config(config)? != Framework::Asgi {
+            Dialect::from_config(config)?;
+            ImportStyle::from_config(config)?;
+        }
+        Ok(())
+    }
+}
+
+/// Operations in matching order: literal path segments win over parameters,
+/// so `/users/me` is tried before `/users/{id}`
+fn routes(schema_ir: &SchemaIR) -> Vec<&OperationDefinition> {
+    let mut operations: Vec<_> = schema_ir.operations.iter().collect();
+    operations.sort_by_key(|op| op.path.matches('{').count());
+    operations
+}
+
+/// Anchored regular expression for a path template, one capture group per
+/// parameter, and the parameter names in capture order
+fn path_pattern(path: &str) -> (String, Vec<String>) {
+    let mut pattern = String::from("^");
+    let mut names = Vec::new();
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        push_escaped(&mut pattern, &rest[..start]);
+        pattern.push_str("([^/]+)");
+        names.push(rest[start + 1..end].to_string());
+        rest = &rest[end + 1..];
+    }
+    push_escaped(&mut pattern, rest);
+    pattern.push('$');
+    (pattern, names)
+}
+
+fn push_escaped(pattern: &mut String, literal: &str) {
+    for c in literal.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+}
+
+/// Whether the request body is JSON, the only kind validated
+fn json_body(operation: &OperationDefinition) -> bool {
+    operation
+        .request_content_type
+        .as_deref()
+        .is_some_and(|content_type| BodyKind::from_content_type(content_type) == BodyKind::Json)
+}
+
+fn generate_typescript(
+    schema_ir: &SchemaIR,
+    config: &GenerationConfig,
+    framework: Framework,
+    dialect: &Dialect,
+    import_style: &ImportStyle,
+) -> String {
+    let client_import = config
+        .options
+        .get("clientImport")
+        .and_then(|v| v.as_str())
+        .unwrap_or("./client");
+    let operations = routes(schema_ir);
+
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "// Generated Request Validation Middleware for {}\n",
+        schema_ir.metadata.title
+    ));
+    output.push_str(&format!("// Version: {}\n\n", schema_ir.metadata.version));
+
+    let (schema_type, check) = match dialect.library {
+        ValidationLibrary::Zod => {
+            output.push_str(&import_style.types(&["z"], "zod"));
+            ("z.ZodType", ZOD_CHECK)
+        }
+        ValidationLibrary::Valibot => {
+            output.push_str(&import_style.namespace("v", "valibot"));
+            ("v.GenericSchema", VALIBOT_CHECK)
+        }
+        ValidationLibrary::Arktype => {
+            output.push_str(&import_style.values(&["type"], "arktype"));
+            ("(value: unknown) => unknown", ARKTYPE_CHECK)
+        }
+    };
+    match framework {
+        Framework::Fastify => {
+            output.push_str(&import_style.types(&["FastifyReply", "FastifyRequest"], "fastify"))
+        }
+        _ => output
+            .push_str(&import_style.types(&["NextFunction", "Request", "Response"], "express")),
+    }
+    let mut configs: Vec<String> = operations
+        .iter()
+        .map(|op| {
+            format!(
+                "{}Config",
+                naming::function_name(&op.id, Language::TypeScript)
+            )
+        })
+        .collect();
+    configs.sort();
+    if !configs.is_empty() {
+        let names: Vec<&str> = configs.iter().map(String::as_str).collect();
+        output.push_str(&import_style.values(&names, client_import));
+    }
+
+    output.push_str(TYPESCRIPT_TYPES);
+    output.push_str(&format!("\ntype Schema = {};\n\n", schema_type));
+    output.push_str(TYPESCRIPT_ROUTE);
+
+    output.push_str("\nconst routes: Route[] = [\n");
+    for operation in &operations {
+        let (pattern, path_names) = path_pattern(&operation.path);
+        let header_names: Vec<String> = operation
+            .parameters
+            .iter()
+            .filter(|p| p.location == ParameterLocation::Header)
+            .map(|p| naming::quote(&p.name))
+            .collect();
+        output.push_str(&format!(
+            "  {{ method: '{}', pattern: /{}/, pathNames: [{}], headerNames: [{}], jsonBody: {}, config: {}Config }},\n",
+            format!("{:?}", operation.method).to_uppercase(),
+            pattern.replace('/', "\\/"),
+            path_names.iter().map(|name| naming::quote(name)).collect::<Vec<_>>().join(", "),
+            header_names.join(", "),
+            json_body(operation),
+            naming::function_name(&operation.id, Language::TypeScript)
+        ));
+    }
+    output.push_str("];\n\n");
+
+    output.push_str(check);
+    output.push_str(TYPESCRIPT_VALIDATE);
+    output.push_str(match framework {
+        Framework::Fastify => FASTIFY_HOOK,
+        _ => EXPRESS_MIDDLEWARE,
+    });
+    output
+}
+
+const TYPESCRIPT_TYPES: &str = r#"
+/** One way a request does not match its operation */
+export interface ValidationIssue {
+  location: 'path' | 'query' | 'header' | 'cookie' | 'body';
+  /** Dot-separated path to the value inside the location; the parameter name for parameters */
+  path: string;
+  message: string;
+}
+
+/** Body of the 400 responses sent for invalid requests */
+export interface ValidationErrorBody {
+  error: 'ValidationError';
+  issues: ValidationIssue[];
+}
+
+/** What validation reads from a request, whatever the framework parsed it with */
+export interface IncomingRequest {
+  method: string;
+  /** Path relative to where the API's paths start, without the query string */
+  path: string;
+  query?: unknown;
+  headers?: Record<string, string | string[] | undefined>;
+  cookies?: Record<string, string | undefined>;
+  body?: unknown;
+}
+"#;
+
+const TYPESCRIPT_ROUTE: &str = r#"type Group = 'query' | 'path' | 'headers' | 'cookies';
+
+interface Route {
+  method: string;
+  pattern: RegExp;
+  pathNames: string[];
+  headerNames: string[];
+  jsonBody: boolean;
+  config: {
+    parameters?: { [group in Group]?: { schema: Schema } };
+    requestBody?: { schema: Schema };
+  };
+}
+
+const groups: [Group, ValidationIssue['location']][] = [
+  ['query', 'query'],
+  ['path', 'path'],
+  ['headers', 'header'],
+  ['cookies', 'cookie'],
+];
+"#;
+
+const ZOD_CHECK: &str = r#"function check(schema: Schema, value: unknown, location: ValidationIssue['location'], issues: ValidationIssue[]): void {
+  const result = schema.safeParse(value);
+  if (!result.success) {
+    for (const issue of result.error.issues) {
+      issues.push({ location, path: issue.path.map(String).join('.'), message: issue.message });
+    }
+  }
+}
+"#;
+
+const VALIBOT_CHECK: &str = r#"function check(schema: Schema, value: unknown, location: ValidationIssue['location'], issues: ValidationIssue[]): void {
+  const result = v.safeParse(schema, value);
+  if (!result.success) {
+    for (const issue of result.issues) {
+      issues.push({ location, path: v.getDotPath(issue) ?? '', message: issue.message });
+    }
+  }
+}
+"#;
+
+const ARKTYPE_CHECK: &str = r#"function check(schema: Schema, value: unknown, location: ValidationIssue['location'], issues: ValidationIssue[]): void {
+  const result = schema(value);
+  if (result instanceof type.errors) {
+    for (const error of result) {
+      issues.push({ location, path: error.path.map(String).join('.'), message: error.message });
+    }
+  }
+}
+"#;
+
+const TYPESCRIPT_VALIDATE: &str = r#"
+function decode(value: string): string {
+  try {
+    return decodeURIComponent(value);
+  } catch {
+    return value;
+  }
+}
+
+/**
+ * Issues with a request against the operation its method and path match
+ * (empty when it is valid), or `undefined` when no operation matches. Header
+ * names match case-insensitively; bodies are validated when present.
+ */
+export function validateRequest(request: IncomingRequest): ValidationIssue[] | undefined {
+  const method = request.method.toUpperCase();
+  for (const route of routes) {
+    const match = route.method === method ? route.pattern.exec(request.path) : null;
+    if (!match) continue;
+
+    const headers: Record<string, string | string[] | undefined> = {};
+    for (const [name, value] of Object.entries(request.headers ?? {})) {
+      headers[name.toLowerCase()] = value;
+    }
+    const values: Record<Group, unknown> = {
+      query: request.query ?? {},
+      path: Object.fromEntries(route.pathNames.map((name, i) => [name, decode(match[i + 1] ?? '')])),
+      headers: Object.fromEntries(
+        route.headerNames.flatMap((name) => {
+          const value = headers[name.toLowerCase()];
+          return value === undefined ? [] : [[name, value]];
+        }),
+      ),
+      cookies: request.cookies ?? {},
+    };
+
+    const issues: ValidationIssue[] = [];
+    for (const [group, location] of groups) {
+      const parameters = route.config.parameters?.[group];
+      if (parameters) check(parameters.schema, values[group], location, issues);
+    }
+    if (route.jsonBody && route.config.requestBody && request.body !== undefined) {
+      check(route.config.requestBody.schema, request.body, 'body', issues);
+    }
+    return issues;
+  }
+  return undefined;
+}
+
+function validationError(issues: ValidationIssue[]): ValidationErrorBody {
+  return { error: 'ValidationError', issues };
+}
+"#;
+
+const EXPRESS_MIDDLEWARE: &str = r#"
+/**
+ * Express middleware answering requests that fail validation with 400 and a
+ * `ValidationErrorBody`. Mount it where the API's paths start, after
+ * `express.json()` (and `cookie-parser` for cookie parameters).
+ */
+export function validationMiddleware() {
+  return (req: Request, res: Response, next: NextFunction): void => {
+    const issues = validateRequest({
+      method: req.method,
+      path: req.path,
+      query: req.query,
+      headers: req.headers,
+      cookies: (req as Request & { cookies?: Record<string, string | undefined> }).cookies,
+      body: req.body,
+    });
+    if (issues?.length) {
+      res.status(400).json(validationError(issues));
+      return;
+    }
+    next();
+  };
+}
+"#;
+
+const FASTIFY_HOOK: &str = r#"
+/**
+ * Fastify `preValidation` hook answering requests that fail validation with
+ * 400 and a `ValidationErrorBody`: `app.addHook('preValidation', validationHook())`.
+ * `basePath` is stripped from request URLs before matching; cookie parameters
+ * need `@fastify/cookie`.
+ */
+export function validationHook(basePath = '') {
+  return async (request: FastifyRequest, reply: FastifyReply) => {
+    const url = request.url.split('?')[0] ?? request.url;
+    const issues = validateRequest({
+      method: request.method,
+      path: url.startsWith(basePath) ? url.slice(basePath.length) : url,
+      query: request.query,
+      headers: request.headers,
+      cookies: (request as FastifyRequest & { cookies?: Record<string, string | undefined> }).cookies,
+      body: request.body,
+    });
+    return issues?.length ? reply.code(400).send(validationError(issues)) : undefined;
+  };
+}
+"#;
+
+fn generate_asgi(schema_ir: &SchemaIR, config: &GenerationConfig) -> String {
+    let models_module = config
+        .options
+        .get("modelsModule")
+        .and_then(|v| v.as_str())
+        .unwrap_or("client");
+    let operations = routes(schema_ir);
+
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "# Generated Request Validation Middleware for {}\n",
+        schema_ir.metadata.title
+    ));
+    output.push_str(&format!("# Version: {}\n\n", schema_ir.metadata.version));
+
+    output.push_str("import json\n");
+    output.push_str("import re\n");
+    output.push_str("from datetime import date, datetime\n");
+    output.push_str("from http.cookies import SimpleCookie\n");
+    output.push_str(
+        "from typing import Any, Awaitable, Callable, Dict, List, NamedTuple, Optional, Tuple\n",
+    );
+    output.push_str("from urllib.parse import parse_qs, unquote\n\n");
+    output.push_str("from pydantic import TypeAdapter, ValidationError\n");
+
+    let mut models: Vec<String> = operations
+        .iter()
+        .filter(|op| json_body(op))
+        .filter_map(|op| op.request_body.as_ref())
+        .map(|b| naming::type_name(&b.name, Language::Python))
+        .collect();
+    models.sort();
+    models.dedup();
+    if !models.is_empty() {
+        output.push_str(&format!(
+            "\nfrom {} import {}\n",
+            models_module,
+            models.join(", ")
+        ));
+    }
+
+    output.push_str(PYTHON_ROUTE);
+
+    output.push_str("\n_ROUTES = [\n");
+    for operation in &operations {
+        let (pattern, path_names) = path_pattern(&operation.path);
+        let body = match operation
+            .request_body
+            .as_ref()
+            .filter(|_| json_body(operation))
+        {
+            Some(body) if body.schema_type == "array" => format!(
+                "TypeAdapter(List[{}])",
+                naming::type_name(&body.name, Language::Python)
+            ),
+            Some(body) => format!(
+                "TypeAdapter({})",
+                naming::type_name(&body.name, Language::Python)
+            ),
+            None => "None".to_string(),
+        };
+        output.push_str(&format!(
+            "    _Route(\"{}\", re.compile({}), [{}], [{}",
+            format!("{:?}", operation.method).to_uppercase(),
+            naming::quote(&pattern),
+            path_names
+                .iter()
+                .map(|name| naming::quote(name))
+                .collect::<Vec<_>>()
+                .join(", "),
+            if operation.parameters.is_empty() {
+                ""
+            } else {
+                "\n"
+            }
+        ));
+        for param in &operation.parameters {
+            let encoding = Plan::of(param);
+            output.push_str(&format!(
+                "        _Parameter({}, \"{}\", {}, \"{}\", {}),\n",
+                naming::quote(&param.name),
+                format!("{:?}", param.location).to_lowercase(),
+                if param.required || param.location == ParameterLocation::Path {
+                    "True"
+                } else {
+                    "False"
+                },
+                encoding.scalar,
+                match (encoding.array, encoding.repeat) {
+                    (true, false) => naming::quote(encoding.separator),
+                    _ => "None".to_string(),
+                }
+            ));
+        }
+        let indent = if operation.parameters.is_empty() {
+            ""
+        } else {
+            "    "
+        };
+        output.push_str(&format!("{}], {}),\n", indent, body));
+    }
+    output.push_str("]\n");
+
+    output.push_str(PYTHON_VALIDATE);
+    output
+}
+
+const PYTHON_ROUTE: &str = r#"
+
+class _Parameter(NamedTuple):
+    name: str
+    location: str
+    required: bool
+    # "string", "integer", "number", "boolean", "date", or "date-time", of
+    # the value or of each item of an array
+    scalar: str
+    # Joins the items of an array sent as one value; None otherwise
+    separator: Optional[str]
+
+
+class _Route(NamedTuple):
+    method: str
+    pattern: "re.Pattern[str]"
+    path_names: List[str]
+    parameters: List[_Parameter]
+    body: Optional[TypeAdapter]
+
+"#;
+
+const PYTHON_VALIDATE: &str = r#"
+
+def _issue(location: str, path: str, message: str) -> Dict[str, str]:
+    return {"location": location, "path": path, "message": message}
+
+
+def _check_scalar(value: str, scalar: str) -> Optional[str]:
+    """Why `value` is not a valid `scalar`, or None when it is"""
+    try:
+        if scalar == "integer":
+            int(value)
+        elif scalar == "number":
+            float(value)
+        elif scalar == "boolean" and value not in ("true", "false"):
+            return "must be true or false"
+        elif scalar == "date":
+            date.fromisoformat(value)
+        elif scalar == "date-time":
+            datetime.fromisoformat(value.replace("Z", "+00:00"))
+    except ValueError:
+        return f"must be a valid {scalar}"
+    return None
+
+
+def _match(method: str, path: str) -> Optional[Tuple[_Route, Dict[str, str]]]:
+    """Operation a request's method and path match, with its path parameters"""
+    for route in _ROUTES:
+        match = route.pattern.match(path) if route.method == method.upper() else None
+        if match is not None:
+            return route, {name: unquote(value) for name, value in zip(route.path_names, match.groups())}
+    return None
+
+
+def _validate(
+    route: _Route,
+    path_values: Dict[str, str],
+    query: Dict[str, List[str]],
+    headers: Dict[str, str],
+    cookies: Dict[str, str],
+    body: bytes,
+) -> List[Dict[str, str]]:
+    sources: Dict[str, Dict[str, Any]] = {
+        "path": path_values,
+        "query": query,
+        "header": {name.lower(): value for name, value in headers.items()},
+        "cookie": cookies,
+    }
+    issues = []
+    for parameter in route.parameters:
+        key = parameter.name.lower() if parameter.location == "header" else parameter.name
+        value = sources[parameter.location].get(key)
+        if value is None:
+            if parameter.required:
+                issues.append(_issue(parameter.location, parameter.name, "is required"))
+            continue
+        values = value if isinstance(value, list) else [value]
+        if parameter.separator is not None:
+            values = [item for joined in values for item in joined.split(parameter.separator)]
+        for item in values:
+            message = _check_scalar(item, parameter.scalar)
+            if message is not None:
+                issues.append(_issue(parameter.location, parameter.name, message))
+                break
+    if route.body is not None and body:
+        try:
+            route.body.validate_json(body)
+        except ValidationError as error:
+            issues.extend(
+                _issue("body", ".".join(str(part) for part in detail["loc"]), detail["msg"])
+                for detail in error.errors()
+            )
+    return issues
+
+
+def validate_request(
+    method: str,
+    path: str,
+    query: Dict[str, List[str]],
+    headers: Dict[str, str],
+    cookies: Dict[str, str],
+    body: bytes = b"",
+) -> Optional[List[Dict[str, str]]]:
+    """Issues with a request against the operation its method and path match
+    (empty when it is valid), or None when no operation matches. `query` maps
+    names to every value sent (as `parse_qs` returns them); header names match
+    case-insensitively; JSON bodies are validated when present."""
+    matched = _match(method, path)
+    if matched is None:
+        return None
+    route, path_values = matched
+    return _validate(route, path_values, query, headers, cookies, body)
+
+
+async def _read_body(receive: Callable[[], Awaitable[Dict[str, Any]]]) -> bytes:
+    chunks = []
+    while True:
+        message = await receive()
+        chunks.append(message.get("body", b""))
+        if not message.get("more_body", False):
+            return b"".join(chunks)
+
+
+class ValidationMiddleware:
+    """ASGI middleware answering HTTP requests that fail validation with 400
+    and a JSON {"error": "ValidationError", "issues": [...]} body; requests no
+    operation matches pass through. Paths are matched below the scope's
+    `root_path`."""
+
+    def __init__(self, app: Any) -> None:
+        self.app = app
+
+    async def __call__(self, scope: Dict[str, Any], receive: Any, send: Any) -> None:
+        root_path = scope.get("root_path", "")
+        path = scope.get("path", "")
+        if root_path and path.startswith(root_path):
+            path = path[len(root_path):]
+        matched = _match(scope["method"], path) if scope["type"] == "http" else None
+        if matched is None:
+            await self.app(scope, receive, send)
+            return
+        route, path_values = matched
+
+        body = await _read_body(receive) if route.body is not None else b""
+        headers = {name.decode("latin-1").lower(): value.decode("latin-1") for name, value in scope["headers"]}
+        cookies = {name: morsel.value for name, morsel in SimpleCookie(headers.get("cookie", "")).items()}
+        query = parse_qs(scope.get("query_string", b"").decode("latin-1"), keep_blank_values=True)
+
+        issues = _validate(route, path_values, query, headers, cookies, body)
+        if issues:
+            payload = json.dumps({"error": "ValidationError", "issues": issues}).encode()
+            await send({
+                "type": "http.response.start",
+                "status": 400,
+                "headers": [(b"content-type", b"application/json"), (b"content-length", str(len(payload)).encode())],
+            })
+            await send({"type": "http.response.body", "body": payload})
+            return
+
+        if route.body is None:
+            await self.app(scope, receive, send)
+            return
+
+        replayed = False
+
+        async def replay() -> Dict[str, Any]:
+            nonlocal replayed
+            if replayed:
+                return await receive()
+            replayed = True
+            return {"type": "http.request", "body": body, "more_body": False}
+
+        await self.app(scope, replay, send)
+"#;
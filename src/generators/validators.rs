@@ -0,0 +1,484 @@
+//! IR-to-validator mapping for the `typescript` generator
+//! (`validationLibrary` option: `zod`, `valibot`, or `arktype`).
+//!
+//! Each library is a [`Dialect`]: a table of patterns where `{}` stands for
+//! the nested expression. [`Dialect::expression`] walks a `TypeInfo` through
+//! the table, and the template reads the same table (as `v`) for the pieces
+//! it builds itself, so adding a library is mostly adding a table.
+
+use super::inline_schemas::Inlined;
+use super::naming::{self, Language};
+use super::ts_imports::ImportStyle;
+use super::ts_optional::Presence;
+use super::zod::ZodVersion;
+use crate::config::GenerationConfig;
+use crate::parsers::{SchemaDefinition, TypeInfo};
+use anyhow::Result;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationLibrary {
+    Zod,
+    Valibot,
+    Arktype,
+}
+
+impl ValidationLibrary {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("validationLibrary").map(|v| v.as_str()) {
+            None | Some(Some("zod")) => Ok(Self::Zod),
+            Some(Some("valibot")) => Ok(Self::Valibot),
+            Some(Some("arktype")) => Ok(Self::Arktype),
+            _ => anyhow::bail!(
+                "{}: option 'validationLibrary' must be \"zod\", \"valibot\", or \"arktype\"",
+                config.generator
+            ),
+        }
+    }
+}
+
+/// Whether client methods parse JSON responses through their schema at
+/// runtime or only type them (`validateResponses` option: `true`, `false`
+/// (default), or `"dev"` for every `NODE_ENV` but `production`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseValidation {
+    Off,
+    Always,
+    Dev,
+}
+
+impl ResponseValidation {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        match config.options.get("validateResponses") {
+            None => Ok(Self::Off),
+            Some(value) => match (value.as_bool(), value.as_str()) {
+                (Some(false), _) => Ok(Self::Off),
+                (Some(true), _) => Ok(Self::Always),
+                (_, Some("dev")) => Ok(Self::Dev),
+                _ => anyhow::bail!(
+                    "{}: option 'validateResponses' must be true, false, or \"dev\"",
+                    config.generator
+                ),
+            },
+        }
+    }
+}
+
+/// How one validation library spells each construct.
+///
+/// Type-level entries (`string` through `nullable`) compose into field
+/// expressions; for arktype these are its string syntax, which `value` turns
+/// into a definition. Runtime entries (`object` onwards) build standalone
+/// schemas around named ones (`UserSchema`).
+#[derive(Debug, Serialize)]
+pub struct Dialect {
+    pub library: ValidationLibrary,
+    pub string: &'static str,
+    pub number: &'static str,
+    pub boolean: &'static str,
+    pub any: &'static str,
+    pub date: &'static str,
+    pub email: &'static str,
+    pub uuid: &'static str,
+    pub url: &'static str,
+    pub array: &'static str,
+    pub tuple: &'static str,
+    /// String-keyed map of `{}` values
+    pub record: &'static str,
+    pub union: &'static str,
+    pub union_separator: &'static str,
+    pub enumeration: &'static str,
+    pub enum_separator: &'static str,
+    /// Reference to another schema by type name
+    pub reference: &'static str,
+    /// Unchecked value of the external type `{}` (`typeOverrides` option)
+    pub custom: &'static str,
+    /// `None` when optionality is marked on the key (`"name?"`)
+    pub optional: Option<&'static str>,
+    pub nullable: &'static str,
+    /// Optional and nullable at once; `None` when optionality is marked on the key
+    pub nullish: Option<&'static str>,
+    /// Expression in value position (object property, scope entry)
+    pub value: &'static str,
+    /// Object schema around `{ ...properties }`
+    pub object: &'static str,
+    pub optional_object: &'static str,
+    pub runtime_array: &'static str,
+    pub runtime_any: &'static str,
+    pub runtime_string: &'static str,
+    /// Query/path values arrive as strings
+    pub coerce_number: &'static str,
+    pub coerce_boolean: &'static str,
+    /// Output type of the schema named `{}`
+    pub infer: &'static str,
+    /// Type of a schema producing `{}`
+    pub schema_type: &'static str,
+    /// Validate `{value}` against `{schema}`, throwing on failure
+    pub parse: &'static str,
+}
+
+impl Dialect {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        Ok(match ValidationLibrary::from_config(config)? {
+            ValidationLibrary::Zod => Self::zod(ZodVersion::from_config(config)?),
+            ValidationLibrary::Valibot => Self::valibot(),
+            ValidationLibrary::Arktype => Self::arktype(),
+        })
+    }
+
+    fn zod(version: ZodVersion) -> Self {
+        Self {
+            library: ValidationLibrary::Zod,
+            string: "z.string()",
+            number: "z.number()",
+            boolean: "z.boolean()",
+            any: "z.any()",
+            date: "z.date().or(z.string())",
+            email: version.email(),
+            uuid: version.uuid(),
+            url: version.url(),
+            array: "z.array({})",
+            tuple: "z.tuple([{}])",
+            record: version.record_pattern(),
+            union: "z.union([{}])",
+            union_separator: ", ",
+            enumeration: "z.enum([{}])",
+            enum_separator: ", ",
+            reference: "z.lazy(() => {}Schema)",
+            custom: "z.custom<{}>()",
+            optional: Some("{}.optional()"),
+            nullable: "{}.nullable()",
+            nullish: Some("{}.nullish()"),
+            value: "{}",
+            object: "z.object({})",
+            optional_object: "{}.optional()",
+            runtime_array: "z.array({})",
+            runtime_any: "z.any()",
+            runtime_string: "z.string()",
+            coerce_number: "z.coerce.number()",
+            coerce_boolean: "z.coerce.boolean()",
+            infer: "z.infer<typeof {}>",
+            schema_type: "z.ZodType<{}>",
+            parse: "{schema}.parse({value})",
+        }
+    }
+
+    fn valibot() -> Self {
+        Self {
+            library: ValidationLibrary::Valibot,
+            string: "v.string()",
+            number: "v.number()",
+            boolean: "v.boolean()",
+            any: "v.any()",
+            date: "v.union([v.date(), v.string()])",
+            email: "v.pipe(v.string(), v.email())",
+            uuid: "v.pipe(v.string(), v.uuid())",
+            url: "v.pipe(v.string(), v.url())",
+            array: "v.array({})",
+            tuple: "v.tuple([{}])",
+            record: "v.record(v.string(), {})",
+            union: "v.union([{}])",
+            union_separator: ", ",
+            enumeration: "v.picklist([{}])",
+            enum_separator: ", ",
+            reference: "v.lazy(() => {}Schema)",
+            custom: "v.custom<{}>(() => true)",
+            optional: Some("v.optional({})"),
+            nullable: "v.nullable({})",
+            nullish: Some("v.nullish({})"),
+            value: "{}",
+            object: "v.object({})",
+            optional_object: "v.optional({})",
+            runtime_array: "v.array({})",
+            runtime_any: "v.any()",
+            runtime_string: "v.string()",
+            coerce_number: "v.pipe(v.string(), v.transform(Number))",
+            coerce_boolean: "v.pipe(v.string(), v.transform((value) => value === 'true'))",
+            infer: "v.InferOutput<typeof {}>",
+            schema_type: "v.GenericSchema<{}>",
+            parse: "v.parse({schema}, {value})",
+        }
+    }
+
+    fn arktype() -> Self {
+        Self {
+            library: ValidationLibrary::Arktype,
+            string: "string",
+            number: "number",
+            boolean: "boolean",
+            any: "unknown",
+            date: "Date | string",
+            email: "string.email",
+            uuid: "string.uuid",
+            url: "string.url",
+            array: "{}[]",
+            tuple: "[{}]",
+            record: "Record<string, {}>",
+            union: "{}",
+            union_separator: " | ",
+            enumeration: "{}",
+            enum_separator: " | ",
+            // Schemas share one scope, so references resolve by name
+            reference: "{}",
+            // String definitions cannot name external types
+            custom: "unknown",
+            optional: None,
+            nullable: "{} | null",
+            nullish: None,
+            value: "\"{}\"",
+            object: "type({})",
+            optional_object: "{}.or(\"undefined\")",
+            runtime_array: "{}.array()",
+            runtime_any: "type(\"unknown\")",
+            runtime_string: "type(\"string\")",
+            coerce_number: "type(\"string.numeric.parse\")",
+            coerce_boolean: "type(\"'true' | 'false'\").pipe((value) => value === 'true')",
+            infer: "typeof {}.infer",
+            schema_type: "Type<{}>",
+            parse: "{schema}.assert({value})",
+        }
+    }
+
+    /// Import lines for the library
+    pub fn imports(&self, style: &ImportStyle) -> String {
+        match self.library {
+            ValidationLibrary::Zod => style.values(&["z"], "zod"),
+            ValidationLibrary::Valibot => style.namespace("v", "valibot"),
+            ValidationLibrary::Arktype => {
+                let mut imports = style.values(&["scope", "type"], "arktype");
+                imports.push_str(&style.types(&["Type"], "arktype"));
+                imports
+            }
+        }
+    }
+
+    /// Object property `(key, value)` for a field, with optionality and nullability applied
+    pub fn property(
+        &self,
+        name: &str,
+        type_info: &TypeInfo,
+        presence: Presence,
+        inlined: &Inlined,
+    ) -> (String, String) {
+        let expression = self.expression(type_info, inlined);
+        match self.optional {
+            Some(optional) => {
+                let mut value = fill(self.value, &expression);
+                let nullish = match presence {
+                    Presence::Nullish => self.nullish,
+                    // valibot lets a key be absent only when its outermost schema
+                    // is optional or nullish, so `v.nullable(v.optional(...))`
+                    // would still require it
+                    Presence::OptionalNullable if self.library == ValidationLibrary::Valibot => {
+                        self.nullish
+                    }
+                    _ => None,
+                };
+                if let Some(nullish) = nullish {
+                    return (naming::ts_property_key(name), fill(nullish, &value));
+                }
+                if presence.optional() {
+                    value = fill(optional, &value);
+                }
+                if presence.nullable() {
+                    value = fill(self.nullable, &value);
+                }
+                (naming::ts_property_key(name), value)
+            }
+            None => {
+                let expression = if presence.nullable() {
+                    fill(self.nullable, &expression)
+                } else {
+                    expression
+                };
+                (
+                    self.property_key(name, !presence.optional()),
+                    fill(self.value, &self.escape(&expression)),
+                )
+            }
+        }
+    }
+
+    /// Object property `(key, value)` for a runtime schema expression
+    pub fn runtime_property(&self, name: &str, schema: &str, required: bool) -> (String, String) {
+        match (required, self.optional) {
+            (false, Some(optional)) => (naming::ts_property_key(name), fill(optional, schema)),
+            _ => (self.property_key(name, required), schema.to_string()),
+        }
+    }
+
+    fn property_key(&self, name: &str, required: bool) -> String {
+        if required || self.optional.is_some() {
+            naming::ts_property_key(name)
+        } else {
+            naming::ts_property_key(&format!("{}?", name))
+        }
+    }
+
+    /// `(open, close)` around `{ properties }` for an object schema extending
+    /// `parents` (type names), and the parent spreads leading its properties
+    pub fn extension(&self, parents: &[String]) -> (String, String, Vec<String>) {
+        let (open, close) = self.object.split_once("{}").unwrap_or(("", ""));
+        let Some((first, rest)) = parents.split_first() else {
+            return (open.to_string(), close.to_string(), Vec::new());
+        };
+
+        match self.library {
+            ValidationLibrary::Zod => {
+                let mut open = format!("{}Schema", first);
+                for parent in rest {
+                    open.push_str(&format!(".extend({}Schema.shape)", parent));
+                }
+                open.push_str(".extend(");
+                (open, ")".to_string(), Vec::new())
+            }
+            ValidationLibrary::Valibot => {
+                let spreads = parents
+                    .iter()
+                    .map(|p| format!("...{}Schema.entries", p))
+                    .collect();
+                (open.to_string(), close.to_string(), spreads)
+            }
+            // Scope entries spread their parents by name
+            ValidationLibrary::Arktype => {
+                let spread = format!("\"...\": {}", naming::quote(&parents.join(" & ")));
+                (open.to_string(), close.to_string(), vec![spread])
+            }
+        }
+    }
+
+    /// Type-level expression for `type_info`; references to `inlined` schemas
+    /// are replaced by their object expression
+    pub fn expression(&self, type_info: &TypeInfo, inlined: &Inlined) -> String {
+        if !type_info.tuple_items.is_empty() {
+            let items: Vec<String> = type_info
+                .tuple_items
+                .iter()
+                .map(|t| self.expression(t, inlined))
+                .collect();
+            return fill(self.tuple, &items.join(", "));
+        }
+
+        if type_info.is_array {
+            let item = match &type_info.array_item_type {
+                Some(item_type) => self.expression(item_type, inlined),
+                None => self.any.to_string(),
+            };
+            return fill(self.array, &self.group(item));
+        }
+
+        if let Some(ref_name) = &type_info.reference {
+            if let Some(expression) = inlined.get(ref_name) {
+                return expression.clone();
+            }
+            return fill(
+                self.reference,
+                &naming::type_name(ref_name, Language::TypeScript),
+            );
+        }
+
+        if let Some(enum_vals) = &type_info.enum_values {
+            let values: Vec<String> = enum_vals.iter().map(|v| self.literal(v)).collect();
+            return fill(self.enumeration, &values.join(self.enum_separator));
+        }
+
+        match type_info.openapi_type.as_str() {
+            "string" => match type_info.format.as_deref() {
+                Some("date" | "date-time") => self.date.to_string(),
+                Some("email") => self.email.to_string(),
+                Some("uuid") => self.uuid.to_string(),
+                Some("uri") => self.url.to_string(),
+                _ => self.string.to_string(),
+            },
+            "integer" | "number" => self.number.to_string(),
+            "boolean" => self.boolean.to_string(),
+            "external" => fill(
+                self.custom,
+                type_info.format.as_deref().unwrap_or("unknown"),
+            ),
+            "object" => match &type_info.map_values {
+                Some(values) => fill(self.record, &self.expression(values, inlined)),
+                None => self.any.to_string(),
+            },
+            "union" => {
+                let members: Vec<String> = type_info
+                    .union_members
+                    .iter()
+                    .map(|m| self.group(self.expression(m, inlined)))
+                    .collect();
+                fill(self.union, &members.join(self.union_separator))
+            }
+            _ => self.any.to_string(),
+        }
+    }
+
+    /// Expression for `type_info` where a schema is declared: the `export const`
+    /// value, or the arktype scope entry
+    pub fn definition(&self, type_info: &TypeInfo, inlined: &Inlined) -> String {
+        let expression = self.expression(type_info, inlined);
+        fill(self.value, &self.escape(&expression))
+    }
+
+    /// String literal inside an expression; arktype literals sit in a string definition
+    fn literal(&self, value: &str) -> String {
+        match self.library {
+            ValidationLibrary::Arktype => {
+                format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+            }
+            _ => naming::quote(value),
+        }
+    }
+
+    /// Parenthesize infix unions before postfix operators (arktype `(a | b)[]`)
+    fn group(&self, expression: String) -> String {
+        if self.union != "{}" || !expression.contains(self.union_separator) {
+            return expression;
+        }
+        format!("({})", expression)
+    }
+
+    /// Escape a type-level expression for its value position
+    fn escape(&self, expression: &str) -> String {
+        if self.value == "{}" {
+            return expression.to_string();
+        }
+        expression.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+}
+
+/// The type of a component schema that is an `enum` rather than an object,
+/// as a field of that type would carry it: string values become a literal
+/// union, other values just their scalar type
+pub fn enum_schema(schema: &SchemaDefinition) -> Option<TypeInfo> {
+    let values = schema.original.get("enum")?.as_array()?;
+    if !schema.fields.is_empty() || values.iter().all(serde_json::Value::is_null) {
+        return None;
+    }
+    let strings: Option<Vec<String>> = values
+        .iter()
+        .filter(|value| !value.is_null())
+        .map(|value| value.as_str().map(str::to_string))
+        .collect();
+    let openapi_type = match schema.original.get("type").and_then(|t| t.as_str()) {
+        Some(openapi_type) => openapi_type,
+        None if strings.is_some() => "string",
+        None => "any",
+    };
+    Some(TypeInfo {
+        openapi_type: openapi_type.to_string(),
+        format: None,
+        is_array: false,
+        array_item_type: None,
+        reference: None,
+        enum_values: strings.filter(|_| openapi_type == "string"),
+        union_members: Vec::new(),
+        tuple_items: Vec::new(),
+        map_values: None,
+    })
+}
+
+pub fn fill(pattern: &str, inner: &str) -> String {
+    pattern.replacen("{}", inner, 1)
+}
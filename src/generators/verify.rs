@@ -0,0 +1,219 @@
+//! Syntax check of generated files (`verify: true` generation option), so a
+//! template regression fails the run instead of shipping code that does not
+//! parse:
+//! - `.ts`: parsed with swc, in process (`verify` feature)
+//! - `.go`: `gofmt -e`
+//! - `.py`: compiled by `python3`
+//!
+//! Other files are not checked. Go and Python need their toolchain on `PATH`.
+
+use super::GeneratedFile;
+use crate::diagnostics::{ErrorGroup, SourceError};
+use anyhow::{Context, Result};
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// A checker missing from `PATH`: a problem with the machine, not with the
+/// file being checked
+#[derive(Debug)]
+pub struct ToolNotFound {
+    pub program: String,
+}
+
+impl fmt::Display for ToolNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "verify: {} not found on PATH", self.program)
+    }
+}
+
+impl std::error::Error for ToolNotFound {}
+
+/// Whether `file` parses in its language
+pub fn check(file: &GeneratedFile) -> Result<()> {
+    let extension = file.filename.rsplit('.').next().unwrap_or_default();
+    match extension {
+        "ts" => check_typescript(file),
+        "go" => check_go(file),
+        "py" => check_python(file),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(feature = "verify")]
+fn check_typescript(file: &GeneratedFile) -> Result<()> {
+    use swc_common::{sync::Lrc, FileName, SourceMap, Spanned};
+    use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax, TsSyntax};
+
+    let source_map: Lrc<SourceMap> = Default::default();
+    let source = source_map.new_source_file(
+        FileName::Custom(file.filename.clone()).into(),
+        file.content.clone(),
+    );
+    // Decorators for the `typescript_nestjs` DTOs and controllers
+    let syntax = Syntax::Typescript(TsSyntax {
+        dts: file.filename.ends_with(".d.ts"),
+        decorators: true,
+        ..Default::default()
+    });
+    let mut parser = Parser::new_from(Lexer::new(
+        syntax,
+        Default::default(),
+        StringInput::from(&*source),
+        None,
+    ));
+
+    // Errors swc recovered from count as much as the one that stopped it
+    let fatal = parser.parse_module().err();
+    let errors: Vec<anyhow::Error> = parser
+        .take_errors()
+        .into_iter()
+        .chain(fatal)
+        .map(|error| {
+            let position = source_map.lookup_char_pos(error.span().lo);
+            SourceError::at_position(
+                &file.filename,
+                &file.content,
+                position.line,
+                position.col_display + 1,
+                error.kind().msg().to_string(),
+            )
+            .into()
+        })
+        .collect();
+    grouped(file, errors)
+}
+
+#[cfg(not(feature = "verify"))]
+fn check_typescript(file: &GeneratedFile) -> Result<()> {
+    anyhow::bail!(
+        "{}: TypeScript verification needs the `verify` feature",
+        file.filename
+    )
+}
+
+fn check_go(file: &GeneratedFile) -> Result<()> {
+    let (success, output) = run("gofmt", &["-e"], &file.content)?;
+    if success {
+        return Ok(());
+    }
+    // `<standard input>:12:5: expected ';', found foo`
+    let errors = output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.strip_prefix("<standard input>:")?.splitn(3, ':');
+            let line = parts.next()?.parse().ok()?;
+            let column = parts.next()?.parse().ok()?;
+            Some((line, column, parts.next()?.trim().to_string()))
+        })
+        .map(|(line, column, message)| {
+            SourceError::at_position(&file.filename, &file.content, line, column, message).into()
+        })
+        .collect::<Vec<_>>();
+    if errors.is_empty() {
+        anyhow::bail!("{}: gofmt failed: {}", file.filename, output.trim());
+    }
+    grouped(file, errors)
+}
+
+/// `compile()` is what `py_compile` runs, minus writing bytecode
+const PYTHON_CHECK: &str = "\
+import sys
+try:
+    compile(sys.stdin.read(), sys.argv[1], 'exec')
+except SyntaxError as e:
+    print(f'{e.lineno}:{e.offset}:{e.msg}')
+    sys.exit(1)
+";
+
+fn check_python(file: &GeneratedFile) -> Result<()> {
+    let (success, output) = run(
+        "python3",
+        &["-c", PYTHON_CHECK, &file.filename],
+        &file.content,
+    )?;
+    if success {
+        return Ok(());
+    }
+    let mut parts = output.trim().splitn(3, ':');
+    match (
+        parts.next().and_then(|l| l.parse().ok()),
+        parts.next().and_then(|c| c.parse().ok()),
+        parts.next(),
+    ) {
+        (Some(line), Some(column), Some(message)) => Err(SourceError::at_position(
+            &file.filename,
+            &file.content,
+            line,
+            column,
+            message.to_string(),
+        )
+        .into()),
+        _ => anyhow::bail!("{}: python3 failed: {}", file.filename, output.trim()),
+    }
+}
+
+/// Runs `program` with `input` on stdin, returning whether it succeeded and
+/// its stdout and stderr
+fn run(program: &str, args: &[&str], input: &str) -> Result<(bool, String)> {
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Err(ToolNotFound {
+                program: program.to_string(),
+            }
+            .into())
+        }
+        child => child.with_context(|| format!("verify: failed to run {}", program))?,
+    };
+    child
+        .stdin
+        .take()
+        .context("verify: no stdin")?
+        .write_all(input.as_bytes())?;
+    let output = child.wait_with_output()?;
+    let text = String::from_utf8_lossy(&output.stdout).into_owned()
+        + &String::from_utf8_lossy(&output.stderr);
+    Ok((output.status.success(), text))
+}
+
+fn grouped(file: &GeneratedFile, mut errors: Vec<anyhow::Error>) -> Result<()> {
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0)),
+        count => Err(ErrorGroup::new(
+            format!("{} syntax errors in {}", count, file.filename),
+            errors,
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_missing_checker_is_reported_as_such() {
+        let error = run("generator-no-such-checker", &[], "").unwrap_err();
+        assert!(error.is::<ToolNotFound>());
+        assert_eq!(
+            error.to_string(),
+            "verify: generator-no-such-checker not found on PATH"
+        );
+    }
+
+    #[test]
+    fn files_without_a_checker_pass() {
+        let file = GeneratedFile {
+            filename: "README.md".to_string(),
+            content: "not code".to_string(),
+        };
+        assert!(check(&file).is_ok());
+    }
+}
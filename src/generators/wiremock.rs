@@ -0,0 +1,350 @@
+//! WireMock stub mappings from the examples of the spec's responses
+//! (`wiremock` generator), as one `{ "mappings": [...] }` file for a WireMock
+//! `mappings` directory or its `/__admin/mappings/import` endpoint.
+//!
+//! Every numbered response of an operation with an example (the media type's
+//! `example`, each of its `examples`, or its schema's `example`) becomes a
+//! stub; responses without content become bodiless ones. Stubs are selected
+//! with Prism's `Prefer` header: the operation's first 2xx response answers
+//! plain requests, `Prefer: code=404` picks another status, and
+//! `example=notFound` a named example. Paths match with their parameters as
+//! wildcards, below `basePath` (default none). Responses with content but no
+//! example get a `no-example` warning instead of a stub.
+
+use super::{GeneratedOutput, Generator, GeneratorWarning};
+use crate::config::GenerationConfig;
+use crate::parsers::{OperationDefinition, SchemaIR};
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// Priorities of the stubs, most specific first (WireMock prefers lower ones)
+const PRIORITY_CODE_AND_EXAMPLE: u32 = 1;
+const PRIORITY_EXAMPLE: u32 = 2;
+const PRIORITY_CODE: u32 = 3;
+const PRIORITY_DEFAULT: u32 = 5;
+
+pub struct WireMockGenerator;
+
+impl Generator for WireMockGenerator {
+    fn name(&self) -> &str {
+        "wiremock"
+    }
+
+    fn file_extension(&self) -> &str {
+        "json"
+    }
+
+    fn generate_from_ir(
+        &self,
+        schema_ir: &SchemaIR,
+        config: &GenerationConfig,
+    ) -> Result<GeneratedOutput> {
+        if schema_ir.original.low_memory {
+            anyhow::bail!(
+                "wiremock: the input was parsed with 'lowMemory', which drops the examples to stub"
+            );
+        }
+        let base_path = config
+            .options
+            .get("basePath")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .trim_end_matches('/');
+
+        let document = &schema_ir.original.data;
+        let mut mappings = Vec::new();
+        let mut warnings = Vec::new();
+        for operation in &schema_ir.operations {
+            mappings.extend(operation_mappings(
+                document,
+                operation,
+                base_path,
+                &mut warnings,
+            ));
+        }
+
+        Ok(GeneratedOutput {
+            filename: config.output_file.clone(),
+            content: serde_json::to_string_pretty(&json!({ "mappings": mappings }))? + "\n",
+            metadata: HashMap::new(),
+            companions: Vec::new(),
+            warnings,
+        })
+    }
+
+    fn validate_config(&self, config: &GenerationConfig) -> Result<()> {
+        if let Some(base_path) = config.options.get("basePath") {
+            if !base_path.as_str().is_some_and(|path| path.starts_with('/')) {
+                anyhow::bail!("wiremock: option 'basePath' must be a path starting with '/'");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A response example: its name under `examples`, if it has one, and value
+struct Example<'a> {
+    name: Option<&'a str>,
+    value: &'a Value,
+}
+
+/// Stubs for the numbered responses of `operation`, in spec order, with a
+/// warning for each response left out for lack of an example
+fn operation_mappings(
+    document: &Value,
+    operation: &OperationDefinition,
+    base_path: &str,
+    warnings: &mut Vec<GeneratorWarning>,
+) -> Vec<Value> {
+    let Some(responses) = operation
+        .original
+        .get("responses")
+        .and_then(Value::as_object)
+    else {
+        return Vec::new();
+    };
+    let responses: Vec<(u16, &Value)> = responses
+        .iter()
+        .filter_map(|(status, response)| Some((status.parse().ok()?, resolve(document, response))))
+        .collect();
+    let default_status = responses
+        .iter()
+        .map(|(status, _)| *status)
+        .find(|status| (200..300).contains(status))
+        .or_else(|| responses.first().map(|(status, _)| *status));
+
+    let method = format!("{:?}", operation.method).to_uppercase();
+    let mut request = Map::new();
+    request.insert("method".to_string(), json!(method));
+    let path = format!("{}{}", base_path, operation.path);
+    match path.contains('{') {
+        true => request.insert("urlPathPattern".to_string(), json!(path_pattern(&path))),
+        false => request.insert("urlPath".to_string(), json!(path)),
+    };
+
+    let mut mappings = Vec::new();
+    for (status, response) in responses {
+        let default = Some(status) == default_status;
+        let headers = response_headers(document, response);
+        let content = response
+            .get("content")
+            .and_then(Value::as_object)
+            .filter(|c| !c.is_empty());
+        let Some(content) = content else {
+            let stub = stub_response(status, headers, None);
+            mappings.push(mapping(operation, &request, status, None, default, stub));
+            continue;
+        };
+
+        let Some((media_type, examples)) = content
+            .iter()
+            .map(|(media_type, media)| (media_type, media_examples(document, media)))
+            .find(|(_, examples)| !examples.is_empty())
+        else {
+            warnings.push(
+                GeneratorWarning::new(
+                    "no-example",
+                    "response has content but no example; no stub written",
+                )
+                .at(format!("operations.{}.responses.{}", operation.id, status)),
+            );
+            continue;
+        };
+        for (index, example) in examples.iter().enumerate() {
+            let mut headers = headers.clone();
+            headers.insert("Content-Type".to_string(), json!(media_type));
+            let stub = stub_response(status, headers, Some((media_type, example.value)));
+            // The first example also answers requests not naming one
+            if index == 0 {
+                mappings.push(mapping(
+                    operation,
+                    &request,
+                    status,
+                    None,
+                    default,
+                    stub.clone(),
+                ));
+            }
+            if let Some(name) = example.name {
+                mappings.push(mapping(
+                    operation,
+                    &request,
+                    status,
+                    Some(name),
+                    default,
+                    stub,
+                ));
+            }
+        }
+    }
+    mappings
+}
+
+/// A stub for `status`, selected by `Prefer` unless it is the default one
+fn mapping(
+    operation: &OperationDefinition,
+    request: &Map<String, Value>,
+    status: u16,
+    example: Option<&str>,
+    default: bool,
+    response: Value,
+) -> Value {
+    let code = format!("code={}", status);
+    let (priority, prefer) = match (default, example) {
+        (true, None) => (PRIORITY_DEFAULT, None),
+        (false, None) => (PRIORITY_CODE, Some(json!({ "contains": code }))),
+        (true, Some(example)) => (
+            PRIORITY_EXAMPLE,
+            Some(json!({ "contains": format!("example={}", example) })),
+        ),
+        (false, Some(example)) => (
+            PRIORITY_CODE_AND_EXAMPLE,
+            Some(
+                json!({ "and": [{ "contains": code }, { "contains": format!("example={}", example) }] }),
+            ),
+        ),
+    };
+
+    let mut request = request.clone();
+    if let Some(prefer) = prefer {
+        request.insert("headers".to_string(), json!({ "Prefer": prefer }));
+    }
+    let name = match example {
+        Some(example) => format!("{} {} {}", operation.id, status, example),
+        None => format!("{} {}", operation.id, status),
+    };
+    json!({
+        "name": name,
+        "priority": priority,
+        "request": request,
+        "response": response,
+    })
+}
+
+fn stub_response(status: u16, headers: Map<String, Value>, body: Option<(&str, &Value)>) -> Value {
+    let mut response = Map::new();
+    response.insert("status".to_string(), json!(status));
+    if !headers.is_empty() {
+        response.insert("headers".to_string(), Value::Object(headers));
+    }
+    match body {
+        // Text bodies are sent as they are, anything else as JSON
+        Some((media_type, Value::String(text))) if !is_json(media_type) => {
+            response.insert("body".to_string(), json!(text));
+        }
+        Some((_, value)) => {
+            response.insert("jsonBody".to_string(), value.clone());
+        }
+        None => {}
+    }
+    Value::Object(response)
+}
+
+/// Examples of a media type object: `example`, then `examples` in spec order,
+/// then its schema's `example`
+fn media_examples<'a>(document: &'a Value, media: &'a Value) -> Vec<Example<'a>> {
+    if let Some(value) = media.get("example") {
+        return vec![Example { name: None, value }];
+    }
+    let named: Vec<Example> = media
+        .get("examples")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, example)| {
+            let value = resolve(document, example).get("value")?;
+            Some(Example {
+                name: Some(name),
+                value,
+            })
+        })
+        .collect();
+    if !named.is_empty() {
+        return named;
+    }
+    media
+        .get("schema")
+        .and_then(|schema| resolve(document, schema).get("example"))
+        .map(|value| vec![Example { name: None, value }])
+        .unwrap_or_default()
+}
+
+/// Headers of a response that carry an example value
+fn response_headers(document: &Value, response: &Value) -> Map<String, Value> {
+    let mut headers = Map::new();
+    for (name, header) in response
+        .get("headers")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        let header = resolve(document, header);
+        let example = header.get("example").or_else(|| {
+            header
+                .get("schema")
+                .and_then(|schema| resolve(document, schema).get("example"))
+        });
+        let value = match example {
+            Some(Value::String(text)) => text.clone(),
+            Some(Value::Null) | None => continue,
+            Some(other) => other.to_string(),
+        };
+        headers.insert(name.clone(), json!(value));
+    }
+    headers
+}
+
+/// `node` with local refs followed; unresolvable refs are returned as they are
+fn resolve<'a>(document: &'a Value, mut node: &'a Value) -> &'a Value {
+    // Bounded so that ref cycles end
+    for _ in 0..16 {
+        let Some(target) = node
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix('#'))
+            .and_then(|pointer| document.pointer(pointer))
+        else {
+            break;
+        };
+        node = target;
+    }
+    node
+}
+
+/// `/users/{id}` as the regex `/users/[^/]+`
+fn path_pattern(path: &str) -> String {
+    let mut pattern = String::new();
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        pattern.push_str(&escape(&rest[..start]));
+        match rest[start..].find('}') {
+            Some(end) => {
+                pattern.push_str("[^/]+");
+                rest = &rest[start + end + 1..];
+            }
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    pattern.push_str(&escape(rest));
+    pattern
+}
+
+fn escape(literal: &str) -> String {
+    let mut escaped = String::new();
+    for c in literal.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn is_json(media_type: &str) -> bool {
+    let essence = media_type.split(';').next().unwrap_or("").trim();
+    essence == "application/json" || essence.ends_with("+json")
+}
@@ -0,0 +1,74 @@
+//! Zod major-version differences shared by the TypeScript generators
+//! (`zodVersion` option, `3` or `4`).
+//!
+//! Zod 4 moves string formats to top-level validators (`z.email()`,
+//! `z.iso.datetime()`) and requires a key schema in `z.record`.
+
+use crate::config::GenerationConfig;
+use anyhow::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZodVersion {
+    V3,
+    V4,
+}
+
+impl ZodVersion {
+    pub fn from_config(config: &GenerationConfig) -> Result<Self> {
+        let Some(value) = config.options.get("zodVersion") else {
+            return Ok(Self::V3);
+        };
+        let major = value.as_u64().or_else(|| {
+            value
+                .as_str()
+                .and_then(|s| s.trim_start_matches('v').parse().ok())
+        });
+        match major {
+            Some(3) => Ok(Self::V3),
+            Some(4) => Ok(Self::V4),
+            _ => anyhow::bail!("{}: option 'zodVersion' must be 3 or 4", config.generator),
+        }
+    }
+
+    pub fn email(self) -> &'static str {
+        match self {
+            Self::V3 => "z.string().email()",
+            Self::V4 => "z.email()",
+        }
+    }
+
+    pub fn uuid(self) -> &'static str {
+        match self {
+            Self::V3 => "z.string().uuid()",
+            Self::V4 => "z.uuid()",
+        }
+    }
+
+    pub fn url(self) -> &'static str {
+        match self {
+            Self::V3 => "z.string().url()",
+            Self::V4 => "z.url()",
+        }
+    }
+
+    /// ISO 8601 date-time string
+    pub fn datetime(self) -> &'static str {
+        match self {
+            Self::V3 => "z.string().datetime()",
+            Self::V4 => "z.iso.datetime()",
+        }
+    }
+
+    /// String-keyed record of `value`
+    pub fn record(self, value: &str) -> String {
+        self.record_pattern().replacen("{}", value, 1)
+    }
+
+    /// String-keyed record, with `{}` standing for the value schema
+    pub fn record_pattern(self) -> &'static str {
+        match self {
+            Self::V3 => "z.record({})",
+            Self::V4 => "z.record(z.string(), {})",
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Client code generation from OpenAPI specs.
+//!
+//! [`generate`] runs the whole pipeline in memory: spec and config text in,
+//! generated files out. The `generator` binary (`cli` feature) adds config
+//! discovery, hooks, the provenance manifest, and writing to disk; the `wasm`
+//! feature exports [`generate`] to JavaScript.
+
+pub mod config;
+pub mod diagnostics;
+pub mod drift;
+pub mod explain;
+pub mod generators;
+pub mod manifest;
+pub mod parsers;
+pub mod stats;
+pub mod verify_server;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+// Legacy processors, superseded by the parser IR
+#[allow(dead_code)]
+mod operation_processor;
+#[allow(dead_code)]
+mod schema_processor;
+
+use anyhow::{Context, Result};
+use config::schema::Config;
+use generators::build_info::Build;
+use generators::{
+    unsupported_constructs, AnyTypes, BuildInfo, GeneratorRegistry, MajorVersion, PostProcessors,
+    UnsupportedPolicy,
+};
+use parsers::{reject_collisions, resolve_name_collisions, ParserRegistry};
+
+/// Every enabled generation of `config` (the YAML config file's contents)
+/// applied to `spec`, as `(filename, content)` pairs in config order.
+///
+/// The input `source` and `output` paths are ignored, as are hooks and the
+/// manifest; custom `template` directories are still read from disk. Configs
+/// with a `gateway` section are refused.
+pub fn generate(spec: &str, config: &str) -> Result<Vec<(String, String)>> {
+    generate_with(&GeneratorRegistry::new(), spec, config)
+}
+
+/// [`generate`] with the generators of `registry`, e.g. one with extra
+/// generators registered or a custom naming strategy
+/// ([`GeneratorRegistry::set_naming`]).
+pub fn generate_with(
+    registry: &GeneratorRegistry,
+    spec: &str,
+    config: &str,
+) -> Result<Vec<(String, String)>> {
+    let config: Config =
+        diagnostics::from_yaml_str("config", config).context("Failed to parse config")?;
+    config.check_version()?;
+    if config.gateway.is_some() {
+        anyhow::bail!("`gateway` configs read several specs; generate them with the CLI");
+    }
+
+    let format = match config.input.as_ref().and_then(|input| input.format.clone()) {
+        Some(format) => format,
+        None => ParserRegistry::new()
+            .detect_content_format(spec)?
+            .unwrap_or("openapi")
+            .to_string(),
+    };
+    let options = config
+        .input
+        .as_ref()
+        .map(|input| input.parser_options())
+        .unwrap_or_default();
+    let mut schema_ir = ParserRegistry::new()
+        .get(&format)
+        .ok_or_else(|| anyhow::anyhow!("Unknown input format: {}", format))?
+        .parse_str(spec, &options)
+        .with_context(|| format!("Failed to parse {} input", format))?;
+
+    let collisions = resolve_name_collisions(&mut schema_ir);
+    if config.strict {
+        reject_collisions(&collisions)?;
+    }
+
+    let unsupported = unsupported_constructs(&schema_ir);
+    // The hashes the CLI's manifest records
+    let spec_sha256 = manifest::sha256_hex(spec.as_bytes());
+    let config_sha256 = manifest::sha256_hex(&serde_json::to_vec(&manifest::sorted_keys(
+        serde_json::to_value(&config)?,
+    ))?);
+    let build = Build {
+        spec_sha256: &spec_sha256,
+        config_sha256: &config_sha256,
+    };
+    let mut files = Vec::new();
+    for gen_config in config.generations.iter().filter(|g| g.enabled) {
+        let generator = registry.require(&gen_config.generator)?;
+        generator.validate_config(gen_config)?;
+        UnsupportedPolicy::from_config(gen_config)?.enforce(gen_config, &unsupported)?;
+        let prepared_ir = generators::prepare(&schema_ir, generator, gen_config)?;
+        let any_fields = generator.any_fields(&prepared_ir);
+        AnyTypes::from_config(gen_config)?.enforce(gen_config, &any_fields)?;
+        let build_info = BuildInfo::from_config(gen_config)?;
+        let post_processors = PostProcessors::from_config(gen_config, registry)?;
+
+        let output = generator
+            .generate_from_ir(&prepared_ir, gen_config)
+            .with_context(|| format!("Failed to generate with '{}'", gen_config.generator))?;
+        let output = build_info.apply(output, &build);
+        let output = MajorVersion::from_config(gen_config).apply(output, &prepared_ir)?;
+        let output = post_processors.apply(output)?;
+        files.extend(
+            output
+                .into_files()
+                .into_iter()
+                .map(|file| (file.filename, file.content)),
+        );
+    }
+
+    Ok(files)
+}
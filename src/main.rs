@@ -1,21 +1,37 @@
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use miette::GraphicalReportHandler;
 use std::fs;
-use std::path::PathBuf;
-
-mod config;
-mod parsers;
-mod generators;
-mod schema_processor;
-mod operation_processor;
-
-use config::{load_config, merge_with_cli_args};
-use parsers::ParserRegistry;
-use generators::GeneratorRegistry;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use generator::config::schema::{Config, GatewayConfig, InputConfig};
+use generator::config::{load_config, merge_with_cli_args, GenerationConfig};
+use generator::diagnostics::{ErrorGroup, SourceError};
+use generator::drift::Outcome;
+use generator::explain;
+use generator::generators::build_info::Build;
+use generator::generators::typecheck::TypeCheck;
+use generator::generators::{
+    self, template_lint, templates, unsupported_constructs, verify, AnyTypes, BuildInfo, Generator,
+    GeneratorRegistry, GeneratorWarning, MajorVersion, PostProcessors, UnsupportedPolicy,
+};
+use generator::manifest::{self, GenerationManifest};
+use generator::parsers::gateway::{self, SchemaPrefix};
+use generator::parsers::ir_file;
+use generator::parsers::{
+    external_refs, reject_collisions, resolve_name_collisions, FeatureUsage, ParserRegistry,
+    SchemaIR,
+};
+use generator::stats;
+use generator::verify_server::{self, Call};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to the OpenAPI specification file (YAML or JSON)
     #[arg(short, long)]
     spec: Option<PathBuf>,
@@ -24,53 +40,242 @@ struct Args {
     #[arg(short, long)]
     output: Option<PathBuf>,
 
-    /// Path to config file (overrides default location)
-    #[arg(short, long)]
+    /// Path to config file (overrides default location); accepted before or
+    /// after a subcommand
+    #[arg(short, long, global = true)]
     config: Option<PathBuf>,
+
+    /// Stop at the first failing generation instead of running the rest and
+    /// reporting every failure at the end
+    #[arg(long)]
+    fail_fast: bool,
+
+    /// Print the JSON context GENERATOR renders its templates with, for the
+    /// spec and the first generation using it in the config, instead of generating
+    #[arg(long, value_name = "GENERATOR")]
+    dump_context: Option<String>,
+
+    /// Print the spec's IR as an exported IR file (JSON with its `irVersion`),
+    /// which the `ir` input format reads back, instead of generating
+    #[arg(long)]
+    dump_ir: bool,
 }
 
-fn main() -> Result<()> {
-    let args = Args::parse();
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Resolve external $refs and write the spec as a single file
+    Bundle {
+        /// Path to the OpenAPI specification file (YAML or JSON)
+        #[arg(short, long)]
+        spec: PathBuf,
+
+        /// Bundled spec to write; JSON for a `.json` extension, YAML otherwise
+        #[arg(short, long)]
+        out: PathBuf,
+    },
+
+    /// Print operation and schema counts, nesting depth, unreferenced schemas,
+    /// and the output size of each configured generation
+    Stats {
+        /// Path to the OpenAPI specification file; defaults to the config's input
+        #[arg(short, long)]
+        spec: Option<PathBuf>,
+    },
+
+    /// Print a schema's or operation's IR node and what each configured
+    /// generation emits for it
+    Explain {
+        /// Schema name or operation id, as in the IR
+        name: String,
+
+        /// Only this generator's generations; its default options when the
+        /// config has none
+        #[arg(short, long)]
+        generator: Option<String>,
+
+        /// Path to the OpenAPI specification file; defaults to the config's input
+        #[arg(short, long)]
+        spec: Option<PathBuf>,
+    },
+
+    /// Call the spec's GET operations on a running server and report where
+    /// its responses drift from the spec
+    VerifySpecAgainstServer(ServerCheck),
+
+    /// Work with custom template packs
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommand,
+    },
+}
 
-    // Load configuration
-    let config = load_config(args.config.as_deref())?;
-    let merged_config = merge_with_cli_args(config, args.spec, args.output);
+#[derive(clap::Args, Debug)]
+struct ServerCheck {
+    /// URL the spec's paths are appended to, such as `https://staging.example.com/v1`
+    #[arg(long)]
+    base_url: String,
 
-    // Validate we have input
-    let input_config = merged_config.input
-        .ok_or_else(|| anyhow::anyhow!("No input source specified. Use --spec or configure input in config file"))?;
+    /// Operation id to call; repeatable, every GET operation when left out
+    #[arg(long = "operation", value_name = "ID")]
+    operations: Vec<String>,
 
-    println!("📖 Reading input from: {:?}", input_config.source);
+    /// Value of a parameter, for every operation that has it; repeatable.
+    /// Parameters without one take their example from the spec
+    #[arg(long = "param", value_name = "NAME=VALUE")]
+    params: Vec<String>,
 
-    // Create parser registry
-    let parser_registry = ParserRegistry::new();
+    /// Header sent with every request, such as `Authorization: Bearer ...`;
+    /// repeatable
+    #[arg(short = 'H', long = "header", value_name = "HEADER")]
+    headers: Vec<String>,
 
-    // Determine input format (explicit or auto-detect)
-    let format = input_config.format.clone().unwrap_or_else(|| {
-        parser_registry
-            .detect_format(&input_config.source)
-            .unwrap_or("openapi")
-            .to_string()
-    });
+    /// Seconds each request may take
+    #[arg(long, default_value_t = 10)]
+    timeout: u64,
 
-    println!("🔍 Detected format: {}", format);
+    /// Path to the OpenAPI specification file; defaults to the config's input
+    #[arg(short, long)]
+    spec: Option<PathBuf>,
+}
 
-    // Get parser
-    let parser = parser_registry.get(&format)
-        .ok_or_else(|| anyhow::anyhow!("Unknown input format: {}", format))?;
+#[derive(Subcommand, Debug)]
+enum TemplateCommand {
+    /// Check a template directory for syntax errors, variables the context
+    /// does not have, and unknown filters, tests, and functions
+    Lint {
+        /// Template directory, as passed to `template` or `templateDirs`
+        dir: PathBuf,
+
+        /// Generator the templates are for (typescript, python, golang);
+        /// inferred from the directory's template names when left out
+        #[arg(short, long)]
+        generator: Option<String>,
+
+        /// Spec whose context the templates are checked against; defaults to
+        /// the config's input
+        #[arg(short, long)]
+        spec: Option<PathBuf>,
+    },
+}
 
-    // Convert serde_yaml::Value to serde_json::Value for options
-    let options_json: std::collections::HashMap<String, serde_json::Value> = input_config.options.iter()
-        .filter_map(|(k, v)| {
-            serde_json::to_value(v).ok().map(|json_v| (k.clone(), json_v))
-        })
-        .collect();
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {:?}", error);
+            // Spec and config errors also get the source lines they point at
+            for located in located_errors(&error) {
+                let mut snippet = String::new();
+                if GraphicalReportHandler::new()
+                    .render_report(&mut snippet, located)
+                    .is_ok()
+                {
+                    eprintln!("\n{}", snippet.trim_end());
+                }
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    // Parse input to intermediate representation
-    let schema_ir = parser.parse(&input_config.source, &options_json)
-        .with_context(|| format!("Failed to parse {} input", format))?;
+/// Source errors in `error`'s chain, including those of grouped errors
+fn located_errors(error: &anyhow::Error) -> Vec<&SourceError> {
+    let mut located = Vec::new();
+    for cause in error.chain() {
+        if let Some(source_error) = cause.downcast_ref::<SourceError>() {
+            located.push(source_error);
+        }
+        if let Some(group) = cause.downcast_ref::<ErrorGroup>() {
+            located.extend(group.errors().iter().flat_map(located_errors));
+        }
+    }
+    located
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
+
+    match &args.command {
+        Some(Command::Bundle { spec, out }) => return bundle(spec, out),
+        Some(Command::Stats { spec }) => return stats(spec.clone(), args.config.as_deref()),
+        Some(Command::Explain {
+            name,
+            generator,
+            spec,
+        }) => {
+            return explain(
+                name,
+                generator.as_deref(),
+                spec.clone(),
+                args.config.as_deref(),
+            )
+        }
+        Some(Command::VerifySpecAgainstServer(check)) => {
+            return verify_spec_against_server(check, args.config.as_deref())
+        }
+        Some(Command::Template {
+            command:
+                TemplateCommand::Lint {
+                    dir,
+                    generator,
+                    spec,
+                },
+        }) => {
+            return template_lint(
+                dir,
+                generator.as_deref(),
+                spec.clone(),
+                args.config.as_deref(),
+            )
+        }
+        None => {}
+    }
+    if let Some(generator) = &args.dump_context {
+        return dump_context(generator, args.spec.clone(), args.config.as_deref());
+    }
+    if args.dump_ir {
+        return dump_ir(args.spec.clone(), args.config.as_deref());
+    }
 
-    println!("✅ Parsed {} schemas and {} operations",
+    // Load configuration
+    let config = load_config(args.config.as_deref())?;
+    let merged_config = merge_with_cli_args(config, args.spec, args.output);
+    // Keys sorted so the hash is stable across runs
+    let config_json = serde_json::to_vec(&manifest::sorted_keys(serde_json::to_value(
+        &merged_config,
+    )?))?;
+
+    let (mut schema_ir, spec_path, spec_content) =
+        match (&merged_config.gateway, &merged_config.input) {
+            (Some(_), Some(_)) => anyhow::bail!("Configure either `input` or `gateway`, not both"),
+            (Some(gateway), None) => {
+                for service in &gateway.services {
+                    println!(
+                        "📖 Reading service '{}' from: {:?}",
+                        service.name, service.input.source
+                    );
+                }
+                read_gateway(gateway)?
+            }
+            (None, Some(input_config)) => read_input(input_config)?,
+            (None, None) => {
+                anyhow::bail!(
+                    "No input source specified. Use --spec or configure input in config file"
+                )
+            }
+        };
+
+    // Names that normalize to the same identifier would overwrite each other
+    let collisions = resolve_name_collisions(&mut schema_ir);
+    if merged_config.strict {
+        reject_collisions(&collisions)?;
+    }
+    for collision in &collisions {
+        println!("⚠️  {}", collision);
+    }
+
+    println!(
+        "✅ Parsed {} schemas and {} operations",
         schema_ir.schemas.len(),
         schema_ir.operations.len()
     );
@@ -79,17 +284,36 @@ fn main() -> Result<()> {
     let generator_registry = GeneratorRegistry::new();
 
     // Determine output directory
-    let output_dir = merged_config.output.unwrap_or_else(|| PathBuf::from("generated"));
+    let output_dir = merged_config
+        .output
+        .unwrap_or_else(|| PathBuf::from("generated"));
     fs::create_dir_all(&output_dir)?;
 
+    // What hook commands see as `{{ output_dir }}`, `{{ api_title }}`, ...
+    let mut hook_context = tera::Context::new();
+    hook_context.insert("output_dir", &output_dir.display().to_string());
+    hook_context.insert("spec", &spec_path.display().to_string());
+    hook_context.insert("api_title", &schema_ir.metadata.title);
+    hook_context.insert("api_version", &schema_ir.metadata.version);
+    hook_context.insert("files", &Vec::<String>::new());
+
     // Execute before hooks
     for hook in &merged_config.hooks.before_generate {
-        println!("🎣 Running before hook: {}", hook);
-        execute_hook(hook)?;
+        execute_hook("before", hook, &hook_context)?;
     }
 
-    // Process each generation configuration
+    // Provenance manifest covering every produced file
+    let mut manifest = GenerationManifest::new(&spec_path, &spec_content, &config_json);
+
+    // Unsupported schema kinds, reported once per construct for `onUnsupported: warn`
+    let unsupported = unsupported_constructs(&schema_ir);
+    let mut warn_unsupported_for: Vec<String> = Vec::new();
+    // Warnings the generators report with their output, by generator
+    let mut warnings: Vec<(String, GeneratorWarning)> = Vec::new();
+
+    // Process each generation configuration; failures are collected unless --fail-fast
     let mut generated_count = 0;
+    let mut failures: Vec<anyhow::Error> = Vec::new();
     for gen_config in &merged_config.generations {
         if !gen_config.enabled {
             println!("⏭️  Skipping disabled generator: {}", gen_config.generator);
@@ -98,31 +322,85 @@ fn main() -> Result<()> {
 
         println!("🔧 Generating with '{}'...", gen_config.generator);
 
-        // Get generator
-        let generator = generator_registry.get(&gen_config.generator)
-            .ok_or_else(|| anyhow::anyhow!("Unknown generator: {}", gen_config.generator))?;
-
-        // Validate config
-        generator.validate_config(gen_config)?;
-
-        // Generate code
-        let output = generator.generate_from_ir(&schema_ir, gen_config)
-            .with_context(|| format!("Failed to generate with '{}'", gen_config.generator))?;
+        let result = run_generation(
+            gen_config,
+            &generator_registry,
+            &schema_ir,
+            &unsupported,
+            &OutputDir {
+                path: &output_dir,
+                incremental: merged_config.incremental,
+            },
+            &mut manifest,
+            &mut warn_unsupported_for,
+        )
+        .with_context(|| format!("'{}' ({})", gen_config.generator, gen_config.output_file));
+        match result {
+            Ok((count, generation_warnings)) => {
+                generated_count += count;
+                warnings.extend(
+                    generation_warnings
+                        .into_iter()
+                        .map(|warning| (gen_config.generator.clone(), warning)),
+                );
+            }
+            Err(error) if args.fail_fast => return Err(error),
+            Err(error) => {
+                println!("❌ {:#}", error);
+                failures.push(error);
+            }
+        }
+    }
 
-        // Write to file
-        let output_path = output_dir.join(&output.filename);
+    if !warnings.is_empty() {
+        println!("⚠️  {} generator warning(s):", warnings.len());
+        for (generator, warning) in &warnings {
+            println!("   - {}: {}", generator, warning);
+        }
+    }
 
-        fs::write(&output_path, output.content)
-            .with_context(|| format!("Failed to write output file: {:?}", output_path))?;
+    // Manifest and after hooks only follow a complete run
+    if !failures.is_empty() {
+        let summary = format!(
+            "{} of {} generations failed",
+            failures.len(),
+            merged_config
+                .generations
+                .iter()
+                .filter(|g| g.enabled)
+                .count()
+        );
+        return Err(ErrorGroup::new(summary, failures).into());
+    }
 
-        println!("✅ Generated: {:?}", output_path);
-        generated_count += 1;
+    if merged_config.manifest {
+        manifest.write(&output_dir, merged_config.incremental)?;
     }
 
-    // Execute after hooks
+    // Execute after hooks, which also see the files written
+    let files: Vec<String> = manifest
+        .files
+        .iter()
+        .map(|file| output_dir.join(&file.path).display().to_string())
+        .collect();
+    hook_context.insert("files", &files);
     for hook in &merged_config.hooks.after_generate {
-        println!("🎣 Running after hook: {}", hook);
-        execute_hook(hook)?;
+        execute_hook("after", hook, &hook_context)?;
+    }
+
+    if !warn_unsupported_for.is_empty() && !unsupported.is_empty() {
+        println!(
+            "⚠️  Unsupported schema constructs emitted as `any` ({}):",
+            warn_unsupported_for.join(", ")
+        );
+        for usage in &unsupported {
+            println!(
+                "   - {} {}: {}",
+                usage.locations.len(),
+                usage.construct,
+                usage.locations.join(", ")
+            );
+        }
     }
 
     if generated_count == 0 {
@@ -134,17 +412,138 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn execute_hook(command: &str) -> Result<()> {
+/// Where generated files go, and whether identical ones are left untouched
+struct OutputDir<'a> {
+    path: &'a Path,
+    incremental: bool,
+}
+
+impl OutputDir<'_> {
+    /// Write `content` to `filename`, returning its path and whether it was
+    /// skipped as unchanged; skipped files keep their mtimes for incremental builds
+    fn write(&self, filename: &str, content: &str) -> Result<(PathBuf, bool)> {
+        let output_path = self.path.join(filename);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let unchanged = self.incremental
+            && fs::read(&output_path).is_ok_and(|existing| existing == content.as_bytes());
+        if !unchanged {
+            fs::write(&output_path, content)
+                .with_context(|| format!("Failed to write output file: {:?}", output_path))?;
+        }
+        Ok((output_path, unchanged))
+    }
+}
+
+/// Render one generation and write its files, returning how many were written
+/// and the warnings the generator reported
+fn run_generation(
+    gen_config: &GenerationConfig,
+    registry: &GeneratorRegistry,
+    schema_ir: &SchemaIR,
+    unsupported: &[&FeatureUsage],
+    output_dir: &OutputDir,
+    manifest: &mut GenerationManifest,
+    warn_unsupported_for: &mut Vec<String>,
+) -> Result<(usize, Vec<GeneratorWarning>)> {
+    let generator = registry.require(&gen_config.generator)?;
+    generator.validate_config(gen_config)?;
+
+    let policy = UnsupportedPolicy::from_config(gen_config)?;
+    policy.enforce(gen_config, unsupported)?;
+    if policy == UnsupportedPolicy::Warn {
+        warn_unsupported_for.push(gen_config.generator.clone());
+    }
+
+    let prepared_ir = generators::prepare(schema_ir, generator, gen_config)?;
+
+    // Fields degraded to `any`, reported or capped
+    let any_types = AnyTypes::from_config(gen_config)?;
+    let any_fields = generator.any_fields(&prepared_ir);
+    any_types.enforce(gen_config, &any_fields)?;
+    if any_types.report && !any_fields.is_empty() {
+        println!(
+            "⚠️  {} field(s) emitted as `any`: {}",
+            any_fields.len(),
+            any_fields.join(", ")
+        );
+    }
+
+    let build_info = BuildInfo::from_config(gen_config)?;
+    let post_processors = PostProcessors::from_config(gen_config, registry)?;
+    let type_check = TypeCheck::from_config(gen_config)?;
+
+    // Generate code
+    let output = generator
+        .generate_from_ir(&prepared_ir, gen_config)
+        .context("Failed to generate")?;
+    let build = Build {
+        spec_sha256: &manifest.spec.sha256,
+        config_sha256: &manifest.config_sha256,
+    };
+    let output = build_info.apply(output, &build);
+    // Under `v{major}/`, with `...V{major}` client aliases
+    let output = MajorVersion::from_config(gen_config).apply(output, &prepared_ir)?;
+    let output = post_processors.apply(output)?;
+
+    // Write the main file and any companions, then check they parse and type-check
+    let verify = gen_config
+        .options
+        .get("verify")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let warnings = output.warnings.clone();
+    let files = output.into_files();
+    let mut written = 0;
+    for file in &files {
+        let (output_path, unchanged) = output_dir.write(&file.filename, &file.content)?;
+
+        manifest.add_file(
+            &file.filename,
+            generator.name(),
+            generator.version(),
+            file.content.as_bytes(),
+        );
+
+        if unchanged {
+            println!("✅ Unchanged: {:?}", output_path);
+        } else {
+            println!("✅ Generated: {:?}", output_path);
+        }
+        written += 1;
+
+        if verify {
+            verify::check(file).map_err(|error| {
+                // A missing checker says nothing about the file
+                if error.is::<verify::ToolNotFound>() {
+                    error
+                } else {
+                    error.context(format!("{:?} does not parse", output_path))
+                }
+            })?;
+        }
+    }
+    type_check
+        .check(output_dir.path, &files)
+        .context("Output does not type-check")?;
+    Ok((written, warnings))
+}
+
+/// Run a hook once its `{{ ... }}` placeholders are filled from `context`
+fn execute_hook(stage: &str, hook: &str, context: &tera::Context) -> Result<()> {
     use std::process::Command;
 
+    let command = tera::Tera::one_off(hook, context, false)
+        .with_context(|| format!("Invalid {} hook: {}", stage, hook))?;
+    let command = command.as_str();
+    println!("🎣 Running {} hook: {}", stage, command);
+
     let output = if cfg!(target_os = "windows") {
-        Command::new("cmd")
-            .args(["/C", command])
-            .output()
+        Command::new("cmd").args(["/C", command]).output()
     } else {
-        Command::new("sh")
-            .args(["-c", command])
-            .output()
+        Command::new("sh").args(["-c", command]).output()
     }?;
 
     if !output.status.success() {
@@ -157,3 +556,339 @@ fn execute_hook(command: &str) -> Result<()> {
 
     Ok(())
 }
+
+fn bundle(spec: &Path, out: &Path) -> Result<()> {
+    println!("📖 Reading input from: {:?}", spec);
+    let document = external_refs::load(spec)?;
+
+    let content = match out.extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::to_string_pretty(&document)? + "\n",
+        _ => serde_yaml::to_string(&document)?,
+    };
+    if let Some(parent) = out.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(out, content).with_context(|| format!("Failed to write {:?}", out))?;
+
+    println!("✨ Bundled spec written to {:?}", out);
+    Ok(())
+}
+
+/// The configured input parsed to the IR, with its path and contents for the
+/// manifest
+fn read_input(input_config: &InputConfig) -> Result<(SchemaIR, PathBuf, Vec<u8>)> {
+    println!("📖 Reading input from: {:?}", input_config.source);
+
+    // Create parser registry
+    let parser_registry = ParserRegistry::new();
+
+    // Determine input format (explicit or auto-detect)
+    let format = match input_config.format.clone() {
+        Some(format) => format,
+        None => parser_registry
+            .detect_format(&input_config.source)?
+            .unwrap_or("openapi")
+            .to_string(),
+    };
+
+    println!("🔍 Detected format: {}", format);
+
+    // Get parser
+    let parser = parser_registry
+        .get(&format)
+        .ok_or_else(|| anyhow::anyhow!("Unknown input format: {}", format))?;
+
+    let options_json = input_config.parser_options();
+
+    // Parse input to intermediate representation
+    let schema_ir = parser
+        .parse(&input_config.source, &options_json)
+        .with_context(|| format!("Failed to parse {} input", format))?;
+
+    let spec_content = fs::read(&input_config.source)
+        .with_context(|| format!("Failed to read input: {:?}", input_config.source))?;
+    Ok((schema_ir, input_config.source.clone(), spec_content))
+}
+
+/// Every gateway service parsed and merged into one IR; the manifest records
+/// the sources together
+fn read_gateway(gateway: &GatewayConfig) -> Result<(SchemaIR, PathBuf, Vec<u8>)> {
+    let schema_prefix = SchemaPrefix::from_config(gateway.schema_prefix.as_deref())?;
+    let mut services = Vec::new();
+    let mut sources = Vec::new();
+    let mut spec_content = Vec::new();
+    for service in &gateway.services {
+        let schema_ir = read_ir(&service.input)
+            .with_context(|| format!("gateway service '{}'", service.name))?;
+        spec_content.extend(
+            fs::read(&service.input.source)
+                .with_context(|| format!("Failed to read input: {:?}", service.input.source))?,
+        );
+        sources.push(service.input.source.display().to_string());
+        services.push(gateway::Service {
+            name: service.name.clone(),
+            path_prefix: service.path_prefix.clone(),
+            schema_ir,
+        });
+    }
+    let schema_ir = gateway::merge(services, schema_prefix)?;
+    Ok((schema_ir, PathBuf::from(sources.join(", ")), spec_content))
+}
+
+/// The configured input parsed to the IR, name collisions resolved, without
+/// the progress output of a generating run
+fn read_ir(input_config: &InputConfig) -> Result<SchemaIR> {
+    let parser_registry = ParserRegistry::new();
+    let format = match input_config.format.clone() {
+        Some(format) => format,
+        None => parser_registry
+            .detect_format(&input_config.source)?
+            .unwrap_or("openapi")
+            .to_string(),
+    };
+    let parser = parser_registry
+        .get(&format)
+        .ok_or_else(|| anyhow::anyhow!("Unknown input format: {}", format))?;
+    let mut schema_ir = parser
+        .parse(&input_config.source, &input_config.parser_options())
+        .with_context(|| format!("Failed to parse {} input", format))?;
+    resolve_name_collisions(&mut schema_ir);
+    Ok(schema_ir)
+}
+
+/// The configured input or gateway parsed to the IR, without progress output
+fn read_configured_ir(config: &Config) -> Result<SchemaIR> {
+    match (&config.gateway, &config.input) {
+        (Some(gateway), _) => Ok(read_gateway(gateway)?.0),
+        (None, Some(input_config)) => read_ir(input_config),
+        (None, None) => {
+            anyhow::bail!("No input source specified. Use --spec or configure input in config file")
+        }
+    }
+}
+
+fn dump_ir(spec: Option<PathBuf>, config_path: Option<&Path>) -> Result<()> {
+    let config = merge_with_cli_args(load_config(config_path)?, spec, None);
+    print!("{}", ir_file::export(&read_configured_ir(&config)?)?);
+    Ok(())
+}
+
+/// A generation of `generator` with its default options
+fn default_generation(generator: &dyn Generator) -> GenerationConfig {
+    GenerationConfig {
+        generator: generator.name().to_string(),
+        output_file: format!("{}.{}", generator.name(), generator.file_extension()),
+        enabled: true,
+        template: None,
+        template_dirs: Vec::new(),
+        plugin: None,
+        options: Default::default(),
+    }
+}
+
+fn dump_context(name: &str, spec: Option<PathBuf>, config_path: Option<&Path>) -> Result<()> {
+    let config = merge_with_cli_args(load_config(config_path)?, spec, None);
+    let schema_ir = read_configured_ir(&config)?;
+
+    let generator_registry = GeneratorRegistry::new();
+    let generator = generator_registry.require(name)?;
+    // The configured generation's options shape the context; defaults otherwise
+    let gen_config = configured_generation(&config, generator);
+    let context = template_context(generator, &schema_ir, &gen_config)?;
+    println!("{}", serde_json::to_string_pretty(&context)?);
+    Ok(())
+}
+
+/// The first enabled generation of `generator` in `config`, or one with its default options
+fn configured_generation(config: &Config, generator: &dyn Generator) -> GenerationConfig {
+    config
+        .generations
+        .iter()
+        .find(|g| g.enabled && g.generator == generator.name())
+        .cloned()
+        .unwrap_or_else(|| default_generation(generator))
+}
+
+/// The JSON context `generator` renders its templates with for `gen_config`
+fn template_context(
+    generator: &dyn Generator,
+    schema_ir: &SchemaIR,
+    gen_config: &GenerationConfig,
+) -> Result<serde_json::Value> {
+    generator.validate_config(gen_config)?;
+
+    let prepared_ir = generators::prepare(schema_ir, generator, gen_config)?;
+    generator
+        .template_context(&prepared_ir, gen_config)?
+        .ok_or_else(|| {
+            anyhow::anyhow!("Generator '{}' does not render templates", generator.name())
+        })
+}
+
+fn template_lint(
+    dir: &Path,
+    generator: Option<&str>,
+    spec: Option<PathBuf>,
+    config_path: Option<&Path>,
+) -> Result<()> {
+    let name = match generator {
+        Some(name) => name,
+        None => template_lint::pack_generator(dir).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Cannot tell which generator {:?} is for; pass --generator",
+                dir
+            )
+        })?,
+    };
+    let builtin = templates::builtin(name)
+        .ok_or_else(|| anyhow::anyhow!("Generator '{}' does not render templates", name))?;
+
+    let config = merge_with_cli_args(load_config(config_path)?, spec, None);
+    let schema_ir = read_configured_ir(&config)?;
+    let generator_registry = GeneratorRegistry::new();
+    let generator = generator_registry.require(name)?;
+    // Layered over the built-in templates, as `templateDirs` would be
+    let gen_config = GenerationConfig {
+        template: None,
+        template_dirs: vec![dir.to_path_buf()],
+        ..configured_generation(&config, generator)
+    };
+    let tera = templates::load(&gen_config, builtin)?;
+    let context = template_context(generator, &schema_ir, &gen_config)?;
+
+    println!("🔍 Linting {:?} as '{}' templates", dir, name);
+    let report = template_lint::lint_pack(&tera, &context, dir, builtin);
+    for file in &report.unrendered {
+        println!(
+            "⚠️  {}: never rendered; no built-in template has this name and no template uses it",
+            file
+        );
+    }
+    for issue in &report.issues {
+        println!("❌ {}: {}", issue.template, issue.message);
+    }
+    if !report.issues.is_empty() {
+        anyhow::bail!(
+            "{} issue(s) in {} template(s)",
+            report.issues.len(),
+            report.templates.len()
+        );
+    }
+    println!("✅ {} template(s) OK", report.templates.len());
+    Ok(())
+}
+
+fn explain(
+    name: &str,
+    generator: Option<&str>,
+    spec: Option<PathBuf>,
+    config_path: Option<&Path>,
+) -> Result<()> {
+    let config = merge_with_cli_args(load_config(config_path)?, spec, None);
+    let schema_ir = read_configured_ir(&config)?;
+
+    let mut generations: Vec<GenerationConfig> = config
+        .generations
+        .iter()
+        .filter(|g| g.enabled && generator.is_none_or(|name| g.generator == name))
+        .cloned()
+        .collect();
+    let generator_registry = GeneratorRegistry::new();
+    if let (Some(name), true) = (generator, generations.is_empty()) {
+        generations = vec![default_generation(generator_registry.require(name)?)];
+    }
+    print!(
+        "{}",
+        explain::report(&schema_ir, name, &generations, &generator_registry)?
+    );
+    Ok(())
+}
+
+fn stats(spec: Option<PathBuf>, config_path: Option<&Path>) -> Result<()> {
+    let config = merge_with_cli_args(load_config(config_path)?, spec, None);
+    let input_config = config.input.as_ref().ok_or_else(|| {
+        anyhow::anyhow!("No input source specified. Use --spec or configure input in config file")
+    })?;
+    let schema_ir = read_ir(input_config)?;
+    let generations: Vec<GenerationConfig> = config
+        .generations
+        .iter()
+        .filter(|g| g.enabled)
+        .cloned()
+        .collect();
+    print!(
+        "{}",
+        stats::report(
+            &schema_ir,
+            &input_config.source,
+            &generations,
+            &GeneratorRegistry::new()
+        )
+    );
+    Ok(())
+}
+
+/// Drift lines printed per operation; the rest are counted
+const MAX_DRIFTS_SHOWN: usize = 20;
+
+fn verify_spec_against_server(check: &ServerCheck, config_path: Option<&Path>) -> Result<()> {
+    let config = merge_with_cli_args(load_config(config_path)?, check.spec.clone(), None);
+    let schema_ir = read_configured_ir(&config)?;
+    let check = verify_server::Check {
+        base_url: &check.base_url,
+        operations: &check.operations,
+        params: &check.params,
+        headers: &check.headers,
+        timeout: check.timeout,
+    };
+    let operations = verify_server::operations(&schema_ir, &check)?;
+
+    println!(
+        "🌐 Calling {} operation(s) on {}",
+        operations.len(),
+        check.base_url.trim_end_matches('/')
+    );
+    let summary =
+        verify_server::verify(
+            &schema_ir,
+            &operations,
+            &check,
+            |operation, call| match call {
+                Call::Skipped { missing } => println!(
+                    "⏭️  {}: no value for required parameter '{}'; pass --param {}=...",
+                    operation.id, missing, missing
+                ),
+                Call::Failed { url, error } => {
+                    println!("❌ {} (GET {}): {:#}", operation.id, url, error)
+                }
+                Call::Answered {
+                    url,
+                    status,
+                    outcome,
+                } => match outcome {
+                    Outcome::Matches => println!("✅ {} (GET {}): {}", operation.id, url, status),
+                    Outcome::Unchecked(note) => {
+                        println!("⚠️  {} (GET {}): {}", operation.id, url, note)
+                    }
+                    Outcome::Drifted(drifts) => {
+                        println!("❌ {} (GET {}): {}", operation.id, url, status);
+                        for drift in drifts.iter().take(MAX_DRIFTS_SHOWN) {
+                            println!("   {}", drift);
+                        }
+                        if drifts.len() > MAX_DRIFTS_SHOWN {
+                            println!("   ... and {} more", drifts.len() - MAX_DRIFTS_SHOWN);
+                        }
+                    }
+                },
+            },
+        )?;
+    println!(
+        "✅ {} operation(s) match the spec{}",
+        summary.called,
+        match summary.skipped {
+            0 => String::new(),
+            _ => format!(", {} skipped", summary.skipped),
+        }
+    );
+    Ok(())
+}
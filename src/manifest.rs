@@ -0,0 +1,126 @@
+//! Provenance manifest written alongside generated files
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+pub const MANIFEST_FILENAME: &str = "generation-manifest.json";
+
+#[derive(Debug, Serialize)]
+pub struct GenerationManifest {
+    pub tool: ToolInfo,
+    pub spec: SpecInfo,
+    pub config_sha256: String,
+    pub generators: Vec<GeneratorInfo>,
+    pub files: Vec<FileEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToolInfo {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpecInfo {
+    pub path: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct GeneratorInfo {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileEntry {
+    /// Path relative to the output directory
+    pub path: String,
+    pub generator: String,
+    pub sha256: String,
+    pub bytes: usize,
+}
+
+impl GenerationManifest {
+    pub fn new(spec_path: &Path, spec_content: &[u8], config_json: &[u8]) -> Self {
+        Self {
+            tool: ToolInfo {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            spec: SpecInfo {
+                path: spec_path.display().to_string(),
+                sha256: sha256_hex(spec_content),
+            },
+            config_sha256: sha256_hex(config_json),
+            generators: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+
+    /// Record a produced file and the generator that produced it
+    pub fn add_file(
+        &mut self,
+        path: &str,
+        generator_name: &str,
+        generator_version: &str,
+        content: &[u8],
+    ) {
+        let generator = GeneratorInfo {
+            name: generator_name.to_string(),
+            version: generator_version.to_string(),
+        };
+        if !self.generators.contains(&generator) {
+            self.generators.push(generator);
+        }
+
+        self.files.push(FileEntry {
+            path: path.to_string(),
+            generator: generator_name.to_string(),
+            sha256: sha256_hex(content),
+            bytes: content.len(),
+        });
+    }
+
+    #[cfg(feature = "cli")]
+    /// Write the manifest; `incremental` leaves an identical one untouched
+    pub fn write(&self, output_dir: &Path, incremental: bool) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        let path = output_dir.join(MANIFEST_FILENAME);
+        let json = serde_json::to_string_pretty(self)? + "\n";
+        if incremental && std::fs::read(&path).is_ok_and(|existing| existing == json.as_bytes()) {
+            return Ok(());
+        }
+        std::fs::write(&path, json).with_context(|| format!("Failed to write manifest: {:?}", path))
+    }
+}
+
+/// `value` with object keys sorted at every level; serde_json keeps insertion
+/// order, which for `HashMap`s differs between runs
+pub fn sorted_keys(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, sorted_keys(v)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(sorted_keys).collect())
+        }
+        other => other,
+    }
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
@@ -1,5 +1,7 @@
 use openapiv3::{OpenAPI, Operation, Parameter, ParameterSchemaOrContent, ReferenceOr, RequestBody, Response, MediaType};
 use serde::Serialize;
+use crate::parsers::component_refs;
+use crate::parsers::operation_ids::OperationIdStrategy;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ProcessedOperation {
@@ -56,6 +58,7 @@ pub fn extract_operations(openapi: &OpenAPI) -> Vec<ProcessedOperation> {
             for (method, op_option) in ops {
                 if let Some(operation) = op_option {
                     operations.push(process_operation(
+                        openapi,
                         operation,
                         method.to_string(),
                         path.clone(),
@@ -68,25 +71,30 @@ pub fn extract_operations(openapi: &OpenAPI) -> Vec<ProcessedOperation> {
     operations
 }
 
-fn process_operation(operation: &Operation, method: String, path: String) -> ProcessedOperation {
-    let id = operation
-        .operation_id
-        .clone()
-        .unwrap_or_else(|| format!("{}_{}", method, path.replace('/', "_").replace(['{', '}'], "")));
+fn process_operation(openapi: &OpenAPI, operation: &Operation, method: String, path: String) -> ProcessedOperation {
+    let id = OperationIdStrategy::default().operation_id(
+        operation.operation_id.as_deref(),
+        operation.summary.as_deref(),
+        &method,
+        &path,
+    );
 
     let parameters = operation
         .parameters
         .iter()
-        .filter_map(process_parameter)
+        .filter_map(|param| process_parameter(openapi, param))
         .collect();
 
-    let request_body = operation.request_body.as_ref().and_then(process_request_body);
+    let request_body = operation
+        .request_body
+        .as_ref()
+        .and_then(|body| process_request_body(openapi, body));
 
     let responses = operation
         .responses
         .responses
         .iter()
-        .filter_map(|(status, resp)| process_response(status.to_string(), resp))
+        .filter_map(|(status, resp)| process_response(openapi, status.to_string(), resp))
         .collect();
 
     ProcessedOperation {
@@ -101,9 +109,9 @@ fn process_operation(operation: &Operation, method: String, path: String) -> Pro
     }
 }
 
-fn process_parameter(param_ref: &ReferenceOr<Parameter>) -> Option<ProcessedParameter> {
-    match param_ref {
-        ReferenceOr::Item(param) => {
+fn process_parameter(openapi: &OpenAPI, param_ref: &ReferenceOr<Parameter>) -> Option<ProcessedParameter> {
+    match component_refs::resolve(openapi, param_ref) {
+        Some(param) => {
             let (name, location, required, schema_type, description) = match param {
                 Parameter::Query { parameter_data, .. } => {
                     let schema_type = extract_parameter_type(&parameter_data.format);
@@ -155,7 +163,7 @@ fn process_parameter(param_ref: &ReferenceOr<Parameter>) -> Option<ProcessedPara
                 description,
             })
         }
-        ReferenceOr::Reference { .. } => None,
+        None => None,
     }
 }
 
@@ -179,9 +187,9 @@ fn extract_parameter_type(format: &ParameterSchemaOrContent) -> String {
     }
 }
 
-fn process_request_body(body_ref: &ReferenceOr<RequestBody>) -> Option<ProcessedRequestBody> {
-    match body_ref {
-        ReferenceOr::Item(body) => {
+fn process_request_body(openapi: &OpenAPI, body_ref: &ReferenceOr<RequestBody>) -> Option<ProcessedRequestBody> {
+    match component_refs::resolve(openapi, body_ref) {
+        Some(body) => {
             let content_type = body
                 .content
                 .keys()
@@ -202,16 +210,17 @@ fn process_request_body(body_ref: &ReferenceOr<RequestBody>) -> Option<Processed
                 schema_type,
             })
         }
-        ReferenceOr::Reference { .. } => None,
+        None => None,
     }
 }
 
 fn process_response(
+    openapi: &OpenAPI,
     status: String,
     response_ref: &ReferenceOr<Response>,
 ) -> Option<ProcessedResponse> {
-    match response_ref {
-        ReferenceOr::Item(response) => {
+    match component_refs::resolve(openapi, response_ref) {
+        Some(response) => {
             let description = response.description.clone();
 
             let content_type = response.content.keys().next().map(|s| s.to_string());
@@ -231,7 +240,7 @@ fn process_response(
                 is_array,
             })
         }
-        ReferenceOr::Reference { .. } => None,
+        None => None,
     }
 }
 
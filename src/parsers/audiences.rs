@@ -0,0 +1,207 @@
+//! Who operations, component schemas, and their properties are for, from their
+//! `x-audience` extension (another one with the `audienceExtension` input
+//! option). The value is an audience name or a list of them (`partner`,
+//! `[partner, internal]`); unmarked nodes are for everyone. Generations keep
+//! what their `audience` option selects, so one spec drives a public, a
+//! partner, and an internal client.
+
+use super::SchemaIR;
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use openapiv3::{OpenAPI, ReferenceOr, Schema, SchemaKind, Type};
+use serde_json::Value;
+use std::collections::HashMap;
+
+const DEFAULT_EXTENSION: &str = "x-audience";
+
+pub struct AudienceExtension(String);
+
+impl AudienceExtension {
+    pub fn from_options(options: &HashMap<String, Value>) -> Result<Self> {
+        match options.get("audienceExtension").map(|v| v.as_str()) {
+            None => Ok(Self(DEFAULT_EXTENSION.to_string())),
+            Some(Some(name)) if name.starts_with("x-") => Ok(Self(name.to_string())),
+            _ => anyhow::bail!(
+                "input: option 'audienceExtension' must be a vendor extension name (\"x-...\")"
+            ),
+        }
+    }
+
+    /// Audiences `extensions` mark the node at `location` for; empty when unmarked
+    fn audiences(
+        &self,
+        extensions: &IndexMap<String, Value>,
+        location: &str,
+    ) -> Result<Vec<String>> {
+        let audiences = match extensions.get(&self.0) {
+            None => Some(Vec::new()),
+            Some(Value::String(audience)) => Some(vec![audience.clone()]),
+            Some(Value::Array(audiences)) => audiences
+                .iter()
+                .map(|a| a.as_str().map(str::to_string))
+                .collect(),
+            Some(_) => None,
+        };
+        audiences.with_context(|| {
+            format!(
+                "Invalid {} extension on {}: expected an audience name or a list of them",
+                self.0, location
+            )
+        })
+    }
+}
+
+/// Record on the IR the audiences the spec marks; runs before schemas are
+/// renamed, while IR names are still the spec's
+pub(super) fn annotate(
+    openapi: &OpenAPI,
+    schema_ir: &mut SchemaIR,
+    extension: &AudienceExtension,
+) -> Result<()> {
+    if let Some(components) = &openapi.components {
+        for (name, schema_ref) in &components.schemas {
+            let ReferenceOr::Item(schema) = schema_ref else {
+                continue;
+            };
+            let Some(definition) = schema_ir.schemas.iter_mut().find(|s| &s.name == name) else {
+                continue;
+            };
+            definition.audiences =
+                extension.audiences(&schema.schema_data.extensions, &format!("schema {}", name))?;
+
+            let mut properties = HashMap::new();
+            collect_properties(schema, &mut properties);
+            for field in &mut definition.fields {
+                if let Some(property) = properties.get(field.name.as_str()) {
+                    let location = format!("property {}.{}", name, field.name);
+                    field.audiences =
+                        extension.audiences(&property.schema_data.extensions, &location)?;
+                }
+            }
+        }
+    }
+
+    for (path, path_item_ref) in &openapi.paths.paths {
+        let ReferenceOr::Item(path_item) = path_item_ref else {
+            continue;
+        };
+        for (method, operation) in path_item.iter() {
+            let location = format!("{} {}", method.to_uppercase(), path);
+            let audiences = extension.audiences(&operation.extensions, &location)?;
+            if audiences.is_empty() {
+                continue;
+            }
+            let definition = schema_ir.operations.iter_mut().find(|op| {
+                op.path == *path && format!("{:?}", op.method).eq_ignore_ascii_case(method)
+            });
+            if let Some(definition) = definition {
+                definition.audiences = audiences;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Inline property schemas of `schema`, including those of its inline `allOf`
+/// members; `$ref` properties carry no extensions of their own
+fn collect_properties<'a>(schema: &'a Schema, properties: &mut HashMap<&'a str, &'a Schema>) {
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::Object(object)) => {
+            for (name, property) in &object.properties {
+                if let ReferenceOr::Item(property) = property {
+                    properties.insert(name, property);
+                }
+            }
+        }
+        SchemaKind::AllOf { all_of } => {
+            for member in all_of {
+                if let ReferenceOr::Item(member) = member {
+                    collect_properties(member, properties);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsers::{InputParser, OpenApiParser, SchemaIR};
+    use anyhow::Result;
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      responses: { "200": { description: ok, content: { application/json: { schema: { $ref: "#/components/schemas/User" } } } } }
+  /reports:
+    get:
+      operationId: listReports
+      x-audience: [partner, internal]
+      x-visibility: internal
+      responses: { "204": { description: ok } }
+components:
+  schemas:
+    User:
+      type: object
+      x-audience: partner
+      properties:
+        name: { type: string }
+        notes: { type: string, x-audience: internal }
+    Admin:
+      allOf:
+        - $ref: "#/components/schemas/User"
+        - type: object
+          properties:
+            level: { type: integer, x-audience: internal }
+"##;
+
+    fn parse(spec: &str, options: &str) -> Result<SchemaIR> {
+        let options: HashMap<String, Value> = serde_json::from_str(options).unwrap();
+        OpenApiParser.parse_str(spec, &options)
+    }
+
+    fn field_audiences(ir: &SchemaIR, schema: &str, field: &str) -> Vec<String> {
+        let schema = ir.schemas.iter().find(|s| s.name == schema).unwrap();
+        let field = schema.fields.iter().find(|f| f.name == field).unwrap();
+        field.audiences.clone()
+    }
+
+    #[test]
+    fn marked_nodes_record_their_audiences() {
+        let ir = parse(SPEC, "{}").unwrap();
+        assert!(ir.operations[0].audiences.is_empty());
+        assert_eq!(ir.operations[1].audiences, ["partner", "internal"]);
+        assert_eq!(ir.schemas[0].audiences, ["partner"]);
+        assert!(field_audiences(&ir, "User", "name").is_empty());
+        assert_eq!(field_audiences(&ir, "User", "notes"), ["internal"]);
+        // Properties of inline allOf members
+        assert_eq!(field_audiences(&ir, "Admin", "level"), ["internal"]);
+    }
+
+    #[test]
+    fn another_extension_can_be_read() {
+        let ir = parse(SPEC, r#"{ "audienceExtension": "x-visibility" }"#).unwrap();
+        assert_eq!(ir.operations[1].audiences, ["internal"]);
+        assert!(ir.schemas[0].audiences.is_empty());
+
+        let error = parse(SPEC, r#"{ "audienceExtension": "visibility" }"#).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("must be a vendor extension name"));
+    }
+
+    #[test]
+    fn malformed_markers_are_rejected() {
+        let spec = SPEC.replace("x-audience: partner\n", "x-audience: 3\n");
+        let error = format!("{:#}", parse(&spec, "{}").unwrap_err());
+        assert!(error.contains(
+            "Invalid x-audience extension on schema User: expected an audience name or a list of them"
+        ));
+    }
+}
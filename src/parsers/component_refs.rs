@@ -0,0 +1,74 @@
+//! Resolution of `#/components/...` references to shared parameters, request
+//! bodies, responses, and response links, so operations see them as if
+//! declared inline, and to the schemas parameters are typed with.
+
+use indexmap::IndexMap;
+use openapiv3::{Components, Link, OpenAPI, Parameter, ReferenceOr, RequestBody, Response, Schema};
+
+/// Longest chain of component refs followed before giving up (guards cycles)
+const MAX_DEPTH: usize = 16;
+
+/// Object kinds stored in a `components` section
+pub trait Component: Sized {
+    const SECTION: &'static str;
+
+    fn section(components: &Components) -> &IndexMap<String, ReferenceOr<Self>>;
+}
+
+impl Component for Parameter {
+    const SECTION: &'static str = "parameters";
+
+    fn section(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.parameters
+    }
+}
+
+impl Component for RequestBody {
+    const SECTION: &'static str = "requestBodies";
+
+    fn section(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.request_bodies
+    }
+}
+
+impl Component for Response {
+    const SECTION: &'static str = "responses";
+
+    fn section(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.responses
+    }
+}
+
+impl Component for Link {
+    const SECTION: &'static str = "links";
+
+    fn section(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.links
+    }
+}
+
+impl Component for Schema {
+    const SECTION: &'static str = "schemas";
+
+    fn section(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.schemas
+    }
+}
+
+/// The item itself, or the component its ref points to; `None` for refs
+/// outside `#/components/<section>/` or to missing entries
+pub fn resolve<'a, T: Component>(openapi: &'a OpenAPI, item: &'a ReferenceOr<T>) -> Option<&'a T> {
+    let mut current = item;
+    for _ in 0..MAX_DEPTH {
+        let reference = match current {
+            ReferenceOr::Item(item) => return Some(item),
+            ReferenceOr::Reference { reference } => reference,
+        };
+        let name = reference
+            .strip_prefix("#/components/")?
+            .strip_prefix(T::SECTION)?
+            .strip_prefix('/')?;
+        current = T::section(openapi.components.as_ref()?).get(name)?;
+    }
+    None
+}
@@ -0,0 +1,43 @@
+//! Translated descriptions from the `x-descriptions` extension, a map from
+//! locale to text next to a node's `description`:
+//!
+//! ```yaml
+//! description: Returns the user
+//! x-descriptions:
+//!   de: Gibt den Benutzer zurück
+//!   pt-BR: Retorna o usuário
+//! ```
+//!
+//! Recorded on the info object, tags, schemas, properties, operations, and
+//! parameters; a generation's `locale` option picks which one its docs use.
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use serde_json::Value;
+
+const EXTENSION: &str = "x-descriptions";
+
+/// The translations `extensions` hold for the node at `location`; empty without any
+pub(super) fn translations(
+    extensions: &IndexMap<String, Value>,
+    location: &str,
+) -> Result<IndexMap<String, String>> {
+    let Some(value) = extensions.get(EXTENSION) else {
+        return Ok(IndexMap::new());
+    };
+    value
+        .as_object()
+        .and_then(|entries| {
+            entries
+                .iter()
+                .map(|(locale, text)| Some((locale.clone(), text.as_str()?.to_string())))
+                .collect()
+        })
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid {} extension on {}: expected a map from locale to description",
+                EXTENSION,
+                location
+            )
+        })
+}
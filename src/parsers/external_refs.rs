@@ -0,0 +1,528 @@
+//! Resolution of `$ref`s into other files, producing one self-contained
+//! document.
+//!
+//! Each external target is copied once into the root document's `components`
+//! and the ref rewritten to point there. The section comes from the target's
+//! pointer (`#/components/parameters/Limit`) or, for other pointers and
+//! whole-file refs, from where the ref appears (a parameter, request body,
+//! response, or otherwise a schema). Path item refs are inlined, as OpenAPI
+//! 3.0 has no component section for them. Refs inside copied nodes resolve
+//! relative to their own file; remote (`http:`) refs are rejected.
+
+use crate::diagnostics::SourceError;
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Read the spec at `source` with its external refs resolved
+pub fn load(source: &Path) -> Result<Value> {
+    let content = fs::read_to_string(source)
+        .with_context(|| format!("Failed to read OpenAPI spec: {:?}", source))?;
+    let mut document = read_document(&source.display().to_string(), &content)?;
+    bundle(&mut document, source)?;
+    Ok(document)
+}
+
+/// Parse a YAML or JSON document, naming it `name` in errors
+pub fn read_document(name: &str, content: &str) -> Result<Value> {
+    // JSON documents are also YAML, but serde_json reports errors better
+    Ok(if content.trim_start().starts_with('{') {
+        serde_json::from_str(content)
+            .map_err(|error| SourceError::from_json(name, content, &error))?
+    } else {
+        super::yaml::read(name, content)?
+    })
+}
+
+/// Resolve every external ref of `document`, which was read from `source`
+pub fn bundle(document: &mut Value, source: &Path) -> Result<()> {
+    let mut bundler = Bundler::new(document, source);
+    bundler.adopt_components(document)?;
+    bundler.walk(document, &bundler.root.clone(), Position::Other)?;
+
+    if !bundler.components.is_empty() {
+        let root = document
+            .as_object_mut()
+            .context("The spec is not a YAML/JSON object")?;
+        let components = root
+            .entry("components")
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .context("`components` is not an object")?;
+        for (section, entries) in std::mem::take(&mut bundler.components) {
+            let section = components
+                .entry(section.clone())
+                .or_insert_with(|| Value::Object(Map::new()))
+                .as_object_mut()
+                .with_context(|| format!("`components.{}` is not an object", section))?;
+            if let Value::Object(entries) = entries {
+                section.extend(entries);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// What a node is, which decides the component section a ref to it lands in
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Position {
+    Other,
+    Paths,
+    PathItem,
+    Parameters,
+    Parameter,
+    RequestBodies,
+    RequestBody,
+    Responses,
+    Response,
+}
+
+impl Position {
+    fn child(self, key: Option<&str>) -> Self {
+        match (self, key) {
+            (Self::Paths, Some(_)) => Self::PathItem,
+            (Self::Parameters, _) => Self::Parameter,
+            (Self::RequestBodies, _) => Self::RequestBody,
+            (Self::Responses, _) => Self::Response,
+            (_, Some("paths")) => Self::Paths,
+            (_, Some("parameters")) => Self::Parameters,
+            (_, Some("requestBody")) => Self::RequestBody,
+            (_, Some("requestBodies")) => Self::RequestBodies,
+            (_, Some("responses")) => Self::Responses,
+            _ => Self::Other,
+        }
+    }
+
+    /// Position of the entries of a `components` section
+    fn component(section: &str) -> Self {
+        match section {
+            "parameters" => Self::Parameter,
+            "requestBodies" => Self::RequestBody,
+            "responses" => Self::Response,
+            _ => Self::Other,
+        }
+    }
+
+    fn section(self) -> Option<&'static str> {
+        match self {
+            Self::PathItem => None,
+            Self::Parameter => Some("parameters"),
+            Self::RequestBody => Some("requestBodies"),
+            Self::Response => Some("responses"),
+            _ => Some("schemas"),
+        }
+    }
+}
+
+struct Bundler {
+    root: PathBuf,
+    /// Parsed external files
+    documents: HashMap<PathBuf, Value>,
+    /// `(file, pointer)` → local ref already assigned
+    assigned: HashMap<(PathBuf, String), String>,
+    /// Component names in use, per section
+    taken: HashMap<String, HashSet<String>>,
+    /// Copied targets, per section
+    components: Map<String, Value>,
+}
+
+impl Bundler {
+    fn new(document: &Value, source: &Path) -> Self {
+        let mut taken: HashMap<String, HashSet<String>> = HashMap::new();
+        if let Some(components) = document.get("components").and_then(|c| c.as_object()) {
+            for (section, entries) in components {
+                if let Some(entries) = entries.as_object() {
+                    taken.insert(section.clone(), entries.keys().cloned().collect());
+                }
+            }
+        }
+
+        Self {
+            root: canonical(source),
+            documents: HashMap::new(),
+            assigned: HashMap::new(),
+            taken,
+            components: Map::new(),
+        }
+    }
+
+    fn walk(&mut self, value: &mut Value, file: &Path, position: Position) -> Result<()> {
+        match value {
+            Value::Object(map) => {
+                if let Some(reference) =
+                    map.get("$ref").and_then(|r| r.as_str()).map(str::to_string)
+                {
+                    if let Some(replacement) = self.resolve(&reference, file, position)? {
+                        *value = replacement;
+                    }
+                    return Ok(());
+                }
+                for (key, child) in map.iter_mut() {
+                    // Instance data may contain a literal `$ref` key
+                    if matches!(
+                        key.as_str(),
+                        "example" | "examples" | "default" | "enum" | "const"
+                    ) {
+                        continue;
+                    }
+                    self.walk(child, file, position.child(Some(key)))?;
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.walk(item, file, position.child(None))?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Inline root components that are only a ref to another file
+    /// (`User: {$ref: schemas/user.yaml}`), keeping their names
+    fn adopt_components(&mut self, document: &mut Value) -> Result<()> {
+        let Some(Value::Object(components)) = document.get_mut("components") else {
+            return Ok(());
+        };
+
+        let mut adopted = Vec::new();
+        for (section, entries) in components.iter() {
+            let Value::Object(entries) = entries else {
+                continue;
+            };
+            for (name, entry) in entries {
+                let reference = match entry.as_object() {
+                    Some(map) if map.len() == 1 => map.get("$ref").and_then(|r| r.as_str()),
+                    _ => None,
+                };
+                let Some(reference) = reference.filter(|r| !r.starts_with('#')) else {
+                    continue;
+                };
+                let key = target_of(reference, &self.root)?;
+                // Other refs to the same target land on this entry
+                self.assigned
+                    .entry(key.clone())
+                    .or_insert_with(|| format!("#/components/{}/{}", section, name));
+                adopted.push((section.clone(), name.clone(), key, reference.to_string()));
+            }
+        }
+
+        for (section, name, (file, pointer), reference) in adopted {
+            let mut node = self
+                .load(&file)?
+                .pointer(&pointer)
+                .cloned()
+                .with_context(|| format!("$ref target not found: {}", reference))?;
+            self.walk(&mut node, &file, Position::component(&section))?;
+            components[&section][&name] = node;
+        }
+        Ok(())
+    }
+
+    /// Replacement for a `$ref` object, or `None` when it stays as is
+    fn resolve(
+        &mut self,
+        reference: &str,
+        file: &Path,
+        position: Position,
+    ) -> Result<Option<Value>> {
+        if reference.starts_with('#') && file == self.root {
+            return Ok(None);
+        }
+        let (target_file, pointer) = target_of(reference, file)?;
+        if target_file == self.root {
+            return Ok(Some(ref_object(&format!("#{}", pointer))));
+        }
+        let key = (target_file.clone(), pointer.clone());
+        if let Some(local) = self.assigned.get(&key) {
+            return Ok(Some(ref_object(local)));
+        }
+
+        let mut node = self
+            .load(&target_file)?
+            .pointer(&pointer)
+            .cloned()
+            .with_context(|| format!("$ref target not found: {}", reference))?;
+
+        let (section, name) = match component_path(&pointer) {
+            Some((section, name)) => (section, name),
+            None => match position.section() {
+                Some(section) => (section.to_string(), default_name(&pointer, &target_file)),
+                None => {
+                    // Inlined: nested refs resolve against the target's file
+                    self.walk(&mut node, &target_file, position)?;
+                    return Ok(Some(node));
+                }
+            },
+        };
+
+        let name = self.claim(&section, &name);
+        let local = format!("#/components/{}/{}", section, name);
+        self.assigned.insert(key, local.clone());
+
+        self.walk(&mut node, &target_file, Position::component(&section))?;
+
+        self.components
+            .entry(section)
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .expect("component sections are objects")
+            .insert(name, node);
+
+        Ok(Some(ref_object(&local)))
+    }
+
+    fn load(&mut self, file: &Path) -> Result<&Value> {
+        if !self.documents.contains_key(file) {
+            let content = fs::read_to_string(file)
+                .with_context(|| format!("Failed to read referenced file: {:?}", file))?;
+            let document = read_document(&file.display().to_string(), &content)
+                .with_context(|| format!("Failed to parse referenced file: {:?}", file))?;
+            self.documents.insert(file.to_path_buf(), document);
+        }
+        Ok(&self.documents[file])
+    }
+
+    /// `name`, or `name2`, `name3`... when another target already has it
+    fn claim(&mut self, section: &str, name: &str) -> String {
+        let taken = self.taken.entry(section.to_string()).or_default();
+        let mut candidate = name.to_string();
+        let mut n = 2;
+        while taken.contains(&candidate) {
+            candidate = format!("{}{}", name, n);
+            n += 1;
+        }
+        taken.insert(candidate.clone());
+        candidate
+    }
+}
+
+/// File and pointer a ref in `file` points to
+fn target_of(reference: &str, file: &Path) -> Result<(PathBuf, String)> {
+    let (target, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+    if target.contains("://") {
+        anyhow::bail!("Remote $ref is not supported: {}", reference);
+    }
+
+    let target_file = if target.is_empty() {
+        file.to_path_buf()
+    } else {
+        canonical(&file.parent().unwrap_or(Path::new(".")).join(target))
+    };
+    Ok((target_file, pointer.to_string()))
+}
+
+/// `(section, name)` for pointers of the form `/components/<section>/<name>`
+fn component_path(pointer: &str) -> Option<(String, String)> {
+    let mut segments = pointer.strip_prefix('/')?.split('/');
+    match (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) {
+        (Some("components"), Some(section), Some(name), None) => {
+            Some((section.to_string(), sanitize(&unescape(name))))
+        }
+        (Some("definitions"), Some(name), None, None) => {
+            Some(("schemas".to_string(), sanitize(&unescape(name))))
+        }
+        _ => None,
+    }
+}
+
+/// Last pointer segment, or the file stem for whole-file refs
+fn default_name(pointer: &str, file: &Path) -> String {
+    let segment = pointer
+        .rsplit('/')
+        .next()
+        .filter(|s| !s.is_empty())
+        .map(unescape);
+    let name = segment.unwrap_or_else(|| {
+        file.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Component")
+            .to_string()
+    });
+    sanitize(&name)
+}
+
+/// Component keys allow `[a-zA-Z0-9._-]`
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn unescape(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+fn ref_object(local: &str) -> Value {
+    let mut map = Map::new();
+    map.insert("$ref".to_string(), Value::String(local.to_string()));
+    Value::Object(map)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A spec split over files under a fresh directory, each `(path, content)`
+    fn spec(test: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("generator-refs-{}-{}", test, std::process::id()));
+        for (path, content) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn load_from(dir: &Path) -> Result<Value> {
+        let loaded = load(&dir.join("openapi.yaml"));
+        fs::remove_dir_all(dir).unwrap();
+        loaded
+    }
+
+    const ROOT: &str = r#"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /users:
+    $ref: paths/users.yaml
+components:
+  schemas:
+    User: { $ref: schemas/user.yaml }
+"#;
+
+    const USERS: &str = r#"
+get:
+  parameters:
+    - $ref: ../common.yaml#/components/parameters/Limit
+  responses:
+    "200":
+      description: ok
+      content: { application/json: { schema: { $ref: ../schemas/user.yaml } } }
+"#;
+
+    #[test]
+    fn refs_resolve_relative_to_the_file_they_are_in() {
+        let dir = spec(
+            "relative",
+            &[
+                ("openapi.yaml", ROOT),
+                ("paths/users.yaml", USERS),
+                (
+                    "common.yaml",
+                    "components: { parameters: { Limit: { name: limit, in: query, schema: { type: integer } } } }",
+                ),
+                ("schemas/user.yaml", "type: object\nproperties: { address: { $ref: address.yaml } }"),
+                ("schemas/address.yaml", "type: object\nproperties: { city: { type: string } }"),
+            ],
+        );
+        let document = load_from(&dir).unwrap();
+
+        // Path items are inlined; the parameter lands in its own section
+        let get = &document["paths"]["/users"]["get"];
+        assert_eq!(
+            get["parameters"][0],
+            json!({ "$ref": "#/components/parameters/Limit" })
+        );
+        assert_eq!(
+            get["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({ "$ref": "#/components/schemas/User" })
+        );
+        let components = &document["components"];
+        assert_eq!(components["parameters"]["Limit"]["name"], "limit");
+        // Nested: user.yaml's ref is relative to schemas/, named by file stem
+        assert_eq!(
+            components["schemas"]["User"]["properties"]["address"],
+            json!({ "$ref": "#/components/schemas/address" })
+        );
+        assert_eq!(
+            components["schemas"]["address"]["properties"]["city"],
+            json!({ "type": "string" })
+        );
+    }
+
+    #[test]
+    fn ref_cycles_point_back_at_the_copied_component() {
+        let dir = spec(
+            "cycle",
+            &[
+                ("openapi.yaml", ROOT),
+                ("paths/users.yaml", USERS),
+                ("common.yaml", "components: { parameters: { Limit: { name: limit, in: query } } }"),
+                (
+                    "schemas/user.yaml",
+                    "type: object\nproperties:\n  manager: { $ref: '#' }\n  team: { $ref: team.yaml }",
+                ),
+                ("schemas/team.yaml", "type: object\nproperties: { lead: { $ref: user.yaml } }"),
+            ],
+        );
+        let document = load_from(&dir).unwrap();
+        let schemas = &document["components"]["schemas"];
+        let user = json!({ "$ref": "#/components/schemas/User" });
+        assert_eq!(schemas["User"]["properties"]["manager"], user);
+        assert_eq!(
+            schemas["User"]["properties"]["team"],
+            json!({ "$ref": "#/components/schemas/team" })
+        );
+        assert_eq!(schemas["team"]["properties"]["lead"], user);
+    }
+
+    #[test]
+    fn missing_files_and_remote_refs_are_errors() {
+        let dir = spec(
+            "missing",
+            &[
+                ("openapi.yaml", ROOT),
+                ("paths/users.yaml", USERS),
+                (
+                    "common.yaml",
+                    "components: { parameters: { Limit: { name: limit, in: query } } }",
+                ),
+                (
+                    "schemas/user.yaml",
+                    "type: object\nproperties: { address: { $ref: address.yaml } }",
+                ),
+            ],
+        );
+        let error = format!("{:#}", load_from(&dir).unwrap_err());
+        assert!(
+            error.starts_with("Failed to read referenced file: "),
+            "{}",
+            error
+        );
+        assert!(error.contains("address.yaml"), "{}", error);
+
+        let dir = spec(
+            "remote",
+            &[(
+                "openapi.yaml",
+                &ROOT.replace("schemas/user.yaml", "https://example.com/user.yaml"),
+            )],
+        );
+        let error = load_from(&dir).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Remote $ref is not supported: https://example.com/user.yaml"
+        );
+    }
+}
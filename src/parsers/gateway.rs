@@ -0,0 +1,183 @@
+//! Gateway mode: the IRs of several services merged into one, so a single
+//! client reaches all of them through the gateway (`gateway` config section).
+//!
+//! - Operations keep the service they come from (`namespace`), which clients
+//!   group them by: `client.users.getUser()`. Ids another service also
+//!   declares get the service's prefix (`billingGetUser`) where generators
+//!   need them unique.
+//! - Schema names declared by more than one service get the service's prefix
+//!   (`UsersAccount`, `BillingAccount`), or every schema does with
+//!   `schemaPrefix: always`; references follow the rename.
+//! - A service's `pathPrefix` is prepended to its operation paths.
+//! - Metadata, servers, and the original document are the first service's;
+//!   tags, channels, and coverage are combined.
+
+use super::name_collisions::rewrite_references;
+use super::{OperationDefinition, OperationNamespace, SchemaIR};
+use crate::generators::naming;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaPrefix {
+    Collisions,
+    Always,
+}
+
+impl SchemaPrefix {
+    pub fn from_config(value: Option<&str>) -> Result<Self> {
+        match value {
+            None | Some("collisions") => Ok(Self::Collisions),
+            Some("always") => Ok(Self::Always),
+            _ => {
+                anyhow::bail!("gateway: option 'schemaPrefix' must be \"collisions\" or \"always\"")
+            }
+        }
+    }
+}
+
+/// One parsed service of the gateway
+pub struct Service {
+    pub name: String,
+    pub path_prefix: Option<String>,
+    pub schema_ir: SchemaIR,
+}
+
+/// The services' IRs as one
+pub fn merge(services: Vec<Service>, schema_prefix: SchemaPrefix) -> Result<SchemaIR> {
+    let mut names = HashSet::new();
+    for service in &services {
+        if !service.name.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !service
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            anyhow::bail!(
+                "gateway: service name '{}' must be an identifier (letters, digits, and `_`)",
+                service.name
+            );
+        }
+        if !names.insert(&service.name) {
+            anyhow::bail!("gateway: service name '{}' is used twice", service.name);
+        }
+    }
+
+    let declared_by = |collect: &dyn Fn(&SchemaIR) -> Vec<String>| {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for service in &services {
+            for name in collect(&service.schema_ir) {
+                *counts.entry(name).or_default() += 1;
+            }
+        }
+        counts
+    };
+    let schema_counts = declared_by(&|ir| ir.schemas.iter().map(|s| s.name.clone()).collect());
+    let operation_counts =
+        declared_by(&|ir| ir.operations.iter().map(|op| op.id.clone()).collect());
+
+    let mut merged: Option<SchemaIR> = None;
+    for service in services {
+        let Service {
+            name,
+            path_prefix,
+            mut schema_ir,
+        } = service;
+        let prefix = naming::pascal_case(&name);
+
+        let renames: HashMap<String, String> = schema_ir
+            .schemas
+            .iter()
+            .filter(|schema| {
+                schema_prefix == SchemaPrefix::Always || schema_counts[&schema.name] > 1
+            })
+            .map(|schema| (schema.name.clone(), format!("{}{}", prefix, schema.name)))
+            .collect();
+        for schema in &mut schema_ir.schemas {
+            if let Some(renamed) = renames.get(&schema.name) {
+                schema.name = renamed.clone();
+            }
+        }
+        rewrite_references(&mut schema_ir, &renames);
+
+        let mut renamed_operations = HashMap::new();
+        for operation in &mut schema_ir.operations {
+            let member = operation.id.clone();
+            if operation_counts[&member] > 1 {
+                operation.id = format!(
+                    "{}{}",
+                    naming::camel_case(&name),
+                    naming::pascal_case(&member)
+                );
+                renamed_operations.insert(member.clone(), operation.id.clone());
+            }
+            if let Some(path_prefix) = &path_prefix {
+                operation.path = format!("{}{}", path_prefix.trim_end_matches('/'), operation.path);
+            }
+            operation.namespace = Some(OperationNamespace {
+                name: name.clone(),
+                member,
+            });
+        }
+        // Links stay within their service
+        for link in schema_ir
+            .operations
+            .iter_mut()
+            .flat_map(|operation| &mut operation.links)
+        {
+            if let Some(renamed) = link
+                .operation
+                .as_ref()
+                .and_then(|id| renamed_operations.get(id))
+            {
+                link.operation = Some(renamed.clone());
+            }
+        }
+        for usage in &mut schema_ir.coverage {
+            for location in &mut usage.locations {
+                *location = format!("{}: {}", name, location);
+            }
+        }
+
+        let Some(combined) = merged.as_mut() else {
+            merged = Some(schema_ir);
+            continue;
+        };
+        combined.schemas.extend(schema_ir.schemas);
+        combined.operations.extend(schema_ir.operations);
+        combined.channels.extend(schema_ir.channels);
+        for tag in schema_ir.metadata.tags {
+            if !combined.metadata.tags.iter().any(|t| t.name == tag.name) {
+                combined.metadata.tags.push(tag);
+            }
+        }
+        for usage in schema_ir.coverage {
+            match combined
+                .coverage
+                .iter_mut()
+                .find(|u| u.construct == usage.construct && u.handling == usage.handling)
+            {
+                Some(existing) => existing.locations.extend(usage.locations),
+                None => combined.coverage.push(usage),
+            }
+        }
+    }
+    merged.ok_or_else(|| anyhow::anyhow!("gateway: no services configured"))
+}
+
+/// Service names with their operations and each one's name in the service,
+/// in service order; empty outside gateway mode
+pub fn namespaces(schema_ir: &SchemaIR) -> Vec<(&str, Vec<(&str, &OperationDefinition)>)> {
+    let mut groups: Vec<(&str, Vec<(&str, &OperationDefinition)>)> = Vec::new();
+    for operation in &schema_ir.operations {
+        let Some(namespace) = &operation.namespace else {
+            continue;
+        };
+        let member = (namespace.member.as_str(), operation);
+        match groups.iter_mut().find(|(name, _)| *name == namespace.name) {
+            Some((_, members)) => members.push(member),
+            None => groups.push((&namespace.name, vec![member])),
+        }
+    }
+    groups
+}
@@ -0,0 +1,721 @@
+//! Differences between two IRs of the same API.
+//!
+//! Operations are matched by method and path, so an `operationId` rename is a
+//! change rather than a removal plus an addition; schemas and their fields are
+//! matched by name. Every change records whether clients generated from the
+//! old IR can break against the new one. Descriptions and examples are not
+//! compared.
+
+use super::{
+    FieldDefinition, OperationDefinition, Parameter, SchemaDefinition, SchemaIR, SchemaReference,
+    TypeInfo,
+};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Changed,
+    Removed,
+}
+
+/// What a change applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeTarget {
+    Operation,
+    Schema,
+    Field,
+}
+
+/// One difference between the old and the new IR
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub target: ChangeTarget,
+    /// `GET /pets/{id}`, `Pet`, or `Pet.name`
+    pub subject: String,
+    /// Operation id, schema name, or field name, as of the new IR where present
+    pub name: String,
+    /// Type of a field (`string`, `Pet[]`), as of the new IR where present
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub field_type: Option<String>,
+    /// What changed, e.g. "type `integer` → `number`"; empty for additions and removals
+    pub details: Vec<String>,
+    /// Whether clients generated from the old IR can break
+    pub breaking: bool,
+}
+
+/// Release a diff calls for under semantic versioning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SemverBump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl SemverBump {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Patch => "patch",
+            Self::Minor => "minor",
+            Self::Major => "major",
+        }
+    }
+
+    /// `version` bumped, keeping a leading `v`; `None` unless it is `major.minor.patch`
+    pub fn apply(self, version: &str) -> Option<String> {
+        let (prefix, number) = match version.strip_prefix('v') {
+            Some(number) => ("v", number),
+            None => ("", version),
+        };
+        let parts: Vec<u64> = number
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        let [major, minor, patch] = parts[..] else {
+            return None;
+        };
+        let (major, minor, patch) = match self {
+            Self::Major => (major + 1, 0, 0),
+            Self::Minor => (major, minor + 1, 0),
+            Self::Patch => (major, minor, patch + 1),
+        };
+        Some(format!("{}{}.{}.{}", prefix, major, minor, patch))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IrDiff {
+    /// Operations first, then schemas with their fields, in new-IR order;
+    /// removals follow in old-IR order
+    pub changes: Vec<Change>,
+}
+
+impl IrDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn has_breaking(&self) -> bool {
+        self.changes.iter().any(|change| change.breaking)
+    }
+
+    pub fn has_additions(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.kind == ChangeKind::Added)
+    }
+
+    /// Major for breaking changes, minor for additions, patch otherwise
+    pub fn suggested_bump(&self) -> SemverBump {
+        if self.has_breaking() {
+            SemverBump::Major
+        } else if self.has_additions() {
+            SemverBump::Minor
+        } else {
+            SemverBump::Patch
+        }
+    }
+}
+
+/// Changes from `old` to `new`
+pub fn diff(old: &SchemaIR, new: &SchemaIR) -> IrDiff {
+    let mut changes = Vec::new();
+    diff_operations(&old.operations, &new.operations, &mut changes);
+    diff_schemas(&old.schemas, &new.schemas, &mut changes);
+    IrDiff { changes }
+}
+
+fn operation_subject(operation: &OperationDefinition) -> String {
+    format!(
+        "{} {}",
+        format!("{:?}", operation.method).to_uppercase(),
+        operation.path
+    )
+}
+
+fn diff_operations(
+    old: &[OperationDefinition],
+    new: &[OperationDefinition],
+    changes: &mut Vec<Change>,
+) {
+    for operation in new {
+        let subject = operation_subject(operation);
+        let Some(previous) = old.iter().find(|op| operation_subject(op) == subject) else {
+            changes.push(Change {
+                kind: ChangeKind::Added,
+                target: ChangeTarget::Operation,
+                subject,
+                name: operation.id.clone(),
+                field_type: None,
+                details: Vec::new(),
+                breaking: false,
+            });
+            continue;
+        };
+
+        let mut details = Vec::new();
+        let mut breaking = false;
+        if previous.id != operation.id {
+            details.push(format!("renamed `{}` → `{}`", previous.id, operation.id));
+            breaking = true;
+        }
+        breaking |= diff_parameters(&previous.parameters, &operation.parameters, &mut details);
+        for (label, before, after) in [
+            (
+                "request body",
+                &previous.request_body,
+                &operation.request_body,
+            ),
+            ("response", &previous.response, &operation.response),
+        ] {
+            if reference_label(before) != reference_label(after) {
+                details.push(format!(
+                    "{} {} → {}",
+                    label,
+                    reference_label(before),
+                    reference_label(after)
+                ));
+                breaking = true;
+            }
+        }
+        for (label, before, after) in [
+            (
+                "request content type",
+                &previous.request_content_type,
+                &operation.request_content_type,
+            ),
+            (
+                "response content type",
+                &previous.response_content_type,
+                &operation.response_content_type,
+            ),
+        ] {
+            if before != after {
+                details.push(format!(
+                    "{} {} → {}",
+                    label,
+                    optional_label(before),
+                    optional_label(after)
+                ));
+                breaking = true;
+            }
+        }
+
+        if !details.is_empty() {
+            changes.push(Change {
+                kind: ChangeKind::Changed,
+                target: ChangeTarget::Operation,
+                subject,
+                name: operation.id.clone(),
+                field_type: None,
+                details,
+                breaking,
+            });
+        }
+    }
+
+    let subjects: Vec<String> = new.iter().map(operation_subject).collect();
+    for operation in old {
+        let subject = operation_subject(operation);
+        if !subjects.contains(&subject) {
+            changes.push(Change {
+                kind: ChangeKind::Removed,
+                target: ChangeTarget::Operation,
+                subject,
+                name: operation.id.clone(),
+                field_type: None,
+                details: Vec::new(),
+                breaking: true,
+            });
+        }
+    }
+}
+
+/// Parameter details, returning whether any of them break callers
+fn diff_parameters(old: &[Parameter], new: &[Parameter], details: &mut Vec<String>) -> bool {
+    let mut breaking = false;
+    for param in new {
+        match old.iter().find(|p| p.name == param.name) {
+            None if param.required => {
+                details.push(format!("required parameter `{}` added", param.name));
+                breaking = true;
+            }
+            None => details.push(format!("optional parameter `{}` added", param.name)),
+            Some(previous) => {
+                if previous.location != param.location {
+                    details.push(format!(
+                        "parameter `{}` moved from {} to {}",
+                        param.name,
+                        format!("{:?}", previous.location).to_lowercase(),
+                        format!("{:?}", param.location).to_lowercase()
+                    ));
+                    breaking = true;
+                }
+                if previous.schema_type != param.schema_type {
+                    details.push(format!(
+                        "parameter `{}` type `{}` → `{}`",
+                        param.name, previous.schema_type, param.schema_type
+                    ));
+                    breaking = true;
+                }
+                if previous.required != param.required {
+                    if param.required {
+                        details.push(format!("parameter `{}` now required", param.name));
+                        breaking = true;
+                    } else {
+                        details.push(format!("parameter `{}` now optional", param.name));
+                    }
+                }
+            }
+        }
+    }
+    for param in old {
+        if !new.iter().any(|p| p.name == param.name) {
+            details.push(format!("parameter `{}` removed", param.name));
+            breaking = true;
+        }
+    }
+    breaking
+}
+
+fn reference_label(reference: &Option<SchemaReference>) -> String {
+    match reference {
+        Some(r) if r.schema_type == "array" => format!("`{}[]`", r.name),
+        Some(r) => format!("`{}`", r.name),
+        None => "none".to_string(),
+    }
+}
+
+fn optional_label(value: &Option<String>) -> String {
+    match value {
+        Some(value) => format!("`{}`", value),
+        None => "none".to_string(),
+    }
+}
+
+fn diff_schemas(old: &[SchemaDefinition], new: &[SchemaDefinition], changes: &mut Vec<Change>) {
+    for schema in new {
+        let Some(previous) = old.iter().find(|s| s.name == schema.name) else {
+            changes.push(Change {
+                kind: ChangeKind::Added,
+                target: ChangeTarget::Schema,
+                subject: schema.name.clone(),
+                name: schema.name.clone(),
+                field_type: None,
+                details: Vec::new(),
+                breaking: false,
+            });
+            continue;
+        };
+        diff_fields(schema, &previous.fields, &schema.fields, changes);
+    }
+
+    for schema in old {
+        if !new.iter().any(|s| s.name == schema.name) {
+            changes.push(Change {
+                kind: ChangeKind::Removed,
+                target: ChangeTarget::Schema,
+                subject: schema.name.clone(),
+                name: schema.name.clone(),
+                field_type: None,
+                details: Vec::new(),
+                breaking: true,
+            });
+        }
+    }
+}
+
+fn diff_fields(
+    schema: &SchemaDefinition,
+    old: &[FieldDefinition],
+    new: &[FieldDefinition],
+    changes: &mut Vec<Change>,
+) {
+    for field in new {
+        let subject = format!("{}.{}", schema.name, field.name);
+        let Some(previous) = old.iter().find(|f| f.name == field.name) else {
+            // Request bodies built by existing callers lack a new required field
+            changes.push(Change {
+                kind: ChangeKind::Added,
+                target: ChangeTarget::Field,
+                subject,
+                name: field.name.clone(),
+                field_type: Some(type_label(&field.type_info)),
+                details: vec![if field.required {
+                    "required"
+                } else {
+                    "optional"
+                }
+                .to_string()],
+                breaking: field.required,
+            });
+            continue;
+        };
+
+        let mut details = Vec::new();
+        let mut breaking = false;
+        let (before, after) = (
+            type_label(&previous.type_info),
+            type_label(&field.type_info),
+        );
+        if before != after {
+            details.push(format!("type `{}` → `{}`", before, after));
+            breaking = true;
+        }
+        if let (Some(before), Some(after)) = (
+            &previous.type_info.enum_values,
+            &field.type_info.enum_values,
+        ) {
+            let added: Vec<_> = after
+                .iter()
+                .filter(|v| !before.contains(v))
+                .map(|v| format!("`{}`", v))
+                .collect();
+            let removed: Vec<_> = before
+                .iter()
+                .filter(|v| !after.contains(v))
+                .map(|v| format!("`{}`", v))
+                .collect();
+            if !added.is_empty() {
+                details.push(format!("enum values {} added", added.join(", ")));
+            }
+            if !removed.is_empty() {
+                details.push(format!("enum values {} removed", removed.join(", ")));
+                breaking = true;
+            }
+        }
+        // Either direction breaks somebody: writers for `required`, readers for optional
+        if previous.required != field.required {
+            details.push(
+                if field.required {
+                    "now required"
+                } else {
+                    "no longer required"
+                }
+                .to_string(),
+            );
+            breaking = true;
+        }
+        if previous.nullable != field.nullable {
+            if field.nullable {
+                details.push("now nullable".to_string());
+                breaking = true;
+            } else {
+                details.push("no longer nullable".to_string());
+            }
+        }
+
+        if !details.is_empty() {
+            changes.push(Change {
+                kind: ChangeKind::Changed,
+                target: ChangeTarget::Field,
+                subject,
+                name: field.name.clone(),
+                field_type: Some(after),
+                details,
+                breaking,
+            });
+        }
+    }
+
+    for field in old {
+        if !new.iter().any(|f| f.name == field.name) {
+            changes.push(Change {
+                kind: ChangeKind::Removed,
+                target: ChangeTarget::Field,
+                subject: format!("{}.{}", schema.name, field.name),
+                name: field.name.clone(),
+                field_type: Some(type_label(&field.type_info)),
+                details: Vec::new(),
+                breaking: true,
+            });
+        }
+    }
+}
+
+/// `Pet`, `string[]`, `Record<string, Pet>`, `string (date-time)`, `Cat | Dog`; enum values are compared separately
+fn type_label(type_info: &TypeInfo) -> String {
+    if !type_info.tuple_items.is_empty() {
+        let items: Vec<String> = type_info.tuple_items.iter().map(type_label).collect();
+        return format!("[{}]", items.join(", "));
+    }
+    if type_info.is_array {
+        return match &type_info.array_item_type {
+            Some(item) => format!("{}[]", type_label(item)),
+            None => "array".to_string(),
+        };
+    }
+    if let Some(reference) = &type_info.reference {
+        return reference.clone();
+    }
+    if let Some(values) = &type_info.map_values {
+        return format!("Record<string, {}>", type_label(values));
+    }
+    if !type_info.union_members.is_empty() {
+        let members: Vec<String> = type_info.union_members.iter().map(type_label).collect();
+        return members.join(" | ");
+    }
+    match &type_info.format {
+        Some(format) => format!("{} ({})", type_info.openapi_type, format),
+        None => type_info.openapi_type.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{InputParser, OpenApiParser};
+    use std::collections::HashMap;
+
+    const BASE: &str = r##"
+openapi: 3.0.3
+info: { title: Pets, version: 1.0.0 }
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      description: Every pet
+      responses:
+        "200": { description: ok, content: { application/json: { schema: { type: array, items: { $ref: "#/components/schemas/Pet" } } } } }
+  /pets/{id}:
+    delete:
+      operationId: deletePet
+      parameters: [{ name: id, in: path, required: true, schema: { type: string } }]
+      responses: { "204": { description: gone } }
+components:
+  schemas:
+    Pet:
+      type: object
+      description: A pet
+      required: [id, name]
+      properties:
+        id: { type: string }
+        name: { type: string, description: What it answers to }
+"##;
+
+    fn parse(spec: &str) -> SchemaIR {
+        OpenApiParser.parse_str(spec, &HashMap::new()).unwrap()
+    }
+
+    /// Diff from `BASE` to `BASE` with `edit` applied, asserting it changed the spec
+    fn diff_after(edit: impl Fn(&str) -> String) -> IrDiff {
+        let edited = edit(BASE);
+        assert_ne!(edited, BASE, "the edit must change the spec");
+        diff(&parse(BASE), &parse(&edited))
+    }
+
+    #[test]
+    fn removed_operation_is_major() {
+        let changes = diff_after(|spec| {
+            let start = spec.find("  /pets/{id}:").unwrap();
+            let end = spec.find("components:").unwrap();
+            format!("{}{}", &spec[..start], &spec[end..])
+        });
+        assert!(changes.has_breaking());
+        assert_eq!(changes.suggested_bump(), SemverBump::Major);
+    }
+
+    #[test]
+    fn new_optional_field_is_minor() {
+        let changes = diff_after(|spec| {
+            spec.replace(
+            "        name: { type: string, description: What it answers to }",
+            "        name: { type: string, description: What it answers to }\n        tag: { type: string }",
+        )
+        });
+        assert!(!changes.has_breaking());
+        assert_eq!(changes.suggested_bump(), SemverBump::Minor);
+    }
+
+    #[test]
+    fn new_required_field_is_major() {
+        let changes = diff_after(|spec| {
+            spec.replace(
+            "      required: [id, name]\n      properties:\n",
+            "      required: [id, name, tag]\n      properties:\n        tag: { type: string }\n",
+        )
+        });
+        assert_eq!(changes.suggested_bump(), SemverBump::Major);
+    }
+
+    #[test]
+    fn description_only_change_is_patch() {
+        let changes = diff_after(|spec| {
+            spec.replace("Every pet", "All the pets")
+                .replace("A pet", "One pet")
+                .replace("What it answers to", "Its name")
+        });
+        assert!(changes.is_empty());
+        assert_eq!(changes.suggested_bump(), SemverBump::Patch);
+    }
+
+    #[test]
+    fn response_field_no_longer_required_is_major() {
+        // `Pet` is only ever read from responses, where readers relied on `name` being there
+        let changes = diff_after(|spec| spec.replace("required: [id, name]", "required: [id]"));
+        assert_eq!(changes.changes.len(), 1);
+        assert_eq!(changes.changes[0].details, vec!["no longer required"]);
+        assert_eq!(changes.suggested_bump(), SemverBump::Major);
+    }
+
+    #[test]
+    fn bumps_apply_to_major_minor_patch_versions() {
+        assert_eq!(SemverBump::Major.apply("1.4.2").as_deref(), Some("2.0.0"));
+        assert_eq!(SemverBump::Minor.apply("1.4.2").as_deref(), Some("1.5.0"));
+        assert_eq!(SemverBump::Patch.apply("v1.4.2").as_deref(), Some("v1.4.3"));
+        for version in ["1.4", "1.4.2.0", "1.4.x", "1.4.2-beta", ""] {
+            assert_eq!(SemverBump::Patch.apply(version), None, "{}", version);
+        }
+    }
+
+    /// `(kind, subject, name, type, details, breaking)` of a change
+    type Summary<'a> = (
+        ChangeKind,
+        &'a str,
+        &'a str,
+        Option<&'a str>,
+        Vec<&'a str>,
+        bool,
+    );
+
+    fn summary(changes: &IrDiff) -> Vec<Summary<'_>> {
+        changes
+            .changes
+            .iter()
+            .map(|c| {
+                let details = c.details.iter().map(String::as_str).collect();
+                (
+                    c.kind,
+                    c.subject.as_str(),
+                    c.name.as_str(),
+                    c.field_type.as_deref(),
+                    details,
+                    c.breaking,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn operations_are_matched_by_method_and_path() {
+        let changes = diff_after(|spec| {
+            spec.replace("operationId: deletePet", "operationId: removePet").replace(
+                "      parameters: [{ name: id, in: path, required: true, schema: { type: string } }]",
+                "      parameters:\n        - { name: id, in: path, required: true, schema: { type: integer } }\n        - { name: force, in: query, schema: { type: boolean } }\n        - { name: reason, in: header, required: true, schema: { type: string } }",
+            )
+        });
+        assert_eq!(
+            summary(&changes),
+            vec![(
+                ChangeKind::Changed,
+                "DELETE /pets/{id}",
+                "removePet",
+                None,
+                vec![
+                    "renamed `deletePet` → `removePet`",
+                    "parameter `id` type `string` → `integer`",
+                    "optional parameter `force` added",
+                    "required parameter `reason` added",
+                ],
+                true,
+            )]
+        );
+    }
+
+    #[test]
+    fn field_changes_name_the_field_and_its_type() {
+        let changes = diff_after(|spec| {
+            spec.replace("        id: { type: string }\n", "        id: { type: integer }\n")
+                .replace(
+                    "        name: { type: string, description: What it answers to }",
+                    "        name: { type: string, nullable: true }\n        kind: { type: string, enum: [cat, dog] }",
+                )
+        });
+        assert_eq!(
+            summary(&changes),
+            vec![
+                (
+                    ChangeKind::Changed,
+                    "Pet.id",
+                    "id",
+                    Some("integer"),
+                    vec!["type `string` → `integer`"],
+                    true
+                ),
+                (
+                    ChangeKind::Changed,
+                    "Pet.name",
+                    "name",
+                    Some("string"),
+                    vec!["now nullable"],
+                    true
+                ),
+                (
+                    ChangeKind::Added,
+                    "Pet.kind",
+                    "kind",
+                    Some("string"),
+                    vec!["optional"],
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn enum_values_break_only_when_removed() {
+        let with_kind = |values: &str| {
+            BASE.replace(
+                "        name: { type: string, description: What it answers to }",
+                &format!("        name: {{ type: string }}\n        kind: {{ type: string, enum: [{}] }}", values),
+            )
+        };
+        let added = diff(&parse(&with_kind("cat")), &parse(&with_kind("cat, dog")));
+        assert_eq!(summary(&added)[0].4, vec!["enum values `dog` added"]);
+        assert!(!added.has_breaking());
+        let removed = diff(&parse(&with_kind("cat, dog")), &parse(&with_kind("cat")));
+        assert_eq!(summary(&removed)[0].4, vec!["enum values `dog` removed"]);
+        assert!(removed.has_breaking());
+    }
+
+    #[test]
+    fn operations_come_before_schemas_with_removals_after_their_section() {
+        let changes = diff_after(|spec| {
+            let start = spec.find("  /pets/{id}:").unwrap();
+            let end = spec.find("components:").unwrap();
+            format!("{}{}", &spec[..start], &spec[end..])
+                .replace(
+                    "        name: { type: string, description: What it answers to }\n",
+                    "",
+                )
+                .replace("required: [id, name]", "required: [id]")
+                + "    Owner:\n      type: object\n      properties: { id: { type: string } }\n"
+        });
+        assert_eq!(
+            summary(&changes),
+            vec![
+                (
+                    ChangeKind::Removed,
+                    "DELETE /pets/{id}",
+                    "deletePet",
+                    None,
+                    vec![],
+                    true
+                ),
+                (
+                    ChangeKind::Removed,
+                    "Pet.name",
+                    "name",
+                    Some("string"),
+                    vec![],
+                    true
+                ),
+                (ChangeKind::Added, "Owner", "Owner", None, vec![], false),
+            ]
+        );
+    }
+}
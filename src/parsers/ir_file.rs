@@ -0,0 +1,193 @@
+//! Exported IR files: the IR as JSON with the version of its shape
+//! (`irVersion`), written by `generator --dump-ir` and read back by the `ir`
+//! input format, so a parsed spec can be stored, post-processed, or fed to
+//! another build of the generator.
+//!
+//! Compatibility policy: [`IR_VERSION`] changes when a field is removed or
+//! renamed or its type changes. Added fields keep it, and are read with their
+//! defaults from files that lack them. Files of an older version are upgraded
+//! on load by `MIGRATIONS`; files of a newer version are refused rather
+//! than read with fields silently dropped.
+
+use super::{InputParser, SchemaIR};
+use crate::diagnostics::{self, SourceError};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Version of the IR shape exported files carry
+pub const IR_VERSION: u64 = 1;
+
+/// Upgrades of exported documents; `MIGRATIONS[n]` takes version `n` to `n + 1`
+const MIGRATIONS: &[fn(&mut Value)] = &[from_unversioned];
+
+/// Version 0 is an IR serialized before files carried `irVersion`; its shape
+/// is version 1's, with fields added since read with their defaults
+fn from_unversioned(_document: &mut Value) {}
+
+#[derive(Serialize)]
+struct Exported<'a> {
+    #[serde(rename = "irVersion")]
+    ir_version: u64,
+    #[serde(flatten)]
+    schema_ir: &'a SchemaIR,
+}
+
+/// `schema_ir` as the contents of an exported IR file
+pub fn export(schema_ir: &SchemaIR) -> Result<String> {
+    let exported = Exported {
+        ir_version: IR_VERSION,
+        schema_ir,
+    };
+    Ok(serde_json::to_string_pretty(&exported)? + "\n")
+}
+
+/// The IR of an exported file's `content`, upgraded to [`IR_VERSION`]
+pub fn import(name: &str, content: &str) -> Result<SchemaIR> {
+    let mut document: Value = serde_json::from_str(content)
+        .map_err(|error| SourceError::from_json(name, content, &error))?;
+    let version = match document.get("irVersion") {
+        None => 0,
+        Some(version) => version.as_u64().ok_or_else(|| {
+            SourceError::at_path(
+                name,
+                content,
+                &["irVersion".to_string()],
+                "must be a number".to_string(),
+            )
+        })?,
+    };
+    if version > IR_VERSION {
+        anyhow::bail!(
+            "{} has IR version {}; this generator reads versions up to {}, upgrade the generator to read it",
+            name,
+            version,
+            IR_VERSION
+        );
+    }
+    for migrate in &MIGRATIONS[version as usize..] {
+        migrate(&mut document);
+    }
+    if let Some(fields) = document.as_object_mut() {
+        fields.shift_remove("irVersion");
+    }
+    Ok(diagnostics::from_value(name, content, document)?)
+}
+
+/// The `ir` input format: exported IR files, taken as they are; parser
+/// options applied when the file was exported
+pub struct IrFileParser;
+
+impl InputParser for IrFileParser {
+    fn format_name(&self) -> &str {
+        "ir"
+    }
+
+    fn supported_extensions(&self) -> Vec<&str> {
+        // Exported files are `.json` like specs; `can_parse` tells them apart
+        vec![]
+    }
+
+    fn can_parse(&self, content: &str) -> bool {
+        super::declares_key(content, "irVersion")
+    }
+
+    fn parse(&self, source: &Path, _options: &HashMap<String, Value>) -> Result<SchemaIR> {
+        self.validate(source)?;
+        let content = fs::read_to_string(source)
+            .with_context(|| format!("Failed to read IR file: {:?}", source))?;
+        import(&source.display().to_string(), &content)
+    }
+
+    fn parse_str(&self, content: &str, _options: &HashMap<String, Value>) -> Result<SchemaIR> {
+        import("ir", content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::OpenApiParser;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      responses: { "200": { description: ok, content: { application/json: { schema: { $ref: "#/components/schemas/User" } } } } }
+components:
+  schemas:
+    User: { type: object, properties: { name: { type: string } } }
+"##;
+
+    fn exported() -> (SchemaIR, Value) {
+        let schema_ir = OpenApiParser.parse_str(SPEC, &HashMap::new()).unwrap();
+        let document = serde_json::from_str(&export(&schema_ir).unwrap()).unwrap();
+        (schema_ir, document)
+    }
+
+    #[test]
+    fn every_version_has_a_migration() {
+        assert_eq!(MIGRATIONS.len() as u64, IR_VERSION);
+    }
+
+    #[test]
+    fn current_files_round_trip() {
+        let (schema_ir, document) = exported();
+        assert_eq!(document["irVersion"], IR_VERSION);
+        let imported = import("ir", &document.to_string()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&imported).unwrap(),
+            serde_json::to_value(&schema_ir).unwrap()
+        );
+    }
+
+    #[test]
+    fn unversioned_files_are_upgraded() {
+        // Version 0: no `irVersion`, and none of the fields added since
+        let (schema_ir, mut document) = exported();
+        let fields = document.as_object_mut().unwrap();
+        fields.shift_remove("irVersion");
+        fields.shift_remove("channels");
+        fields.shift_remove("coverage");
+
+        let imported = import("ir", &document.to_string()).unwrap();
+        assert!(imported.channels.is_empty() && imported.coverage.is_empty());
+        assert_eq!(imported.metadata.title, schema_ir.metadata.title);
+        assert_eq!(imported.schemas[0].name, "User");
+        assert_eq!(imported.operations[0].id, "listUsers");
+        assert_eq!(
+            export(&imported).unwrap(),
+            export(&SchemaIR {
+                channels: Vec::new(),
+                coverage: Vec::new(),
+                ..schema_ir
+            })
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn newer_and_malformed_versions_are_refused() {
+        let (_, mut document) = exported();
+        document["irVersion"] = (IR_VERSION + 1).into();
+        let error = import("dump.json", &document.to_string()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "dump.json has IR version {}; this generator reads versions up to {}, upgrade the generator to read it",
+                IR_VERSION + 1,
+                IR_VERSION
+            )
+        );
+
+        document["irVersion"] = "one".into();
+        let error = import("dump.json", &document.to_string()).unwrap_err();
+        assert!(error.to_string().contains("must be a number"));
+    }
+}
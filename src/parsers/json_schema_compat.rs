@@ -0,0 +1,135 @@
+//! Rewrites of JSON Schema 2020-12 keywords (OpenAPI 3.1) that the OpenAPI 3.0
+//! model in `openapiv3` either rejects or silently drops.
+//!
+//! - `type: [a, b]` becomes an `anyOf` over single-type schemas, and a `"null"`
+//!   entry becomes `nullable: true`
+//! - `prefixItems` moves to the `x-prefixItems` extension so tuple item schemas
+//!   survive deserialization; a boolean `items` (closed tuple) is dropped
+//!
+//! Runs on the raw document before it is deserialized.
+
+use serde_json::{Map, Value};
+
+/// Extension key holding the tuple item schemas moved out of `prefixItems`
+pub const PREFIX_ITEMS_EXTENSION: &str = "x-prefixItems";
+
+/// Keywords whose values are instance data, not schemas
+const DATA_KEYWORDS: &[&str] = &["example", "examples", "default", "enum", "const"];
+
+/// Keywords whose values map names to schemas
+const NAMED_SCHEMA_MAPS: &[&str] = &["properties", "patternProperties", "schemas", "$defs"];
+
+/// Keywords that only apply to one kind of type, moved onto that `anyOf` member
+const TYPE_KEYWORDS: &[(&str, &[&str])] = &[
+    ("string", &["format", "pattern", "minLength", "maxLength"]),
+    ("number", &["format", "minimum", "maximum", "multipleOf"]),
+    ("integer", &["format", "minimum", "maximum", "multipleOf"]),
+    (
+        "array",
+        &[
+            "items",
+            PREFIX_ITEMS_EXTENSION,
+            "minItems",
+            "maxItems",
+            "uniqueItems",
+        ],
+    ),
+    (
+        "object",
+        &[
+            "properties",
+            "required",
+            "additionalProperties",
+            "minProperties",
+            "maxProperties",
+        ],
+    ),
+];
+
+/// Rewrite every schema in the document in place
+pub fn normalize(document: &mut Value) {
+    walk(document, false);
+}
+
+fn walk(value: &mut Value, named_schemas: bool) {
+    match value {
+        Value::Object(map) => {
+            if !named_schemas {
+                rewrite_prefix_items(map);
+                rewrite_type_array(map);
+            }
+            for (key, child) in map.iter_mut() {
+                if !named_schemas && DATA_KEYWORDS.contains(&key.as_str()) {
+                    continue;
+                }
+                walk(
+                    child,
+                    !named_schemas && NAMED_SCHEMA_MAPS.contains(&key.as_str()),
+                );
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| walk(item, false)),
+        _ => {}
+    }
+}
+
+fn rewrite_prefix_items(map: &mut Map<String, Value>) {
+    let Some(prefix_items) = map.remove("prefixItems") else {
+        return;
+    };
+    map.insert(PREFIX_ITEMS_EXTENSION.to_string(), prefix_items);
+    map.entry("type").or_insert_with(|| Value::from("array"));
+    if map.get("items").is_some_and(Value::is_boolean) {
+        map.remove("items");
+    }
+}
+
+fn rewrite_type_array(map: &mut Map<String, Value>) {
+    let Some(Value::Array(types)) = map.get("type") else {
+        return;
+    };
+    let mut types: Vec<String> = types
+        .iter()
+        .filter_map(|t| t.as_str().map(str::to_string))
+        .collect();
+    map.remove("type");
+
+    if types.iter().any(|t| t == "null") {
+        types.retain(|t| t != "null");
+        map.insert("nullable".to_string(), Value::Bool(true));
+    }
+
+    match types.as_slice() {
+        [] => {}
+        [single] => {
+            map.insert("type".to_string(), Value::from(single.as_str()));
+        }
+        _ => {
+            let members: Vec<Value> = types.iter().map(|t| member_schema(map, t)).collect();
+            for (_, keywords) in TYPE_KEYWORDS {
+                for keyword in *keywords {
+                    map.remove(*keyword);
+                }
+            }
+            map.insert("anyOf".to_string(), Value::Array(members));
+        }
+    }
+}
+
+/// Single-type schema carrying the parent's keywords that apply to `type_name`
+fn member_schema(parent: &Map<String, Value>, type_name: &str) -> Value {
+    let mut member = Map::new();
+    member.insert("type".to_string(), Value::from(type_name));
+
+    let keywords = TYPE_KEYWORDS
+        .iter()
+        .find(|(name, _)| *name == type_name)
+        .map(|(_, keywords)| *keywords)
+        .unwrap_or_default();
+    for keyword in keywords {
+        if let Some(value) = parent.get(*keyword) {
+            member.insert(keyword.to_string(), value.clone());
+        }
+    }
+    Value::Object(member)
+}
@@ -0,0 +1,152 @@
+//! Response `links` between operations.
+//!
+//! Links are read with the responses declaring them. Once every operation has
+//! its final id, their targets are resolved to IR ids: an `operationId`, or a
+//! local `operationRef` such as `#/paths/~1users~1{id}/get`. Targets outside
+//! the document, or left out of the IR, stay unresolved.
+
+use super::component_refs;
+use super::{OperationDefinition, OperationLink};
+use openapiv3::{LinkOperation, OpenAPI, Operation, PathItem, ReferenceOr, StatusCode};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Links of every response of `operation`, in spec order
+pub(super) fn extract(openapi: &OpenAPI, operation: &Operation) -> Vec<OperationLink> {
+    let responses = operation
+        .responses
+        .responses
+        .iter()
+        .map(|(status, response)| (status_name(status), response))
+        .chain(
+            operation
+                .responses
+                .default
+                .iter()
+                .map(|response| ("default".to_string(), response)),
+        );
+
+    let mut links = Vec::new();
+    for (status, response) in responses {
+        let Some(response) = component_refs::resolve(openapi, response) else {
+            continue;
+        };
+        for (name, link) in &response.links {
+            let Some(link) = component_refs::resolve(openapi, link) else {
+                continue;
+            };
+            let target = match &link.operation {
+                LinkOperation::OperationId(id) => id.clone(),
+                LinkOperation::OperationRef(reference) => reference.clone(),
+            };
+            links.push(OperationLink {
+                name: name.clone(),
+                status: status.clone(),
+                target,
+                operation: None,
+                parameters: link
+                    .parameters
+                    .iter()
+                    .map(|(parameter, value)| (parameter.clone(), expression(value)))
+                    .collect(),
+                description: link.description.clone(),
+            });
+        }
+    }
+    links
+}
+
+/// Point the links of `operations` at the IR ids of their targets
+pub(super) fn resolve(openapi: &OpenAPI, operations: &mut [OperationDefinition]) {
+    let ids: HashMap<(String, String), String> = operations
+        .iter()
+        .map(|op| {
+            (
+                (op.path.clone(), format!("{:?}", op.method).to_lowercase()),
+                op.id.clone(),
+            )
+        })
+        .collect();
+    let mut declared = HashMap::new();
+    for (path, item) in &openapi.paths.paths {
+        if let ReferenceOr::Item(item) = item {
+            for (method, operation) in methods(item) {
+                if let Some(id) = &operation.operation_id {
+                    declared
+                        .entry(id.as_str())
+                        .or_insert((path.clone(), method.to_string()));
+                }
+            }
+        }
+    }
+
+    for operation in operations {
+        for link in &mut operation.links {
+            let location = match link.target.strip_prefix("#/paths/") {
+                Some(pointer) => pointer
+                    .rsplit_once('/')
+                    .map(|(path, method)| (unescape(path), method.to_lowercase())),
+                // A document elsewhere
+                None if link.target.contains('#') => None,
+                None => declared.get(link.target.as_str()).cloned(),
+            };
+            link.operation = location.and_then(|location| ids.get(&location).cloned());
+        }
+    }
+}
+
+fn methods(item: &PathItem) -> impl Iterator<Item = (&'static str, &Operation)> {
+    [
+        ("get", &item.get),
+        ("put", &item.put),
+        ("post", &item.post),
+        ("delete", &item.delete),
+        ("options", &item.options),
+        ("head", &item.head),
+        ("patch", &item.patch),
+        ("trace", &item.trace),
+    ]
+    .into_iter()
+    .filter_map(|(method, operation)| Some((method, operation.as_ref()?)))
+}
+
+/// `200`, `2XX`
+pub(super) fn status_name(status: &StatusCode) -> String {
+    match status {
+        StatusCode::Code(code) => code.to_string(),
+        StatusCode::Range(range) => format!("{}XX", range),
+    }
+}
+
+/// A runtime expression as written, or a constant as JSON
+fn expression(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// A JSON pointer segment of a path, also percent-decoded (`%7Bid%7D`)
+fn unescape(segment: &str) -> String {
+    let segment = segment.replace("~1", "/").replace("~0", "~");
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| segment.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -1,13 +1,31 @@
-pub mod schema_ir;
+pub mod audiences;
+pub mod component_refs;
+pub mod descriptions;
+pub mod external_refs;
+pub mod gateway;
+pub mod ir_diff;
+pub mod ir_file;
+pub mod json_schema_compat;
+pub mod links;
+pub mod name_collisions;
+pub mod openapi_coverage;
+pub mod openapi_errors;
 pub mod openapi_parser;
+pub mod operation_ids;
+pub mod originals;
+pub mod schema_ir;
+pub mod yaml;
 
 use anyhow::Result;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::io::Read;
 use std::path::Path;
 
-pub use schema_ir::*;
+pub use ir_file::IrFileParser;
+pub use name_collisions::{reject_collisions, resolve_name_collisions};
 pub use openapi_parser::OpenApiParser;
+pub use schema_ir::*;
 
 /// Input parser trait - converts any format to unified IR
 pub trait InputParser: Send + Sync {
@@ -20,6 +38,15 @@ pub trait InputParser: Send + Sync {
     /// Parse input file into intermediate representation
     fn parse(&self, source: &Path, options: &HashMap<String, Value>) -> Result<SchemaIR>;
 
+    /// Parse input already in memory (no filesystem access)
+    fn parse_str(&self, content: &str, options: &HashMap<String, Value>) -> Result<SchemaIR>;
+
+    /// Whether `content` looks like this format's input, for detecting the
+    /// format of inputs without an explicit `format`
+    fn can_parse(&self, _content: &str) -> bool {
+        false
+    }
+
     /// Validate input file before parsing
     fn validate(&self, source: &Path) -> Result<()> {
         if !source.exists() {
@@ -42,30 +69,155 @@ impl ParserRegistry {
 
         // Register built-in parsers
         registry.register(Box::new(OpenApiParser));
+        registry.register(Box::new(IrFileParser));
 
         registry
     }
 
     pub fn register(&mut self, parser: Box<dyn InputParser>) {
-        self.parsers.insert(parser.format_name().to_string(), parser);
+        self.parsers
+            .insert(parser.format_name().to_string(), parser);
+    }
+
+    pub fn get(&self, format: &str) -> Option<&dyn InputParser> {
+        self.parsers.get(format).map(|p| p.as_ref())
     }
 
-    pub fn get(&self, format: &str) -> Option<&Box<dyn InputParser>> {
-        self.parsers.get(format)
+    /// Auto-detect the format of the input at `path`: a parser recognizing its
+    /// content, preferring the one its extension (in any case) names, and
+    /// otherwise the extension alone. Inputs declaring a format no parser
+    /// reads, like Swagger 2, are an error rather than a guess
+    pub fn detect_format(&self, path: &Path) -> Result<Option<&str>> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        let by_extension = extension.and_then(|ext| {
+            self.sorted()
+                .into_iter()
+                .find(|parser| parser.supported_extensions().contains(&ext.as_str()))
+        });
+        let recognizing: Vec<_> = match sniff_prefix(path) {
+            Some(content) => self.recognizing(&content)?,
+            None => Vec::new(),
+        };
+        let parser = match by_extension {
+            Some(parser) if recognizing.is_empty() => parser,
+            Some(parser)
+                if recognizing
+                    .iter()
+                    .any(|p| p.format_name() == parser.format_name()) =>
+            {
+                parser
+            }
+            _ => match recognizing.first() {
+                Some(parser) => *parser,
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(parser.format_name()))
     }
 
-    /// Auto-detect format from file extension
-    pub fn detect_format(&self, path: &Path) -> Option<&str> {
-        let ext = path.extension()?.to_str()?;
+    /// Auto-detect the format of input held in memory from its content
+    pub fn detect_content_format(&self, content: &str) -> Result<Option<&str>> {
+        Ok(self
+            .recognizing(content)?
+            .first()
+            .map(|parser| parser.format_name()))
+    }
 
-        for parser in self.parsers.values() {
-            if parser.supported_extensions().contains(&ext) {
-                return Some(parser.format_name());
+    /// The parsers recognizing `content`, or an error when none does and it
+    /// declares an unsupported format
+    fn recognizing(&self, content: &str) -> Result<Vec<&dyn InputParser>> {
+        let recognizing: Vec<_> = self
+            .sorted()
+            .into_iter()
+            .filter(|parser| parser.can_parse(content))
+            .collect();
+        if recognizing.is_empty() {
+            if let Some((_, name)) = UNSUPPORTED_FORMATS
+                .iter()
+                .find(|(key, _)| declares_key(content, key))
+            {
+                anyhow::bail!(
+                    "{} input is not supported; convert it to OpenAPI 3 first",
+                    name
+                );
             }
         }
+        Ok(recognizing)
+    }
+
+    /// The parsers by format name, so detection does not depend on hash order
+    fn sorted(&self) -> Vec<&dyn InputParser> {
+        let mut parsers: Vec<_> = self.parsers.values().map(|p| p.as_ref()).collect();
+        parsers.sort_by(|a, b| a.format_name().cmp(b.format_name()));
+        parsers
+    }
+}
+
+/// Top-level keys of formats no parser reads, with their names for errors
+const UNSUPPORTED_FORMATS: [(&str, &str); 2] = [("swagger", "Swagger 2"), ("asyncapi", "AsyncAPI")];
 
-        None
+/// Bytes of an input read for content detection; formats declare themselves
+/// near the top
+const SNIFF_BYTES: u64 = 64 * 1024;
+
+/// The start of the file at `path`, `None` when it cannot be read
+fn sniff_prefix(path: &Path) -> Option<String> {
+    let mut prefix = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(SNIFF_BYTES)
+        .read_to_end(&mut prefix)
+        .ok()?;
+    Some(String::from_utf8_lossy(&prefix).into_owned())
+}
+
+/// Whether `content` has a top-level key `key`: in YAML a line starting with
+/// `key:`, quoted or not, and in JSON a member of the outermost object
+pub(crate) fn declares_key(content: &str, key: &str) -> bool {
+    let content = content.trim_start_matches('\u{feff}');
+    let followed_by_colon = |rest: &str| rest.trim_start().starts_with(':');
+
+    if !content.trim_start().starts_with('{') {
+        let names = [
+            key.to_string(),
+            format!("\"{}\"", key),
+            format!("'{}'", key),
+        ];
+        return content.lines().any(|line| {
+            names.iter().any(|name| {
+                line.strip_prefix(name.as_str())
+                    .is_some_and(followed_by_colon)
+            })
+        });
     }
+
+    let mut depth = 0usize;
+    let mut string_start = None;
+    let mut escaped = false;
+    for (index, c) in content.char_indices() {
+        match (string_start, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(start), '"') => {
+                string_start = None;
+                if depth == 1
+                    && &content[start..index] == key
+                    && followed_by_colon(&content[index + 1..])
+                {
+                    return true;
+                }
+            }
+            (Some(_), _) => {}
+            (None, '"') => string_start = Some(index + 1),
+            (None, '{' | '[') => depth += 1,
+            (None, '}' | ']') => depth = depth.saturating_sub(1),
+            (None, _) => {}
+        }
+    }
+    false
 }
 
 impl Default for ParserRegistry {
@@ -73,3 +225,71 @@ impl Default for ParserRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(content: &str) -> Result<Option<String>> {
+        Ok(ParserRegistry::new()
+            .detect_content_format(content)?
+            .map(str::to_string))
+    }
+
+    #[test]
+    fn formats_are_detected_from_json_and_yaml_content() {
+        assert_eq!(
+            detect(r#"{"info": {"openapi": 1}, "openapi": "3.0.3"}"#)
+                .unwrap()
+                .as_deref(),
+            Some("openapi")
+        );
+        assert_eq!(
+            detect("openapi: 3.1.0\ninfo: {}").unwrap().as_deref(),
+            Some("openapi")
+        );
+        assert_eq!(
+            detect("# exported\n---\n\"irVersion\": 3")
+                .unwrap()
+                .as_deref(),
+            Some("ir")
+        );
+        assert_eq!(detect("info:\n  openapi: 3.0.3").unwrap(), None);
+    }
+
+    #[test]
+    fn swagger_and_asyncapi_inputs_are_rejected() {
+        let unsupported = |content| detect(content).unwrap_err().to_string();
+        assert_eq!(
+            unsupported(r#"{ "swagger": "2.0", "paths": {} }"#),
+            "Swagger 2 input is not supported; convert it to OpenAPI 3 first"
+        );
+        assert_eq!(
+            unsupported("swagger: '2.0'\npaths: {}"),
+            "Swagger 2 input is not supported; convert it to OpenAPI 3 first"
+        );
+        assert_eq!(
+            unsupported("# events\n---\nasyncapi: 2.6.0\nchannels: {}"),
+            "AsyncAPI input is not supported; convert it to OpenAPI 3 first"
+        );
+        assert_eq!(
+            unsupported(r#"{"asyncapi": "3.0.0"}"#),
+            "AsyncAPI input is not supported; convert it to OpenAPI 3 first"
+        );
+    }
+
+    #[test]
+    fn unsupported_inputs_are_rejected_whatever_their_extension() {
+        let path =
+            std::env::temp_dir().join(format!("generator-swagger-{}.yaml", std::process::id()));
+        std::fs::write(&path, "swagger: \"2.0\"\n").unwrap();
+        let detected = ParserRegistry::new()
+            .detect_format(&path)
+            .map(|f| f.map(str::to_string));
+        std::fs::remove_file(&path).unwrap();
+        assert!(detected
+            .unwrap_err()
+            .to_string()
+            .starts_with("Swagger 2 input"));
+    }
+}
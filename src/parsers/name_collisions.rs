@@ -0,0 +1,375 @@
+//! Detection of schema names and operation ids that collide once generators
+//! normalize them into identifiers.
+//!
+//! `user_profile`, `UserProfile`, and `user-profile` all become the same type
+//! name in at least one target language, so only the first occurrence keeps its
+//! name; later ones get a numeric suffix (`UserProfile2`) and every reference is
+//! rewritten. The renaming depends only on spec order, so it is deterministic.
+
+use super::{SchemaIR, TypeInfo};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A name that had to be changed to keep generated identifiers unique
+#[derive(Debug, Clone)]
+pub struct NameCollision {
+    pub kind: CollisionKind,
+    pub original: String,
+    pub conflicts_with: String,
+    pub renamed: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionKind {
+    Schema,
+    Operation,
+}
+
+impl fmt::Display for NameCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            CollisionKind::Schema => "schema",
+            CollisionKind::Operation => "operation id",
+        };
+        write!(
+            f,
+            "{} '{}' collides with '{}', renamed to '{}'",
+            kind, self.original, self.conflicts_with, self.renamed
+        )
+    }
+}
+
+/// Fail listing every collision, for `strict: true`
+pub fn reject_collisions(collisions: &[NameCollision]) -> Result<()> {
+    if collisions.is_empty() {
+        return Ok(());
+    }
+    let details: Vec<_> = collisions.iter().map(|c| format!("  - {}", c)).collect();
+    anyhow::bail!(
+        "Name collisions found (strict mode):\n{}",
+        details.join("\n")
+    );
+}
+
+/// Rename colliding schemas and operations in place, returning what changed
+pub fn resolve_name_collisions(schema_ir: &mut SchemaIR) -> Vec<NameCollision> {
+    let mut collisions = Vec::new();
+
+    let mut schema_names = UniqueNames::new(schema_ir.schemas.iter().map(|s| s.name.as_str()));
+    let mut renames = HashMap::new();
+    for schema in &mut schema_ir.schemas {
+        if let Some((conflicts_with, renamed)) = schema_names.claim(&schema.name) {
+            collisions.push(NameCollision {
+                kind: CollisionKind::Schema,
+                original: schema.name.clone(),
+                conflicts_with,
+                renamed: renamed.clone(),
+            });
+            renames.insert(schema.name.clone(), renamed.clone());
+            schema.name = renamed;
+        }
+    }
+
+    let mut operation_ids = UniqueNames::new(schema_ir.operations.iter().map(|op| op.id.as_str()));
+    for operation in &mut schema_ir.operations {
+        if let Some((conflicts_with, renamed)) = operation_ids.claim(&operation.id) {
+            collisions.push(NameCollision {
+                kind: CollisionKind::Operation,
+                original: operation.id.clone(),
+                conflicts_with,
+                renamed: renamed.clone(),
+            });
+            operation.id = renamed;
+        }
+    }
+
+    if !renames.is_empty() {
+        rewrite_references(schema_ir, &renames);
+    }
+
+    collisions
+}
+
+/// Names claimed so far, keyed by their normalized form
+struct UniqueNames {
+    claimed: HashMap<String, String>,
+    /// Every original name is reserved so a suffixed name never shadows a later one
+    taken: HashSet<String>,
+}
+
+impl UniqueNames {
+    fn new<'a>(names: impl Iterator<Item = &'a str>) -> Self {
+        Self {
+            claimed: HashMap::new(),
+            taken: names.map(normalize).collect(),
+        }
+    }
+
+    /// Register `name`; on collision returns the earlier name and a free replacement
+    fn claim(&mut self, name: &str) -> Option<(String, String)> {
+        let key = normalize(name);
+        let Some(existing) = self.claimed.get(&key).cloned() else {
+            self.claimed.insert(key, name.to_string());
+            return None;
+        };
+
+        let renamed = (2..)
+            .map(|n| format!("{}{}", name, n))
+            .find(|candidate| !self.taken.contains(&normalize(candidate)))
+            .expect("unbounded suffix search");
+        self.taken.insert(normalize(&renamed));
+        self.claimed.insert(normalize(&renamed), renamed.clone());
+        Some((existing, renamed))
+    }
+}
+
+/// Case- and separator-insensitive form shared by every target language
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Point references at the renamed schemas; spec names are exact, so the
+/// original name identifies the renamed schema unambiguously
+pub(super) fn rewrite_references(schema_ir: &mut SchemaIR, renames: &HashMap<String, String>) {
+    let rename = |name: &mut String| {
+        if let Some(renamed) = renames.get(name.as_str()) {
+            *name = renamed.clone();
+        }
+    };
+
+    for schema in &mut schema_ir.schemas {
+        for field in &mut schema.fields {
+            rewrite_type_info(&mut field.type_info, &rename);
+        }
+        for parent in &mut schema.parents {
+            rename(parent);
+        }
+    }
+
+    for operation in &mut schema_ir.operations {
+        if let Some(body) = &mut operation.request_body {
+            rename(&mut body.name);
+        }
+        if let Some(response) = &mut operation.response {
+            rename(&mut response.name);
+        }
+        for response in &mut operation.responses {
+            if let Some(body) = &mut response.schema {
+                rename(&mut body.name);
+            }
+            if let Some(type_info) = &mut response.inline_type {
+                rewrite_type_info(type_info, &rename);
+            }
+        }
+    }
+
+    for channel in &mut schema_ir.channels {
+        if let Some(send) = &mut channel.send {
+            rename(send);
+        }
+        if let Some(receive) = &mut channel.receive {
+            rename(receive);
+        }
+    }
+}
+
+fn rewrite_type_info(type_info: &mut TypeInfo, rename: &impl Fn(&mut String)) {
+    if let Some(reference) = &mut type_info.reference {
+        rename(reference);
+    }
+    for item in type_info
+        .array_item_type
+        .iter_mut()
+        .chain(type_info.map_values.iter_mut())
+    {
+        rewrite_type_info(item, rename);
+    }
+    for member in type_info
+        .union_members
+        .iter_mut()
+        .chain(type_info.tuple_items.iter_mut())
+    {
+        rewrite_type_info(member, rename);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{InputParser, OpenApiParser};
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /profiles:
+    post:
+      operationId: get_profile
+      requestBody: { content: { application/json: { schema: { $ref: "#/components/schemas/UserProfile" } } } }
+      responses:
+        "200": { description: ok, content: { application/json: { schema: { type: array, items: { $ref: "#/components/schemas/user-profile" } } } } }
+        "400": { description: bad, content: { application/json: { schema: { oneOf: [{ $ref: "#/components/schemas/UserProfile" }, { type: string }] } } } }
+  /profile:
+    get:
+      operationId: getProfile
+      responses: { "204": { description: ok } }
+    put:
+      operationId: GetProfile
+      responses: { "204": { description: ok } }
+components:
+  schemas:
+    user_profile:
+      type: object
+      properties: { id: { type: string } }
+    UserProfile:
+      type: object
+      properties: { id: { type: string } }
+    user-profile:
+      type: object
+      properties: { id: { type: string } }
+    UserProfile2:
+      type: object
+      properties: { id: { type: string } }
+    Holder:
+      type: object
+      properties:
+        one: { $ref: "#/components/schemas/UserProfile" }
+        many: { type: array, items: { $ref: "#/components/schemas/user-profile" } }
+        kept: { $ref: "#/components/schemas/UserProfile2" }
+"##;
+
+    fn resolved() -> (SchemaIR, Vec<NameCollision>) {
+        let mut ir = OpenApiParser.parse_str(SPEC, &HashMap::new()).unwrap();
+        let collisions = resolve_name_collisions(&mut ir);
+        (ir, collisions)
+    }
+
+    #[test]
+    fn later_schemas_get_a_suffix_no_spec_name_uses() {
+        let (ir, collisions) = resolved();
+
+        let names: Vec<_> = ir.schemas.iter().map(|s| s.name.as_str()).collect();
+        // `UserProfile2` is the spec's own, so the suffixes skip it
+        assert_eq!(
+            names,
+            vec![
+                "user_profile",
+                "UserProfile3",
+                "user-profile4",
+                "UserProfile2",
+                "Holder"
+            ]
+        );
+
+        let schemas: Vec<_> = collisions
+            .iter()
+            .filter(|c| c.kind == CollisionKind::Schema)
+            .map(|c| {
+                (
+                    c.original.as_str(),
+                    c.conflicts_with.as_str(),
+                    c.renamed.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            schemas,
+            vec![
+                ("UserProfile", "user_profile", "UserProfile3"),
+                ("user-profile", "user_profile", "user-profile4")
+            ]
+        );
+    }
+
+    #[test]
+    fn references_follow_the_renamed_schemas() {
+        let (ir, _) = resolved();
+
+        let holder = ir.schemas.iter().find(|s| s.name == "Holder").unwrap();
+        let field = |name: &str| {
+            &holder
+                .fields
+                .iter()
+                .find(|f| f.name == name)
+                .unwrap()
+                .type_info
+        };
+        assert_eq!(field("one").reference.as_deref(), Some("UserProfile3"));
+        let many = field("many").array_item_type.as_deref().unwrap();
+        assert_eq!(many.reference.as_deref(), Some("user-profile4"));
+        assert_eq!(field("kept").reference.as_deref(), Some("UserProfile2"));
+
+        let op = ir
+            .operations
+            .iter()
+            .find(|op| op.id == "get_profile")
+            .unwrap();
+        assert_eq!(op.request_body.as_ref().unwrap().name, "UserProfile3");
+        assert_eq!(op.response.as_ref().unwrap().name, "user-profile4");
+        assert_eq!(
+            op.responses[0].schema.as_ref().unwrap().name,
+            "user-profile4"
+        );
+        let error = op.responses[1].inline_type.as_ref().unwrap();
+        assert_eq!(
+            error.union_members[0].reference.as_deref(),
+            Some("UserProfile3")
+        );
+    }
+
+    #[test]
+    fn colliding_operation_ids_are_suffixed_in_spec_order() {
+        let (ir, collisions) = resolved();
+
+        let ids: Vec<_> = ir.operations.iter().map(|op| op.id.as_str()).collect();
+        assert_eq!(ids, vec!["get_profile", "getProfile2", "GetProfile3"]);
+
+        let operations: Vec<_> = collisions
+            .iter()
+            .filter(|c| c.kind == CollisionKind::Operation)
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            operations,
+            vec![
+                "operation id 'getProfile' collides with 'get_profile', renamed to 'getProfile2'",
+                "operation id 'GetProfile' collides with 'get_profile', renamed to 'GetProfile3'",
+            ]
+        );
+    }
+
+    #[test]
+    fn renaming_is_deterministic() {
+        let (first, _) = resolved();
+        for _ in 0..5 {
+            let (again, _) = resolved();
+            let names = |ir: &SchemaIR| {
+                ir.schemas
+                    .iter()
+                    .map(|s| s.name.clone())
+                    .collect::<Vec<_>>()
+            };
+            let ids = |ir: &SchemaIR| {
+                ir.operations
+                    .iter()
+                    .map(|op| op.id.clone())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(names(&again), names(&first));
+            assert_eq!(ids(&again), ids(&first));
+        }
+    }
+
+    #[test]
+    fn strict_mode_lists_every_collision() {
+        let (_, collisions) = resolved();
+        let message = reject_collisions(&collisions).unwrap_err().to_string();
+        assert!(message.starts_with("Name collisions found (strict mode):\n"));
+        assert_eq!(message.lines().count(), 1 + collisions.len());
+        assert!(reject_collisions(&[]).is_ok());
+    }
+}
@@ -0,0 +1,628 @@
+//! Spec feature coverage analysis for OpenAPI inputs.
+//!
+//! Walks the source document alongside the parser and records which constructs
+//! were encountered and whether the IR represents them fully, with weaker
+//! typing, or not at all.
+
+use super::component_refs;
+use super::json_schema_compat::PREFIX_ITEMS_EXTENSION;
+use super::openapi_parser::{
+    codegen_ignored, extract_schema_reference, hoistable_body_schema, merged_parameters,
+    preferred_media_type,
+};
+use super::{BodyKind, FeatureHandling, FeatureUsage};
+use indexmap::IndexMap;
+use openapiv3::{
+    MediaType, OpenAPI, Operation, Parameter, ParameterSchemaOrContent, PathItem, PathStyle,
+    QueryStyle, ReferenceOr, Schema, SchemaKind, StatusCode, Type,
+};
+
+use FeatureHandling::{Degraded, Ignored, Supported, Unsupported};
+
+/// Collect feature usage for an OpenAPI document
+pub fn analyze_coverage(openapi: &OpenAPI) -> Vec<FeatureUsage> {
+    let mut recorder = CoverageRecorder::default();
+
+    if let Some(components) = &openapi.components {
+        for (name, schema_ref) in &components.schemas {
+            let location = format!("components.schemas.{}", name);
+            match schema_ref {
+                ReferenceOr::Reference { .. } => {
+                    recorder.record("component schema aliases ($ref)", Ignored, location)
+                }
+                ReferenceOr::Item(schema) => {
+                    visit_component_schema(&mut recorder, schema, &location)
+                }
+            }
+        }
+
+        // The package README explains how to authenticate with each one
+        if !components.security_schemes.is_empty() {
+            recorder.record("security schemes", Supported, "components.securitySchemes");
+        }
+    }
+
+    for (path, path_item_ref) in &openapi.paths.paths {
+        let location = format!("paths.{}", path);
+        match path_item_ref {
+            ReferenceOr::Reference { .. } => recorder.record("path item $refs", Ignored, location),
+            ReferenceOr::Item(path_item) => {
+                visit_path_item(&mut recorder, openapi, path_item, &location)
+            }
+        }
+    }
+
+    if openapi.security.is_some() {
+        recorder.record("security requirements", Ignored, "security");
+    }
+    if openapi.servers.len() > 1 {
+        recorder.record("additional servers", Supported, "servers");
+    }
+    for (index, server) in openapi.servers.iter().enumerate() {
+        if server.variables.as_ref().is_some_and(|v| !v.is_empty()) {
+            recorder.record("server variables", Ignored, format!("servers[{}]", index));
+        }
+    }
+    if !openapi.tags.is_empty() {
+        recorder.record("tag definitions", Supported, "tags");
+    }
+    if openapi.external_docs.is_some() {
+        recorder.record("externalDocs", Supported, "externalDocs");
+    }
+    if openapi.extensions.contains_key("x-channels") {
+        recorder.record("x-channels WebSocket channels", Supported, "x-channels");
+    }
+
+    recorder.finish()
+}
+
+#[derive(Default)]
+struct CoverageRecorder {
+    entries: IndexMap<(&'static str, FeatureHandling), Vec<String>>,
+}
+
+impl CoverageRecorder {
+    fn record(
+        &mut self,
+        construct: &'static str,
+        handling: FeatureHandling,
+        location: impl Into<String>,
+    ) {
+        self.entries
+            .entry((construct, handling))
+            .or_default()
+            .push(location.into());
+    }
+
+    fn finish(self) -> Vec<FeatureUsage> {
+        self.entries
+            .into_iter()
+            .map(|((construct, handling), locations)| FeatureUsage {
+                construct: construct.to_string(),
+                handling,
+                locations,
+            })
+            .collect()
+    }
+}
+
+fn visit_component_schema(recorder: &mut CoverageRecorder, schema: &Schema, location: &str) {
+    // Excluded schemas are never generated, so their constructs don't count
+    if codegen_ignored(&schema.schema_data.extensions) {
+        recorder.record("x-codegen-ignore exclusions", Supported, location);
+        return;
+    }
+    visit_schema_data(recorder, schema, location);
+
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::Object(obj_type)) => {
+            recorder.record("object schemas", Supported, location);
+            for (prop_name, prop_ref) in &obj_type.properties {
+                let prop_location = format!("{}.properties.{}", location, prop_name);
+                visit_value_schema(recorder, prop_ref, &prop_location);
+            }
+            if obj_type.additional_properties.is_some() {
+                recorder.record("additionalProperties", Ignored, location);
+            }
+        }
+        SchemaKind::Type(_) => recorder.record("non-object component schemas", Degraded, location),
+        SchemaKind::AllOf { .. } => recorder.record("allOf schemas", Supported, location),
+        kind => record_composite(recorder, kind, location),
+    }
+}
+
+fn visit_value_schema(
+    recorder: &mut CoverageRecorder,
+    schema_ref: &ReferenceOr<Box<Schema>>,
+    location: &str,
+) {
+    let schema = match schema_ref {
+        ReferenceOr::Reference { .. } => {
+            recorder.record("$ref properties", Supported, location);
+            return;
+        }
+        ReferenceOr::Item(schema) => schema.as_ref(),
+    };
+
+    visit_schema_data(recorder, schema, location);
+
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::String(string_type)) => {
+            if string_type.enumeration.is_empty() {
+                recorder.record("string properties", Supported, location);
+            } else {
+                recorder.record("string enums", Supported, location);
+            }
+            if string_type.pattern.is_some()
+                || string_type.min_length.is_some()
+                || string_type.max_length.is_some()
+            {
+                recorder.record(
+                    "string constraints (pattern, minLength, maxLength)",
+                    Supported,
+                    location,
+                );
+            }
+        }
+        SchemaKind::Type(Type::Number(num_type)) => {
+            recorder.record("number properties", Supported, location);
+            if !num_type.enumeration.is_empty() {
+                recorder.record("numeric enums", Degraded, location);
+            }
+            if num_type.minimum.is_some()
+                || num_type.maximum.is_some()
+                || num_type.multiple_of.is_some()
+            {
+                recorder.record(
+                    "numeric constraints (minimum, maximum, multipleOf)",
+                    Supported,
+                    location,
+                );
+            }
+        }
+        SchemaKind::Type(Type::Integer(int_type)) => {
+            recorder.record("integer properties", Supported, location);
+            if !int_type.enumeration.is_empty() {
+                recorder.record("numeric enums", Degraded, location);
+            }
+            if int_type.minimum.is_some()
+                || int_type.maximum.is_some()
+                || int_type.multiple_of.is_some()
+            {
+                recorder.record(
+                    "numeric constraints (minimum, maximum, multipleOf)",
+                    Supported,
+                    location,
+                );
+            }
+        }
+        SchemaKind::Type(Type::Boolean(_)) => {
+            recorder.record("boolean properties", Supported, location)
+        }
+        SchemaKind::Type(Type::Array(_))
+            if schema
+                .schema_data
+                .extensions
+                .contains_key(PREFIX_ITEMS_EXTENSION) =>
+        {
+            recorder.record("tuples (prefixItems)", Supported, location)
+        }
+        SchemaKind::Type(Type::Array(array_type)) => match &array_type.items {
+            Some(items) => {
+                recorder.record("arrays", Supported, location);
+                visit_value_schema(recorder, items, &format!("{}.items", location));
+            }
+            None => recorder.record("arrays without items", Degraded, location),
+        },
+        SchemaKind::Type(Type::Object(obj_type)) => {
+            if !obj_type.properties.is_empty() {
+                recorder.record("inline object properties", Degraded, location);
+            } else if obj_type.additional_properties.is_some() {
+                recorder.record("map properties (additionalProperties)", Degraded, location);
+            } else {
+                recorder.record("free-form objects", Supported, location);
+            }
+        }
+        SchemaKind::OneOf { .. } | SchemaKind::AnyOf { .. } => {
+            recorder.record("oneOf/anyOf unions", Supported, location)
+        }
+        kind => record_composite(recorder, kind, location),
+    }
+}
+
+fn visit_media_types(
+    recorder: &mut CoverageRecorder,
+    content: &IndexMap<String, MediaType>,
+    location: &str,
+    request: bool,
+) {
+    if let Some((media_type, _)) = preferred_media_type(content) {
+        match BodyKind::from_content_type(media_type) {
+            BodyKind::Json => {}
+            BodyKind::Xml => recorder.record("XML bodies", Supported, location),
+            // Clients send binary request bodies as raw strings
+            BodyKind::Binary if request => {
+                recorder.record("binary request bodies", Degraded, location)
+            }
+            BodyKind::Binary => {
+                recorder.record("binary responses (file downloads)", Supported, location)
+            }
+            BodyKind::Text => recorder.record("plain-text bodies", Supported, location),
+        }
+    }
+    if content.len() > 1 {
+        recorder.record("alternative media types", Ignored, location);
+    }
+}
+
+fn record_composite(recorder: &mut CoverageRecorder, kind: &SchemaKind, location: &str) {
+    let construct = match kind {
+        SchemaKind::OneOf { .. } => "oneOf schemas",
+        SchemaKind::AnyOf { .. } => "anyOf schemas",
+        SchemaKind::AllOf { .. } => "allOf schemas",
+        SchemaKind::Not { .. } => "not schemas",
+        _ => "untyped schemas",
+    };
+    recorder.record(construct, Unsupported, location);
+}
+
+fn visit_schema_data(recorder: &mut CoverageRecorder, schema: &Schema, location: &str) {
+    let data = &schema.schema_data;
+    if data.nullable {
+        recorder.record("nullable", Supported, location);
+    }
+    if data.discriminator.is_some() {
+        recorder.record("discriminator", Ignored, location);
+    }
+}
+
+fn visit_path_item(
+    recorder: &mut CoverageRecorder,
+    openapi: &OpenAPI,
+    path_item: &PathItem,
+    location: &str,
+) {
+    if !path_item.parameters.is_empty() {
+        recorder.record("path-level parameters", Supported, location);
+    }
+    if !path_item.servers.is_empty() {
+        recorder.record("path-level servers", Ignored, location);
+    }
+    if path_item.trace.is_some() {
+        recorder.record("TRACE operations", Ignored, format!("{}.trace", location));
+    }
+
+    let operations = [
+        ("get", &path_item.get),
+        ("post", &path_item.post),
+        ("put", &path_item.put),
+        ("delete", &path_item.delete),
+        ("patch", &path_item.patch),
+        ("head", &path_item.head),
+        ("options", &path_item.options),
+    ];
+
+    for (method, operation) in operations {
+        if let Some(operation) = operation {
+            visit_operation(
+                recorder,
+                openapi,
+                path_item,
+                operation,
+                &format!("{}.{}", location, method),
+            );
+        }
+    }
+}
+
+fn visit_operation(
+    recorder: &mut CoverageRecorder,
+    openapi: &OpenAPI,
+    path_item: &PathItem,
+    operation: &Operation,
+    location: &str,
+) {
+    if codegen_ignored(&operation.extensions) {
+        recorder.record("x-codegen-ignore exclusions", Supported, location);
+        return;
+    }
+    recorder.record("operations", Supported, location);
+
+    // Path-level parameters apply to every operation that does not redeclare them
+    for param_ref in &merged_parameters(openapi, &path_item.parameters, &operation.parameters) {
+        let Some(param) = component_refs::resolve(openapi, param_ref) else {
+            if let ReferenceOr::Reference { reference } = param_ref {
+                recorder.record(
+                    "unresolved $ref parameters",
+                    Ignored,
+                    format!("{} ({})", location, reference),
+                );
+            }
+            continue;
+        };
+        if let ReferenceOr::Reference { reference } = param_ref {
+            recorder.record(
+                "$ref parameters",
+                Supported,
+                format!("{} ({})", location, reference),
+            );
+        }
+
+        let (construct, data) = match param {
+            Parameter::Query { parameter_data, .. } => ("query parameters", parameter_data),
+            Parameter::Path { parameter_data, .. } => ("path parameters", parameter_data),
+            Parameter::Header { parameter_data, .. } => ("header parameters", parameter_data),
+            Parameter::Cookie { parameter_data, .. } => ("cookie parameters", parameter_data),
+        };
+        let param_location = format!("{}.parameters.{}", location, data.name);
+        recorder.record(construct, Supported, param_location.clone());
+        if matches!(
+            param,
+            Parameter::Query {
+                style: QueryStyle::DeepObject,
+                ..
+            } | Parameter::Path {
+                style: PathStyle::Label | PathStyle::Matrix,
+                ..
+            }
+        ) {
+            recorder.record(
+                "label, matrix, and deepObject styles (encoded like simple and form)",
+                Degraded,
+                param_location.clone(),
+            );
+        }
+
+        // Scalars and arrays of them are encoded per type and style; objects
+        // and untyped schemas go out as strings
+        let schema = match &data.format {
+            ParameterSchemaOrContent::Schema(schema_ref) => {
+                component_refs::resolve(openapi, schema_ref)
+            }
+            ParameterSchemaOrContent::Content(_) => {
+                recorder.record(
+                    "content parameters (sent as strings)",
+                    Degraded,
+                    param_location,
+                );
+                continue;
+            }
+        };
+        match schema.map(|schema| &schema.schema_kind) {
+            Some(SchemaKind::Type(Type::Object(_))) => recorder.record(
+                "object parameters (sent as strings)",
+                Degraded,
+                param_location,
+            ),
+            Some(SchemaKind::Type(_)) => {
+                recorder.record("typed parameters", Supported, param_location)
+            }
+            _ => recorder.record(
+                "untyped parameters (sent as strings)",
+                Degraded,
+                param_location,
+            ),
+        }
+    }
+
+    let request_body = operation
+        .request_body
+        .as_ref()
+        .and_then(|body| component_refs::resolve(openapi, body));
+    if let Some(ReferenceOr::Reference { .. }) = &operation.request_body {
+        let body_location = format!("{}.requestBody", location);
+        match request_body {
+            Some(_) => recorder.record("$ref request bodies", Supported, body_location),
+            None => recorder.record("unresolved $ref request bodies", Ignored, body_location),
+        }
+    }
+    if let Some(body) = request_body {
+        let body_location = format!("{}.requestBody", location);
+        visit_media_types(recorder, &body.content, &body_location, true);
+        if extract_schema_reference(&body.content).is_some() {
+            recorder.record("request bodies", Supported, body_location);
+        } else if hoistable_body_schema(&body.content).is_some() {
+            recorder.record(
+                "inline request bodies (hoisted to named schemas)",
+                Supported,
+                body_location,
+            );
+        } else {
+            recorder.record("inline request bodies", Degraded, body_location);
+        }
+    }
+
+    let mut success_seen = false;
+    for (status, response_ref) in &operation.responses.responses {
+        let response_location = format!("{}.responses.{}", location, status);
+        let is_success = match status {
+            StatusCode::Code(code) => (200..300).contains(code),
+            StatusCode::Range(range) => *range == 2,
+        };
+
+        let Some(response) = component_refs::resolve(openapi, response_ref) else {
+            recorder.record("unresolved $ref responses", Ignored, response_location);
+            continue;
+        };
+        if let ReferenceOr::Reference { .. } = response_ref {
+            recorder.record("$ref responses", Supported, response_location.clone());
+        }
+
+        if is_success && !success_seen {
+            visit_media_types(recorder, &response.content, &response_location, false);
+        }
+
+        if !is_success {
+            if response.content.is_empty() {
+                recorder.record(
+                    "error responses without a body",
+                    Supported,
+                    response_location.clone(),
+                );
+            } else {
+                recorder.record(
+                    "typed error responses",
+                    Supported,
+                    response_location.clone(),
+                );
+            }
+        } else if success_seen || response.content.is_empty() {
+            recorder.record(
+                "additional success responses",
+                Ignored,
+                response_location.clone(),
+            );
+        } else if extract_schema_reference(&response.content).is_some() {
+            success_seen = true;
+            recorder.record("success responses", Supported, response_location.clone());
+        } else if hoistable_body_schema(&response.content).is_some() {
+            success_seen = true;
+            recorder.record(
+                "inline success responses (hoisted to named schemas)",
+                Supported,
+                response_location.clone(),
+            );
+        } else {
+            success_seen = true;
+            recorder.record(
+                "inline success responses",
+                Degraded,
+                response_location.clone(),
+            );
+        }
+
+        for header in response.headers.keys() {
+            let header_location = format!("{}.headers.{}", response_location, header);
+            // A success `ETag` is threaded into conditional requests
+            if is_success && header.eq_ignore_ascii_case("etag") {
+                recorder.record("ETag response headers", Supported, header_location);
+            } else {
+                recorder.record("response headers", Ignored, header_location);
+            }
+        }
+        if !response.links.is_empty() {
+            recorder.record("response links", Supported, response_location);
+        }
+    }
+
+    if operation.responses.default.is_some() {
+        recorder.record(
+            "default responses",
+            Supported,
+            format!("{}.responses.default", location),
+        );
+    }
+    if !operation.callbacks.is_empty() {
+        recorder.record("callbacks", Ignored, location);
+    }
+    if operation.security.is_some() {
+        recorder.record("security requirements", Ignored, location);
+    }
+    if !operation.servers.is_empty() {
+        recorder.record("operation servers", Ignored, location);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /users/{id}:
+    parameters:
+      - { name: id, in: path, required: true, schema: { type: integer } }
+    put:
+      operationId: updateUser
+      parameters:
+        - { name: If-Match, in: header, schema: { type: string } }
+        - { name: tags, in: query, schema: { type: array, items: { type: string } } }
+        - { name: filter, in: query, style: deepObject, schema: { type: object } }
+      responses:
+        "200":
+          description: ok
+          headers:
+            ETag: { schema: { type: string } }
+            X-Trace: { schema: { type: string } }
+          content: { application/json: { schema: { $ref: "#/components/schemas/User" } } }
+        "404": { description: missing, content: { application/json: { schema: { $ref: "#/components/schemas/User" } } } }
+        default: { description: error, content: { application/json: { schema: { $ref: "#/components/schemas/User" } } } }
+components:
+  securitySchemes:
+    bearer: { type: http, scheme: bearer }
+  schemas:
+    User: { type: object, properties: { name: { type: string } } }
+"##;
+
+    fn handling(usage: &[FeatureUsage], construct: &str) -> (FeatureHandling, Vec<String>) {
+        let entry = usage
+            .iter()
+            .find(|entry| entry.construct == construct)
+            .unwrap_or_else(|| panic!("no {:?} entry", construct));
+        (entry.handling, entry.locations.clone())
+    }
+
+    #[test]
+    fn categories_match_what_the_parser_and_generators_handle() {
+        let openapi: OpenAPI = serde_yaml::from_str(SPEC).unwrap();
+        let usage = analyze_coverage(&openapi);
+        let op = "paths./users/{id}.put";
+
+        assert_eq!(
+            handling(&usage, "path-level parameters"),
+            (Supported, vec!["paths./users/{id}".to_string()])
+        );
+        assert_eq!(
+            handling(&usage, "path parameters"),
+            (Supported, vec![format!("{}.parameters.id", op)])
+        );
+        assert_eq!(
+            handling(&usage, "typed parameters"),
+            (
+                Supported,
+                vec![
+                    format!("{}.parameters.id", op),
+                    format!("{}.parameters.If-Match", op),
+                    format!("{}.parameters.tags", op),
+                ]
+            )
+        );
+        assert_eq!(
+            handling(&usage, "object parameters (sent as strings)").0,
+            Degraded
+        );
+        assert_eq!(
+            handling(
+                &usage,
+                "label, matrix, and deepObject styles (encoded like simple and form)"
+            )
+            .1,
+            [format!("{}.parameters.filter", op)]
+        );
+        assert_eq!(
+            handling(&usage, "typed error responses"),
+            (Supported, vec![format!("{}.responses.404", op)])
+        );
+        assert_eq!(handling(&usage, "default responses").0, Supported);
+        assert_eq!(
+            handling(&usage, "ETag response headers"),
+            (
+                Supported,
+                vec![format!("{}.responses.200.headers.ETag", op)]
+            )
+        );
+        assert_eq!(
+            handling(&usage, "response headers"),
+            (
+                Ignored,
+                vec![format!("{}.responses.200.headers.X-Trace", op)]
+            )
+        );
+        assert_eq!(handling(&usage, "security schemes").0, Supported);
+        assert!(!usage
+            .iter()
+            .any(|entry| entry.construct.starts_with("non-string parameter")));
+    }
+}
@@ -0,0 +1,204 @@
+//! The innermost failing nodes of a document that does not deserialize as
+//! OpenAPI.
+//!
+//! openapiv3 models `$ref`-or-item nodes as untagged enums and flattens path
+//! items and schema kinds, so serde reports failures inside them against the
+//! enclosing node (`paths`), and only the first. Deserializing the nodes
+//! beneath one by one, as the types they should be, finds the one at fault
+//! (`paths./users.get.responses.200.description`) in every path item and
+//! component.
+
+use super::external_refs;
+use super::json_schema_compat;
+use crate::diagnostics::{path_segments, ErrorGroup, SourceError};
+use openapiv3::{
+    Example, Header, Link, Operation, Parameter, PathItem, RequestBody, Response, Schema,
+    SecurityScheme,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Path below the node that was deserialized, and serde's message
+type Failure = (Vec<String>, String);
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// `error` moved to the innermost failing nodes of `content`, grouped when
+/// there are several; nodes bundled from other files keep the original error
+pub fn narrow(error: SourceError, name: &str, content: &str) -> anyhow::Error {
+    let Ok(mut document) = external_refs::read_document(name, content) else {
+        return error.into();
+    };
+    json_schema_compat::normalize(&mut document);
+    let mut located: Vec<anyhow::Error> = failures(&document)
+        .into_iter()
+        .map(|(path, message)| SourceError::at_path(name, content, &path, message).into())
+        .collect();
+    match located.len() {
+        0 => error.into(),
+        1 => located.remove(0),
+        count => ErrorGroup::new(
+            format!("{} nodes of {} are malformed", count, name),
+            located,
+        )
+        .into(),
+    }
+}
+
+/// The failure in each path item and component that has one
+fn failures(document: &Value) -> Vec<Failure> {
+    let mut failures = Vec::new();
+    for (path, item) in entries(document.get("paths")) {
+        if let Some(failure) = path_item_failure(item) {
+            failures.push(prefixed(&["paths", path], failure));
+        }
+    }
+
+    for (section, components) in entries(document.get("components")) {
+        let check: fn(&Value) -> Option<Failure> = match section.as_str() {
+            "schemas" => schema_failure,
+            "responses" => response_failure,
+            "parameters" => parameter_failure,
+            "requestBodies" => request_body_failure,
+            "headers" => failure::<Header>,
+            "securitySchemes" => failure::<SecurityScheme>,
+            "examples" => failure::<Example>,
+            "links" => failure::<Link>,
+            _ => continue,
+        };
+        for (name, component) in entries(Some(components)) {
+            if is_ref(component) {
+                continue;
+            }
+            if let Some(failure) = check(component) {
+                failures.push(prefixed(&["components", section, name], failure));
+            }
+        }
+    }
+    failures
+}
+
+/// Why `value` is not a `T`
+fn failure<T: DeserializeOwned>(value: &Value) -> Option<Failure> {
+    let error = serde_path_to_error::deserialize::<_, T>(value).err()?;
+    Some((path_segments(error.path()), error.into_inner().to_string()))
+}
+
+fn prefixed(prefix: &[&str], (path, message): Failure) -> Failure {
+    let mut full: Vec<String> = prefix.iter().map(|segment| segment.to_string()).collect();
+    full.extend(path);
+    (full, message)
+}
+
+fn entries(value: Option<&Value>) -> impl Iterator<Item = (&String, &Value)> {
+    value.and_then(Value::as_object).into_iter().flatten()
+}
+
+fn items(value: Option<&Value>) -> impl Iterator<Item = (String, &Value)> {
+    value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(index, item)| (index.to_string(), item))
+}
+
+fn is_ref(value: &Value) -> bool {
+    value.get("$ref").is_some()
+}
+
+fn path_item_failure(item: &Value) -> Option<Failure> {
+    if is_ref(item) {
+        return None;
+    }
+    for method in METHODS {
+        if let Some(failure) = item.get(method).and_then(operation_failure) {
+            return Some(prefixed(&[method], failure));
+        }
+    }
+    for (index, parameter) in items(item.get("parameters")) {
+        if let Some(failure) = parameter_failure(parameter) {
+            return Some(prefixed(&["parameters", &index], failure));
+        }
+    }
+    failure::<PathItem>(item)
+}
+
+fn operation_failure(operation: &Value) -> Option<Failure> {
+    for (index, parameter) in items(operation.get("parameters")) {
+        if let Some(failure) = parameter_failure(parameter) {
+            return Some(prefixed(&["parameters", &index], failure));
+        }
+    }
+    if let Some(failure) = operation.get("requestBody").and_then(request_body_failure) {
+        return Some(prefixed(&["requestBody"], failure));
+    }
+    for (status, response) in entries(operation.get("responses")) {
+        if let Some(failure) = response_failure(response) {
+            return Some(prefixed(&["responses", status], failure));
+        }
+    }
+    failure::<Operation>(operation)
+}
+
+fn parameter_failure(parameter: &Value) -> Option<Failure> {
+    if is_ref(parameter) {
+        return None;
+    }
+    if let Some(failure) = parameter.get("schema").and_then(schema_failure) {
+        return Some(prefixed(&["schema"], failure));
+    }
+    failure::<Parameter>(parameter)
+}
+
+fn request_body_failure(body: &Value) -> Option<Failure> {
+    if is_ref(body) {
+        return None;
+    }
+    content_failure(body).or_else(|| failure::<RequestBody>(body))
+}
+
+fn response_failure(response: &Value) -> Option<Failure> {
+    if is_ref(response) {
+        return None;
+    }
+    content_failure(response).or_else(|| failure::<Response>(response))
+}
+
+/// Failure in the schema of one of `node`'s `content` entries
+fn content_failure(node: &Value) -> Option<Failure> {
+    entries(node.get("content")).find_map(|(media_type, media)| {
+        let failure = media.get("schema").and_then(schema_failure)?;
+        Some(prefixed(&["content", media_type, "schema"], failure))
+    })
+}
+
+fn schema_failure(schema: &Value) -> Option<Failure> {
+    if is_ref(schema) {
+        return None;
+    }
+    for (name, property) in entries(schema.get("properties")) {
+        if let Some(failure) = schema_failure(property) {
+            return Some(prefixed(&["properties", name], failure));
+        }
+    }
+    for key in ["items", "additionalProperties", "not"] {
+        if let Some(failure) = schema
+            .get(key)
+            .filter(|v| v.is_object())
+            .and_then(schema_failure)
+        {
+            return Some(prefixed(&[key], failure));
+        }
+    }
+    for key in ["allOf", "oneOf", "anyOf"] {
+        for (index, member) in items(schema.get(key)) {
+            if let Some(failure) = schema_failure(member) {
+                return Some(prefixed(&[key, &index], failure));
+            }
+        }
+    }
+    failure::<Schema>(schema)
+}
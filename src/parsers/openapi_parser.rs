@@ -1,9 +1,38 @@
-use super::{InputParser, SchemaIR, OriginalData, Metadata, SchemaDefinition, FieldDefinition, TypeInfo};
-use super::{OperationDefinition, HttpMethod, Parameter, ParameterLocation};
+use super::audiences::{self, AudienceExtension};
+use super::component_refs;
+use super::descriptions::translations;
+use super::external_refs;
+use super::json_schema_compat::{self, PREFIX_ITEMS_EXTENSION};
+use super::links;
+use super::name_collisions::rewrite_references;
+use super::openapi_coverage::analyze_coverage;
+use super::openapi_errors;
+use super::operation_ids::{dedupe_operation_ids, OperationIdStrategy};
+use super::originals::Originals;
+use super::{
+    BodyKind, Constraints, ExternalDocs, RateLimit, ResponseDefinition, ServerDefinition,
+    TagDefinition,
+};
+use super::{
+    ChannelDefinition, HttpMethod, OperationDefinition, Parameter, ParameterLocation,
+    SchemaReference,
+};
+use super::{
+    FieldDefinition, InputParser, Metadata, OriginalData, SchemaDefinition, SchemaIR, TypeInfo,
+};
+use crate::diagnostics::{self, ErrorGroup};
+use crate::generators::naming;
 use anyhow::{Context, Result};
-use openapiv3::{OpenAPI, ReferenceOr, Schema, SchemaKind, Type, Operation, PathItem};
+use indexmap::IndexMap;
+use openapiv3::{
+    MediaType, OpenAPI, Operation, PathItem, ReferenceOr, Schema, SchemaKind, StatusCode, Type,
+    VariantOrUnknownOrEmpty,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::Deserialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
@@ -18,74 +47,322 @@ impl InputParser for OpenApiParser {
         vec!["yaml", "yml", "json"]
     }
 
-    fn parse(&self, source: &Path, _options: &HashMap<String, Value>) -> Result<SchemaIR> {
+    fn can_parse(&self, content: &str) -> bool {
+        super::declares_key(content, "openapi")
+    }
+
+    fn parse(&self, source: &Path, options: &HashMap<String, Value>) -> Result<SchemaIR> {
         self.validate(source)?;
 
+        // Read here rather than by `external_refs::load` to keep the text for error locations
+        let name = source.display().to_string();
         let content = fs::read_to_string(source)
             .with_context(|| format!("Failed to read OpenAPI spec: {:?}", source))?;
+        let mut document = external_refs::read_document(&name, &content)?;
+        external_refs::bundle(&mut document, source)?;
+        self.parse_document(document, options, &name, &content)
+    }
 
-        let openapi: OpenAPI = if source.extension().and_then(|s| s.to_str()) == Some("json") {
-            serde_json::from_str(&content)?
-        } else {
-            serde_yaml::from_str(&content)?
-        };
+    fn parse_str(&self, content: &str, options: &HashMap<String, Value>) -> Result<SchemaIR> {
+        self.parse_document(
+            external_refs::read_document("spec", content)?,
+            options,
+            "spec",
+            content,
+        )
+    }
+}
+
+impl OpenApiParser {
+    /// Parse a document whose external refs, if any, are already resolved;
+    /// `name` and `content` are the source it was read from, for errors
+    fn parse_document(
+        &self,
+        mut document: Value,
+        options: &HashMap<String, Value>,
+        name: &str,
+        content: &str,
+    ) -> Result<SchemaIR> {
+        let operation_ids = OperationIdStrategy::from_options(options)?;
+        let originals = Originals::from_options(options);
+        let audience_extension = AudienceExtension::from_options(options)?;
+
+        json_schema_compat::normalize(&mut document);
+        let openapi: OpenAPI = diagnostics::from_value(name, content, document)
+            .map_err(|error| openapi_errors::narrow(error, name, content))?;
 
-        // Serialize full OpenAPI spec to JSON for original data
-        let original_json = serde_json::to_value(&openapi)?;
+        // Serialize the OpenAPI spec to JSON for original data
+        let original_json = originals.document(&openapi)?;
 
         // Extract custom metadata (x- extensions)
-        let mut custom_metadata = HashMap::new();
+        let mut custom_metadata = IndexMap::new();
         for (key, value) in &openapi.info.extensions {
             custom_metadata.insert(key.clone(), value.clone());
         }
 
+        // Inline operation bodies are hoisted into `schemas` while extracting operations
+        let mut schemas = extract_schemas(&openapi, originals)?;
+
         // Build SchemaIR
-        Ok(SchemaIR {
+        let mut schema_ir = SchemaIR {
             metadata: Metadata {
                 title: openapi.info.title.clone(),
                 version: openapi.info.version.clone(),
                 description: openapi.info.description.clone(),
-                base_url: openapi
-                    .servers
-                    .first()
-                    .map(|s| s.url.clone()),
+                translations: translations(&openapi.info.extensions, "info")?,
+                base_url: openapi.servers.first().map(|s| s.url.clone()),
+                base_path: openapi.servers.first().and_then(|s| base_path(&s.url)),
+                servers: extract_servers(&openapi),
+                tags: extract_tags(&openapi)?,
+                external_docs: openapi.external_docs.as_ref().map(external_docs),
                 custom: custom_metadata,
+                rate_limit: rate_limit_extension(&openapi.extensions)
+                    .context("Invalid top-level x-rate-limit extension")?,
             },
-            schemas: extract_schemas(&openapi)?,
-            operations: extract_operations(&openapi)?,
+            operations: extract_operations(&openapi, &mut schemas, operation_ids, originals)?,
+            schemas,
+            channels: extract_channels(&openapi)?,
             original: OriginalData {
                 format: "openapi".to_string(),
                 data: original_json,
                 extensions: extract_global_extensions(&openapi),
+                parser_options: options.clone(),
+                low_memory: originals == Originals::Skip,
             },
-        })
+            coverage: analyze_coverage(&openapi),
+        };
+        audiences::annotate(&openapi, &mut schema_ir, &audience_extension)?;
+
+        // Excluded schemas stay referenceable, as opaque values
+        let ignored = codegen_ignored_schemas(&openapi);
+        if !ignored.is_empty() {
+            opaque_references(&mut schema_ir, &ignored);
+        }
+
+        // Schema names are overridden last so every reference sees the final name
+        let renames = codegen_schema_names(&openapi);
+        if !renames.is_empty() {
+            for schema in &mut schema_ir.schemas {
+                if let Some(renamed) = renames.get(&schema.name) {
+                    schema.name = renamed.clone();
+                }
+            }
+            rewrite_references(&mut schema_ir, &renames);
+        }
+
+        Ok(schema_ir)
     }
 }
 
-fn extract_schemas(openapi: &OpenAPI) -> Result<Vec<SchemaDefinition>> {
-    let mut schemas = Vec::new();
+/// Vendor extension overriding the generated name of a component schema
+const CODEGEN_NAME_EXTENSION: &str = "x-codegen-name";
+
+/// Vendor extension overriding the generated name of an operation
+const CODEGEN_OPERATION_NAME_EXTENSION: &str = "x-codegen-operation-name";
+
+/// Vendor extension excluding a component schema or an operation from generation
+const CODEGEN_IGNORE_EXTENSION: &str = "x-codegen-ignore";
+
+fn codegen_schema_names(openapi: &OpenAPI) -> HashMap<String, String> {
+    let mut renames = HashMap::new();
+    if let Some(components) = &openapi.components {
+        for (schema_name, schema_ref) in &components.schemas {
+            if let ReferenceOr::Item(schema) = schema_ref {
+                if let Some(name) =
+                    codegen_name(&schema.schema_data.extensions, CODEGEN_NAME_EXTENSION)
+                {
+                    renames.insert(schema_name.clone(), name);
+                }
+            }
+        }
+    }
+    renames
+}
+
+pub(super) fn codegen_ignored(extensions: &IndexMap<String, Value>) -> bool {
+    extensions
+        .get(CODEGEN_IGNORE_EXTENSION)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
 
+fn codegen_ignored_schemas(openapi: &OpenAPI) -> HashSet<String> {
+    let mut ignored = HashSet::new();
     if let Some(components) = &openapi.components {
         for (schema_name, schema_ref) in &components.schemas {
             if let ReferenceOr::Item(schema) = schema_ref {
-                let original_json = serde_json::to_value(schema)?;
-
-                schemas.push(SchemaDefinition {
-                    name: schema_name.clone(),
-                    fields: extract_fields(schema)?,
-                    description: schema.schema_data.description.clone(),
-                    original: original_json,
-                });
+                if codegen_ignored(&schema.schema_data.extensions) {
+                    ignored.insert(schema_name.clone());
+                }
+            }
+        }
+    }
+    ignored
+}
+
+/// References to excluded schemas become `any`; bodies and channel messages
+/// of such a schema are left untyped
+fn opaque_references(schema_ir: &mut SchemaIR, ignored: &HashSet<String>) {
+    for schema in &mut schema_ir.schemas {
+        for field in &mut schema.fields {
+            opaque_type_info(&mut field.type_info, ignored);
+        }
+        schema.parents.retain(|parent| !ignored.contains(parent));
+    }
+
+    for operation in &mut schema_ir.operations {
+        for body in [&mut operation.request_body, &mut operation.response] {
+            if body
+                .as_ref()
+                .is_some_and(|body| ignored.contains(&body.name))
+            {
+                *body = None;
+            }
+        }
+        for response in &mut operation.responses {
+            if response
+                .schema
+                .as_ref()
+                .is_some_and(|body| ignored.contains(&body.name))
+            {
+                response.schema = None;
+            }
+            if let Some(type_info) = &mut response.inline_type {
+                opaque_type_info(type_info, ignored);
             }
         }
     }
 
-    Ok(schemas)
+    for channel in &mut schema_ir.channels {
+        for message in [&mut channel.send, &mut channel.receive] {
+            if message.as_ref().is_some_and(|name| ignored.contains(name)) {
+                *message = None;
+            }
+        }
+    }
+}
+
+fn opaque_type_info(type_info: &mut TypeInfo, ignored: &HashSet<String>) {
+    if type_info
+        .reference
+        .as_ref()
+        .is_some_and(|name| ignored.contains(name))
+    {
+        type_info.reference = None;
+        type_info.openapi_type = "any".to_string();
+    }
+    for item in type_info
+        .array_item_type
+        .iter_mut()
+        .chain(type_info.map_values.iter_mut())
+    {
+        opaque_type_info(item, ignored);
+    }
+    for member in type_info
+        .union_members
+        .iter_mut()
+        .chain(type_info.tuple_items.iter_mut())
+    {
+        opaque_type_info(member, ignored);
+    }
+}
+
+fn codegen_name(extensions: &IndexMap<String, Value>, key: &str) -> Option<String> {
+    extensions
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn extract_schemas(openapi: &OpenAPI, originals: Originals) -> Result<Vec<SchemaDefinition>> {
+    let Some(components) = &openapi.components else {
+        return Ok(Vec::new());
+    };
+    let declared: Vec<(&String, &Schema)> = components
+        .schemas
+        .iter()
+        .filter_map(|(schema_name, schema_ref)| match schema_ref {
+            ReferenceOr::Item(schema) if !codegen_ignored(&schema.schema_data.extensions) => {
+                Some((schema_name, schema))
+            }
+            _ => None,
+        })
+        .collect();
+
+    // Schemas are independent; the indexed collect keeps them in spec order
+    #[cfg(feature = "parallel")]
+    let declared = declared.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let declared = declared.into_iter();
+    declared
+        .map(|(schema_name, schema)| extract_schema(schema_name, schema, originals))
+        .collect()
 }
 
-fn extract_fields(schema: &Schema) -> Result<Vec<FieldDefinition>> {
+fn extract_schema(
+    schema_name: &str,
+    schema: &Schema,
+    originals: Originals,
+) -> Result<SchemaDefinition> {
+    Ok(SchemaDefinition {
+        name: schema_name.to_string(),
+        fields: extract_fields(schema, originals)?,
+        description: schema.schema_data.description.clone(),
+        translations: translations(
+            &schema.schema_data.extensions,
+            &format!("schema {}", schema_name),
+        )?,
+        parents: extract_parents(schema),
+        merge_patch_of: None,
+        audiences: Vec::new(),
+        original: originals.schema(schema)?,
+    })
+}
+
+/// Names of the schemas an `allOf` schema extends by `$ref`
+fn extract_parents(schema: &Schema) -> Vec<String> {
+    let SchemaKind::AllOf { all_of } = &schema.schema_kind else {
+        return Vec::new();
+    };
+    all_of
+        .iter()
+        .flat_map(|member| match member {
+            ReferenceOr::Reference { reference } => {
+                vec![reference
+                    .split('/')
+                    .next_back()
+                    .unwrap_or("Unknown")
+                    .to_string()]
+            }
+            ReferenceOr::Item(inline) => extract_parents(inline),
+        })
+        .collect()
+}
+
+fn extract_fields(schema: &Schema, originals: Originals) -> Result<Vec<FieldDefinition>> {
     let mut fields = Vec::new();
 
+    // Inline `allOf` members contribute fields; `$ref` members are parents
+    if let SchemaKind::AllOf { all_of } = &schema.schema_kind {
+        for member in all_of {
+            if let ReferenceOr::Item(inline) = member {
+                for mut field in extract_fields(inline, originals)? {
+                    match fields
+                        .iter_mut()
+                        .find(|f: &&mut FieldDefinition| f.name == field.name)
+                    {
+                        Some(existing) => {
+                            field.required |= existing.required;
+                            *existing = field;
+                        }
+                        None => fields.push(field),
+                    }
+                }
+            }
+        }
+    }
+
     if let SchemaKind::Type(Type::Object(obj_type)) = &schema.schema_kind {
         let required = &obj_type.required;
 
@@ -96,8 +373,9 @@ fn extract_fields(schema: &Schema) -> Result<Vec<FieldDefinition>> {
                 ReferenceOr::Item(schema_box) => schema_box.as_ref(),
                 ReferenceOr::Reference { reference } => {
                     // Handle references
-                    let ref_name = reference.split('/').last().unwrap_or("Unknown");
-                    let original_json = serde_json::json!({ "$ref": reference });
+                    let ref_name = reference.split('/').next_back().unwrap_or("Unknown");
+                    let original_json =
+                        originals.node(&serde_json::json!({ "$ref": reference }))?;
 
                     fields.push(FieldDefinition {
                         name: field_name.clone(),
@@ -108,23 +386,37 @@ fn extract_fields(schema: &Schema) -> Result<Vec<FieldDefinition>> {
                             array_item_type: None,
                             reference: Some(ref_name.to_string()),
                             enum_values: None,
+                            union_members: Vec::new(),
+                            tuple_items: Vec::new(),
+                            map_values: None,
                         },
                         required: is_required,
+                        nullable: false,
+                        constraints: Constraints::default(),
                         description: None,
+                        translations: IndexMap::new(),
+                        audiences: Vec::new(),
                         original: original_json,
                     });
                     continue;
                 }
             };
 
-            let original_json = serde_json::to_value(field_schema)?;
+            let original_json = originals.node(field_schema)?;
             let type_info = extract_type_info(field_schema);
 
             fields.push(FieldDefinition {
                 name: field_name.clone(),
                 type_info,
                 required: is_required,
+                nullable: field_schema.schema_data.nullable,
+                constraints: extract_constraints(field_schema),
                 description: field_schema.schema_data.description.clone(),
+                translations: translations(
+                    &field_schema.schema_data.extensions,
+                    &format!("property {}", field_name),
+                )?,
+                audiences: Vec::new(),
                 original: original_json,
             });
         }
@@ -133,13 +425,43 @@ fn extract_fields(schema: &Schema) -> Result<Vec<FieldDefinition>> {
     Ok(fields)
 }
 
+fn extract_constraints(schema: &Schema) -> Constraints {
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::String(string_type)) => Constraints {
+            min_length: string_type.min_length,
+            max_length: string_type.max_length,
+            pattern: string_type.pattern.clone(),
+            ..Default::default()
+        },
+        SchemaKind::Type(Type::Number(num_type)) => Constraints {
+            minimum: num_type.minimum,
+            maximum: num_type.maximum,
+            exclusive_minimum: num_type.exclusive_minimum,
+            exclusive_maximum: num_type.exclusive_maximum,
+            multiple_of: num_type.multiple_of,
+            ..Default::default()
+        },
+        SchemaKind::Type(Type::Integer(int_type)) => Constraints {
+            minimum: int_type.minimum.map(|v| v as f64),
+            maximum: int_type.maximum.map(|v| v as f64),
+            exclusive_minimum: int_type.exclusive_minimum,
+            exclusive_maximum: int_type.exclusive_maximum,
+            multiple_of: int_type.multiple_of.map(|v| v as f64),
+            ..Default::default()
+        },
+        SchemaKind::Type(Type::Array(array_type)) => Constraints {
+            min_items: array_type.min_items,
+            max_items: array_type.max_items,
+            ..Default::default()
+        },
+        _ => Constraints::default(),
+    }
+}
+
 fn extract_type_info(schema: &Schema) -> TypeInfo {
     match &schema.schema_kind {
         SchemaKind::Type(Type::String(string_type)) => {
-            let format_str = match &string_type.format {
-                openapiv3::VariantOrUnknownOrEmpty::Item(fmt) => Some(format!("{:?}", fmt)),
-                _ => None,
-            };
+            let format_str = format_name(&string_type.format);
 
             TypeInfo {
                 openapi_type: "string".to_string(),
@@ -158,13 +480,13 @@ fn extract_type_info(schema: &Schema) -> TypeInfo {
                 } else {
                     None
                 },
+                union_members: Vec::new(),
+                tuple_items: Vec::new(),
+                map_values: None,
             }
         }
         SchemaKind::Type(Type::Number(num_type)) => {
-            let format_str = match &num_type.format {
-                openapiv3::VariantOrUnknownOrEmpty::Item(fmt) => Some(format!("{:?}", fmt)),
-                _ => None,
-            };
+            let format_str = format_name(&num_type.format);
 
             TypeInfo {
                 openapi_type: "number".to_string(),
@@ -173,13 +495,13 @@ fn extract_type_info(schema: &Schema) -> TypeInfo {
                 array_item_type: None,
                 reference: None,
                 enum_values: None,
+                union_members: Vec::new(),
+                tuple_items: Vec::new(),
+                map_values: None,
             }
         }
         SchemaKind::Type(Type::Integer(int_type)) => {
-            let format_str = match &int_type.format {
-                openapiv3::VariantOrUnknownOrEmpty::Item(fmt) => Some(format!("{:?}", fmt)),
-                _ => None,
-            };
+            let format_str = format_name(&int_type.format);
 
             TypeInfo {
                 openapi_type: "integer".to_string(),
@@ -188,6 +510,9 @@ fn extract_type_info(schema: &Schema) -> TypeInfo {
                 array_item_type: None,
                 reference: None,
                 enum_values: None,
+                union_members: Vec::new(),
+                tuple_items: Vec::new(),
+                map_values: None,
             }
         }
         SchemaKind::Type(Type::Boolean(_)) => TypeInfo {
@@ -197,15 +522,16 @@ fn extract_type_info(schema: &Schema) -> TypeInfo {
             array_item_type: None,
             reference: None,
             enum_values: None,
+            union_members: Vec::new(),
+            tuple_items: Vec::new(),
+            map_values: None,
         },
         SchemaKind::Type(Type::Array(array_type)) => {
             let item_type = if let Some(items) = &array_type.items {
                 match items {
-                    ReferenceOr::Item(item_schema) => {
-                        Box::new(extract_type_info(item_schema))
-                    }
+                    ReferenceOr::Item(item_schema) => Box::new(extract_type_info(item_schema)),
                     ReferenceOr::Reference { reference } => {
-                        let ref_name = reference.split('/').last().unwrap_or("Unknown");
+                        let ref_name = reference.split('/').next_back().unwrap_or("Unknown");
                         Box::new(TypeInfo {
                             openapi_type: "object".to_string(),
                             format: None,
@@ -213,6 +539,9 @@ fn extract_type_info(schema: &Schema) -> TypeInfo {
                             array_item_type: None,
                             reference: Some(ref_name.to_string()),
                             enum_values: None,
+                            union_members: Vec::new(),
+                            tuple_items: Vec::new(),
+                            map_values: None,
                         })
                     }
                 }
@@ -224,6 +553,9 @@ fn extract_type_info(schema: &Schema) -> TypeInfo {
                     array_item_type: None,
                     reference: None,
                     enum_values: None,
+                    union_members: Vec::new(),
+                    tuple_items: Vec::new(),
+                    map_values: None,
                 })
             };
 
@@ -234,6 +566,9 @@ fn extract_type_info(schema: &Schema) -> TypeInfo {
                 array_item_type: Some(item_type),
                 reference: None,
                 enum_values: None,
+                union_members: Vec::new(),
+                tuple_items: extract_tuple_items(schema),
+                map_values: None,
             }
         }
         SchemaKind::Type(Type::Object(_)) => TypeInfo {
@@ -243,7 +578,28 @@ fn extract_type_info(schema: &Schema) -> TypeInfo {
             array_item_type: None,
             reference: None,
             enum_values: None,
+            union_members: Vec::new(),
+            tuple_items: Vec::new(),
+            map_values: None,
         },
+        SchemaKind::OneOf { one_of: members } | SchemaKind::AnyOf { any_of: members } => {
+            let mut union_members: Vec<TypeInfo> = members.iter().map(member_type_info).collect();
+            if union_members.len() == 1 {
+                return union_members.remove(0);
+            }
+
+            TypeInfo {
+                openapi_type: "union".to_string(),
+                format: None,
+                is_array: false,
+                array_item_type: None,
+                reference: None,
+                enum_values: None,
+                union_members,
+                tuple_items: Vec::new(),
+                map_values: None,
+            }
+        }
         _ => TypeInfo {
             openapi_type: "any".to_string(),
             format: None,
@@ -251,27 +607,111 @@ fn extract_type_info(schema: &Schema) -> TypeInfo {
             array_item_type: None,
             reference: None,
             enum_values: None,
+            union_members: Vec::new(),
+            tuple_items: Vec::new(),
+            map_values: None,
         },
     }
 }
 
-fn extract_operations(openapi: &OpenAPI) -> Result<Vec<OperationDefinition>> {
-    let mut operations = Vec::new();
+fn member_type_info(schema_ref: &ReferenceOr<Schema>) -> TypeInfo {
+    match schema_ref {
+        ReferenceOr::Item(schema) => extract_type_info(schema),
+        ReferenceOr::Reference { reference } => TypeInfo {
+            openapi_type: "object".to_string(),
+            format: None,
+            is_array: false,
+            array_item_type: None,
+            reference: Some(
+                reference
+                    .split('/')
+                    .next_back()
+                    .unwrap_or("Unknown")
+                    .to_string(),
+            ),
+            enum_values: None,
+            union_members: Vec::new(),
+            tuple_items: Vec::new(),
+            map_values: None,
+        },
+    }
+}
+
+/// Tuple item types, kept in an extension because openapiv3 drops `prefixItems`
+fn extract_tuple_items(schema: &Schema) -> Vec<TypeInfo> {
+    schema
+        .schema_data
+        .extensions
+        .get(PREFIX_ITEMS_EXTENSION)
+        .and_then(|items| serde_json::from_value::<Vec<ReferenceOr<Schema>>>(items.clone()).ok())
+        .map(|items| items.iter().map(member_type_info).collect())
+        .unwrap_or_default()
+}
+
+/// Spec spelling of a schema `format` (e.g., "date-time", "email")
+fn format_name<T: serde::Serialize>(format: &VariantOrUnknownOrEmpty<T>) -> Option<String> {
+    match format {
+        VariantOrUnknownOrEmpty::Item(fmt) => serde_json::to_value(fmt)
+            .ok()
+            .and_then(|v| v.as_str().map(|s| s.to_string())),
+        VariantOrUnknownOrEmpty::Unknown(other) => Some(other.clone()),
+        VariantOrUnknownOrEmpty::Empty => None,
+    }
+}
+
+fn extract_operations(
+    openapi: &OpenAPI,
+    schemas: &mut Vec<SchemaDefinition>,
+    operation_ids: OperationIdStrategy,
+    originals: Originals,
+) -> Result<Vec<OperationDefinition>> {
+    let mut extracted = Vec::new();
 
     for (path, path_item_ref) in &openapi.paths.paths {
         if let ReferenceOr::Item(path_item) = path_item_ref {
-            extract_operations_from_path(path, path_item, &mut operations)?;
+            extract_operations_from_path(
+                openapi,
+                path,
+                path_item,
+                &mut extracted,
+                schemas,
+                operation_ids,
+                originals,
+            );
         }
     }
 
+    // Every invalid operation is reported, not just the first
+    let mut operations = Vec::new();
+    let mut errors = Vec::new();
+    for result in extracted {
+        match result {
+            Ok(operation) => operations.push(operation),
+            Err(error) => errors.push(error),
+        }
+    }
+    match errors.len() {
+        0 => {}
+        1 => return Err(errors.remove(0)),
+        count => {
+            return Err(ErrorGroup::new(format!("{} operations are invalid", count), errors).into())
+        }
+    }
+
+    dedupe_operation_ids(&mut operations);
+    links::resolve(openapi, &mut operations);
     Ok(operations)
 }
 
 fn extract_operations_from_path(
+    openapi: &OpenAPI,
     path: &str,
     path_item: &PathItem,
-    operations: &mut Vec<OperationDefinition>,
-) -> Result<()> {
+    operations: &mut Vec<Result<OperationDefinition>>,
+    schemas: &mut Vec<SchemaDefinition>,
+    operation_ids: OperationIdStrategy,
+    originals: Originals,
+) {
     let ops = vec![
         (&path_item.get, HttpMethod::Get),
         (&path_item.post, HttpMethod::Post),
@@ -283,63 +723,518 @@ fn extract_operations_from_path(
     ];
 
     for (op_option, method) in ops {
-        if let Some(operation) = op_option {
-            let op_def = extract_operation(path, method, operation)?;
-            operations.push(op_def);
+        if let Some(operation) = op_option
+            .as_ref()
+            .filter(|op| !codegen_ignored(&op.extensions))
+        {
+            // Parameters of the path item apply to each of its operations
+            let merged;
+            let operation = match path_item.parameters.is_empty() {
+                true => operation,
+                false => {
+                    merged = Operation {
+                        parameters: merged_parameters(
+                            openapi,
+                            &path_item.parameters,
+                            &operation.parameters,
+                        ),
+                        ..operation.clone()
+                    };
+                    &merged
+                }
+            };
+            operations.push(extract_operation(
+                openapi,
+                path,
+                method,
+                operation,
+                schemas,
+                operation_ids,
+                originals,
+            ));
         }
     }
-
-    Ok(())
 }
 
 fn extract_operation(
+    openapi: &OpenAPI,
     path: &str,
     method: HttpMethod,
     operation: &Operation,
+    schemas: &mut Vec<SchemaDefinition>,
+    operation_ids: OperationIdStrategy,
+    originals: Originals,
 ) -> Result<OperationDefinition> {
-    let original_json = serde_json::to_value(operation)?;
+    let original_json = originals.node(operation)?;
+
+    let id = codegen_name(&operation.extensions, CODEGEN_OPERATION_NAME_EXTENSION).unwrap_or_else(
+        || {
+            operation_ids.operation_id(
+                operation.operation_id.as_deref(),
+                operation.summary.as_deref(),
+                &format!("{:?}", method),
+                path,
+            )
+        },
+    );
+    let type_prefix = naming::pascal_case(&id);
+    let rate_limit = rate_limit_extension(&operation.extensions).with_context(|| {
+        format!(
+            "Invalid x-rate-limit extension on {} {}",
+            format!("{:?}", method).to_uppercase(),
+            path
+        )
+    })?;
+    let idempotency = match operation.extensions.get("x-idempotency") {
+        None => None,
+        Some(Value::Bool(flag)) => Some(*flag),
+        Some(_) => anyhow::bail!(
+            "Invalid x-idempotency extension on {} {}: expected a boolean",
+            format!("{:?}", method).to_uppercase(),
+            path
+        ),
+    };
+    let timeout_ms = match operation.extensions.get("x-timeout-ms") {
+        None => None,
+        Some(value) => match value.as_u64().filter(|ms| *ms > 0) {
+            Some(ms) => Some(ms),
+            None => anyhow::bail!(
+                "Invalid x-timeout-ms extension on {} {}: expected a positive integer",
+                format!("{:?}", method).to_uppercase(),
+                path
+            ),
+        },
+    };
+    let feature_flag = match operation.extensions.get("x-feature-flag") {
+        None => None,
+        Some(Value::String(flag)) if !flag.trim().is_empty() => Some(flag.trim().to_string()),
+        Some(_) => anyhow::bail!(
+            "Invalid x-feature-flag extension on {} {}: expected a flag name",
+            format!("{:?}", method).to_uppercase(),
+            path
+        ),
+    };
+
+    // Shared `components` entries are resolved as if declared inline
+    let request_content = operation
+        .request_body
+        .as_ref()
+        .and_then(|body| component_refs::resolve(openapi, body))
+        .map(|body| &body.content);
+    let response_content = success_response_content(openapi, operation);
+
+    let request_body = match request_content {
+        Some(content) => extract_body_schema(
+            content,
+            &format!("{}Request", type_prefix),
+            schemas,
+            originals,
+        )?,
+        None => None,
+    };
+    let response = match response_content {
+        Some(content) => extract_body_schema(
+            content,
+            &format!("{}Response", type_prefix),
+            schemas,
+            originals,
+        )?,
+        None => None,
+    };
 
     let parameters = operation
         .parameters
         .iter()
-        .filter_map(|param_ref| {
-            if let ReferenceOr::Item(param) = param_ref {
-                Some(Parameter {
-                    name: param.parameter_data_ref().name.clone(),
-                    location: match param {
-                        openapiv3::Parameter::Query { .. } => ParameterLocation::Query,
-                        openapiv3::Parameter::Header { .. } => ParameterLocation::Header,
-                        openapiv3::Parameter::Path { .. } => ParameterLocation::Path,
-                        openapiv3::Parameter::Cookie { .. } => ParameterLocation::Cookie,
-                    },
-                    required: param.parameter_data_ref().required,
-                    schema_type: "string".to_string(), // Simplified for now
-                    description: param.parameter_data_ref().description.clone(),
-                })
-            } else {
-                None
-            }
-        })
-        .collect();
+        .filter_map(|param_ref| component_refs::resolve(openapi, param_ref))
+        .map(|param| extract_parameter(openapi, param))
+        .collect::<Result<_>>()?;
 
+    let location = format!("{} {}", format!("{:?}", method).to_uppercase(), path);
     Ok(OperationDefinition {
-        id: operation
-            .operation_id
-            .clone()
-            .unwrap_or_else(|| format!("{}_{}", format!("{:?}", method).to_lowercase(), path.replace('/', "_"))),
+        id,
         method,
         path: path.to_string(),
         parameters,
-        request_body: None, // TODO: extract request body
-        response: None,     // TODO: extract response
+        request_body,
+        response: response.clone(),
+        request_content_type: request_content
+            .and_then(preferred_media_type)
+            .map(|(media_type, _)| media_type.clone()),
+        response_content_type: response_content
+            .and_then(preferred_media_type)
+            .map(|(media_type, _)| media_type.clone()),
+        response_headers: success_response_headers(openapi, operation),
+        responses: extract_responses(openapi, operation, response.as_ref()),
+        summary: operation.summary.clone(),
         description: operation.description.clone(),
+        translations: translations(&operation.extensions, &location)?,
         tags: operation.tags.clone(),
+        rate_limit,
+        idempotency,
+        timeout_ms,
+        feature_flag,
+        external_docs: operation.external_docs.as_ref().map(external_docs),
+        links: links::extract(openapi, operation),
+        audiences: Vec::new(),
+        namespace: None,
         original: original_json,
     })
 }
 
-fn extract_global_extensions(openapi: &OpenAPI) -> HashMap<String, Value> {
-    let mut extensions = HashMap::new();
+/// Parameters of an operation: those of its path item first, each replaced by
+/// the operation's own of the same name and location, then the operation's others
+pub(super) fn merged_parameters(
+    openapi: &OpenAPI,
+    shared: &[ReferenceOr<openapiv3::Parameter>],
+    own: &[ReferenceOr<openapiv3::Parameter>],
+) -> Vec<ReferenceOr<openapiv3::Parameter>> {
+    // Name and location; unresolvable refs never match
+    let key = |param_ref: &ReferenceOr<openapiv3::Parameter>| {
+        component_refs::resolve(openapi, param_ref).map(|param| {
+            (
+                std::mem::discriminant(param),
+                param.parameter_data_ref().name.clone(),
+            )
+        })
+    };
+
+    let mut own = own.to_vec();
+    let mut merged: Vec<_> = shared
+        .iter()
+        .map(|param_ref| {
+            let overriding = key(param_ref).and_then(|shared_key| {
+                own.iter()
+                    .position(|own_ref| key(own_ref).as_ref() == Some(&shared_key))
+            });
+            match overriding {
+                Some(index) => own.remove(index),
+                None => param_ref.clone(),
+            }
+        })
+        .collect();
+    merged.extend(own);
+    merged
+}
+
+/// IR parameter with its schema type and serialization style
+fn extract_parameter(openapi: &OpenAPI, param: &openapiv3::Parameter) -> Result<Parameter> {
+    let (location, style) = match param {
+        openapiv3::Parameter::Query { style, .. } => (ParameterLocation::Query, style_name(style)),
+        openapiv3::Parameter::Header { style, .. } => {
+            (ParameterLocation::Header, style_name(style))
+        }
+        openapiv3::Parameter::Path { style, .. } => (ParameterLocation::Path, style_name(style)),
+        openapiv3::Parameter::Cookie { style, .. } => {
+            (ParameterLocation::Cookie, style_name(style))
+        }
+    };
+    let data = param.parameter_data_ref();
+    let schema = match &data.format {
+        openapiv3::ParameterSchemaOrContent::Schema(schema) => {
+            component_refs::resolve(openapi, schema)
+        }
+        openapiv3::ParameterSchemaOrContent::Content(_) => None,
+    };
+    let (schema_type, format) = schema.map_or(("string".to_string(), None), scalar_type);
+    let (item_type, item_format) = match schema.map(|schema| &schema.schema_kind) {
+        Some(SchemaKind::Type(Type::Array(array))) => {
+            let items = array.items.clone().map(ReferenceOr::unbox);
+            let item = items
+                .as_ref()
+                .and_then(|items| component_refs::resolve(openapi, items));
+            let (item_type, item_format) = item.map_or(("string".to_string(), None), scalar_type);
+            (Some(item_type), item_format)
+        }
+        _ => (None, None),
+    };
+
+    Ok(Parameter {
+        name: data.name.clone(),
+        location,
+        required: data.required,
+        schema_type,
+        format,
+        item_type,
+        item_format,
+        style,
+        explode: data.explode,
+        description: data.description.clone(),
+        translations: translations(&data.extensions, &format!("parameter {}", data.name))?,
+        example: data.example.clone(),
+    })
+}
+
+/// `(type, format)` of a parameter schema or its array items
+fn scalar_type(schema: &Schema) -> (String, Option<String>) {
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::String(string)) => {
+            ("string".to_string(), format_name(&string.format))
+        }
+        SchemaKind::Type(Type::Integer(integer)) => {
+            ("integer".to_string(), format_name(&integer.format))
+        }
+        SchemaKind::Type(Type::Number(number)) => {
+            ("number".to_string(), format_name(&number.format))
+        }
+        SchemaKind::Type(Type::Boolean(_)) => ("boolean".to_string(), None),
+        SchemaKind::Type(Type::Array(_)) => ("array".to_string(), None),
+        SchemaKind::Type(Type::Object(_)) => ("object".to_string(), None),
+        _ => ("string".to_string(), None),
+    }
+}
+
+/// Spec spelling of a parameter `style` (`form`, `spaceDelimited`, ...)
+fn style_name<T: serde::Serialize>(style: &T) -> Option<String> {
+    serde_json::to_value(style)
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+}
+
+/// Content of the first successful (2xx) response that has a body
+fn success_response_content<'a>(
+    openapi: &'a OpenAPI,
+    operation: &'a Operation,
+) -> Option<&'a IndexMap<String, MediaType>> {
+    operation
+        .responses
+        .responses
+        .iter()
+        .filter(|(status, _)| match status {
+            StatusCode::Code(code) => (200..300).contains(code),
+            StatusCode::Range(range) => *range == 2,
+        })
+        .find_map(|(_, response_ref)| {
+            component_refs::resolve(openapi, response_ref)
+                .filter(|response| !response.content.is_empty())
+                .map(|response| &response.content)
+        })
+}
+
+/// Every response of `operation`; the first 2xx one with content carries
+/// `response`, the operation's (possibly hoisted) success body
+fn extract_responses(
+    openapi: &OpenAPI,
+    operation: &Operation,
+    response: Option<&SchemaReference>,
+) -> Vec<ResponseDefinition> {
+    let declared = operation
+        .responses
+        .responses
+        .iter()
+        .map(|(status, response_ref)| (links::status_name(status), response_ref))
+        .chain(
+            operation
+                .responses
+                .default
+                .iter()
+                .map(|response_ref| ("default".to_string(), response_ref)),
+        );
+
+    let mut success_body_seen = false;
+    let mut responses = Vec::new();
+    for (status, response_ref) in declared {
+        let Some(declared) = component_refs::resolve(openapi, response_ref) else {
+            continue;
+        };
+        let media = preferred_media_type(&declared.content);
+        let success_body = !success_body_seen && status.starts_with('2') && media.is_some();
+        success_body_seen |= success_body;
+        let schema = match success_body {
+            true => response.cloned(),
+            false => extract_schema_reference(&declared.content),
+        };
+        let inline_type = match schema {
+            Some(_) => None,
+            None => media
+                .and_then(|(_, media)| media.schema.as_ref())
+                .map(member_type_info),
+        };
+        responses.push(ResponseDefinition {
+            status,
+            content_type: media.map(|(media_type, _)| media_type.clone()),
+            schema,
+            inline_type,
+            description: Some(declared.description.clone())
+                .filter(|description| !description.is_empty()),
+        });
+    }
+    responses
+}
+
+/// Names of the headers the successful (2xx) responses declare, in spec order
+fn success_response_headers(openapi: &OpenAPI, operation: &Operation) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (status, response_ref) in &operation.responses.responses {
+        let success = match status {
+            StatusCode::Code(code) => (200..300).contains(code),
+            StatusCode::Range(range) => *range == 2,
+        };
+        let Some(response) = component_refs::resolve(openapi, response_ref).filter(|_| success)
+        else {
+            continue;
+        };
+        for name in response.headers.keys() {
+            if !names.iter().any(|known| known.eq_ignore_ascii_case(name)) {
+                names.push(name.clone());
+            }
+        }
+    }
+    names
+}
+
+/// Schema reference for a body, hoisting an inline object schema (or the inline
+/// item schema of an array) into a synthesized named schema
+fn extract_body_schema(
+    content: &IndexMap<String, MediaType>,
+    type_name: &str,
+    schemas: &mut Vec<SchemaDefinition>,
+    originals: Originals,
+) -> Result<Option<SchemaReference>> {
+    if let Some(schema_ref) = extract_schema_reference(content) {
+        return Ok(Some(schema_ref));
+    }
+
+    let Some((schema, schema_type)) = hoistable_body_schema(content) else {
+        return Ok(None);
+    };
+
+    // `ListUsersResponse` for objects, `ListUsersResponseItem` for array items
+    let base_name = match schema_type {
+        "array" => format!("{}Item", type_name),
+        _ => type_name.to_string(),
+    };
+    let mut name = base_name.clone();
+    let mut suffix = 2;
+    while schemas.iter().any(|s| s.name == name) {
+        name = format!("{}{}", base_name, suffix);
+        suffix += 1;
+    }
+
+    schemas.push(SchemaDefinition {
+        name: name.clone(),
+        fields: extract_fields(schema, originals)?,
+        description: schema.schema_data.description.clone(),
+        translations: translations(&schema.schema_data.extensions, &format!("schema {}", name))?,
+        parents: extract_parents(schema),
+        merge_patch_of: None,
+        audiences: Vec::new(),
+        original: originals.schema(schema)?,
+    });
+
+    Ok(Some(SchemaReference {
+        name,
+        schema_type: schema_type.to_string(),
+    }))
+}
+
+/// Inline object schema of a body (or of its array items) with at least one property
+pub(super) fn hoistable_body_schema(
+    content: &IndexMap<String, MediaType>,
+) -> Option<(&Schema, &'static str)> {
+    let ReferenceOr::Item(schema) = preferred_media_type(content)?.1.schema.as_ref()? else {
+        return None;
+    };
+
+    let has_properties = |schema: &Schema| matches!(&schema.schema_kind, SchemaKind::Type(Type::Object(obj)) if !obj.properties.is_empty());
+
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::Array(array_type)) => match array_type.items.as_ref()? {
+            ReferenceOr::Item(items) if has_properties(items) => Some((items.as_ref(), "array")),
+            _ => None,
+        },
+        _ if has_properties(schema) => Some((schema, "object")),
+        _ => None,
+    }
+}
+
+/// Media type generators target when a body offers several: JSON if present, else the first
+pub(super) fn preferred_media_type(
+    content: &IndexMap<String, MediaType>,
+) -> Option<(&String, &MediaType)> {
+    content
+        .iter()
+        .find(|(media_type, _)| BodyKind::from_content_type(media_type) == BodyKind::Json)
+        .or_else(|| content.iter().next())
+}
+
+/// Named schema carried by a body: a `$ref`, or an array of `$ref`s
+pub(super) fn extract_schema_reference(
+    content: &IndexMap<String, MediaType>,
+) -> Option<SchemaReference> {
+    let schema_ref = preferred_media_type(content)?.1.schema.as_ref()?;
+
+    let ref_name = |reference: &str| reference.split('/').next_back().map(|s| s.to_string());
+
+    match schema_ref {
+        ReferenceOr::Reference { reference } => Some(SchemaReference {
+            name: ref_name(reference)?,
+            schema_type: "object".to_string(),
+        }),
+        ReferenceOr::Item(schema) => match &schema.schema_kind {
+            SchemaKind::Type(Type::Array(array_type)) => match array_type.items.as_ref()? {
+                ReferenceOr::Reference { reference } => Some(SchemaReference {
+                    name: ref_name(reference)?,
+                    schema_type: "array".to_string(),
+                }),
+                ReferenceOr::Item(_) => None,
+            },
+            _ => None,
+        },
+    }
+}
+
+fn extract_servers(openapi: &OpenAPI) -> Vec<ServerDefinition> {
+    openapi
+        .servers
+        .iter()
+        .map(|server| ServerDefinition {
+            url: server.url.clone(),
+            description: server.description.clone(),
+            environment: server
+                .extensions
+                .get("x-environment")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        })
+        .collect()
+}
+
+/// Path after the host of a server URL (`https://host/api/v2` → `/api/v2`);
+/// relative URLs such as `/api` are a path already
+fn base_path(url: &str) -> Option<String> {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map(|start| &rest[start..])?,
+        None if url.starts_with('/') => url,
+        None => return None,
+    };
+    let path = path.trim_end_matches('/');
+    (!path.is_empty()).then(|| path.to_string())
+}
+
+fn extract_tags(openapi: &OpenAPI) -> Result<Vec<TagDefinition>> {
+    openapi
+        .tags
+        .iter()
+        .map(|tag| {
+            Ok(TagDefinition {
+                name: tag.name.clone(),
+                description: tag.description.clone(),
+                translations: translations(&tag.extensions, &format!("tag {}", tag.name))?,
+                external_docs: tag.external_docs.as_ref().map(external_docs),
+            })
+        })
+        .collect()
+}
+
+fn external_docs(docs: &openapiv3::ExternalDocumentation) -> ExternalDocs {
+    ExternalDocs {
+        url: docs.url.clone(),
+        description: docs.description.clone(),
+    }
+}
+
+fn extract_global_extensions(openapi: &OpenAPI) -> IndexMap<String, Value> {
+    let mut extensions = IndexMap::new();
 
     for (key, value) in &openapi.extensions {
         extensions.insert(format!("openapi.{}", key), value.clone());
@@ -347,3 +1242,210 @@ fn extract_global_extensions(openapi: &OpenAPI) -> HashMap<String, Value> {
 
     extensions
 }
+
+fn rate_limit_extension(extensions: &IndexMap<String, Value>) -> Result<Option<RateLimit>> {
+    extensions
+        .get("x-rate-limit")
+        .map(RateLimit::from_value)
+        .transpose()
+}
+
+/// Channel declaration in the `x-channels` extension
+#[derive(Deserialize)]
+struct RawChannel {
+    path: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    send: Option<ReferenceOr<Schema>>,
+    #[serde(default)]
+    receive: Option<ReferenceOr<Schema>>,
+}
+
+/// Extract WebSocket channels declared in the top-level `x-channels` extension.
+/// Message schemas given as `$ref` are typed; inline schemas fall back to `any`.
+fn extract_channels(openapi: &OpenAPI) -> Result<Vec<ChannelDefinition>> {
+    let Some(raw) = openapi.extensions.get("x-channels") else {
+        return Ok(Vec::new());
+    };
+
+    let raw_channels: IndexMap<String, RawChannel> =
+        serde_json::from_value(raw.clone()).context("Invalid x-channels extension")?;
+
+    let message_schema = |schema: &Option<ReferenceOr<Schema>>| match schema {
+        Some(ReferenceOr::Reference { reference }) => reference
+            .split('/')
+            .next_back()
+            .map(|name| name.to_string()),
+        _ => None,
+    };
+
+    Ok(raw_channels
+        .into_iter()
+        .map(|(name, channel)| ChannelDefinition {
+            send: message_schema(&channel.send),
+            receive: message_schema(&channel.receive),
+            name,
+            path: channel.path,
+            description: channel.description,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(spec: &str) -> SchemaIR {
+        OpenApiParser.parse_str(spec, &HashMap::new()).unwrap()
+    }
+
+    #[test]
+    fn path_item_parameters_apply_to_each_operation() {
+        let ir = parse(
+            r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+components:
+  parameters:
+    Trace: { name: X-Trace, in: header, schema: { type: string } }
+paths:
+  /items/{id}:
+    parameters:
+      - { name: id, in: path, required: true, schema: { type: string } }
+      - $ref: "#/components/parameters/Trace"
+      - { name: id, in: query, schema: { type: string } }
+    get:
+      operationId: getItem
+      parameters:
+        - { name: id, in: path, required: true, schema: { type: integer } }
+        - { name: page, in: query, schema: { type: integer } }
+      responses: { "204": { description: ok } }
+    delete:
+      operationId: deleteItem
+      responses: { "204": { description: ok } }
+"##,
+        );
+
+        let parameters = |id: &str| -> Vec<(String, ParameterLocation, String)> {
+            ir.operations
+                .iter()
+                .find(|op| op.id == id)
+                .unwrap()
+                .parameters
+                .iter()
+                .map(|p| (p.name.clone(), p.location, p.schema_type.clone()))
+                .collect()
+        };
+        let param = |name: &str, location: ParameterLocation, schema_type: &str| {
+            (name.to_string(), location, schema_type.to_string())
+        };
+
+        // Redeclared by name and location, in the path item's position
+        assert_eq!(
+            parameters("getItem"),
+            vec![
+                param("id", ParameterLocation::Path, "integer"),
+                param("X-Trace", ParameterLocation::Header, "string"),
+                param("id", ParameterLocation::Query, "string"),
+                param("page", ParameterLocation::Query, "integer"),
+            ]
+        );
+        assert_eq!(
+            parameters("deleteItem"),
+            vec![
+                param("id", ParameterLocation::Path, "string"),
+                param("X-Trace", ParameterLocation::Header, "string"),
+                param("id", ParameterLocation::Query, "string"),
+            ]
+        );
+    }
+
+    #[test]
+    fn merged_parameters_override_by_name_and_location() {
+        let openapi: OpenAPI = serde_yaml::from_str(
+            r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths: {}
+components:
+  parameters:
+    Page: { name: page, in: query, schema: { type: integer } }
+"##,
+        )
+        .unwrap();
+        let parameters = |yaml: &str| -> Vec<ReferenceOr<openapiv3::Parameter>> {
+            serde_yaml::from_str(yaml).unwrap()
+        };
+        let shared = parameters(
+            r##"
+- { name: id, in: path, required: true, schema: { type: string }, description: shared }
+- { name: id, in: header, schema: { type: string } }
+- $ref: "#/components/parameters/Page"
+- $ref: "#/components/parameters/Missing"
+"##,
+        );
+        let own = parameters(
+            r##"
+- { name: limit, in: query, schema: { type: integer } }
+- { name: page, in: query, schema: { type: string }, description: own }
+- { name: id, in: path, required: true, schema: { type: string }, description: own }
+- $ref: "#/components/parameters/Missing"
+"##,
+        );
+
+        let summary: Vec<String> = merged_parameters(&openapi, &shared, &own)
+            .iter()
+            .map(
+                |param_ref| match component_refs::resolve(&openapi, param_ref) {
+                    Some(param) => {
+                        let data = param.parameter_data_ref();
+                        format!("{} {:?}", data.name, data.description.as_deref())
+                    }
+                    None => "unresolved".to_string(),
+                },
+            )
+            .collect();
+        // Own `path id` and inline `query page` replace the shared ones in place; the
+        // header of the same name stays, and unresolvable refs never replace anything
+        assert_eq!(
+            summary,
+            vec![
+                "id Some(\"own\")",
+                "id None",
+                "page Some(\"own\")",
+                "unresolved",
+                "limit None",
+                "unresolved",
+            ]
+        );
+    }
+
+    #[test]
+    fn error_responses_put_exact_statuses_before_ranges_and_default() {
+        let ir = parse(
+            r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /items:
+    get:
+      operationId: listItems
+      responses:
+        "200": { description: ok, content: { application/json: { schema: { type: string } } } }
+        "304": { description: unchanged, content: { application/json: { schema: { type: string } } } }
+        "4XX": { description: rejected, content: { application/json: { schema: { type: string } } } }
+        "404": { description: missing, content: { application/json: { schema: { type: string } } } }
+        "409": { description: conflict }
+        default: { description: failure, content: { application/json: { schema: { type: string } } } }
+"##,
+        );
+
+        let statuses: Vec<_> = ir.operations[0]
+            .error_responses()
+            .iter()
+            .map(|r| r.status.as_str())
+            .collect();
+        assert_eq!(statuses, vec!["404", "4XX", "default"]);
+    }
+}
@@ -0,0 +1,366 @@
+//! Operation id normalization (`operationIdStrategy` input option), applied
+//! once while building the IR so every generator sees the same ids.
+//!
+//! - `preserve` (default): spec ids as written; missing ones built from the
+//!   method and path snake cased (`get_users_id`)
+//! - `camelCase`: spec ids camel-cased (`get-user` → `getUser`); missing ones
+//!   built camel-cased (`getUsersById`)
+//! - `methodPath`: every id built from the method and path, camel-cased,
+//!   ignoring the spec's ids and summaries
+//!
+//! Missing ids are built from the first source `operationIdFallback` lists
+//! that gives one: `summary` (`Activate a user` → `activateAUser`, camel-cased
+//! in every style, and re-cased by each generator's naming like any id:
+//! `activate_a_user` in Python, `ActivateAUser` in Go) or `methodPath`. The
+//! default is `[summary, methodPath]`; `[methodPath]` ignores summaries. The
+//! method and path are always the last resort.
+//!
+//! `stripPathParams: true` leaves path parameters out of ids built from paths
+//! (`getUsers` for `GET /users/{id}`), including ones sharing a segment
+//! (`/reports/{id}.{format}`). Ids that end up repeated get a numeric suffix
+//! in spec order, skipping ids the spec already uses.
+//! `x-codegen-operation-name` overrides are used as is.
+
+use super::OperationDefinition;
+use crate::generators::naming;
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Style {
+    #[default]
+    Preserve,
+    CamelCase,
+    MethodPath,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OperationIdStrategy {
+    style: Style,
+    strip_path_params: bool,
+    /// `operationIdFallback` tries the method and path before summaries
+    ignore_summaries: bool,
+}
+
+impl OperationIdStrategy {
+    pub fn from_options(options: &HashMap<String, Value>) -> Result<Self> {
+        let style = match options.get("operationIdStrategy").map(|v| v.as_str()) {
+            None | Some(Some("preserve")) => Style::Preserve,
+            Some(Some("camelCase")) => Style::CamelCase,
+            Some(Some("methodPath")) => Style::MethodPath,
+            _ => anyhow::bail!(
+                "input: option 'operationIdStrategy' must be \"preserve\", \"camelCase\", or \"methodPath\""
+            ),
+        };
+        let strip_path_params = options
+            .get("stripPathParams")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let ignore_summaries = match options.get("operationIdFallback") {
+            None => false,
+            Some(Value::Array(sources)) => {
+                let position = |source: &str| sources.iter().position(|s| s.as_str() == Some(source));
+                if sources.iter().any(|s| !matches!(s.as_str(), Some("summary" | "methodPath"))) {
+                    anyhow::bail!("input: option 'operationIdFallback' lists \"summary\" and \"methodPath\" in the order to try them");
+                }
+                match (position("summary"), position("methodPath")) {
+                    (Some(summary), Some(method_path)) => method_path < summary,
+                    (summary, _) => summary.is_none(),
+                }
+            }
+            Some(_) => anyhow::bail!("input: option 'operationIdFallback' must be a list of \"summary\" and \"methodPath\""),
+        };
+        Ok(Self {
+            style,
+            strip_path_params,
+            ignore_summaries,
+        })
+    }
+
+    /// Id for an operation declared with `spec_id` (its `operationId`, if any)
+    /// and `summary`
+    pub fn operation_id(
+        &self,
+        spec_id: Option<&str>,
+        summary: Option<&str>,
+        method: &str,
+        path: &str,
+    ) -> String {
+        match (self.style, spec_id) {
+            (Style::Preserve, Some(id)) => id.to_string(),
+            (Style::CamelCase, Some(id)) => naming::camel_case(id),
+            (Style::MethodPath, _) => naming::camel_case(&self.path_words(method, path).join("_")),
+            (style, None) => {
+                let summary = summary
+                    .filter(|_| !self.ignore_summaries)
+                    .map(summary_words)
+                    .filter(|words| !words.is_empty());
+                match (style, summary) {
+                    (_, Some(words)) => naming::camel_case(&words.join("_")),
+                    (Style::Preserve, None) => self.path_words(method, path).join("_"),
+                    (_, None) => naming::camel_case(&self.path_words(method, path).join("_")),
+                }
+            }
+        }
+    }
+
+    /// Snake-case words of `method path`; camel-cased styles mark path parameters with `by`
+    fn path_words(&self, method: &str, path: &str) -> Vec<String> {
+        let mut words = vec![method.to_lowercase()];
+        for (is_param, text) in path.split('/').flat_map(segment_parts) {
+            if is_param && self.strip_path_params {
+                continue;
+            }
+            if is_param && self.style != Style::Preserve {
+                words.push("by".to_string());
+            }
+            words.extend(
+                text.split(|c: char| !c.is_alphanumeric())
+                    .filter(|word| !word.is_empty())
+                    .map(naming::snake_case),
+            );
+        }
+        words
+    }
+}
+
+/// Literal text and `{parameter}` names of a path segment, in order, flagged
+/// `true` for parameters (`{id}.{format}` is two)
+fn segment_parts(segment: &str) -> Vec<(bool, &str)> {
+    let mut parts = Vec::new();
+    let mut rest = segment;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|close| open + close) else {
+            break;
+        };
+        parts.push((false, &rest[..open]));
+        parts.push((true, &rest[open + 1..close]));
+        rest = &rest[close + 1..];
+    }
+    parts.push((false, rest));
+    parts
+}
+
+/// Lowercase words of a summary; apostrophes join words (`user's` → `users`)
+fn summary_words(summary: &str) -> Vec<String> {
+    summary
+        .replace(['\'', '\u{2019}'], "")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Suffix repeated ids (`getUsers`, `getUsers2`) in spec order; a suffix
+/// never takes an id a later operation already has
+pub fn dedupe_operation_ids(operations: &mut [OperationDefinition]) {
+    let mut taken: HashSet<String> = operations.iter().map(|op| op.id.clone()).collect();
+    let mut seen = HashSet::new();
+    for operation in operations {
+        if seen.insert(operation.id.clone()) {
+            continue;
+        }
+        let mut n = 2;
+        while taken.contains(&format!("{}{}", operation.id, n)) {
+            n += 1;
+        }
+        operation.id = format!("{}{}", operation.id, n);
+        taken.insert(operation.id.clone());
+        seen.insert(operation.id.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{InputParser, OpenApiParser};
+
+    fn strategy(options: serde_json::Value) -> OperationIdStrategy {
+        let options: HashMap<String, Value> = serde_json::from_value(options).unwrap();
+        OperationIdStrategy::from_options(&options).unwrap()
+    }
+
+    #[test]
+    fn preserve_keeps_spec_ids_and_snake_cases_built_ones() {
+        let preserve = strategy(serde_json::json!({}));
+        assert_eq!(
+            preserve.operation_id(Some("get-user"), None, "GET", "/users/{id}"),
+            "get-user"
+        );
+        assert_eq!(
+            preserve.operation_id(
+                None,
+                Some("Activate a user's account"),
+                "POST",
+                "/users/{id}/activate"
+            ),
+            "activateAUsersAccount"
+        );
+        assert_eq!(
+            preserve.operation_id(None, None, "GET", "/users/{userId}"),
+            "get_users_user_id"
+        );
+    }
+
+    #[test]
+    fn camel_case_normalizes_spec_and_built_ids() {
+        let camel = strategy(serde_json::json!({ "operationIdStrategy": "camelCase" }));
+        assert_eq!(
+            camel.operation_id(Some("get-user"), None, "GET", "/users/{id}"),
+            "getUser"
+        );
+        assert_eq!(
+            camel.operation_id(Some("list_all_users"), None, "GET", "/users"),
+            "listAllUsers"
+        );
+        assert_eq!(
+            camel.operation_id(None, Some("Activate a user"), "POST", "/users/{id}"),
+            "activateAUser"
+        );
+        assert_eq!(
+            camel.operation_id(None, None, "GET", "/users/{userId}"),
+            "getUsersByUserId"
+        );
+    }
+
+    #[test]
+    fn method_path_ignores_spec_ids_and_summaries() {
+        let method_path = strategy(serde_json::json!({ "operationIdStrategy": "methodPath" }));
+        assert_eq!(
+            method_path.operation_id(
+                Some("fetchPosts"),
+                Some("Posts of a user"),
+                "GET",
+                "/users/{userId}/posts"
+            ),
+            "getUsersByUserIdPosts"
+        );
+        assert_eq!(
+            method_path.operation_id(None, None, "DELETE", "/api/v1/user-groups"),
+            "deleteApiV1UserGroups"
+        );
+    }
+
+    #[test]
+    fn templated_segments_are_parameters_wherever_they_sit() {
+        let method_path = strategy(serde_json::json!({ "operationIdStrategy": "methodPath" }));
+        assert_eq!(
+            method_path.operation_id(None, None, "GET", "/reports/{id}.{format}"),
+            "getReportsByIdByFormat"
+        );
+        assert_eq!(
+            method_path.operation_id(None, None, "GET", "/files/v{version}"),
+            "getFilesVByVersion"
+        );
+
+        let stripped = strategy(
+            serde_json::json!({ "operationIdStrategy": "methodPath", "stripPathParams": true }),
+        );
+        assert_eq!(
+            stripped.operation_id(None, None, "GET", "/reports/{id}.{format}"),
+            "getReports"
+        );
+        assert_eq!(
+            stripped.operation_id(None, None, "GET", "/users/{userId}/posts"),
+            "getUsersPosts"
+        );
+
+        let preserve = strategy(serde_json::json!({}));
+        assert_eq!(
+            preserve.operation_id(None, None, "GET", "/reports/{id}.{format}"),
+            "get_reports_id_format"
+        );
+    }
+
+    #[test]
+    fn fallback_order_decides_between_summary_and_path() {
+        let path_first =
+            strategy(serde_json::json!({ "operationIdFallback": ["methodPath", "summary"] }));
+        assert_eq!(
+            path_first.operation_id(None, Some("Activate a user"), "POST", "/users"),
+            "post_users"
+        );
+        let summary_only = strategy(serde_json::json!({ "operationIdFallback": ["summary"] }));
+        assert_eq!(
+            summary_only.operation_id(None, Some("Activate a user"), "POST", "/users"),
+            "activateAUser"
+        );
+        // Summaries without words fall back to the path
+        assert_eq!(
+            summary_only.operation_id(None, Some("--"), "POST", "/users"),
+            "post_users"
+        );
+    }
+
+    #[test]
+    fn summary_ids_are_camel_cased_and_named_like_spec_ids() {
+        use crate::generators::naming::{Language, NamingStrategy};
+        use std::sync::Arc;
+
+        /// Python methods in snake case, as a custom strategy may name them
+        struct SnakeMethods;
+        impl NamingStrategy for SnakeMethods {
+            fn function_name(&self, name: &str, language: Language) -> String {
+                match language {
+                    Language::Python => naming::snake_case(name),
+                    _ => naming::DefaultNaming.function_name(name, language),
+                }
+            }
+        }
+
+        let preserve = strategy(serde_json::json!({}));
+        let id = preserve.operation_id(None, Some("Create a user, now!"), "POST", "/users");
+        assert_eq!(id, "createAUserNow");
+        assert_eq!(
+            preserve.operation_id(None, Some("Activate user"), "POST", "/users/{id}"),
+            "activateUser"
+        );
+
+        assert_eq!(
+            naming::function_name(&id, Language::TypeScript),
+            "createAUserNow"
+        );
+        assert_eq!(naming::function_name(&id, Language::Go), "CreateAUserNow");
+        let strategy: Arc<dyn NamingStrategy> = Arc::new(SnakeMethods);
+        assert_eq!(
+            naming::scoped(&strategy, || naming::function_name(&id, Language::Python)),
+            "create_a_user_now"
+        );
+    }
+
+    #[test]
+    fn invalid_options_are_rejected() {
+        for options in [
+            serde_json::json!({ "operationIdStrategy": "kebab" }),
+            serde_json::json!({ "operationIdFallback": ["operationId"] }),
+            serde_json::json!({ "operationIdFallback": "summary" }),
+        ] {
+            let options: HashMap<String, Value> = serde_json::from_value(options).unwrap();
+            assert!(OperationIdStrategy::from_options(&options).is_err());
+        }
+    }
+
+    #[test]
+    fn ids_repeated_after_normalization_are_suffixed_around_spec_ids() {
+        let spec = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /a:
+    get: { operationId: get-user, responses: { "204": { description: ok } } }
+  /b:
+    get: { operationId: get_user, responses: { "204": { description: ok } } }
+  /c:
+    get: { operationId: getUser2, responses: { "204": { description: ok } } }
+  /d:
+    get: { operationId: GetUser, responses: { "204": { description: ok } } }
+"##;
+        let options: HashMap<String, Value> =
+            serde_json::from_value(serde_json::json!({ "operationIdStrategy": "camelCase" }))
+                .unwrap();
+        let ir = OpenApiParser.parse_str(spec, &options).unwrap();
+        let ids: Vec<_> = ir.operations.iter().map(|op| op.id.as_str()).collect();
+        assert_eq!(ids, vec!["getUser", "getUser3", "getUser2", "getUser4"]);
+    }
+}
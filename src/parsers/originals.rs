@@ -0,0 +1,77 @@
+//! How much of the source document the IR keeps as raw JSON (`lowMemory`
+//! parser option).
+//!
+//! By default `original.data` holds the whole document and every schema,
+//! field, and operation its own copy of its source, which for very large specs
+//! multiplies the memory the parsed document already takes. With `lowMemory`
+//! the IR keeps only what generators read back:
+//! - `original.data` is the document's top-level metadata (`openapi`, `info`,
+//!   `servers`, `tags`, `security`, and `components.securitySchemes`), without
+//!   `paths` or component schemas
+//! - node originals are `null`, except for scalar component schemas, whose
+//!   `enum` and `type` back Go's typed enums
+
+use anyhow::Result;
+use openapiv3::{OpenAPI, Schema, SchemaKind, Type};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Originals {
+    Keep,
+    Skip,
+}
+
+impl Originals {
+    pub fn from_options(options: &HashMap<String, Value>) -> Self {
+        match options.get("lowMemory").and_then(|v| v.as_bool()) {
+            Some(true) => Self::Skip,
+            _ => Self::Keep,
+        }
+    }
+
+    /// `original.data` for `openapi`
+    pub fn document(self, openapi: &OpenAPI) -> Result<Value> {
+        if self == Self::Keep {
+            return Ok(serde_json::to_value(openapi)?);
+        }
+
+        let mut document = serde_json::json!({
+            "openapi": openapi.openapi,
+            "info": openapi.info,
+            "servers": openapi.servers,
+            "tags": openapi.tags,
+        });
+        if let Some(security) = &openapi.security {
+            document["security"] = serde_json::to_value(security)?;
+        }
+        if let Some(components) = &openapi.components {
+            document["components"] =
+                serde_json::json!({ "securitySchemes": components.security_schemes });
+        }
+        Ok(document)
+    }
+
+    /// `original` of a field or operation
+    pub fn node<T: Serialize>(self, value: &T) -> Result<Value> {
+        match self {
+            Self::Keep => Ok(serde_json::to_value(value)?),
+            Self::Skip => Ok(Value::Null),
+        }
+    }
+
+    /// `original` of a schema; scalar ones are small and always kept
+    pub fn schema(self, schema: &Schema) -> Result<Value> {
+        let scalar = matches!(
+            schema.schema_kind,
+            SchemaKind::Type(
+                Type::String(_) | Type::Number(_) | Type::Integer(_) | Type::Boolean(_)
+            )
+        );
+        match self {
+            Self::Skip if !scalar => Ok(Value::Null),
+            _ => Ok(serde_json::to_value(schema)?),
+        }
+    }
+}
@@ -1,3 +1,6 @@
+use crate::generators::naming::{self, Language};
+use anyhow::Result;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
@@ -8,14 +11,22 @@ pub struct SchemaIR {
     /// Normalized metadata extracted from source
     pub metadata: Metadata,
 
-    /// Normalized schema definitions
+    /// Normalized schema definitions, in spec order
     pub schemas: Vec<SchemaDefinition>,
 
-    /// Normalized operation definitions
+    /// Normalized operation definitions, in spec order
     pub operations: Vec<OperationDefinition>,
 
+    /// Message channels (e.g., WebSocket) with typed payloads
+    #[serde(default)]
+    pub channels: Vec<ChannelDefinition>,
+
     /// Original source data (preserves all information for extensibility)
     pub original: OriginalData,
+
+    /// Source constructs encountered and how they were handled
+    #[serde(default)]
+    pub coverage: Vec<FeatureUsage>,
 }
 
 /// Original source data - preserves everything from the input
@@ -29,7 +40,39 @@ pub struct OriginalData {
 
     /// Format-specific extensions/metadata
     #[serde(default)]
-    pub extensions: HashMap<String, JsonValue>,
+    pub extensions: IndexMap<String, JsonValue>,
+
+    /// Parser options the IR was produced with, so another version of the
+    /// document can be parsed alike
+    #[serde(default)]
+    pub parser_options: HashMap<String, JsonValue>,
+
+    /// Parsed with `lowMemory`: `data` holds only the document's top-level
+    /// metadata and most node originals are `null`
+    #[serde(default)]
+    pub low_memory: bool,
+}
+
+/// How the parser handled a source construct
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeatureHandling {
+    /// Fully represented in the IR
+    Supported,
+    /// Represented with weaker typing (e.g., an untyped object)
+    Degraded,
+    /// Schema kind the IR cannot express, mapped to `any`
+    Unsupported,
+    /// Dropped entirely
+    Ignored,
+}
+
+/// A spec construct and the locations where it was encountered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureUsage {
+    pub construct: String,
+    pub handling: FeatureHandling,
+    pub locations: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,19 +80,91 @@ pub struct Metadata {
     pub title: String,
     pub version: String,
     pub description: Option<String>,
+
+    /// `description` in other languages by locale (`x-descriptions`)
+    #[serde(default)]
+    pub translations: IndexMap<String, String>,
     pub base_url: Option<String>,
 
+    /// Path component of `base_url` (e.g. `/api/v2`) without a trailing slash;
+    /// `None` when the server is at the root
+    #[serde(default)]
+    pub base_path: Option<String>,
+
+    /// Declared servers, in spec order; `base_url` is the first one
+    #[serde(default)]
+    pub servers: Vec<ServerDefinition>,
+
+    /// Tag declarations, in spec order
+    #[serde(default)]
+    pub tags: Vec<TagDefinition>,
+
+    /// Further documentation for the whole API (`externalDocs`)
+    #[serde(default)]
+    pub external_docs: Option<ExternalDocs>,
+
     /// Custom metadata from source (preserves non-standard fields)
     #[serde(default)]
-    pub custom: HashMap<String, JsonValue>,
+    pub custom: IndexMap<String, JsonValue>,
+
+    /// API-wide request budget from the top-level `x-rate-limit` extension
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// A server the API is deployed to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerDefinition {
+    pub url: String,
+    pub description: Option<String>,
+
+    /// Environment name from the `x-environment` extension (e.g., "staging")
+    pub environment: Option<String>,
+}
+
+/// Human-facing label for a group of operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagDefinition {
+    pub name: String,
+    pub description: Option<String>,
+
+    /// `description` in other languages by locale (`x-descriptions`)
+    #[serde(default)]
+    pub translations: IndexMap<String, String>,
+    pub external_docs: Option<ExternalDocs>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalDocs {
+    pub url: String,
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SchemaDefinition {
     pub name: String,
+    /// Own fields in the order the source declares them; those of `parents`
+    /// are not repeated
     pub fields: Vec<FieldDefinition>,
     pub description: Option<String>,
 
+    /// `description` in other languages by locale (`x-descriptions`)
+    #[serde(default)]
+    pub translations: IndexMap<String, String>,
+
+    /// Schemas extended through `allOf` `$ref` members, in declaration order
+    #[serde(default)]
+    pub parents: Vec<String>,
+
+    /// Schema this is the JSON Merge Patch copy of, for bodies sent as
+    /// `application/merge-patch+json` (`patchBodies` option)
+    #[serde(default)]
+    pub merge_patch_of: Option<String>,
+
+    /// Audiences the schema is for (`x-audience`); empty for everyone
+    #[serde(default)]
+    pub audiences: Vec<String>,
+
     /// Original schema data
     pub original: JsonValue,
 }
@@ -59,65 +174,73 @@ pub struct FieldDefinition {
     pub name: String,
     pub type_info: TypeInfo,
     pub required: bool,
+
+    /// `nullable: true` (or `"null"` in a 3.1 type array)
+    #[serde(default)]
+    pub nullable: bool,
+
+    /// Validation keywords on the field's own schema
+    #[serde(default)]
+    pub constraints: Constraints,
+
     pub description: Option<String>,
 
+    /// `description` in other languages by locale (`x-descriptions`)
+    #[serde(default)]
+    pub translations: IndexMap<String, String>,
+
+    /// Audiences the field is for (`x-audience`); empty for everyone
+    #[serde(default)]
+    pub audiences: Vec<String>,
+
     /// Original field data
     pub original: JsonValue,
 }
 
+/// Validation keywords; exclusive bounds follow OpenAPI 3.0 (flags on the bound)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Constraints {
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub pattern: Option<String>,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    #[serde(default)]
+    pub exclusive_minimum: bool,
+    #[serde(default)]
+    pub exclusive_maximum: bool,
+    pub multiple_of: Option<f64>,
+    pub min_items: Option<usize>,
+    pub max_items: Option<usize>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypeInfo {
     pub openapi_type: String,
+    /// The spec's `format`; for "external" types (`typeOverrides` option), the
+    /// target language type they are emitted as
     pub format: Option<String>,
     pub is_array: bool,
     pub array_item_type: Option<Box<TypeInfo>>,
     pub reference: Option<String>,
     pub enum_values: Option<Vec<String>>,
-}
-
-impl TypeInfo {
-    pub fn to_typescript(&self) -> String {
-        if self.is_array {
-            if let Some(item_type) = &self.array_item_type {
-                return format!("z.array({})", item_type.to_typescript_zod());
-            }
-            return "z.array(z.any())".to_string();
-        }
 
-        self.to_typescript_zod()
-    }
-
-    pub fn to_typescript_zod(&self) -> String {
-        if let Some(ref_name) = &self.reference {
-            return ref_name.clone();
-        }
+    /// Alternatives of a `oneOf`/`anyOf` or multi-type schema (`openapi_type` "union")
+    #[serde(default)]
+    pub union_members: Vec<TypeInfo>,
 
-        if let Some(enum_vals) = &self.enum_values {
-            let values: Vec<String> = enum_vals.iter().map(|v| format!("\"{}\"", v)).collect();
-            return format!("z.enum([{}])", values.join(", "));
-        }
+    /// Positional item types of a `prefixItems` tuple; `array_item_type` stays
+    /// the fallback for generators without tuple support
+    #[serde(default)]
+    pub tuple_items: Vec<TypeInfo>,
 
-        match self.openapi_type.as_str() {
-            "string" => {
-                if let Some(fmt) = &self.format {
-                    match fmt.as_str() {
-                        "date" | "date-time" => "z.date().or(z.string())".to_string(),
-                        "email" => "z.string().email()".to_string(),
-                        "uuid" => "z.string().uuid()".to_string(),
-                        "uri" => "z.string().url()".to_string(),
-                        _ => "z.string()".to_string(),
-                    }
-                } else {
-                    "z.string()".to_string()
-                }
-            }
-            "integer" | "number" => "z.number()".to_string(),
-            "boolean" => "z.boolean()".to_string(),
-            "object" => "z.any()".to_string(),
-            _ => "z.any()".to_string(),
-        }
-    }
+    /// Value type of a string-keyed map (`openapi_type` "object"); `None` for
+    /// objects with unknown values
+    #[serde(default)]
+    pub map_values: Option<Box<TypeInfo>>,
+}
 
+impl TypeInfo {
     pub fn to_python(&self) -> String {
         if self.is_array {
             if let Some(item_type) = &self.array_item_type {
@@ -131,7 +254,7 @@ impl TypeInfo {
 
     pub fn to_python_type(&self) -> String {
         if let Some(ref_name) = &self.reference {
-            return ref_name.clone();
+            return naming::type_name(ref_name, Language::Python);
         }
 
         if self.enum_values.is_some() {
@@ -152,7 +275,11 @@ impl TypeInfo {
             "integer" => "int".to_string(),
             "number" => "float".to_string(),
             "boolean" => "bool".to_string(),
-            "object" => "Dict[str, Any]".to_string(),
+            "external" => self.format.clone().unwrap_or_else(|| "Any".to_string()),
+            "object" => match &self.map_values {
+                Some(values) => format!("Dict[str, {}]", values.to_python()),
+                None => "Dict[str, Any]".to_string(),
+            },
             _ => "Any".to_string(),
         }
     }
@@ -170,7 +297,7 @@ impl TypeInfo {
 
     pub fn to_golang_type(&self) -> String {
         if let Some(ref_name) = &self.reference {
-            return ref_name.clone();
+            return naming::type_name(ref_name, Language::Go);
         }
 
         if self.enum_values.is_some() {
@@ -202,7 +329,14 @@ impl TypeInfo {
                 }
             }
             "boolean" => "bool".to_string(),
-            "object" => "map[string]interface{}".to_string(),
+            "external" => self
+                .format
+                .clone()
+                .unwrap_or_else(|| "interface{}".to_string()),
+            "object" => match &self.map_values {
+                Some(values) => format!("map[string]{}", values.to_golang()),
+                None => "map[string]interface{}".to_string(),
+            },
             _ => "interface{}".to_string(),
         }
     }
@@ -216,15 +350,208 @@ pub struct OperationDefinition {
     pub parameters: Vec<Parameter>,
     pub request_body: Option<SchemaReference>,
     pub response: Option<SchemaReference>,
+
+    /// Media type of the request body; set even when the body has no named schema
+    #[serde(default)]
+    pub request_content_type: Option<String>,
+    /// Media type of the success response body
+    #[serde(default)]
+    pub response_content_type: Option<String>,
+    /// Headers the success (2xx) responses declare, such as `ETag`
+    #[serde(default)]
+    pub response_headers: Vec<String>,
+    /// Every response the operation declares, by status in spec order with
+    /// `default` last; `response` is the body of the first 2xx one with content
+    #[serde(default)]
+    pub responses: Vec<ResponseDefinition>,
+
+    #[serde(default)]
+    pub summary: Option<String>,
     pub description: Option<String>,
 
+    /// `description` in other languages by locale (`x-descriptions`)
+    #[serde(default)]
+    pub translations: IndexMap<String, String>,
+
     #[serde(default)]
     pub tags: Vec<String>,
 
+    /// Operation-specific request budget from its `x-rate-limit` extension
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+
+    /// `x-idempotency` extension: `true` asks for an idempotency key on every
+    /// call, `false` opts out of the `idempotencyKey: unsafe` option
+    #[serde(default)]
+    pub idempotency: Option<bool>,
+
+    /// `x-timeout-ms` extension: how long a call may take by default, in milliseconds
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// `x-feature-flag` extension: flag the client's provider must turn on
+    /// before the operation is called
+    #[serde(default)]
+    pub feature_flag: Option<String>,
+
+    /// Further documentation the operation points to (`externalDocs`)
+    #[serde(default)]
+    pub external_docs: Option<ExternalDocs>,
+
+    /// Operations its responses link to (`links`), with the values they pass on
+    #[serde(default)]
+    pub links: Vec<OperationLink>,
+
+    /// Audiences the operation is for (`x-audience`); empty for everyone
+    #[serde(default)]
+    pub audiences: Vec<String>,
+
+    /// Gateway service the operation belongs to, in a merged IR
+    #[serde(default)]
+    pub namespace: Option<OperationNamespace>,
+
     /// Original operation data
     pub original: JsonValue,
 }
 
+impl OperationDefinition {
+    /// Whether the success response is a file, which clients hand back as
+    /// bytes or a stream instead of decoding it
+    pub fn downloads(&self) -> bool {
+        self.response_content_type
+            .as_deref()
+            .is_some_and(|content_type| {
+                BodyKind::from_content_type(content_type) == BodyKind::Binary
+            })
+    }
+
+    /// The response a call returns: the first success one with a body
+    pub fn returned_response(&self) -> Option<&ResponseDefinition> {
+        self.responses
+            .iter()
+            .find(|r| r.is_success() && r.content_type.is_some())
+    }
+
+    /// The error responses with a body: exact statuses, then ranges, then `default`
+    pub fn error_responses(&self) -> Vec<&ResponseDefinition> {
+        let mut errors: Vec<_> = self
+            .responses
+            .iter()
+            .filter(|r| r.is_error() && r.content_type.is_some())
+            .collect();
+        errors.sort_by_key(|r| match r.status.as_str() {
+            "default" => 2,
+            status if status.ends_with("XX") => 1,
+            _ => 0,
+        });
+        errors
+    }
+}
+
+/// A response an operation declares for a status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseDefinition {
+    /// `200`, `2XX`, or `default`
+    pub status: String,
+    /// Media type of the body generators read (JSON if offered); `None` without a body
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Named schema of the body: a `$ref`, an array of `$ref`s, or the schema
+    /// an inline success body was hoisted into
+    #[serde(default)]
+    pub schema: Option<SchemaReference>,
+    /// Type of a body without a named schema (`string`, an array of integers,
+    /// an inline error object)
+    #[serde(default)]
+    pub inline_type: Option<TypeInfo>,
+    pub description: Option<String>,
+}
+
+impl ResponseDefinition {
+    /// Whether the status is a success one (`2xx` or `2XX`)
+    pub fn is_success(&self) -> bool {
+        self.status.starts_with('2')
+    }
+
+    /// Whether the status is an error one (`4xx`, `5xx`, their ranges, or `default`)
+    pub fn is_error(&self) -> bool {
+        self.status.starts_with(['4', '5']) || self.status == "default"
+    }
+
+    /// Whether the body is a list
+    pub fn is_array(&self) -> bool {
+        match &self.schema {
+            Some(schema) => schema.schema_type == "array",
+            None => self
+                .inline_type
+                .as_ref()
+                .is_some_and(|type_info| type_info.is_array),
+        }
+    }
+}
+
+/// A response link: a follow-up operation and where its parameters come from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationLink {
+    /// Key of the link in the response's `links`
+    pub name: String,
+    /// Status code of the response declaring it
+    pub status: String,
+    /// `operationId` or `operationRef` as the spec wrote it
+    pub target: String,
+    /// IR id of the linked operation; `None` when it isn't part of the IR
+    pub operation: Option<String>,
+    /// Parameters of the linked operation, each with the runtime expression
+    /// (`$response.body#/id`) or constant filling it
+    #[serde(default)]
+    pub parameters: IndexMap<String, String>,
+    pub description: Option<String>,
+}
+
+/// Service grouping of an operation; `id` may carry the service prefix when
+/// another service declares the same id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationNamespace {
+    /// Service name (`users` in `client.users.getUser()`)
+    pub name: String,
+    /// Operation id within the service
+    pub member: String,
+}
+
+/// Client-side request budget, enforced as a token bucket
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub requests_per_second: f64,
+    /// Requests allowed back to back
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Parse `{ requestsPerSecond, burst }` (an `x-rate-limit` extension or the
+    /// `rateLimit` option); burst defaults to one second's worth of requests
+    pub fn from_value(value: &JsonValue) -> Result<Self> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase", deny_unknown_fields)]
+        struct Raw {
+            requests_per_second: f64,
+            #[serde(default)]
+            burst: Option<u32>,
+        }
+
+        let raw: Raw = serde_json::from_value(value.clone())?;
+        if !(raw.requests_per_second.is_finite() && raw.requests_per_second > 0.0) {
+            anyhow::bail!("requestsPerSecond must be a positive number");
+        }
+        Ok(Self {
+            requests_per_second: raw.requests_per_second,
+            burst: raw
+                .burst
+                .unwrap_or(raw.requests_per_second.ceil() as u32)
+                .max(1),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum HttpMethod {
@@ -242,11 +569,41 @@ pub struct Parameter {
     pub name: String,
     pub location: ParameterLocation,
     pub required: bool,
+    /// `string`, `integer`, `number`, `boolean`, `array`, or `object`; refs to
+    /// component schemas are followed, and `content` parameters are strings
     pub schema_type: String,
+
+    /// `format` of the schema (`date`, `date-time`, `int64`, ...)
+    #[serde(default)]
+    pub format: Option<String>,
+
+    /// Type and format of the items of an `array` parameter
+    #[serde(default)]
+    pub item_type: Option<String>,
+    #[serde(default)]
+    pub item_format: Option<String>,
+
+    /// Serialization `style` (`form`, `simple`, `spaceDelimited`, ...), the
+    /// location's default when the spec has none
+    #[serde(default)]
+    pub style: Option<String>,
+
+    /// `explode` as given in the spec; `None` for the style's default
+    #[serde(default)]
+    pub explode: Option<bool>,
+
     pub description: Option<String>,
+
+    /// `description` in other languages by locale (`x-descriptions`)
+    #[serde(default)]
+    pub translations: IndexMap<String, String>,
+
+    /// Example value from the spec, if any
+    #[serde(default)]
+    pub example: Option<JsonValue>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ParameterLocation {
     Query,
@@ -255,8 +612,62 @@ pub enum ParameterLocation {
     Cookie,
 }
 
+/// Bidirectional message channel (e.g., a WebSocket endpoint)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelDefinition {
+    pub name: String,
+    pub path: String,
+    pub description: Option<String>,
+
+    /// Schema of messages sent by the client
+    pub send: Option<String>,
+
+    /// Schema of messages received from the server
+    pub receive: Option<String>,
+}
+
+/// How a body is encoded on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyKind {
+    Json,
+    Xml,
+    /// `application/octet-stream`, `application/pdf`, `application/zip`, and
+    /// `image/*`, `audio/*`, and `video/*` media types: files, downloaded as bytes
+    Binary,
+    /// `text/plain` and any other media type, sent as a raw string
+    Text,
+}
+
+impl BodyKind {
+    pub fn from_content_type(content_type: &str) -> Self {
+        let media_type = content_type.to_ascii_lowercase();
+        if media_type.contains("json") {
+            BodyKind::Json
+        } else if media_type.contains("xml") {
+            BodyKind::Xml
+        } else if [
+            "application/octet-stream",
+            "application/pdf",
+            "application/zip",
+            "image/",
+            "audio/",
+            "video/",
+        ]
+        .iter()
+        .any(|binary| media_type.starts_with(binary))
+        {
+            BodyKind::Binary
+        } else {
+            BodyKind::Text
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SchemaReference {
     pub name: String,
+
+    /// "object" for a direct reference, "array" for a list of `name`
     pub schema_type: String,
 }
@@ -0,0 +1,318 @@
+//! YAML spec reading with merge keys and duplicate key checks.
+//!
+//! Anchors and aliases are expanded by the YAML parser itself; `<<` merge keys
+//! (`<<: *base` or `<<: [*base, *other]`) are applied afterwards, with keys the
+//! mapping sets itself winning over merged ones and earlier merged mappings
+//! winning over later ones. A mapping that repeats a key is an error naming
+//! both lines, where deserializing would silently keep the last value.
+
+use crate::diagnostics::SourceError;
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+const MERGE_KEY: &str = "<<";
+
+/// The YAML document `content`, named `name` in errors
+pub(super) fn read(name: &str, content: &str) -> Result<Value> {
+    let mut scan = KeyScan::default();
+    // Syntax errors are left to serde_yaml, which reports them with context
+    let _ = Parser::new_from_str(content).load(&mut scan, true);
+    if let Some(duplicate) = scan.duplicate {
+        let message = format!(
+            "duplicate key '{}', first defined on line {}",
+            duplicate.key, duplicate.first_line
+        );
+        return Err(SourceError::at_position(
+            name,
+            content,
+            duplicate.line,
+            duplicate.column,
+            message,
+        )
+        .into());
+    }
+
+    if !scan.merges {
+        return Ok(serde_yaml::from_str(content)
+            .map_err(|error| SourceError::from_yaml(name, content, &error))?);
+    }
+    let mut document: serde_yaml::Value = serde_yaml::from_str(content)
+        .map_err(|error| SourceError::from_yaml(name, content, &error))?;
+    apply_merges(&mut document, &mut Vec::new()).map_err(|(path, message)| {
+        SourceError::at_path(name, content, &path, message.to_string())
+    })?;
+    Ok(serde_json::to_value(document)?)
+}
+
+/// Replace the `<<` entries of every mapping under `value` with the entries
+/// they merge in, at the position of the `<<` key.
+///
+/// Not serde_yaml's `Value::apply_merge`: it merges a mapping before the ones
+/// merged into it, so a chained merge (`leaf: { <<: *middle }` where `middle`
+/// has its own `<<: *root`) leaves a literal `<<` key behind. It also appends
+/// merged keys at the end and moves the mapping's last key into the place of
+/// `<<`, reordering properties that generated code declares in spec order, and
+/// its errors carry no path to the offending key.
+fn apply_merges(
+    value: &mut serde_yaml::Value,
+    path: &mut Vec<String>,
+) -> Result<(), (Vec<String>, &'static str)> {
+    match value {
+        serde_yaml::Value::Mapping(mapping) if mapping.contains_key(MERGE_KEY) => {
+            let entries = std::mem::take(mapping);
+            for (key, value) in &entries {
+                if key.as_str() != Some(MERGE_KEY) {
+                    continue;
+                }
+                path.push(MERGE_KEY.to_string());
+                let sources = match value.clone() {
+                    serde_yaml::Value::Mapping(source) => vec![source],
+                    serde_yaml::Value::Sequence(items) => items
+                        .into_iter()
+                        .map(|item| match item {
+                            serde_yaml::Value::Mapping(source) => Ok(source),
+                            _ => Err((
+                                path.clone(),
+                                "merge key '<<' lists something other than a mapping",
+                            )),
+                        })
+                        .collect::<Result<_, _>>()?,
+                    _ => {
+                        return Err((
+                            path.clone(),
+                            "merge key '<<' must be a mapping or a list of mappings",
+                        ))
+                    }
+                };
+                for source in sources {
+                    let mut source = serde_yaml::Value::Mapping(source);
+                    apply_merges(&mut source, path)?;
+                    if let serde_yaml::Value::Mapping(source) = source {
+                        for (key, value) in source {
+                            if !entries.contains_key(&key) && !mapping.contains_key(&key) {
+                                mapping.insert(key, value);
+                            }
+                        }
+                    }
+                }
+                path.pop();
+            }
+            // Own keys keep their place; the merged ones sit where `<<` was
+            let mut merged = std::mem::take(mapping);
+            for (key, value) in entries {
+                if key.as_str() == Some(MERGE_KEY) {
+                    mapping.extend(std::mem::take(&mut merged));
+                } else {
+                    mapping.insert(key, value);
+                }
+            }
+            apply_merges(value, path)
+        }
+        serde_yaml::Value::Mapping(mapping) => {
+            for (key, value) in mapping.iter_mut() {
+                path.push(key_segment(key));
+                apply_merges(value, path)?;
+                path.pop();
+            }
+            Ok(())
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                path.push(index.to_string());
+                apply_merges(item, path)?;
+                path.pop();
+            }
+            Ok(())
+        }
+        serde_yaml::Value::Tagged(tagged) => apply_merges(&mut tagged.value, path),
+        _ => Ok(()),
+    }
+}
+
+/// A mapping key as a path segment
+fn key_segment(key: &serde_yaml::Value) -> String {
+    match key {
+        serde_yaml::Value::String(key) => key.clone(),
+        other => serde_yaml::to_string(other)
+            .unwrap_or_default()
+            .trim_end()
+            .to_string(),
+    }
+}
+
+/// The first repeated key of a document
+struct Duplicate {
+    key: String,
+    first_line: usize,
+    line: usize,
+    column: usize,
+}
+
+enum Frame {
+    /// Scalar keys seen so far with their lines, and the nodes read
+    Mapping {
+        keys: HashMap<String, usize>,
+        nodes: usize,
+    },
+    Sequence,
+}
+
+/// Walks the parser's events for repeated keys and plain `<<` keys
+#[derive(Default)]
+struct KeyScan {
+    frames: Vec<Frame>,
+    duplicate: Option<Duplicate>,
+    merges: bool,
+}
+
+impl KeyScan {
+    /// Count a finished node towards the collection holding it
+    fn finish_node(&mut self) {
+        if let Some(Frame::Mapping { nodes, .. }) = self.frames.last_mut() {
+            *nodes += 1;
+        }
+    }
+}
+
+impl MarkedEventReceiver for KeyScan {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        if self.duplicate.is_some() {
+            return;
+        }
+        match event {
+            Event::Scalar(value, style, ..) => {
+                if let Some(Frame::Mapping { keys, nodes }) = self.frames.last_mut() {
+                    // Even positions of a mapping are keys
+                    if *nodes % 2 == 0 {
+                        if value == MERGE_KEY && style == TScalarStyle::Plain {
+                            self.merges = true;
+                        }
+                        if let Some(&first_line) = keys.get(&value) {
+                            self.duplicate = Some(Duplicate {
+                                key: value,
+                                first_line,
+                                line: mark.line(),
+                                column: mark.col() + 1,
+                            });
+                            return;
+                        }
+                        keys.insert(value, mark.line());
+                    }
+                }
+                self.finish_node();
+            }
+            Event::Alias(_) => self.finish_node(),
+            Event::MappingStart(..) => self.frames.push(Frame::Mapping {
+                keys: HashMap::new(),
+                nodes: 0,
+            }),
+            Event::SequenceStart(..) => self.frames.push(Frame::Sequence),
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.frames.pop();
+                self.finish_node();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn own_keys_win_over_merged_ones_and_earlier_merges_over_later() {
+        let document = read(
+            "spec.yaml",
+            r#"
+base: &base { type: string, format: uuid, description: base }
+other: &other { format: email, maxLength: 10, description: other }
+single:
+  description: own
+  <<: *base
+listed:
+  <<: [*base, *other]
+  minLength: 1
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            document["single"],
+            json!({ "description": "own", "type": "string", "format": "uuid" })
+        );
+        assert_eq!(
+            document["listed"],
+            json!({ "type": "string", "format": "uuid", "description": "base", "maxLength": 10, "minLength": 1 })
+        );
+        // Merged keys sit where `<<` was, own keys keep their place
+        let keys: Vec<_> = document["single"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        assert_eq!(keys, vec!["description", "type", "format"]);
+    }
+
+    #[test]
+    fn merges_inside_merged_mappings_are_applied_first() {
+        let document = read(
+            "spec.yaml",
+            r#"
+root: &root { a: 1, b: 1 }
+middle: &middle { <<: *root, b: 2 }
+leaf: { <<: *middle, c: 3 }
+"#,
+        )
+        .unwrap();
+        assert_eq!(document["leaf"], json!({ "a": 1, "b": 2, "c": 3 }));
+    }
+
+    #[test]
+    fn quoted_merge_keys_are_plain_keys() {
+        let document = read(
+            "spec.yaml",
+            "base: &base { a: 1 }\nquoted: { '<<': *base }\n",
+        )
+        .unwrap();
+        assert_eq!(document["quoted"], json!({ "<<": { "a": 1 } }));
+    }
+
+    #[test]
+    fn merging_a_scalar_is_an_error() {
+        let error = read("spec.yaml", "schema:\n  <<: 5\n")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("merge key '<<' must be a mapping or a list of mappings"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn duplicate_keys_report_both_lines() {
+        let content = "openapi: 3.0.3\npaths:\n  /pets:\n    get: {}\n    post: {}\n    get: {}\n";
+        let error = read("spec.yaml", content).unwrap_err().to_string();
+        assert!(
+            error.contains("duplicate key 'get', first defined on line 4"),
+            "{}",
+            error
+        );
+        assert!(error.contains("spec.yaml:6:5"), "{}", error);
+    }
+
+    #[test]
+    fn the_same_key_in_sibling_mappings_is_no_duplicate() {
+        let content = "a:\n  name: x\nb:\n  name: y\nlist:\n  - name: x\n  - name: y\n";
+        assert_eq!(
+            read("spec.yaml", content).unwrap()["list"],
+            json!([{ "name": "x" }, { "name": "y" }])
+        );
+    }
+}
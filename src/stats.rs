@@ -0,0 +1,350 @@
+//! Size and shape of a parsed spec, for auditing it before enabling new
+//! targets (`generator stats`).
+
+use crate::config::GenerationConfig;
+use crate::generators::{self, GeneratorRegistry};
+use crate::parsers::{SchemaIR, TypeInfo};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub struct SpecStats {
+    pub operations: usize,
+    /// `GET`, `POST`, ... in first-use order
+    pub operations_by_method: Vec<(String, usize)>,
+    /// Declared tags first; operations with several tags count once per tag
+    pub operations_by_tag: Vec<(String, usize)>,
+    pub untagged_operations: usize,
+    pub schemas: usize,
+    /// Longest chain of schemas nested through field references, outermost first;
+    /// a schema with only scalar fields is a chain of one
+    pub deepest_chain: Vec<String>,
+    /// Schemas no operation, channel, or other schema refers to
+    pub unreferenced_schemas: Vec<String>,
+}
+
+impl SpecStats {
+    pub fn from_ir(schema_ir: &SchemaIR) -> Self {
+        let mut operations_by_method: Vec<(String, usize)> = Vec::new();
+        let mut operations_by_tag: Vec<(String, usize)> = schema_ir
+            .metadata
+            .tags
+            .iter()
+            .map(|tag| (tag.name.clone(), 0))
+            .collect();
+        let mut untagged_operations = 0;
+
+        for operation in &schema_ir.operations {
+            increment(
+                &mut operations_by_method,
+                &format!("{:?}", operation.method).to_uppercase(),
+            );
+            if operation.tags.is_empty() {
+                untagged_operations += 1;
+            }
+            for tag in &operation.tags {
+                increment(&mut operations_by_tag, tag);
+            }
+        }
+        operations_by_tag.retain(|(_, count)| *count > 0);
+
+        Self {
+            operations: schema_ir.operations.len(),
+            operations_by_method,
+            operations_by_tag,
+            untagged_operations,
+            schemas: schema_ir.schemas.len(),
+            deepest_chain: deepest_chain(schema_ir),
+            unreferenced_schemas: unreferenced_schemas(schema_ir),
+        }
+    }
+
+    /// Nesting depth of [`Self::deepest_chain`]
+    pub fn max_depth(&self) -> usize {
+        self.deepest_chain.len()
+    }
+}
+
+/// What `generator stats` prints: the stats of `schema_ir`, read from
+/// `source`, and the size of each of `generations` rendered in memory
+pub fn report(
+    schema_ir: &SchemaIR,
+    source: &Path,
+    generations: &[GenerationConfig],
+    registry: &GeneratorRegistry,
+) -> String {
+    let stats = SpecStats::from_ir(schema_ir);
+    let mut report = format!(
+        "📊 {} {} ({:?})\n",
+        schema_ir.metadata.title, schema_ir.metadata.version, source
+    );
+
+    let _ = writeln!(report, "\nOperations: {}", stats.operations);
+    for (method, count) in &stats.operations_by_method {
+        let _ = writeln!(report, "   {:<8} {}", method, count);
+    }
+    if !stats.operations_by_tag.is_empty() {
+        report.push_str("By tag:\n");
+        for (tag, count) in &stats.operations_by_tag {
+            let _ = writeln!(report, "   {:<24} {}", tag, count);
+        }
+        if stats.untagged_operations > 0 {
+            let _ = writeln!(
+                report,
+                "   {:<24} {}",
+                "(untagged)", stats.untagged_operations
+            );
+        }
+    }
+
+    let _ = writeln!(report, "\nSchemas: {}", stats.schemas);
+    if stats.max_depth() > 0 {
+        let _ = writeln!(
+            report,
+            "Max nesting depth: {} ({})",
+            stats.max_depth(),
+            stats.deepest_chain.join(" → ")
+        );
+    }
+    let _ = match stats.unreferenced_schemas.as_slice() {
+        [] => writeln!(report, "Unreferenced schemas: none"),
+        names => writeln!(
+            report,
+            "Unreferenced schemas ({}): {}",
+            names.len(),
+            names.join(", ")
+        ),
+    };
+
+    // Sizes come from rendering each generation in memory; nothing is written
+    report.push_str("\nOutput per generation:\n");
+    if generations.is_empty() {
+        report.push_str("   none enabled in the config\n");
+    }
+    for gen_config in generations {
+        let rendered = registry
+            .require(&gen_config.generator)
+            .and_then(|generator| {
+                generator.validate_config(gen_config)?;
+                let prepared_ir = generators::prepare(schema_ir, generator, gen_config)?;
+                Ok(generator
+                    .generate_from_ir(&prepared_ir, gen_config)?
+                    .into_files())
+            });
+        let _ = match rendered {
+            Ok(files) => {
+                let bytes: usize = files.iter().map(|file| file.content.len()).sum();
+                let lines: usize = files.iter().map(|file| file.content.lines().count()).sum();
+                writeln!(
+                    report,
+                    "   {:<20} {:<24} {:>10} {:>7} lines  {} file(s)",
+                    gen_config.generator,
+                    gen_config.output_file,
+                    human_size(bytes),
+                    lines,
+                    files.len()
+                )
+            }
+            Err(error) => writeln!(
+                report,
+                "   {:<20} {:<24} ⚠️  {:#}",
+                gen_config.generator, gen_config.output_file, error
+            ),
+        };
+    }
+    report
+}
+
+/// `512 B`, `12.3 KB`, `1.4 MB`
+fn human_size(bytes: usize) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+fn increment(counts: &mut Vec<(String, usize)>, key: &str) {
+    match counts.iter_mut().find(|(k, _)| k == key) {
+        Some((_, count)) => *count += 1,
+        None => counts.push((key.to_string(), 1)),
+    }
+}
+
+/// Schemas named anywhere in `type_info`
+fn collect_references<'a>(type_info: &'a TypeInfo, references: &mut Vec<&'a str>) {
+    if let Some(reference) = &type_info.reference {
+        references.push(reference);
+    }
+    for item in type_info
+        .array_item_type
+        .iter()
+        .chain(&type_info.map_values)
+    {
+        collect_references(item, references);
+    }
+    for member in type_info.union_members.iter().chain(&type_info.tuple_items) {
+        collect_references(member, references);
+    }
+}
+
+/// Schemas each schema refers to through its fields and parents
+fn schema_graph(schema_ir: &SchemaIR) -> HashMap<&str, Vec<&str>> {
+    schema_ir
+        .schemas
+        .iter()
+        .map(|schema| {
+            let mut references: Vec<&str> = schema.parents.iter().map(String::as_str).collect();
+            for field in &schema.fields {
+                collect_references(&field.type_info, &mut references);
+            }
+            (schema.name.as_str(), references)
+        })
+        .collect()
+}
+
+fn deepest_chain(schema_ir: &SchemaIR) -> Vec<String> {
+    let graph = schema_graph(schema_ir);
+    let mut memo: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut deepest: Vec<&str> = Vec::new();
+    for schema in &schema_ir.schemas {
+        let chain = chain_from(&schema.name, &graph, &mut HashSet::new(), &mut memo);
+        if chain.len() > deepest.len() {
+            deepest = chain;
+        }
+    }
+    deepest.into_iter().map(str::to_string).collect()
+}
+
+/// Longest acyclic chain starting at `name`; references back into the
+/// current chain (recursive schemas) end it
+fn chain_from<'a>(
+    name: &'a str,
+    graph: &HashMap<&'a str, Vec<&'a str>>,
+    visiting: &mut HashSet<&'a str>,
+    memo: &mut HashMap<&'a str, Vec<&'a str>>,
+) -> Vec<&'a str> {
+    if let Some(chain) = memo.get(name) {
+        return chain.clone();
+    }
+    let Some(references) = graph.get(name) else {
+        return Vec::new();
+    };
+
+    visiting.insert(name);
+    let mut longest: Vec<&str> = Vec::new();
+    for &reference in references {
+        if visiting.contains(reference) {
+            continue;
+        }
+        let chain = chain_from(reference, graph, visiting, memo);
+        if chain.len() > longest.len() {
+            longest = chain;
+        }
+    }
+    visiting.remove(name);
+
+    let mut chain = vec![name];
+    chain.extend(longest);
+    memo.insert(name, chain.clone());
+    chain
+}
+
+fn unreferenced_schemas(schema_ir: &SchemaIR) -> Vec<String> {
+    let mut referenced: HashSet<&str> = HashSet::new();
+    for operation in &schema_ir.operations {
+        for body in [&operation.request_body, &operation.response]
+            .into_iter()
+            .flatten()
+        {
+            referenced.insert(&body.name);
+        }
+        for response in &operation.responses {
+            referenced.extend(response.schema.iter().map(|body| body.name.as_str()));
+            let mut references = Vec::new();
+            if let Some(type_info) = &response.inline_type {
+                collect_references(type_info, &mut references);
+            }
+            referenced.extend(references);
+        }
+    }
+    for channel in &schema_ir.channels {
+        for message in [&channel.send, &channel.receive].into_iter().flatten() {
+            referenced.insert(message);
+        }
+    }
+    for (name, references) in schema_graph(schema_ir) {
+        referenced.extend(
+            references
+                .into_iter()
+                .filter(|reference| *reference != name),
+        );
+    }
+
+    schema_ir
+        .schemas
+        .iter()
+        .filter(|schema| !referenced.contains(schema.name.as_str()))
+        .map(|schema| schema.name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{InputParser, OpenApiParser};
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: Shop, version: 2.0.0 }
+tags: [{ name: users }]
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      tags: [users]
+      responses: { "200": { description: ok, content: { application/json: { schema: { $ref: "#/components/schemas/User" } } } } }
+    post:
+      operationId: createUser
+      responses: { "204": { description: ok } }
+components:
+  schemas:
+    User: { type: object, properties: { address: { $ref: "#/components/schemas/Address" } } }
+    Address: { type: object, properties: { city: { type: string } } }
+    Orphan: { type: object, properties: { id: { type: string } } }
+"##;
+
+    #[test]
+    fn human_size_picks_the_unit() {
+        assert_eq!(human_size(512), "512 B");
+        assert_eq!(human_size(12_595), "12.3 KB");
+        assert_eq!(human_size(1_468_006), "1.4 MB");
+    }
+
+    #[test]
+    fn report_lists_counts_depth_and_generation_sizes() {
+        let ir = OpenApiParser
+            .parse_str(SPEC, &std::collections::HashMap::new())
+            .unwrap();
+        let generations: Vec<GenerationConfig> = serde_yaml::from_str(
+            "[{ generator: typescript, outputFile: client.ts }, { generator: nope, outputFile: x }]",
+        )
+        .unwrap();
+        let report = report(
+            &ir,
+            Path::new("shop.yaml"),
+            &generations,
+            &GeneratorRegistry::new(),
+        );
+
+        assert!(report.starts_with("📊 Shop 2.0.0 (\"shop.yaml\")\n"));
+        assert!(report.contains("\nOperations: 2\n   GET      1\n   POST     1\n"));
+        assert!(report
+            .contains("By tag:\n   users                    1\n   (untagged)               1\n"));
+        assert!(report.contains("Max nesting depth: 2 (User → Address)\n"));
+        assert!(report.contains("Unreferenced schemas (1): Orphan\n"));
+        assert!(report.contains("   typescript           client.ts  "));
+        assert!(report.contains("   nope                 x                        ⚠️  "));
+    }
+}
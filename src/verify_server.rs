@@ -0,0 +1,446 @@
+//! Calls of a deployed API for `generator verify-spec-against-server`: the
+//! GET operations to call, the request each one makes, and sending it with
+//! `curl`. [`drift::check`] compares what the server answers with the spec.
+//!
+//! Parameters take their `--param` value or their example from the spec;
+//! operations with a required parameter that has neither are skipped. Path,
+//! query, and cookie values are percent-encoded.
+
+use crate::drift::{self, Outcome};
+use crate::parsers::{HttpMethod, OperationDefinition, ParameterLocation, SchemaIR};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// What to call and how
+#[derive(Debug, Clone, Copy)]
+pub struct Check<'a> {
+    /// URL the spec's paths are appended to
+    pub base_url: &'a str,
+    /// Operation ids to call; every GET operation when empty
+    pub operations: &'a [String],
+    /// `NAME=VALUE` parameter values, for every operation that has the parameter
+    pub params: &'a [String],
+    /// `Name: value` headers sent with every request
+    pub headers: &'a [String],
+    /// Seconds each request may take
+    pub timeout: u64,
+}
+
+/// What became of one operation's call
+#[derive(Debug)]
+pub enum Call {
+    /// Not called: the required parameter had no value
+    Skipped { missing: String },
+    /// The request could not be sent or answered
+    Failed { url: String, error: anyhow::Error },
+    /// The server answered `status`, and the response was checked against the spec
+    Answered {
+        url: String,
+        status: u16,
+        outcome: Outcome,
+    },
+}
+
+impl Call {
+    fn failed(&self) -> bool {
+        matches!(
+            self,
+            Call::Failed { .. }
+                | Call::Answered {
+                    outcome: Outcome::Drifted(_),
+                    ..
+                }
+        )
+    }
+}
+
+/// How many operations were called and skipped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub called: usize,
+    pub skipped: usize,
+}
+
+/// The operations `check` calls, in spec order
+pub fn operations<'a>(
+    schema_ir: &'a SchemaIR,
+    check: &Check,
+) -> Result<Vec<&'a OperationDefinition>> {
+    let operations: Vec<&OperationDefinition> = match check.operations {
+        [] => schema_ir
+            .operations
+            .iter()
+            .filter(|op| matches!(op.method, HttpMethod::Get))
+            .collect(),
+        ids => ids
+            .iter()
+            .map(|id| {
+                let operation = schema_ir
+                    .operations
+                    .iter()
+                    .find(|op| &op.id == id)
+                    .ok_or_else(|| anyhow::anyhow!("No operation '{}' in the spec", id))?;
+                // Anything else could change the server's state
+                if !matches!(operation.method, HttpMethod::Get) {
+                    anyhow::bail!(
+                        "Operation '{}' is not a GET operation; only those are called",
+                        id
+                    );
+                }
+                Ok(operation)
+            })
+            .collect::<Result<_>>()?,
+    };
+    if operations.is_empty() {
+        anyhow::bail!("The spec has no GET operations to call");
+    }
+    Ok(operations)
+}
+
+/// Call each of `operations` and check its response, handing every call to
+/// `on_call` as it completes; an error once any drifted or failed
+pub fn verify(
+    schema_ir: &SchemaIR,
+    operations: &[&OperationDefinition],
+    check: &Check,
+    mut on_call: impl FnMut(&OperationDefinition, &Call),
+) -> Result<Summary> {
+    let values = check
+        .params
+        .iter()
+        .map(|param| {
+            param
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("--param {:?} is not NAME=VALUE", param))
+        })
+        .collect::<Result<HashMap<&str, &str>>>()?;
+    if let Some(header) = check.headers.iter().find(|header| !header.contains(':')) {
+        anyhow::bail!("--header {:?} is not 'Name: value'", header);
+    }
+
+    let base_url = check.base_url.trim_end_matches('/');
+    let (mut failed, mut skipped) = (0, 0);
+    for operation in operations {
+        let call = match request(operation, base_url, &values, check.headers) {
+            Err(missing) => Call::Skipped { missing },
+            Ok(request) => match curl(&request, check.timeout) {
+                Err(error) => Call::Failed {
+                    url: request.url,
+                    error,
+                },
+                Ok((status, content_type, body)) => {
+                    let response = drift::Response {
+                        status,
+                        content_type: content_type.as_deref(),
+                        body: &body,
+                    };
+                    Call::Answered {
+                        outcome: drift::check(schema_ir, operation, response),
+                        url: request.url,
+                        status,
+                    }
+                }
+            },
+        };
+        match &call {
+            Call::Skipped { .. } => skipped += 1,
+            call if call.failed() => failed += 1,
+            _ => {}
+        }
+        on_call(operation, &call);
+    }
+
+    let called = operations.len() - skipped;
+    if failed > 0 {
+        anyhow::bail!(
+            "{} of {} called operation(s) drifted from the spec or failed",
+            failed,
+            called
+        );
+    }
+    Ok(Summary { called, skipped })
+}
+
+/// A GET request of an operation
+#[derive(Debug, Clone, PartialEq)]
+struct Request {
+    url: String,
+    headers: Vec<String>,
+    cookies: Vec<String>,
+}
+
+/// The request calling `operation`, with `sent_headers` and parameters from
+/// `values` or their examples; the name of a required parameter with neither
+/// as the error
+fn request(
+    operation: &OperationDefinition,
+    base_url: &str,
+    values: &HashMap<&str, &str>,
+    sent_headers: &[String],
+) -> std::result::Result<Request, String> {
+    let sends = |name: &str| {
+        sent_headers.iter().any(|header| {
+            header
+                .split(':')
+                .next()
+                .is_some_and(|sent| sent.trim().eq_ignore_ascii_case(name))
+        })
+    };
+    let mut path = operation.path.clone();
+    let mut query = Vec::new();
+    let mut headers = sent_headers.to_vec();
+    if !sends("accept") {
+        let accept = operation
+            .response_content_type
+            .as_deref()
+            .unwrap_or("application/json");
+        headers.push(format!("Accept: {}", accept));
+    }
+    let mut cookies = Vec::new();
+    for param in &operation.parameters {
+        if param.location == ParameterLocation::Header && sends(&param.name) {
+            continue;
+        }
+        let value: Vec<String> = match (values.get(param.name.as_str()), &param.example) {
+            (Some(value), _) => vec![value.to_string()],
+            (None, Some(Value::Array(items))) => items.iter().map(parameter_text).collect(),
+            (None, Some(example)) => vec![parameter_text(example)],
+            (None, None) if param.required => return Err(param.name.clone()),
+            (None, None) => continue,
+        };
+        let encoded =
+            || -> Vec<String> { value.iter().map(|value| percent_encode(value)).collect() };
+        match param.location {
+            ParameterLocation::Path => {
+                path = path.replace(&format!("{{{}}}", param.name), &encoded().join(","));
+            }
+            ParameterLocation::Query => {
+                for value in encoded() {
+                    query.push(format!("{}={}", percent_encode(&param.name), value));
+                }
+            }
+            ParameterLocation::Header => {
+                headers.push(format!("{}: {}", param.name, value.join(",")))
+            }
+            // `;`, `,`, spaces, and quotes would end or break the cookie
+            ParameterLocation::Cookie => {
+                cookies.push(format!("{}={}", param.name, encoded().join(",")))
+            }
+        }
+    }
+
+    let mut url = format!("{}{}", base_url, path);
+    if !query.is_empty() {
+        url.push('?');
+        url.push_str(&query.join("&"));
+    }
+    Ok(Request {
+        url,
+        headers,
+        cookies,
+    })
+}
+
+fn parameter_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Everything but unreserved characters as `%XX`
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// The status and media type curl writes after the body, on lines of their own
+const TRAILER: &str = "\n%{http_code}\n%{content_type}";
+
+/// Send `request` with curl: the status, `Content-Type`, and body of the
+/// response, after redirects
+fn curl(request: &Request, timeout: u64) -> Result<(u16, Option<String>, Vec<u8>)> {
+    let mut command = std::process::Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--location", "--max-time"])
+        .arg(timeout.to_string())
+        .args(["--write-out", TRAILER]);
+    for header in &request.headers {
+        command.arg("--header").arg(header);
+    }
+    if !request.cookies.is_empty() {
+        command.arg("--cookie").arg(request.cookies.join("; "));
+    }
+    let output = match command.arg("--").arg(&request.url).output() {
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            anyhow::bail!("curl not found on PATH; it is needed to call the server")
+        }
+        output => output.context("Failed to run curl")?,
+    };
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    split_trailer(output.stdout)
+}
+
+/// The body curl printed, and the status and media type of its [`TRAILER`]
+fn split_trailer(mut stdout: Vec<u8>) -> Result<(u16, Option<String>, Vec<u8>)> {
+    let trailer_start = stdout
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .and_then(|last| stdout[..last].iter().rposition(|byte| *byte == b'\n'))
+        .ok_or_else(|| anyhow::anyhow!("curl printed no status"))?;
+    let trailer = String::from_utf8_lossy(&stdout[trailer_start + 1..]).into_owned();
+    stdout.truncate(trailer_start);
+    let (status, content_type) = trailer.split_once('\n').unwrap_or((&trailer, ""));
+    let status = status
+        .trim()
+        .parse()
+        .with_context(|| format!("curl printed no status: {:?}", trailer))?;
+    let content_type =
+        Some(content_type.trim().to_string()).filter(|content_type| !content_type.is_empty());
+    Ok((status, content_type, stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{InputParser, OpenApiParser};
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: T, version: 1.0.0 }
+paths:
+  /users/{id}:
+    get:
+      operationId: getUser
+      parameters:
+        - { name: id, in: path, required: true, schema: { type: string } }
+        - { name: tags, in: query, schema: { type: array, items: { type: string } }, example: [a b, c&d] }
+        - { name: X-Tenant, in: header, schema: { type: string }, example: acme }
+        - { name: session, in: cookie, schema: { type: string }, example: "a;b c" }
+        - { name: page, in: query, schema: { type: integer } }
+      responses: { "200": { description: ok, content: { application/xml: { schema: { type: string } } } } }
+    delete:
+      operationId: deleteUser
+      parameters: [{ name: id, in: path, required: true, schema: { type: string } }]
+      responses: { "204": { description: gone } }
+"##;
+
+    fn ir() -> SchemaIR {
+        OpenApiParser.parse_str(SPEC, &HashMap::new()).unwrap()
+    }
+
+    fn check<'a>(operations: &'a [String], headers: &'a [String]) -> Check<'a> {
+        Check {
+            base_url: "http://localhost:8080/",
+            operations,
+            params: &[],
+            headers,
+            timeout: 10,
+        }
+    }
+
+    #[test]
+    fn percent_encode_keeps_only_unreserved_characters() {
+        assert_eq!(percent_encode("a-Z_0.9~"), "a-Z_0.9~");
+        assert_eq!(percent_encode("a b/c?d=e&f;g"), "a%20b%2Fc%3Fd%3De%26f%3Bg");
+        assert_eq!(percent_encode("é"), "%C3%A9");
+    }
+
+    #[test]
+    fn requests_encode_parameters_by_location() {
+        let ir = ir();
+        let values = HashMap::from([("id", "a/b")]);
+        let request = request(&ir.operations[0], "http://localhost", &values, &[]).unwrap();
+        assert_eq!(
+            request,
+            Request {
+                url: "http://localhost/users/a%2Fb?tags=a%20b&tags=c%26d".to_string(),
+                headers: vec![
+                    "Accept: application/xml".to_string(),
+                    "X-Tenant: acme".to_string()
+                ],
+                cookies: vec!["session=a%3Bb%20c".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn sent_headers_replace_accept_and_header_parameters() {
+        let ir = ir();
+        let values = HashMap::from([("id", "1")]);
+        let sent = ["accept: */*".to_string(), "x-tenant: other".to_string()];
+        let request = request(&ir.operations[0], "http://localhost", &values, &sent).unwrap();
+        assert_eq!(request.headers, sent);
+    }
+
+    #[test]
+    fn required_parameters_without_a_value_skip_the_call() {
+        let ir = ir();
+        assert_eq!(
+            request(&ir.operations[0], "http://localhost", &HashMap::new(), &[]),
+            Err("id".to_string())
+        );
+    }
+
+    #[test]
+    fn only_get_operations_are_called() {
+        let ir = ir();
+        let ids: Vec<_> = operations(&ir, &check(&[], &[]))
+            .unwrap()
+            .iter()
+            .map(|op| op.id.as_str())
+            .collect();
+        assert_eq!(ids, ["getUser"]);
+
+        let unsafe_call = ["deleteUser".to_string()];
+        let error = operations(&ir, &check(&unsafe_call, &[])).unwrap_err();
+        assert!(error.to_string().contains("is not a GET operation"));
+        let unknown = ["nope".to_string()];
+        assert!(operations(&ir, &check(&unknown, &[])).is_err());
+    }
+
+    #[test]
+    fn malformed_params_and_headers_are_rejected_before_calling() {
+        let ir = ir();
+        let operations = operations(&ir, &check(&[], &[])).unwrap();
+        let params = ["id".to_string()];
+        let bad_param = Check {
+            params: &params,
+            ..check(&[], &[])
+        };
+        let mut calls = 0;
+        let error = verify(&ir, &operations, &bad_param, |_, _| calls += 1).unwrap_err();
+        assert!(error.to_string().contains("is not NAME=VALUE"));
+
+        let headers = ["Authorization Bearer x".to_string()];
+        let error = verify(&ir, &operations, &check(&[], &headers), |_, _| calls += 1).unwrap_err();
+        assert!(error.to_string().contains("is not 'Name: value'"));
+        assert_eq!(calls, 0);
+    }
+
+    #[test]
+    fn trailer_splits_status_and_media_type_from_the_body() {
+        let (status, content_type, body) =
+            split_trailer(b"{\"a\":\n1}\n200\napplication/json".to_vec()).unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(content_type.as_deref(), Some("application/json"));
+        assert_eq!(body, b"{\"a\":\n1}");
+
+        let (status, content_type, body) = split_trailer(b"\n204\n".to_vec()).unwrap();
+        assert_eq!((status, content_type, body), (204, None, Vec::new()));
+
+        assert!(split_trailer(b"no trailer".to_vec()).is_err());
+        assert!(split_trailer(b"body\nabc\ntext/plain".to_vec()).is_err());
+    }
+}
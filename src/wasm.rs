@@ -0,0 +1,28 @@
+//! JavaScript bindings for wasm32 builds (`wasm` feature).
+//!
+//! ```js
+//! import init, { generate } from './generator.js';
+//! await init();
+//! for (const file of generate(specText, configText)) {
+//!   console.log(file.filename, file.content);
+//! }
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+/// A generated file, as returned to JavaScript
+#[wasm_bindgen(getter_with_clone)]
+pub struct GeneratedFile {
+    pub filename: String,
+    pub content: String,
+}
+
+/// [`crate::generate`] for JavaScript; errors are thrown with the full context chain
+#[wasm_bindgen]
+pub fn generate(spec: &str, config: &str) -> Result<Vec<GeneratedFile>, JsError> {
+    let files = crate::generate(spec, config).map_err(|e| JsError::new(&format!("{:#}", e)))?;
+    Ok(files
+        .into_iter()
+        .map(|(filename, content)| GeneratedFile { filename, content })
+        .collect())
+}